@@ -0,0 +1,67 @@
+// What every candidate costs: deriving its address, scoring it, both
+// together, and a worker's batch of them in each mode, measured one thread at
+// a time. `cargo bench` compares each run against the last, so a change to
+// the hot path shows up as a regression or an improvement right away.
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use uniswapv4_challenge_miner::miner::Miner;
+use uniswapv4_challenge_miner::mode::{Derivation, Mode};
+use uniswapv4_challenge_miner::salts::Sequential;
+use uniswapv4_challenge_miner::{compute_score, compute_score_at_least, create2_addr, decode_hex, DEPLOYER_ADDRESS, INITCODE_HASH, SUBMITTER_ADDRESS};
+
+// As many salts as a worker hashes between looking at its controls.
+const BATCH: u64 = 1 << 12;
+
+fn salt(counter: u64) -> [u8; 32] {
+    let mut salt = [0; 32];
+    salt[..20].copy_from_slice(&SUBMITTER_ADDRESS);
+    salt[24..].copy_from_slice(&counter.to_be_bytes());
+    salt
+}
+
+fn per_candidate(c: &mut Criterion) {
+    let salt = salt(7);
+    let address = create2_addr(&DEPLOYER_ADDRESS, &salt, &INITCODE_HASH);
+    // Scores most often seen are 0, so the early exit is what usually runs.
+    let scoring = [address, decode_hex("0x0000000044449dbd6b1b8e5dcac0e2ce0ec9ef2e").unwrap()];
+
+    c.bench_function("create2_addr", |b| b.iter(|| create2_addr(black_box(&DEPLOYER_ADDRESS), black_box(&salt), black_box(&INITCODE_HASH))));
+    for (name, address) in ["typical", "scoring"].into_iter().zip(scoring) {
+        c.bench_function(&format!("compute_score/{}", name), |b| b.iter(|| compute_score(black_box(&address))));
+        c.bench_function(&format!("compute_score_at_least/{}", name), |b| b.iter(|| compute_score_at_least(black_box(&address), black_box(130))));
+    }
+    c.bench_function("create2_addr+compute_score", |b| {
+        b.iter(|| compute_score_at_least(&create2_addr(black_box(&DEPLOYER_ADDRESS), black_box(&salt), black_box(&INITCODE_HASH)), 130))
+    });
+}
+
+fn batch_per_mode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("batch");
+    group.throughput(Throughput::Elements(BATCH));
+    for mode in [Mode::Create2, Mode::Create3, Mode::Create, Mode::Eoa] {
+        let derivation = Derivation { mode, deployer: DEPLOYER_ADDRESS, code_hash: INITCODE_HASH, create3_caller: None };
+        // Counters from 1, which are valid private keys for --mode eoa too.
+        group.bench_function(format!("{:?}", mode).to_lowercase(), |b| {
+            b.iter(|| (1..=BATCH).map(|counter| compute_score_at_least(&derivation.address(&salt(counter)), 130)).max())
+        });
+    }
+    group.finish();
+}
+
+// The whole worker loop as a search runs it, salts and bookkeeping included.
+fn search(c: &mut Criterion) {
+    let mut group = c.benchmark_group("search");
+    group.throughput(Throughput::Elements(16 * BATCH));
+    group.sample_size(20);
+    group.bench_function("create2/1-thread", |b| {
+        b.iter_batched(
+            || Miner::builder().threads(1).salts(Sequential { submitter: SUBMITTER_ADDRESS, start: 0, end: Some(16 * BATCH) }),
+            |builder| builder.run().unwrap().wait().unwrap(),
+            BatchSize::PerIteration,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, per_candidate, batch_per_mode, search);
+criterion_main!(benches);
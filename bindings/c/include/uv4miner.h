@@ -0,0 +1,75 @@
+#ifndef UV4MINER_H
+#define UV4MINER_H
+
+/* Generated by cbindgen from src/lib.rs, do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// uv4_search found a candidate scoring above `min_score`.
+#define UV4_FOUND 1
+
+// uv4_search tried every salt without beating `min_score`.
+#define UV4_NOT_FOUND 0
+
+// A null pointer or zero threads.
+#define UV4_INVALID_ARGUMENT -1
+
+// The search thread panicked.
+#define UV4_SEARCH_FAILED -2
+
+// The best salt of a search, the address it deploys to and its score.
+typedef struct Uv4Result {
+  uint8_t salt[32];
+  uint8_t address[20];
+  uint32_t score;
+} Uv4Result;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Write to `out` the address of the contract `deployer` CREATE2s with
+// `salt`, for initcode hashing to `code_hash`.
+//
+// # Safety
+//
+// `deployer` and `out` must point to 20 bytes, `salt` and `code_hash` to 32.
+void uv4_create2_addr(const uint8_t *deployer,
+                      const uint8_t *salt,
+                      const uint8_t *code_hash,
+                      uint8_t *out);
+
+// The challenge's score of `address`.
+//
+// # Safety
+//
+// `address` must point to 20 bytes.
+uint32_t uv4_compute_score(const uint8_t *address);
+
+// Search the salts `submitter`, four zero bytes and a big-endian counter from
+// `start` up to (not including) `end`, on `threads` threads, the way
+// `--salts sequential` does. The best salt scoring above `min_score` goes to
+// `out`. Returns UV4_FOUND, UV4_NOT_FOUND, UV4_INVALID_ARGUMENT or
+// UV4_SEARCH_FAILED.
+//
+// # Safety
+//
+// `deployer` and `submitter` must point to 20 bytes, `code_hash` to 32, and
+// `out` to a Uv4Result.
+int32_t uv4_search(const uint8_t *deployer,
+                   const uint8_t *code_hash,
+                   const uint8_t *submitter,
+                   uint64_t start,
+                   uint64_t end,
+                   uint32_t min_score,
+                   uint32_t threads,
+                   struct Uv4Result *out);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* UV4MINER_H */
@@ -0,0 +1,96 @@
+//! C ABI for the miner's address derivation, scoring and search, so other
+//! mining software scores addresses exactly as the Rust miner does. The header
+//! is `include/uv4miner.h`; addresses are 20 bytes, salts and hashes 32.
+
+use std::slice;
+
+use uniswapv4_challenge_miner::miner::Miner;
+use uniswapv4_challenge_miner::salts::Sequential;
+use uniswapv4_challenge_miner::{compute_score, create2_addr};
+
+/// uv4_search found a candidate scoring above `min_score`.
+pub const UV4_FOUND: i32 = 1;
+/// uv4_search tried every salt without beating `min_score`.
+pub const UV4_NOT_FOUND: i32 = 0;
+/// A null pointer or zero threads.
+pub const UV4_INVALID_ARGUMENT: i32 = -1;
+/// The search thread panicked.
+pub const UV4_SEARCH_FAILED: i32 = -2;
+
+/// The best salt of a search, the address it deploys to and its score.
+#[repr(C)]
+pub struct Uv4Result {
+    pub salt: [u8; 32],
+    pub address: [u8; 20],
+    pub score: u32,
+}
+
+unsafe fn array<const N: usize>(ptr: *const u8) -> [u8; N] {
+    slice::from_raw_parts(ptr, N).try_into().unwrap()
+}
+
+/// Write to `out` the address of the contract `deployer` CREATE2s with
+/// `salt`, for initcode hashing to `code_hash`.
+///
+/// # Safety
+///
+/// `deployer` and `out` must point to 20 bytes, `salt` and `code_hash` to 32.
+#[no_mangle]
+pub unsafe extern "C" fn uv4_create2_addr(deployer: *const u8, salt: *const u8, code_hash: *const u8, out: *mut u8) {
+    let address = create2_addr(&array(deployer), &array(salt), &array(code_hash));
+    slice::from_raw_parts_mut(out, 20).copy_from_slice(&address);
+}
+
+/// The challenge's score of `address`.
+///
+/// # Safety
+///
+/// `address` must point to 20 bytes.
+#[no_mangle]
+pub unsafe extern "C" fn uv4_compute_score(address: *const u8) -> u32 {
+    compute_score(&array(address))
+}
+
+/// Search the salts `submitter`, four zero bytes and a big-endian counter from
+/// `start` up to (not including) `end`, on `threads` threads, the way
+/// `--salts sequential` does. The best salt scoring above `min_score` goes to
+/// `out`. Returns UV4_FOUND, UV4_NOT_FOUND, UV4_INVALID_ARGUMENT or
+/// UV4_SEARCH_FAILED.
+///
+/// # Safety
+///
+/// `deployer` and `submitter` must point to 20 bytes, `code_hash` to 32, and
+/// `out` to a Uv4Result.
+#[no_mangle]
+pub unsafe extern "C" fn uv4_search(
+    deployer: *const u8,
+    code_hash: *const u8,
+    submitter: *const u8,
+    start: u64,
+    end: u64,
+    min_score: u32,
+    threads: u32,
+    out: *mut Uv4Result,
+) -> i32 {
+    if deployer.is_null() || code_hash.is_null() || submitter.is_null() || out.is_null() || threads == 0 {
+        return UV4_INVALID_ARGUMENT;
+    }
+    let miner = Miner::builder()
+        .deployer(array(deployer))
+        .code_hash(array(code_hash))
+        .salts(Sequential { submitter: array(submitter), start, end: Some(end) })
+        .min_score(min_score)
+        .threads(threads as usize)
+        .run();
+    let Ok(miner) = miner else {
+        return UV4_INVALID_ARGUMENT;
+    };
+    match miner.wait() {
+        Ok(Some(best)) => {
+            *out = Uv4Result { salt: best.salt, address: best.address, score: best.score };
+            UV4_FOUND
+        }
+        Ok(None) => UV4_NOT_FOUND,
+        Err(_) => UV4_SEARCH_FAILED,
+    }
+}
@@ -0,0 +1,33 @@
+// The C entry points against the library calls they wrap.
+
+use uniswapv4_challenge_miner::{compute_score, create2_addr};
+use uv4miner::{uv4_compute_score, uv4_create2_addr, uv4_search, Uv4Result, UV4_FOUND, UV4_INVALID_ARGUMENT, UV4_NOT_FOUND};
+
+#[test]
+fn entry_points_match_the_library() {
+    let deployer = [0x48; 20];
+    let code_hash = [0x94; 32];
+    let submitter = [0x11; 20];
+    let salt = |counter: u64| {
+        let mut salt = [0; 32];
+        salt[..20].copy_from_slice(&submitter);
+        salt[24..].copy_from_slice(&counter.to_be_bytes());
+        salt
+    };
+
+    let mut address = [0; 20];
+    unsafe { uv4_create2_addr(deployer.as_ptr(), salt(7).as_ptr(), code_hash.as_ptr(), address.as_mut_ptr()) };
+    assert_eq!(address, create2_addr(&deployer, &salt(7), &code_hash));
+    assert_eq!(unsafe { uv4_compute_score(address.as_ptr()) }, compute_score(&address));
+
+    let expected = (0..10_000).map(|counter| compute_score(&create2_addr(&deployer, &salt(counter), &code_hash))).max().unwrap();
+    let mut result = Uv4Result { salt: [0; 32], address: [0; 20], score: 0 };
+    let search = |min_score, result: &mut Uv4Result| unsafe {
+        uv4_search(deployer.as_ptr(), code_hash.as_ptr(), submitter.as_ptr(), 0, 10_000, min_score, 2, result)
+    };
+    assert_eq!(search(0, &mut result), UV4_FOUND);
+    assert_eq!(result.score, expected);
+    assert_eq!(create2_addr(&deployer, &result.salt, &code_hash), result.address);
+    assert_eq!(search(expected, &mut result), UV4_NOT_FOUND);
+    assert_eq!(unsafe { uv4_search(deployer.as_ptr(), code_hash.as_ptr(), submitter.as_ptr(), 0, 1, 0, 0, &mut result) }, UV4_INVALID_ARGUMENT);
+}
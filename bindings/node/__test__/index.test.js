@@ -0,0 +1,39 @@
+// Run with `npm run build && npm test` from bindings/node.
+
+const test = require('node:test');
+const assert = require('node:assert');
+const miner = require('..');
+
+const ZERO_ADDRESS = '0x' + '00'.repeat(20);
+// Example 0 of EIP-1014: initcode 0x00.
+const CODE_HASH = '0xbc36789e7a1e281436464229828f817d6612f7b477d66591ff96a9e064bcc98a';
+
+test('derivation and score', () => {
+  const address = miner.create2Address(ZERO_ADDRESS, '0x' + '00'.repeat(32), CODE_HASH);
+  assert.strictEqual(address, '0x4d1a2e2bb4f88f0250f26ffff098b0b30b26bf38');
+  assert.strictEqual(miner.computeScore('0x0000000044449dbd6b1b8e5dcac0e2ce0ec9ef2e'), 144);
+  assert.throws(() => miner.computeScore('0x12'));
+});
+
+test('ranged search reports its bests', async () => {
+  const bests = [];
+  const search = miner.startSearch(
+    { deployer: ZERO_ADDRESS, codeHash: CODE_HASH, submitter: ZERO_ADDRESS, start: 0, end: 50000, threads: 2 },
+    (best) => bests.push(best),
+  );
+  const best = await search.wait();
+  assert.strictEqual(miner.create2Address(ZERO_ADDRESS, best.salt, CODE_HASH), best.address);
+  assert.strictEqual(miner.computeScore(best.address), best.score);
+  // New bests arrive on the event loop, maybe after the promise.
+  await new Promise((resolve) => setImmediate(resolve));
+  assert.deepStrictEqual(bests[bests.length - 1], best);
+});
+
+test('open-ended search can be paused and stopped', async () => {
+  const search = miner.startSearch({ threads: 1 }, undefined, () => {});
+  search.pause();
+  assert.strictEqual(search.stats().paused, true);
+  search.resume();
+  await search.stop();
+  assert.strictEqual(search.stats(), null);
+});
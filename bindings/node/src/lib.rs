@@ -0,0 +1,208 @@
+//! Node.js addon: derivation, scoring and a background search reporting new
+//! bests and progress as events. Addresses, salts and hashes are 0x-prefixed
+//! hex strings, as ethers and viem use them.
+
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+use napi::bindgen_prelude::*;
+use napi::threadsafe_function::{ErrorStrategy, ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi::JsFunction;
+use napi_derive::napi;
+use uniswapv4_challenge_miner::miner::{Miner, Stats};
+use uniswapv4_challenge_miner::salts::Sequential;
+use uniswapv4_challenge_miner::{create2_addr, decode_hex, Candidate};
+
+fn parse<const N: usize>(name: &str, value: &str) -> Result<[u8; N]> {
+    decode_hex(value).map_err(|e| Error::from_reason(format!("{}: {}", name, e)))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    format!("0x{}", bytes.iter().map(|b| format!("{:02x}", b)).collect::<String>())
+}
+
+#[napi(object)]
+pub struct JsCandidate {
+    pub salt: String,
+    pub address: String,
+    pub score: u32,
+}
+
+impl From<Candidate> for JsCandidate {
+    fn from(c: Candidate) -> Self {
+        JsCandidate { salt: to_hex(&c.salt), address: to_hex(&c.address), score: c.score }
+    }
+}
+
+#[napi(object)]
+pub struct JsStats {
+    pub hashes: f64,
+    pub elapsed_ms: f64,
+    pub best: Option<JsCandidate>,
+    pub paused: bool,
+    pub finished: bool,
+}
+
+impl From<Stats> for JsStats {
+    fn from(stats: Stats) -> Self {
+        JsStats {
+            hashes: stats.hashes as f64,
+            elapsed_ms: stats.elapsed.as_secs_f64() * 1000.0,
+            best: stats.best.map(JsCandidate::from),
+            paused: stats.paused,
+            finished: stats.finished,
+        }
+    }
+}
+
+/// The address `deployer` CREATE2s with `salt`, for initcode hashing to `codeHash`.
+#[napi]
+pub fn create2_address(deployer: String, salt: String, code_hash: String) -> Result<String> {
+    let address = create2_addr(&parse("deployer", &deployer)?, &parse("salt", &salt)?, &parse("codeHash", &code_hash)?);
+    Ok(to_hex(&address))
+}
+
+/// The challenge's score of `address`.
+#[napi]
+pub fn compute_score(address: String) -> Result<u32> {
+    Ok(uniswapv4_challenge_miner::compute_score(&parse("address", &address)?))
+}
+
+/// Defaults are the command-line miner's. With `start` or `end` the salts are
+/// sequential over that counter range, otherwise random.
+#[napi(object)]
+pub struct SearchOptions {
+    pub deployer: Option<String>,
+    pub code_hash: Option<String>,
+    pub submitter: Option<String>,
+    pub start: Option<i64>,
+    pub end: Option<i64>,
+    pub min_score: Option<u32>,
+    pub threads: Option<u32>,
+    /// How often `onProgress` is called, 1000 by default.
+    pub progress_interval_ms: Option<u32>,
+}
+
+/// Start searching in the background. `onNewBest` is called with every new
+/// best and `onProgress` with the stats every `progressIntervalMs`.
+#[napi(
+    ts_args_type = "options: SearchOptions, onNewBest?: (best: JsCandidate) => void, onProgress?: (stats: JsStats) => void"
+)]
+pub fn start_search(options: SearchOptions, on_new_best: Option<JsFunction>, on_progress: Option<JsFunction>) -> Result<SearchHandle> {
+    let mut builder = Miner::builder();
+    if let Some(deployer) = &options.deployer {
+        builder = builder.deployer(parse("deployer", deployer)?);
+    }
+    if let Some(code_hash) = &options.code_hash {
+        builder = builder.code_hash(parse("codeHash", code_hash)?);
+    }
+    let submitter = options.submitter.as_deref().map(|submitter| parse("submitter", submitter)).transpose()?;
+    if let Some(submitter) = submitter {
+        builder = builder.submitter(submitter);
+    }
+    if options.start.is_some() || options.end.is_some() {
+        let counter = |name: &str, value: i64| u64::try_from(value).map_err(|_| Error::from_reason(format!("{} is negative", name)));
+        let start = options.start.map(|start| counter("start", start)).transpose()?.unwrap_or(0);
+        let end = options.end.map(|end| counter("end", end)).transpose()?;
+        let submitter = submitter.unwrap_or(uniswapv4_challenge_miner::SUBMITTER_ADDRESS);
+        builder = builder.salts(Sequential { submitter, start, end });
+    }
+    if let Some(min_score) = options.min_score {
+        builder = builder.min_score(min_score);
+    }
+    if let Some(threads) = options.threads {
+        builder = builder.threads(threads as usize);
+    }
+    if let Some(callback) = on_new_best {
+        let callback: ThreadsafeFunction<Candidate, ErrorStrategy::Fatal> =
+            callback.create_threadsafe_function(0, |ctx| Ok(vec![JsCandidate::from(ctx.value)]))?;
+        builder = builder.on_new_best(move |c| {
+            callback.call(*c, ThreadsafeFunctionCallMode::NonBlocking);
+        });
+    }
+    let miner = Arc::new(Mutex::new(Some(builder.run().map_err(|e| Error::from_reason(e.to_string()))?)));
+    if let Some(callback) = on_progress {
+        let callback: ThreadsafeFunction<Stats, ErrorStrategy::Fatal> =
+            callback.create_threadsafe_function(0, |ctx| Ok(vec![JsStats::from(ctx.value)]))?;
+        let interval = Duration::from_millis(options.progress_interval_ms.unwrap_or(1000).into());
+        let miner = Arc::clone(&miner);
+        // Ends with the search, releasing the callback so Node can exit.
+        thread::spawn(move || loop {
+            thread::sleep(interval);
+            let Some(stats) = miner.lock().unwrap_or_else(PoisonError::into_inner).as_ref().map(Miner::stats) else { break };
+            callback.call(stats, ThreadsafeFunctionCallMode::NonBlocking);
+            if stats.finished {
+                break;
+            }
+        });
+    }
+    Ok(SearchHandle { miner })
+}
+
+/// A running search.
+#[napi]
+pub struct SearchHandle {
+    // Taken when the search is stopped or waited for.
+    miner: Arc<Mutex<Option<Miner>>>,
+}
+
+pub struct Finish {
+    miner: Arc<Mutex<Option<Miner>>>,
+    stop: bool,
+}
+
+impl Task for Finish {
+    type Output = Option<Candidate>;
+    type JsValue = Option<JsCandidate>;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        // Polled rather than joined, so stats and progress keep working meanwhile.
+        if !self.stop {
+            while matches!(self.miner.lock().unwrap_or_else(PoisonError::into_inner).as_ref(), Some(miner) if !miner.stats().finished) {
+                thread::sleep(Duration::from_millis(50));
+            }
+        }
+        let miner = self.miner.lock().unwrap_or_else(PoisonError::into_inner).take();
+        Ok(miner.map(Miner::stop).transpose().map_err(|e| Error::from_reason(e.to_string()))?.flatten())
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+        Ok(output.map(JsCandidate::from))
+    }
+}
+
+#[napi]
+impl SearchHandle {
+    #[napi]
+    pub fn pause(&self) {
+        if let Some(miner) = self.miner.lock().unwrap_or_else(PoisonError::into_inner).as_ref() {
+            miner.pause();
+        }
+    }
+
+    #[napi]
+    pub fn resume(&self) {
+        if let Some(miner) = self.miner.lock().unwrap_or_else(PoisonError::into_inner).as_ref() {
+            miner.resume();
+        }
+    }
+
+    /// Null once the search has been stopped or waited for.
+    #[napi]
+    pub fn stats(&self) -> Option<JsStats> {
+        self.miner.lock().unwrap_or_else(PoisonError::into_inner).as_ref().map(|miner| miner.stats().into())
+    }
+
+    /// Stop the search; resolves to the best candidate found.
+    #[napi(ts_return_type = "Promise<JsCandidate | null>")]
+    pub fn stop(&self) -> AsyncTask<Finish> {
+        AsyncTask::new(Finish { miner: Arc::clone(&self.miner), stop: true })
+    }
+
+    /// Resolves to the best candidate once a search with an `end` is done.
+    #[napi(ts_return_type = "Promise<JsCandidate | null>")]
+    pub fn wait(&self) -> AsyncTask<Finish> {
+        AsyncTask::new(Finish { miner: Arc::clone(&self.miner), stop: false })
+    }
+}
@@ -0,0 +1,159 @@
+//! Python bindings: address derivation, scoring and the search, at native
+//! speed. Addresses, salts and hashes are `bytes`.
+
+use std::time::{Duration, Instant};
+
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use uniswapv4_challenge_miner::miner::Miner;
+use uniswapv4_challenge_miner::salts::Sequential;
+use uniswapv4_challenge_miner::{compute_score, create2_addr, create3_addr, create_addr, odds};
+
+// How often a running search checks for Ctrl-C.
+const POLL: Duration = Duration::from_millis(50);
+
+fn array<const N: usize>(name: &str, bytes: &[u8]) -> PyResult<[u8; N]> {
+    bytes
+        .try_into()
+        .map_err(|_| PyValueError::new_err(format!("{} must be {} bytes, got {}", name, N, bytes.len())))
+}
+
+/// A salt, the address it deploys to and its score.
+#[pyclass(frozen, name = "Candidate", module = "uniswapv4_miner")]
+struct PyCandidate(uniswapv4_challenge_miner::Candidate);
+
+#[pymethods]
+impl PyCandidate {
+    #[getter]
+    fn salt<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.0.salt)
+    }
+
+    #[getter]
+    fn address<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.0.address)
+    }
+
+    #[getter]
+    fn score(&self) -> u32 {
+        self.0.score
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Candidate(salt=0x{}, address=0x{}, score={})", hex(&self.0.salt), hex(&self.0.address), self.0.score)
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// The address `deployer` CREATE2s with `salt`, for initcode hashing to `code_hash`.
+#[pyfunction]
+fn create2_address<'py>(py: Python<'py>, deployer: &[u8], salt: &[u8], code_hash: &[u8]) -> PyResult<Bound<'py, PyBytes>> {
+    let address = create2_addr(&array("deployer", deployer)?, &array("salt", salt)?, &array("code_hash", code_hash)?);
+    Ok(PyBytes::new(py, &address))
+}
+
+/// The address `deployer` deploys to through a CREATE3 proxy with `salt`.
+#[pyfunction]
+fn create3_address<'py>(py: Python<'py>, deployer: &[u8], salt: &[u8]) -> PyResult<Bound<'py, PyBytes>> {
+    let address = create3_addr(&array("deployer", deployer)?, &array("salt", salt)?);
+    Ok(PyBytes::new(py, &address))
+}
+
+/// The address `sender` CREATEs at `nonce`.
+#[pyfunction]
+fn create_address<'py>(py: Python<'py>, sender: &[u8], nonce: u64) -> PyResult<Bound<'py, PyBytes>> {
+    Ok(PyBytes::new(py, &create_addr(&array("sender", sender)?, nonce)))
+}
+
+/// The challenge's score of `address`.
+#[pyfunction]
+fn score(address: &[u8]) -> PyResult<u32> {
+    Ok(compute_score(&array("address", address)?))
+}
+
+/// Scores of the sequential salts `submitter`, four zero bytes and each
+/// counter from `start` on, `count` of them, for looking at the distribution.
+#[pyfunction]
+fn scores(py: Python<'_>, deployer: &[u8], code_hash: &[u8], submitter: &[u8], start: u64, count: u64) -> PyResult<Vec<u32>> {
+    let deployer = array("deployer", deployer)?;
+    let code_hash = array("code_hash", code_hash)?;
+    let salts = Sequential { submitter: array("submitter", submitter)?, start, end: None };
+    let end = start.checked_add(count).ok_or_else(|| PyValueError::new_err("start + count overflows"))?;
+    Ok(py.allow_threads(|| (start..end).map(|counter| compute_score(&create2_addr(&deployer, &salts.salt(counter), &code_hash))).collect()))
+}
+
+/// Probability that a random address scores at least `score`.
+#[pyfunction]
+fn chance_at_least(score: u32) -> f64 {
+    odds::chance_at_least(score)
+}
+
+/// Search the sequential salts from `start` up to (not including) `end`, or
+/// until `timeout` seconds or Ctrl-C without an end, and return the best
+/// candidate scoring above `min_score`, if any. `progress(hashes, best)` is
+/// called every `interval` seconds; returning False stops the search.
+#[pyfunction]
+#[pyo3(signature = (deployer, code_hash, submitter, start=0, end=None, *, min_score=0, threads=None, timeout=None, progress=None, interval=1.0))]
+#[allow(clippy::too_many_arguments)]
+fn search(
+    py: Python<'_>,
+    deployer: &[u8],
+    code_hash: &[u8],
+    submitter: &[u8],
+    start: u64,
+    end: Option<u64>,
+    min_score: u32,
+    threads: Option<usize>,
+    timeout: Option<f64>,
+    progress: Option<Bound<'_, PyAny>>,
+    interval: f64,
+) -> PyResult<Option<PyCandidate>> {
+    let mut builder = Miner::builder()
+        .deployer(array("deployer", deployer)?)
+        .code_hash(array("code_hash", code_hash)?)
+        .salts(Sequential { submitter: array("submitter", submitter)?, start, end })
+        .min_score(min_score);
+    if let Some(threads) = threads {
+        builder = builder.threads(threads);
+    }
+    let miner = builder.run().map_err(|e| PyValueError::new_err(e.to_string()))?;
+    let started = Instant::now();
+    let mut reported = started;
+    let interval = Duration::from_secs_f64(interval.max(0.0));
+    let deadline = timeout.map(|timeout| started + Duration::from_secs_f64(timeout.max(0.0)));
+    loop {
+        py.allow_threads(|| std::thread::sleep(POLL));
+        // Raises KeyboardInterrupt; dropping the miner stops it.
+        py.check_signals()?;
+        if miner.stats().finished || deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            break;
+        }
+        if let Some(progress) = progress.as_ref().filter(|_| reported.elapsed() >= interval) {
+            reported = Instant::now();
+            let stats = miner.stats();
+            let keep_going = progress.call1((stats.hashes, stats.best.map(PyCandidate)))?;
+            if let Ok(false) = keep_going.extract::<bool>() {
+                break;
+            }
+        }
+    }
+    let best = py.allow_threads(|| miner.stop()).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+    Ok(best.map(PyCandidate))
+}
+
+#[pymodule]
+fn uniswapv4_miner(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyCandidate>()?;
+    m.add_function(wrap_pyfunction!(create2_address, m)?)?;
+    m.add_function(wrap_pyfunction!(create3_address, m)?)?;
+    m.add_function(wrap_pyfunction!(create_address, m)?)?;
+    m.add_function(wrap_pyfunction!(score, m)?)?;
+    m.add_function(wrap_pyfunction!(scores, m)?)?;
+    m.add_function(wrap_pyfunction!(chance_at_least, m)?)?;
+    m.add_function(wrap_pyfunction!(search, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,30 @@
+# Run with `maturin develop && pytest` from bindings/python.
+
+import uniswapv4_miner as m
+
+# Example 0 of EIP-1014: initcode 0x00.
+CODE_HASH = bytes.fromhex("bc36789e7a1e281436464229828f817d6612f7b477d66591ff96a9e064bcc98a")
+
+
+def test_derivation_and_score():
+    address = m.create2_address(bytes(20), bytes(32), CODE_HASH)
+    assert address.hex() == "4d1a2e2bb4f88f0250f26ffff098b0b30b26bf38"
+    assert m.score(bytes.fromhex("0000000044449dbd6b1b8e5dcac0e2ce0ec9ef2e")) == 144
+
+
+def test_search_finds_the_best_of_its_range():
+    scores = m.scores(bytes(20), CODE_HASH, bytes(20), 0, 50_000)
+    best = m.search(bytes(20), CODE_HASH, bytes(20), 0, 50_000, threads=2)
+    assert best.score == max(scores)
+    assert m.create2_address(bytes(20), best.salt, CODE_HASH) == best.address
+
+
+def test_progress_can_stop_the_search():
+    calls = []
+
+    def progress(hashes, best):
+        calls.append(hashes)
+        return False
+
+    m.search(bytes(20), CODE_HASH, bytes(20), progress=progress, interval=0.1)
+    assert len(calls) == 1
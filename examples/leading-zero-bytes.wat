@@ -0,0 +1,22 @@
+;; Score addresses by their number of leading zero bytes.
+;;
+;;     uniswapv4-challenge-miner --threads 0 --scorer wasm:examples/leading-zero-bytes.wat
+;;
+;; Any language targeting wasm works the same way: export `memory`,
+;; `alloc(len) -> ptr` and `score(ptr, len) -> u32`.
+(module
+  (memory (export "memory") 1)
+
+  ;; The address always goes to the start of memory.
+  (func (export "alloc") (param $len i32) (result i32)
+    i32.const 0)
+
+  (func (export "score") (param $ptr i32) (param $len i32) (result i32)
+    (local $i i32)
+    (block $done
+      (loop $next
+        (br_if $done (i32.ge_u (local.get $i) (local.get $len)))
+        (br_if $done (i32.load8_u (i32.add (local.get $ptr) (local.get $i))))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $next)))
+    (local.get $i)))
@@ -0,0 +1,26 @@
+// Documents read from files and the network: checkpoints, jobs and results.
+// Reading any bytes must not panic, and a document read once must read back
+// the same after writing it out again.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use uniswapv4_challenge_miner::schema::{BestResult, Checkpoint, JobSpec};
+
+fn round_trips<T: Serialize + DeserializeOwned>(data: &[u8]) {
+    let Ok(document) = serde_json::from_slice::<T>(data) else { return };
+    let written = serde_json::to_string(&document).unwrap();
+    let reread: T = serde_json::from_str(&written).unwrap_or_else(|e| panic!("{} does not read back: {}", written, e));
+    assert_eq!(serde_json::to_string(&reread).unwrap(), written);
+}
+
+fuzz_target!(|data: &[u8]| {
+    round_trips::<Checkpoint>(data);
+    round_trips::<JobSpec>(data);
+    round_trips::<BestResult>(data);
+    if let Ok(job) = serde_json::from_slice::<JobSpec>(data) {
+        let _ = job.builder();
+    }
+});
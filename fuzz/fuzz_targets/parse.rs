@@ -0,0 +1,51 @@
+// The command-line and file parsers: hex, salt ranges, hook flags, targets,
+// scorer names, and --targets lines. None may panic, and what they accept
+// has to come back out the same.
+
+#![no_main]
+
+use std::str::FromStr;
+
+use clap::Parser;
+use libfuzzer_sys::fuzz_target;
+use uniswapv4_challenge_miner::monitor::Target;
+use uniswapv4_challenge_miner::pattern::{self, PatternArgs};
+use uniswapv4_challenge_miner::salts::parse_range;
+use uniswapv4_challenge_miner::score::ScorerSpec;
+use uniswapv4_challenge_miner::{decode_hex, rpc};
+
+// A line of a --targets file, less its name.
+#[derive(Parser)]
+struct TargetLine {
+    #[command(flatten)]
+    pattern: PatternArgs,
+}
+
+fn round_trips<const N: usize>(input: &str) {
+    if let Ok(bytes) = decode_hex::<N>(input) {
+        assert_eq!(decode_hex::<N>(&rpc::to_hex(&bytes)).unwrap(), bytes);
+    }
+}
+
+fuzz_target!(|input: &str| {
+    round_trips::<20>(input);
+    round_trips::<32>(input);
+
+    if let Ok((start, end)) = parse_range(input) {
+        assert!(end.is_none_or(|end| end > start));
+    }
+    if let Ok(flags) = pattern::parse_hook_flags(input) {
+        assert!(flags < 1 << 14);
+    }
+    let _ = Target::from_str(input);
+    if let Ok(spec) = ScorerSpec::from_str(input) {
+        assert_eq!(ScorerSpec::from_str(&spec.to_string()).unwrap(), spec);
+    }
+
+    let args = std::iter::once("target").chain(input.split_whitespace());
+    if let Ok(line) = TargetLine::try_parse_from(args) {
+        if let Some(pattern) = line.pattern.pattern() {
+            pattern.matches(&[0x44; 20]);
+        }
+    }
+});
@@ -0,0 +1,46 @@
+// compute_score against a slow, literal reading of the challenge's rules on
+// the lowercase hex address, and compute_score_at_least against both.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use uniswapv4_challenge_miner::{compute_score, compute_score_at_least, explain_score};
+
+fn reference_score(address: &[u8; 20]) -> u32 {
+    let hex: Vec<char> = address.iter().flat_map(|b| format!("{:02x}", b).chars().collect::<Vec<_>>()).collect();
+    let zeros = hex.iter().take_while(|c| **c == '0').count();
+    if zeros < 40 && hex[zeros] != '4' {
+        return 0;
+    }
+    let run = hex[zeros..].iter().take_while(|c| **c == '4').count();
+    let mut score = 10 * zeros as u32;
+    if run >= 4 {
+        score += 40;
+    }
+    // The contract counts the run up to four and checks the count once it ends.
+    if run == 4 {
+        score += 20;
+    }
+    // The contract looks at the second and third digits from the end.
+    if hex[37] == '4' && hex[38] == '4' {
+        score += 20;
+    }
+    score + hex.iter().filter(|c| **c == '4').count() as u32
+}
+
+fuzz_target!(|input: ([u8; 20], u32)| {
+    let (address, min_score) = input;
+    let score = compute_score(&address);
+    assert_eq!(score, reference_score(&address), "0x{}", hex_string(&address));
+    assert_eq!(score, explain_score(&address).total());
+    let at_least = compute_score_at_least(&address, min_score);
+    if score >= min_score {
+        assert_eq!(at_least, score);
+    } else {
+        assert!(at_least < min_score);
+    }
+});
+
+fn hex_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -0,0 +1,128 @@
+// Limits on what submissions may cost, so automated re-submission can never
+// spend more than intended.
+
+use clap::Args;
+
+use crate::challenge;
+use crate::decode_hex;
+use crate::rpc::RpcClient;
+
+const WEI_PER_ETH: f64 = 1e18;
+// Chainlink ETH / USD aggregator on mainnet.
+const ETH_USD_FEED_HEX: &str = "5f4eC3Df9cbd43714FE2740f5E3616155c5b8419";
+// Oldest price the feed may give, in seconds: its heartbeat is an hour, so
+// anything much older means the feed has stopped updating.
+pub const MAX_PRICE_AGE: u64 = 2 * 60 * 60;
+
+/// Cost limits for submission transactions
+#[derive(Args, Debug, Clone)]
+pub struct Budget {
+    /// Most a single submission may cost in ETH (gas limit times max fee)
+    #[arg(long, value_name = "ETH")]
+    pub max_cost_eth: Option<f64>,
+
+    /// Most a single submission may cost in USD
+    #[arg(long, value_name = "USD")]
+    pub max_cost_usd: Option<f64>,
+
+    /// Most all submissions of this run may cost together, in ETH
+    #[arg(long, value_name = "ETH")]
+    pub total_budget_eth: Option<f64>,
+
+    /// Most all submissions of this run may cost together, in USD
+    #[arg(long, value_name = "USD")]
+    pub total_budget_usd: Option<f64>,
+
+    /// Only warn when a limit is exceeded instead of refusing to send
+    #[arg(long)]
+    pub warn_over_budget: bool,
+
+    /// ETH price in USD (default: read from the Chainlink feed)
+    #[arg(long, value_name = "USD")]
+    pub eth_usd: Option<f64>,
+
+    /// Chainlink ETH / USD aggregator to read the price from
+    #[arg(long, value_name = "ADDRESS", default_value = ETH_USD_FEED_HEX, value_parser = decode_hex::<20>)]
+    pub price_feed: [u8; 20],
+}
+
+pub fn format_eth(wei: u128) -> String {
+    format!("{:.6} ETH", wei as f64 / WEI_PER_ETH)
+}
+
+// `latestRoundData()` of a Chainlink aggregator with 8 decimals.
+fn chainlink_price(rpc: &RpcClient, feed: &[u8; 20]) -> Result<f64, String> {
+    let output = rpc.call(feed, &challenge::selector("latestRoundData()"))?;
+    parse_round(&output, rpc.latest_block()?.timestamp)
+}
+
+// The price in what `latestRoundData()` returned, (roundId, answer, startedAt,
+// updatedAt, answeredInRound), refusing a round that is stale at `now` or an
+// answer that is not positive.
+pub fn parse_round(output: &[u8], now: u64) -> Result<f64, String> {
+    if output.len() < 5 * 32 {
+        return Err(format!("latestRoundData() returned {} bytes", output.len()));
+    }
+    let word = |i: usize| &output[32 * i..32 * (i + 1)];
+    // Every field fits in 128 bits, but a negative or implausibly large answer.
+    let low = |i: usize| u128::from_be_bytes(word(i)[16..].try_into().unwrap());
+    if word(1)[..16].iter().any(|b| *b != 0) || low(1) == 0 {
+        return Err("implausible ETH / USD answer".to_string());
+    }
+    let (round, answer, updated_at, answered_in) = (low(0), low(1), low(3), low(4));
+    if answered_in < round {
+        return Err("the ETH / USD answer is carried over from an earlier round".to_string());
+    }
+    match (now as u128).checked_sub(updated_at) {
+        _ if updated_at == 0 => Err("the ETH / USD round was never updated".to_string()),
+        Some(age) if age > MAX_PRICE_AGE as u128 => Err(format!("the ETH / USD answer is {}s old", age)),
+        // Updated in a block after `now`, say on a node further ahead.
+        _ => Ok(answer as f64 / 1e8),
+    }
+}
+
+impl Budget {
+    fn uses_usd(&self) -> bool {
+        self.max_cost_usd.is_some() || self.total_budget_usd.is_some()
+    }
+
+    fn eth_price(&self, rpc: &RpcClient) -> Result<f64, String> {
+        match self.eth_usd {
+            Some(price) => Ok(price),
+            None => chainlink_price(rpc, &self.price_feed).map_err(|e| format!("cannot read ETH price (pass --eth-usd): {}", e)),
+        }
+    }
+
+    // Check a transaction that may cost up to `cost` wei on top of `spent` wei
+    // already committed, printing the cost. Errors when over a limit, unless
+    // only warning was asked for.
+    pub fn check(&self, rpc: &RpcClient, cost: u128, spent: u128) -> Result<(), String> {
+        let price = if self.uses_usd() { Some(self.eth_price(rpc)?) } else { None };
+        let eth = |wei: u128| wei as f64 / WEI_PER_ETH;
+        let usd = |wei: u128| price.map(|price| eth(wei) * price);
+        match usd(cost) {
+            Some(usd) => println!("Submission costs at most {} (${:.2})", format_eth(cost), usd),
+            None => println!("Submission costs at most {}", format_eth(cost)),
+        }
+
+        let total = cost + spent;
+        let limits = [
+            (self.max_cost_eth, Some(eth(cost)), "submission could cost", "ETH", "--max-cost-eth"),
+            (self.max_cost_usd, usd(cost), "submission could cost", "USD", "--max-cost-usd"),
+            (self.total_budget_eth, Some(eth(total)), "submissions could cost a total of", "ETH", "--total-budget-eth"),
+            (self.total_budget_usd, usd(total), "submissions could cost a total of", "USD", "--total-budget-usd"),
+        ];
+        for (limit, value, what, unit, flag) in limits {
+            if let (Some(limit), Some(value)) = (limit, value) {
+                if value > limit {
+                    let message = format!("{} {:.6} {}, above {} {}", what, value, unit, flag, limit);
+                    if !self.warn_over_budget {
+                        return Err(message);
+                    }
+                    eprintln!("Warning: {}", message);
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,150 @@
+// Calls against the UniswapV4DeployerCompetition contract, which is also the
+// CREATE2 deployer whose addresses are being mined.
+// https://github.com/Uniswap/v4-periphery/blob/main/src/UniswapV4DeployerCompetition.sol
+
+use crate::keccak256;
+use crate::rpc::RpcClient;
+
+pub fn selector(signature: &str) -> [u8; 4] {
+    let hash = keccak256(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+// Calldata for `updateBestAddress(bytes32 salt)`.
+pub fn update_best_address_calldata(salt: &[u8; 32]) -> Vec<u8> {
+    let mut data = selector("updateBestAddress(bytes32)").to_vec();
+    data.extend_from_slice(salt);
+    data
+}
+
+// The contract only accepts salts whose first 20 bytes are the sender or zero.
+pub fn salt_accepts_sender(salt: &[u8; 32], sender: &[u8; 20]) -> bool {
+    salt[..20] == sender[..] || salt[..20] == [0; 20]
+}
+
+fn call_word(rpc: &RpcClient, contract: &[u8; 20], signature: &str) -> Result<[u8; 32], String> {
+    let output = rpc.call(contract, &selector(signature))?;
+    output
+        .get(..32)
+        .and_then(|word| word.try_into().ok())
+        .ok_or_else(|| format!("{} returned {} bytes", signature, output.len()))
+}
+
+// Unix timestamp after which `updateBestAddress` reverts.
+pub fn competition_deadline(rpc: &RpcClient, contract: &[u8; 20]) -> Result<u64, String> {
+    let word = call_word(rpc, contract, "competitionDeadline()")?;
+    if word[..24] != [0; 24] {
+        return Err("competitionDeadline() does not fit in u64".to_string());
+    }
+    Ok(u64::from_be_bytes(word[24..].try_into().unwrap()))
+}
+
+fn call_address(rpc: &RpcClient, contract: &[u8; 20], signature: &str) -> Result<[u8; 20], String> {
+    let word = call_word(rpc, contract, signature)?;
+    Ok(word[12..].try_into().unwrap())
+}
+
+// Address the current best salt deploys to.
+pub fn best_address(rpc: &RpcClient, contract: &[u8; 20]) -> Result<[u8; 20], String> {
+    call_address(rpc, contract, "bestAddress()")
+}
+
+pub fn best_address_submitter(rpc: &RpcClient, contract: &[u8; 20]) -> Result<[u8; 20], String> {
+    call_address(rpc, contract, "bestAddressSubmitter()")
+}
+
+pub fn best_address_salt(rpc: &RpcClient, contract: &[u8; 20]) -> Result<[u8; 32], String> {
+    call_word(rpc, contract, "bestAddressSalt()")
+}
+
+pub fn init_code_hash(rpc: &RpcClient, contract: &[u8; 20]) -> Result<[u8; 32], String> {
+    call_word(rpc, contract, "initCodeHash()")
+}
+
+// Outcome of simulating `updateBestAddress(salt)` from a given sender.
+#[derive(Debug)]
+pub enum Simulation {
+    Accepted,
+    // The contract's own CREATE2 result for the salt and its score.
+    WorseAddress { address: [u8; 20], score: u32 },
+    InvalidSender,
+    CompetitionOver,
+    Reverted(Vec<u8>),
+}
+
+// Argument `index` of ABI-encoded error data (after the 4-byte selector).
+fn error_word(data: &[u8], index: usize) -> Option<&[u8]> {
+    data.get(4 + 32 * index..4 + 32 * (index + 1))
+}
+
+fn word_to_u32(word: &[u8]) -> Option<u32> {
+    if word[..28].iter().any(|b| *b != 0) {
+        return None;
+    }
+    Some(u32::from_be_bytes(word[28..].try_into().unwrap()))
+}
+
+pub fn simulate_update(rpc: &RpcClient, contract: &[u8; 20], sender: &[u8; 20], salt: &[u8; 32]) -> Result<Simulation, String> {
+    let revert = match rpc.call_from(sender, contract, &update_best_address_calldata(salt))? {
+        Ok(_) => return Ok(Simulation::Accepted),
+        Err(revert) => revert,
+    };
+    let error_selector = revert.get(..4).unwrap_or_default();
+    Ok(if error_selector == selector("WorseAddress(address,address,uint256,uint256)") {
+        let decoded = (|| {
+            let address = error_word(&revert, 0)?[12..].try_into().ok()?;
+            let score = word_to_u32(error_word(&revert, 2)?)?;
+            Some(Simulation::WorseAddress { address, score })
+        })();
+        decoded.unwrap_or(Simulation::Reverted(revert))
+    } else if error_selector == selector("InvalidSender(bytes32,address)") {
+        Simulation::InvalidSender
+    } else if error_selector == selector("CompetitionOver(uint256,uint256)") {
+        Simulation::CompetitionOver
+    } else {
+        Simulation::Reverted(revert)
+    })
+}
+
+// Make sure salts mined for `submitter` against `code_hash` can actually be
+// submitted, before spending hours on them.
+pub fn check_submitter(rpc: &RpcClient, contract: &[u8; 20], submitter: &[u8; 20], code_hash: &[u8; 32]) -> Result<(), String> {
+    if rpc.get_code(contract)?.is_empty() {
+        return Err(format!("no contract deployed at 0x{}", hex::encode(contract)));
+    }
+    let on_chain_hash = init_code_hash(rpc, contract)?;
+    if on_chain_hash != *code_hash {
+        return Err(format!(
+            "contract expects initcode hash 0x{} but the miner uses 0x{}",
+            hex::encode(on_chain_hash),
+            hex::encode(code_hash)
+        ));
+    }
+
+    // A salt bound to the submitter with an all-zero tail: the contract checks
+    // the sender before the score, so anything but InvalidSender means the
+    // submitter is accepted.
+    let mut salt = [0; 32];
+    salt[..20].copy_from_slice(submitter);
+    match simulate_update(rpc, contract, submitter, &salt)? {
+        Simulation::InvalidSender => Err(format!(
+            "the contract rejects salts bound to 0x{} when sent from that address",
+            hex::encode(submitter)
+        )),
+        Simulation::CompetitionOver => Err("the competition is over".to_string()),
+        Simulation::Reverted(data) => Err(format!("unexpected revert while simulating a submission: 0x{}", hex::encode(data))),
+        Simulation::Accepted | Simulation::WorseAddress { .. } => Ok(()),
+    }
+}
+
+// `score(address) returns (uint256)` on a contract exposing VanityAddressLib.score.
+pub fn library_score(rpc: &RpcClient, scorer: &[u8; 20], address: &[u8; 20]) -> Result<u32, String> {
+    let mut data = selector("score(address)").to_vec();
+    data.extend_from_slice(&[0; 12]);
+    data.extend_from_slice(address);
+    let output = rpc.call(scorer, &data)?;
+    output
+        .get(..32)
+        .and_then(word_to_u32)
+        .ok_or_else(|| format!("score(address) returned 0x{}", hex::encode(&output)))
+}
@@ -0,0 +1,119 @@
+// Checkpoints of a run, so a crash, a reboot or a restart carries on with the
+// search instead of starting it over. Every --checkpoint-interval and at the
+// end, the checkpoint file gets where the salts stand, the counters covered,
+// the best and --top results and the work done so far. A run given a
+// checkpoint file that exists picks up from it, unless --fresh, and only when
+// it is of the same job.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use alloy_primitives::B256;
+use clap::Args;
+use serde::{Deserialize, Serialize};
+
+use crate::salts::SaltGenerator;
+use crate::schema::{self, Checkpoint, CounterRange, Version};
+use crate::search::Control;
+use crate::top::Top;
+use crate::{persist, runtime, Candidate};
+
+#[derive(Args, Debug, Clone)]
+pub struct CheckpointArgs {
+    /// Checkpoint the search to this file, and carry on from it when it exists
+    #[arg(long, value_name = "PATH")]
+    pub checkpoint: Option<PathBuf>,
+
+    /// How often to write the checkpoint (e.g. 1m)
+    #[arg(long, default_value = "1m", value_parser = humantime::parse_duration, requires = "checkpoint")]
+    pub checkpoint_interval: Duration,
+
+    /// Fail unless there is a checkpoint to carry on from
+    #[arg(long, requires = "checkpoint", conflicts_with = "fresh")]
+    pub resume: bool,
+
+    /// Start the search over, replacing the checkpoint
+    #[arg(long, requires = "checkpoint")]
+    pub fresh: bool,
+}
+
+// The checkpoint file: the library's checkpoint and the job it is of.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Saved {
+    pub job: B256,
+    #[serde(flatten)]
+    pub checkpoint: Checkpoint,
+}
+
+impl CheckpointArgs {
+    // The checkpoint to carry on from, if any.
+    pub fn load(&self) -> Result<Option<Saved>, String> {
+        let Some(path) = self.checkpoint.as_ref().filter(|_| !self.fresh) else { return Ok(None) };
+        match std::fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json).map(Some).map_err(|e| format!("cannot read the checkpoint {}: {}", path.display(), e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound && !self.resume => Ok(None),
+            Err(e) => Err(format!("cannot read the checkpoint {}: {}", path.display(), e)),
+        }
+    }
+}
+
+// Check `saved` is of `job` before carrying on from it.
+pub fn check_job(saved: &Saved, job: B256, path: &Path) -> Result<(), String> {
+    if saved.job != job {
+        return Err(format!("{} is a checkpoint of another job, pass --fresh to start over or another --checkpoint", path.display()));
+    }
+    Ok(())
+}
+
+// Everything a checkpoint is taken of.
+pub struct Writer {
+    pub path: PathBuf,
+    pub job: B256,
+    pub salts: Arc<dyn SaltGenerator>,
+    pub control: Arc<Control>,
+    pub best: Arc<Mutex<Option<Candidate>>>,
+    pub top: Option<Arc<Top>>,
+    // What the runs before this one did.
+    pub covered: Vec<CounterRange>,
+    pub hashes: u64,
+    pub elapsed: Duration,
+    pub started: Instant,
+}
+
+impl Writer {
+    pub fn snapshot(&self) -> Saved {
+        let taken = self.control.taken.lock().unwrap_or_else(PoisonError::into_inner).clone();
+        let salts = self.salts.position(&taken);
+        let mut covered = self.covered.clone();
+        schema::extend_covered(&mut covered, salts.as_ref());
+        let peppers = schema::pepper_ranges(salts.as_ref());
+        let checkpoint = Checkpoint {
+            version: Version,
+            salts,
+            covered,
+            peppers,
+            best: *self.best.lock().unwrap_or_else(PoisonError::into_inner),
+            top: self.top.as_ref().map_or_else(Vec::new, |top| top.entries()),
+            hashes: self.hashes + self.control.hashes.load(std::sync::atomic::Ordering::Relaxed),
+            elapsed: self.elapsed + self.started.elapsed(),
+        };
+        Saved { job: self.job, checkpoint }
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(&self.snapshot()).unwrap();
+        persist::write(&self.path, json + "\n").map_err(|e| format!("cannot write the checkpoint {}: {}", self.path.display(), e))
+    }
+}
+
+// Write the checkpoint every `interval` until shutdown.
+pub fn spawn(writer: Arc<Writer>, interval: Duration) {
+    runtime::spawn_blocking("checkpoint", move |mut shutdown| {
+        while shutdown.sleep_blocking(interval) {
+            if let Err(e) = writer.save() {
+                eprintln!("Error: {}", e);
+            }
+        }
+    });
+}
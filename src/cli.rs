@@ -0,0 +1,1094 @@
+// The command line: its options and subcommands, and mine(), which wires a
+// search up to the chain, the output and the services around it. The binary
+// only calls run().
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use clap::{Parser, Subcommand};
+use crate::{
+    budget, challenge, checkpoint, codehash, color, commands, daemon, deadline, energy, eoa, estimate, events, factory, gas, heartbeat, histogram, hooks, inhibit, initcode, logfile, mode, monitor,
+    network, odds, pattern, prove, provenance, relay, report, resubmit, results, rpc, runtime, salts, schedule, score, search, selftest, service, shared, snapshot, submit, timeline, top, track, verify, wallet,
+};
+use crate::error::{exit_code, MinerError};
+#[cfg(feature = "sqlite")]
+use crate::{db, schema};
+#[cfg(target_os = "linux")]
+use crate::systemd;
+#[cfg(feature = "tui")]
+use crate::tui;
+use crate::{decode_hex, explain_score, Candidate, CREATE3_PROXY_HASH, SUBMITTER_ADDRESS_HEX};
+
+/// Miner for the Uniswap v4 address challenge
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None, args_conflicts_with_subcommands = true)]
+struct Args {
+    /// Number of threads to use (0 for all)
+    #[arg(short, long, required_unless_present = "service")]
+    threads: Option<usize>,
+
+    /// Scoring rules to mine for: uniswap-v4, profile:<preset[@version] or path>
+    /// for a JSON scoring profile, script:<path> for a Rhai script
+    /// (needs the `script` feature) or wasm:<path> for a WebAssembly module
+    /// (needs the `wasm` feature)
+    #[arg(long, env = "SCORER", default_value = "uniswap-v4", conflicts_with = "pattern")]
+    scorer: score::ScorerSpec,
+
+    /// Maximize this instead of a score
+    #[arg(long, value_enum, value_name = "OBJECTIVE", conflicts_with_all = ["scorer", "pattern"])]
+    maximize: Option<score::Objective>,
+
+    /// Only consider addresses starting with at least this many zero nibbles,
+    /// whatever their score
+    #[arg(long, value_name = "N", default_value_t = 0, conflicts_with = "pattern")]
+    require_leading_zero_nibbles: u32,
+
+    /// Print how the challenge's rules add up to the score of every result
+    #[arg(long, conflicts_with_all = ["maximize", "pattern"])]
+    explain: bool,
+
+    /// Rules deciding between candidates with equal scores, applied in order
+    #[arg(long, value_enum, value_name = "RULE", value_delimiter = ',')]
+    tiebreak: Vec<score::Tiebreak>,
+
+    /// Also report the best address for these objectives as they improve
+    #[arg(long, value_enum, value_name = "OBJECTIVE", value_delimiter = ',')]
+    track: Vec<score::Objective>,
+
+    /// Count every score computed and write the counts, with the numbers expected
+    /// in theory, to this JSON file every --odds-interval
+    #[arg(long, value_name = "PATH", conflicts_with = "pattern")]
+    histogram: Option<PathBuf>,
+
+    /// Keep the best N results, not just the best one, and list them on exit
+    #[arg(long, value_name = "N", conflicts_with = "pattern", value_parser = clap::value_parser!(u64).range(1..))]
+    top: Option<u64>,
+
+    /// Write the --top results to this JSON file whenever they change
+    #[arg(long, value_name = "PATH", requires = "top")]
+    top_file: Option<PathBuf>,
+
+    /// Write a report of the run into this directory when it ends, as
+    /// report-<time>.json and .txt
+    #[arg(long, value_name = "DIR")]
+    report_dir: Option<PathBuf>,
+
+    /// Append every candidate scoring at least --min-score, best or not, to
+    /// this JSON Lines file
+    #[arg(long, value_name = "PATH", conflicts_with = "pattern", group = "candidate_output")]
+    candidate_log: Option<PathBuf>,
+
+    /// Append the same candidates to this CSV file
+    #[arg(long, value_name = "PATH", conflicts_with = "pattern", group = "candidate_output")]
+    csv: Option<PathBuf>,
+
+    /// Lowest score written to --candidate-log and --csv
+    #[arg(long, value_name = "N", default_value_t = 100, requires = "candidate_output")]
+    min_score: u32,
+
+    /// Append the hashes, hashrate and best score to this CSV file with every
+    /// status line
+    #[arg(long, value_name = "PATH")]
+    stats_csv: Option<PathBuf>,
+
+    /// Permission bits wanted by the hook-flags objective, e.g. BEFORE_SWAP|AFTER_SWAP
+    #[arg(long, value_name = "MASK", value_parser = pattern::parse_hook_flags)]
+    objective_hook_flags: Option<u16>,
+
+    #[command(flatten, next_help_heading = "Pattern matching")]
+    pattern: pattern::PatternArgs,
+
+    /// Address embedded in the first 20 bytes of every salt (the account that will submit)
+    #[arg(long, default_value = SUBMITTER_ADDRESS_HEX, value_parser = decode_hex::<20>)]
+    submitter: [u8; 20],
+
+    /// JSON-RPC endpoint; enables following the on-chain best score. Repeat
+    /// (or separate with commas) to fail over between several providers
+    #[arg(long, value_delimiter = ',')]
+    rpc_url: Vec<String>,
+
+    #[command(flatten)]
+    network: network::NetworkArgs,
+
+    /// Mine for a well-known CREATE2 factory instead of the challenge (needs
+    /// --init-code-hash; salts are bound to --submitter as the caller)
+    #[arg(long, value_enum, conflicts_with = "contract")]
+    factory: Option<factory::Factory>,
+
+    /// How salts become addresses
+    #[arg(long, value_enum, default_value_t = mode::Mode::Create2)]
+    mode: mode::Mode,
+
+    /// Caller the CREATE3 factory mixes into the salt (keccak256(caller ++ salt)),
+    /// for factories that namespace salts by sender
+    #[arg(long, value_name = "ADDRESS", value_parser = decode_hex::<20>)]
+    create3_caller: Option<[u8; 20]>,
+
+    /// Account deploying with --mode create (repeat or separate with commas)
+    #[arg(long, value_name = "ADDRESS", value_delimiter = ',', value_parser = decode_hex::<20>)]
+    sender: Vec<[u8; 20]>,
+
+    /// File of --mode create accounts, one address per line
+    #[arg(long, value_name = "PATH")]
+    senders_file: Option<PathBuf>,
+
+    /// Highest nonce searched with --mode create
+    #[arg(long, default_value_t = 1000)]
+    max_nonce: u64,
+
+    /// Directory --mode eoa saves found keys to, encrypted with the passphrase
+    /// from --password-env
+    #[arg(long, value_name = "PATH", default_value = "vanity-keys")]
+    key_dir: PathBuf,
+
+    /// How --mode create2 and create3 salts are generated
+    #[arg(long, value_enum, default_value_t = salts::Strategy::Random, conflicts_with = "salts_file")]
+    salts: salts::Strategy,
+
+    /// Counters searched by --salts sequential, START..END (END excluded) or
+    /// START.. to keep going
+    #[arg(long, value_name = "RANGE", default_value = "0..", value_parser = salts::parse_range)]
+    salt_range: (u64, Option<u64>),
+
+    /// Peppers --salts random draws from, START..END (END excluded), say the
+    /// share of a fleet given to this machine so no two machines overlap
+    #[arg(long, value_name = "RANGE", value_parser = salts::parse_pepper_range)]
+    pepper_range: Option<std::ops::Range<u64>>,
+
+    /// Share out the peppers of a fleet by the 32-byte hex master seed in this
+    /// file, the same on every machine, with --worker-index
+    #[arg(long, value_name = "PATH", requires = "worker_index", conflicts_with = "pepper_range")]
+    seed_file: Option<PathBuf>,
+
+    /// This machine's index in the fleet sharing --seed-file, from 0; each
+    /// index gets peppers of its own
+    #[arg(long, value_name = "N", requires = "seed_file")]
+    worker_index: Option<u16>,
+
+    /// Try the salts in this file, one 32-byte hex salt per line, used as given
+    #[arg(long, value_name = "PATH")]
+    salts_file: Option<PathBuf>,
+
+    /// Hash of the initcode being deployed (default: the network's, or read from the contract)
+    #[arg(long, value_parser = decode_hex::<32>)]
+    init_code_hash: Option<[u8; 32]>,
+
+    /// More initcode hashes to mine with the same salts, each reported with its
+    /// own best (repeat or separate with commas)
+    #[arg(long, value_name = "HASH", value_delimiter = ',', value_parser = decode_hex::<32>, conflicts_with = "pattern")]
+    extra_init_code_hash: Vec<[u8; 32]>,
+
+    /// Hash the initcode stored as the code of this contract instead of trusting a hash
+    #[arg(long, value_name = "ADDRESS", value_parser = decode_hex::<20>, conflicts_with = "initcode_tx")]
+    initcode_address: Option<[u8; 20]>,
+
+    /// Bytes to skip at the start of --initcode-address's code (1 for SSTORE2 contracts)
+    #[arg(long, default_value_t = 0, requires = "initcode_address")]
+    initcode_skip: usize,
+
+    /// Hash the initcode of this contract-creation transaction instead of trusting a hash
+    #[arg(long, value_name = "TX_HASH")]
+    initcode_tx: Option<String>,
+
+    /// WebSocket endpoint for following NewAddressFound events as they happen
+    #[arg(long)]
+    ws_url: Option<String>,
+
+    /// Cross-check the score of every reported candidate against the contract
+    #[arg(long)]
+    verify_scores: bool,
+
+    /// Contract exposing VanityAddressLib's `score(address)` to verify against
+    /// (default: decode scores from simulated submissions)
+    #[arg(long, requires = "verify_scores", value_parser = decode_hex::<20>)]
+    score_contract: Option<[u8; 20]>,
+
+    /// Score to beat: beat-chain follows the on-chain best (the default with
+    /// --rpc-url), a number reports only scores at least that high
+    #[arg(long, value_name = "TARGET")]
+    target: Option<monitor::Target>,
+
+    /// How often to poll the challenge contract (e.g. 30s)
+    #[arg(long, default_value = "30s", value_parser = humantime::parse_duration)]
+    watch_interval: Duration,
+
+    /// Submit the best salt automatically this long before the deadline (e.g. 10m)
+    #[arg(long, value_name = "DURATION", value_parser = humantime::parse_duration, requires = "key_source")]
+    auto_submit_before_deadline: Option<Duration>,
+
+    /// Submit every new best right away whenever someone else holds the lead on-chain
+    #[arg(long, requires = "key_source")]
+    auto_resubmit: bool,
+
+    /// Submit the best salt as soon as the chance of improving on it before the
+    /// deadline drops below this many percent
+    #[arg(long, value_name = "PERCENT", requires = "key_source")]
+    secure_below: Option<f64>,
+
+    /// How often to print the chance of improving on the best before the deadline
+    #[arg(long, default_value = "5m", value_parser = humantime::parse_duration)]
+    odds_interval: Duration,
+
+    /// How often to print a status line with the hashrate and the best so far
+    /// (0s for never)
+    #[arg(long, default_value = "1m", value_parser = humantime::parse_duration)]
+    status_interval: Duration,
+
+    /// Maximum number of submissions made by --auto-resubmit
+    #[arg(long, default_value_t = 3, requires = "auto_resubmit")]
+    max_submissions: u32,
+
+    #[command(flatten, next_help_heading = "Auto-submit signer")]
+    wallet: wallet::WalletArgs,
+
+    #[command(flatten, next_help_heading = "Auto-submit fees")]
+    gas: gas::GasStrategy,
+
+    #[command(flatten, next_help_heading = "Auto-submit fees")]
+    budget: budget::Budget,
+
+    #[command(flatten, next_help_heading = "Auto-submit signer")]
+    relay: relay::RelayArgs,
+
+    /// Gas limit for the auto-submit transaction (default: estimate plus 20%)
+    #[arg(long)]
+    gas_limit: Option<u64>,
+
+    /// Record this run, its new bests and its status lines in this SQLite
+    /// database, read back with `history` (needs the `sqlite` feature)
+    #[arg(long, value_name = "PATH")]
+    db: Option<PathBuf>,
+
+    /// Show a live dashboard instead of the stream of status lines, q to stop
+    /// (needs the `tui` feature)
+    #[arg(long)]
+    tui: bool,
+
+    /// Run as a Windows service: install registers this command line to start
+    /// on boot, uninstall removes it, and the service manager starts it with run
+    #[arg(long, value_enum, value_name = "ACTION")]
+    service: Option<service::Action>,
+
+    /// How long the workers get to finish their batches once stopped, before
+    /// the miner exits without them (e.g. 30s)
+    #[arg(long, default_value = "30s", value_parser = humantime::parse_duration)]
+    stop_timeout: Duration,
+
+    /// Keep the system from going to sleep while the search runs, unless it
+    /// is paused (Linux, macOS and Windows)
+    #[arg(long)]
+    keep_awake: bool,
+
+    /// Only mine within this window of the week, in local time: DAYS,
+    /// HH:MM-HH:MM or both, e.g. "mon-fri 22:00-07:00" (repeat for more)
+    #[arg(long, value_name = "WINDOW")]
+    schedule: Vec<schedule::Window>,
+
+    /// Share the best with the other miners of the same job on this machine,
+    /// say one per NUMA node, so only scores beating all of them are reported
+    #[arg(long)]
+    share_best: bool,
+
+    /// The file --share-best miners share (default: one per job in the
+    /// temporary directory)
+    #[arg(long, value_name = "PATH", requires = "share_best")]
+    share_file: Option<PathBuf>,
+
+    /// Keep mining in the background, with a PID file and a control socket
+    /// for `status` and `stop` (unix)
+    #[arg(long, conflicts_with_all = ["tui", "service"])]
+    daemon: bool,
+
+    /// Where the output of a --daemon miner goes
+    #[arg(long, value_name = "PATH", default_value = concat!(env!("CARGO_PKG_NAME"), ".log"))]
+    daemon_log: PathBuf,
+
+    #[command(flatten)]
+    instance: daemon::Instance,
+
+    #[command(flatten, next_help_heading = "Energy")]
+    energy: energy::EnergyArgs,
+
+    #[command(flatten, next_help_heading = "Checkpoints")]
+    checkpoint: checkpoint::CheckpointArgs,
+
+    #[command(flatten, next_help_heading = "Logging")]
+    log: logfile::LogArgs,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    Submit(Box<submit::SubmitArgs>),
+    Prove(Box<prove::ProveArgs>),
+    Estimate(estimate::EstimateArgs),
+    #[cfg(feature = "sqlite")]
+    History(db::HistoryArgs),
+    /// Show how a --daemon miner is doing
+    Status(daemon::Instance),
+    /// Stop a --daemon miner, letting the run wrap up, and wait for it
+    Stop(daemon::Instance),
+}
+
+// Report `error` and exit with its code, telling a bad invocation apart from
+// a failure while running.
+fn fail(error: MinerError) -> ! {
+    eprintln!("Error: {}", error);
+    report::exit(&format!("error: {}", error));
+    runtime::exit(error.exit_code());
+}
+
+fn exit_with_error(message: &str) -> ! {
+    fail(MinerError::Config(message.to_string()))
+}
+
+fn runtime_error(message: &str) -> ! {
+    fail(MinerError::Runtime(message.to_string()))
+}
+
+// Salt with only the submitter bytes filled in, as mined by the workers.
+fn salt_prefix(submitter: &[u8; 20]) -> [u8; 32] {
+    let mut salt = [0; 32];
+    salt[..20].copy_from_slice(submitter);
+    salt
+}
+
+// Salts from a --salts-file, read as they are needed. Lines that are not a
+// salt are reported and skipped.
+fn read_salts(path: &std::path::Path) -> Result<impl Iterator<Item = [u8; 32]>, String> {
+    let file = std::fs::File::open(path).map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+    let path = path.to_path_buf();
+    let lines = std::io::BufRead::lines(std::io::BufReader::new(file));
+    let error_path = path.clone();
+    let lines = lines.map_while(move |line| line.map_err(|e| eprintln!("Cannot read {}: {}", error_path.display(), e)).ok());
+    Ok(lines.enumerate().filter_map(move |(i, line)| {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        decode_hex::<32>(line).map_err(|e| eprintln!("Skipping line {} of {}: {}", i + 1, path.display(), e)).ok()
+    }))
+}
+
+// Run the command line, mining or running a subcommand as asked.
+pub fn run() {
+    // A bad invocation is a configuration error, not clap's usual 2.
+    let mut args = Args::try_parse().unwrap_or_else(|e| {
+        e.print().ok();
+        std::process::exit(if e.use_stderr() { exit_code::CONFIG } else { 0 });
+    });
+    if let Some(command) = args.command {
+        let result = match command {
+            Command::Submit(submit_args) => submit::run(*submit_args),
+            Command::Prove(prove_args) => prove::run(*prove_args),
+            Command::Estimate(estimate_args) => estimate::run(estimate_args),
+            #[cfg(feature = "sqlite")]
+            Command::History(history_args) => db::run(history_args),
+            #[cfg(unix)]
+            Command::Status(instance) => daemon::status(&instance),
+            #[cfg(unix)]
+            Command::Stop(instance) => daemon::stop(&instance),
+            #[cfg(not(unix))]
+            Command::Status(_) | Command::Stop(_) => {
+                exit_with_error("--daemon is only supported on unix, see --service install on Windows")
+            }
+        };
+        if let Err(e) = result {
+            runtime_error(&e);
+        }
+        return;
+    }
+    if args.daemon && !daemon::is_daemon() {
+        #[cfg(unix)]
+        if let Err(e) = daemon::start(&args.instance, &args.daemon_log, std::env::args_os().skip(1)) {
+            runtime_error(&e);
+        }
+        #[cfg(not(unix))]
+        exit_with_error("--daemon is only supported on unix, see --service install on Windows");
+        return;
+    }
+    match args.service.take() {
+        None => mine(args),
+        #[cfg(windows)]
+        Some(action) => {
+            let result = match action {
+                service::Action::Install => service::install(),
+                service::Action::Uninstall => service::uninstall(),
+                service::Action::Run => service::run(move || mine(args)),
+            };
+            if let Err(e) = result {
+                runtime_error(&e);
+            }
+        }
+        #[cfg(not(windows))]
+        Some(_) => exit_with_error("--service is only supported on Windows"),
+    }
+}
+
+fn mine(mut args: Args) {
+    let max_threads = num_cpus::get();
+    let num_threads = match args.threads.unwrap_or(0) {
+        0 => max_threads,
+        n => n,
+    };
+
+    let deployer = match args.factory {
+        Some(factory) => factory.address(),
+        None => args.network.contract().unwrap_or_else(|e| exit_with_error(&e)),
+    };
+    let rpc_urls = args.network.rpc_urls(std::mem::take(&mut args.rpc_url));
+    let rpc = (!rpc_urls.is_empty()).then(|| rpc::RpcClient::new(&rpc_urls));
+    check_options(&args, rpc.is_some(), num_threads);
+    let initcode_source = match (args.initcode_address, args.initcode_tx.take()) {
+        (Some(address), _) => Some(initcode::InitcodeSource::Code { address, skip: args.initcode_skip }),
+        (None, Some(tx)) => Some(initcode::InitcodeSource::CreationTx(tx)),
+        (None, None) => None,
+    };
+    if args.mode == mode::Mode::Eoa {
+        eoa::init(args.key_dir.clone(), &args.wallet.password_env).unwrap_or_else(|e| exit_with_error(&e));
+    }
+    let mut senders = std::mem::take(&mut args.sender);
+    if let Some(path) = &args.senders_file {
+        let file = std::fs::read_to_string(path)
+            .unwrap_or_else(|e| exit_with_error(&format!("cannot read {}: {}", path.display(), e)));
+        for line in file.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+            senders.push(decode_hex::<20>(line).unwrap_or_else(|e| exit_with_error(&format!("{}: {:?}: {}", path.display(), line, e))));
+        }
+    }
+    if (args.mode == mode::Mode::Create) == senders.is_empty() {
+        exit_with_error("--mode create searches the nonces of --sender or --senders-file accounts, and only it uses them");
+    }
+    // For the job hash results are stamped with.
+    let job_senders: Vec<String> = senders.iter().map(|sender| format!("0x{}", hex::encode(sender))).collect();
+    let saved = args.checkpoint.load().unwrap_or_else(|e| exit_with_error(&e));
+    // Salts from a file are tried from the top again.
+    let position = saved.as_ref().and_then(|saved| saved.checkpoint.salts.clone());
+    let salts = salt_generator(&args, senders, position);
+    let expected_hash = args.init_code_hash.or(args.network.network.config().init_code_hash);
+    let code_hash = match (initcode_source, expected_hash, &rpc) {
+        _ if args.mode == mode::Mode::Create3 => CREATE3_PROXY_HASH,
+        // Not used by CREATE or keys.
+        _ if matches!(args.mode, mode::Mode::Create | mode::Mode::Eoa) => [0; 32],
+        (Some(source), _, Some(rpc)) => initcode::verified_hash(rpc, &source, expected_hash)
+            .unwrap_or_else(|e| runtime_error(&format!("{}, refusing to mine", e))),
+        (_, Some(hash), _) => hash,
+        (_, None, Some(rpc)) => challenge::init_code_hash(rpc, &deployer)
+            .unwrap_or_else(|e| runtime_error(&format!("cannot read initCodeHash(): {}", e))),
+        (_, None, None) => exit_with_error(&format!("no known initcode hash on {}, pass --init-code-hash", args.network.network)),
+    };
+    // Following the challenge compares against on-chain scores.
+    if rpc.is_some() && args.scorer != score::ScorerSpec::UniswapV4 {
+        exit_with_error(&format!("--scorer {} cannot follow the challenge, drop --rpc-url", args.scorer));
+    }
+    if rpc.is_some() && !matches!(args.maximize, None | Some(score::Objective::ChallengeScore)) {
+        exit_with_error("--maximize cannot follow the challenge, drop --rpc-url");
+    }
+    let pattern = pattern::Targets::from_args(&args.pattern)
+        .unwrap_or_else(|e| exit_with_error(&e));
+    if rpc.is_some() && pattern.is_some() {
+        exit_with_error("pattern matching cannot follow the challenge, drop --rpc-url");
+    }
+    let scorer: Box<dyn score::Scorer> = match args.maximize {
+        Some(objective) => objective.scorer(args.objective_hook_flags).unwrap_or_else(|e| fail(e)),
+        None => args.scorer.build().unwrap_or_else(|e| fail(e)),
+    };
+    let objective = args.maximize;
+    // Rarity is only known for the challenge's own scoring.
+    let challenge_scoring = args.scorer == score::ScorerSpec::UniswapV4;
+    if args.explain && !challenge_scoring {
+        exit_with_error(&format!("--explain only knows the challenge's rules, not --scorer {}", args.scorer));
+    }
+    let explain = args.explain;
+    // The dashboard shows captured lines as they are, escape codes and all.
+    color::init(challenge_scoring && matches!(objective, None | Some(score::Objective::ChallengeScore)) && pattern.is_none() && !args.tui);
+    let tracker = track::Tracker::new(&args.track, args.objective_hook_flags).unwrap_or_else(|e| fail(e));
+    let tracker = (!tracker.is_empty()).then_some(tracker);
+    let extra_hashes = codehash::ExtraHashes::new(&args.extra_init_code_hash, args.require_leading_zero_nibbles);
+    let extra_hashes = (!extra_hashes.is_empty()).then_some(extra_hashes);
+    let top = args.top.map(|size| Arc::new(top::Top::new(size as usize, args.top_file.clone(), explain)));
+    let candidate_logs: Vec<results::CandidateLog> = [(&args.candidate_log, results::Format::JsonLines), (&args.csv, results::Format::Csv)]
+        .into_iter()
+        .filter_map(|(path, format)| Some(results::CandidateLog::open(path.as_deref()?, format).unwrap_or_else(|e| exit_with_error(&e))))
+        .collect();
+    let histogram = args.histogram.clone().map(|path| {
+        let theory = challenge_scoring && args.require_leading_zero_nibbles == 0 && matches!(objective, None | Some(score::Objective::ChallengeScore));
+        let histogram = Arc::new(histogram::Histogram::new(theory));
+        histogram::spawn(path, args.odds_interval, Arc::clone(&histogram));
+        histogram
+    });
+    let submitter = args.submitter;
+    if submitter == [0; 20] {
+        eprintln!(
+            "WARNING: salts mined for the zero address can be submitted by anyone. Whoever sees \
+             your transaction in the mempool can front-run it and take the lead with your salt."
+        );
+    }
+    if let Some(factory) = args.factory {
+        if rpc.is_some() {
+            exit_with_error("--factory mines outside the challenge, drop --rpc-url");
+        }
+        if args.mode != mode::Mode::Create2 || args.init_code_hash.is_none() {
+            exit_with_error("--factory needs --mode create2 and the --init-code-hash of what it deploys");
+        }
+        factory.check_salt(&salt_prefix(&submitter), &submitter).unwrap_or_else(|e| fail(e));
+        println!("{}", factory.deploy_hint(&submitter));
+    }
+    let derivation = mode::Derivation {
+        mode: args.mode,
+        deployer,
+        code_hash,
+        create3_caller: args.create3_caller,
+    };
+    // Refuse to mine with a derivation or scoring that gets known answers wrong.
+    let challenge_scorer = (challenge_scoring && objective.is_none()).then_some(&*scorer);
+    selftest::check(args.mode, challenge_scorer).unwrap_or_else(|e| fail(e));
+    let best_address: Arc<Mutex<Option<Candidate>>> = Arc::new(Mutex::new(None));
+    let goal = Arc::new(monitor::Goal::new(rpc.is_some()));
+    let target = Arc::clone(&goal.target);
+    if let Some(spec) = args.target {
+        goal.set(spec).unwrap_or_else(|e| fail(e));
+    }
+    let control: Arc<search::Control> = Default::default();
+
+    let followers = match rpc {
+        Some(rpc) => follow_chain(&mut args, rpc, deployer, code_hash, &goal, &best_address, &control),
+        None => Vec::new(),
+    };
+
+    let scorer_name = match (objective, &pattern) {
+        (_, Some(_)) => "pattern".to_string(),
+        (Some(objective), None) => format!("maximize {:?}", objective).to_lowercase(),
+        (None, None) => args.scorer.to_string(),
+    };
+    let job = serde_json::json!({
+        "mode": format!("{:?}", args.mode).to_lowercase(),
+        "deployer": format!("0x{}", hex::encode(deployer)),
+        "code_hash": format!("0x{}", hex::encode(code_hash)),
+        "create3_caller": args.create3_caller.map(|caller| format!("0x{}", hex::encode(caller))),
+        "senders": job_senders,
+        "max_nonce": args.max_nonce,
+        "submitter": format!("0x{}", hex::encode(submitter)),
+        "salts": format!("{:?}", args.salts).to_lowercase(),
+        "salt_range": format!("{:?}", args.salt_range),
+        "salts_file": args.salts_file.as_ref().map(|path| path.display().to_string()),
+        "scorer": scorer_name,
+        "min_score": args.min_score,
+    });
+    let job_hash = provenance::job_hash(&job);
+    // What makes scores comparable, for --share-best.
+    let share_key = provenance::job_hash(&serde_json::json!([job["mode"], job["deployer"], job["code_hash"], job["create3_caller"], job["scorer"]]));
+    provenance::init(provenance::Provenance::new(Some(job_hash)), Arc::clone(&salts));
+    let checkpoint = args.checkpoint.checkpoint.clone().map(|path| {
+        let mut writer = checkpoint::Writer {
+            path,
+            job: job_hash,
+            salts: Arc::clone(&salts),
+            control: Arc::clone(&control),
+            best: Arc::clone(&best_address),
+            top: top.clone(),
+            covered: Vec::new(),
+            hashes: 0,
+            elapsed: Duration::ZERO,
+            started: Instant::now(),
+        };
+        if let Some(saved) = saved {
+            checkpoint::check_job(&saved, job_hash, &writer.path).unwrap_or_else(|e| exit_with_error(&e));
+            let best = saved.checkpoint.best;
+            println!(
+                "Carrying on from {}: {} hashes over {}, {}",
+                writer.path.display(),
+                odds::format_count(saved.checkpoint.hashes as f64),
+                deadline::format_countdown(saved.checkpoint.elapsed),
+                best.map_or("nothing found yet".to_string(), |c| format!("best score {}", c.score))
+            );
+            if let Some(best) = best {
+                target.fetch_max(best.score, Ordering::Relaxed);
+            }
+            *best_address.lock().unwrap_or_else(PoisonError::into_inner) = best;
+            if let Some(top) = &top {
+                saved.checkpoint.top.into_iter().for_each(|c| top.insert(c));
+            }
+            (writer.covered, writer.hashes, writer.elapsed) = (saved.checkpoint.covered, saved.checkpoint.hashes, saved.checkpoint.elapsed);
+        }
+        let writer = Arc::new(writer);
+        checkpoint::spawn(Arc::clone(&writer), args.checkpoint.checkpoint_interval);
+        writer
+    });
+    let before = checkpoint.as_ref().map_or_else(Vec::new, |writer| writer.covered.clone());
+    let coverage = Arc::new(salts::Coverage { salts: Arc::clone(&salts), before });
+    let logs = args.log.open().unwrap_or_else(|e| exit_with_error(&e));
+    for log in &logs {
+        let mode = format!("{:?}", args.mode).to_lowercase();
+        log.write(logfile::Level::Info, &format!("started: threads={} mode={} scorer={} deployer=0x{}", num_threads, mode, scorer_name, hex::encode(deployer)));
+    }
+    #[cfg(feature = "sqlite")]
+    let db = args.db.as_deref().map(|path| {
+        let scorer = scorer_name.clone();
+        let run = db::RunInfo { mode: format!("{:?}", args.mode).to_lowercase(), deployer, code_hash, scorer, threads: num_threads };
+        let db = db::Db::start(path, &run).unwrap_or_else(|e| runtime_error(&e));
+        salts.avoid_peppers(&db.used_peppers());
+        Arc::new(db)
+    });
+    salts.check_peppers(num_threads).unwrap_or_else(|e| fail(e));
+    if args.stats_csv.is_some() && args.status_interval.is_zero() {
+        exit_with_error("--stats-csv is written with the status lines, drop --status-interval 0s");
+    }
+    let hashrates: Arc<Mutex<Vec<f64>>> = Default::default();
+    if !args.status_interval.is_zero() {
+        let mut recorders: Vec<heartbeat::Recorder> = Vec::new();
+        if args.report_dir.is_some() {
+            let hashrates = Arc::clone(&hashrates);
+            recorders.push(Box::new(move |status| hashrates.lock().unwrap_or_else(PoisonError::into_inner).push(status.hashrate)));
+        }
+        if let Some(path) = &args.stats_csv {
+            let stats = results::StatsCsv::open(path).unwrap_or_else(|e| exit_with_error(&e));
+            recorders.push(Box::new(move |status| stats.record(status.hashes, status.hashrate, status.best.map(|c| c.score))));
+        }
+        for log in logs.clone() {
+            recorders.push(Box::new(move |status| log.write(logfile::Level::Info, &logfile::describe_status(status))));
+        }
+        #[cfg(feature = "sqlite")]
+        if let Some(db) = db.clone() {
+            let (control, coverage) = (Arc::clone(&control), Arc::clone(&coverage));
+            recorders.push(Box::new(move |status| {
+                db.record_stats(status.hashes, status.hashrate, status.best.map(|c| c.score));
+                let taken = control.taken.lock().unwrap_or_else(PoisonError::into_inner).clone();
+                db.record_peppers(&schema::pepper_ranges(coverage.position(&taken).as_ref()));
+            }));
+        }
+        let eta = (challenge_scoring && matches!(objective, None | Some(score::Objective::ChallengeScore)) && pattern.is_none()).then(|| Arc::clone(&target));
+        heartbeat::spawn(args.status_interval, Arc::clone(&best_address), eta, Arc::clone(&coverage), Arc::clone(&control), recorders);
+    }
+
+    // A found key is saved as it is reported, and only then.
+    let reproduce = move |salt: &[u8; 32]| match args.mode {
+        mode::Mode::Eoa => eoa::save(salt),
+        mode => mode.describe(salt),
+    };
+    let bus = hooks::EventBus::default();
+    bus.subscribe(move |event| match event {
+        hooks::Event::NewBest(c) if explain => {
+            println!("New best address: {} with score: {} ({}), {}{}", color::address(&c.address), c.score, odds::rarity(c.score), reproduce(&c.salt), deadline::countdown());
+            println!("  {}", explain_score(&c.address));
+        }
+        hooks::Event::NewBest(c) => match objective {
+            Some(objective) => println!("New best address: 0x{} with {}, {}", hex::encode(c.address), objective.describe(c.score), reproduce(&c.salt)),
+            None if challenge_scoring => println!("New best address: {} with score: {} ({}), {}{}", color::address(&c.address), c.score, odds::rarity(c.score), reproduce(&c.salt), deadline::countdown()),
+            None => println!("New best address: {} with score: {}, {}{}", color::address(&c.address), c.score, reproduce(&c.salt), deadline::countdown()),
+        },
+        hooks::Event::Progress(_) => {}
+        hooks::Event::Error(e) => eprintln!("Error: {}", e),
+    });
+    for log in logs.clone() {
+        bus.subscribe(move |event| log.event(event, args.mode));
+    }
+    #[cfg(feature = "sqlite")]
+    if let Some(db) = db.clone() {
+        bus.subscribe(move |event| {
+            if let hooks::Event::NewBest(c) = event {
+                db.record_best(c);
+            }
+        });
+    }
+    let timeline = Arc::new(timeline::Timeline::default());
+    {
+        let (timeline, control) = (Arc::clone(&timeline), Arc::clone(&control));
+        bus.subscribe(move |event| {
+            if let hooks::Event::NewBest(c) = event {
+                timeline.record(c.score, control.hashes.load(Ordering::Relaxed));
+            }
+        });
+    }
+    for follower in followers {
+        bus.subscribe(move |event| {
+            if let hooks::Event::NewBest(c) = event {
+                follower.send(**c).ok();
+            }
+        });
+    }
+
+    #[cfg(feature = "tui")]
+    let dashboard = args.tui.then(|| {
+        let view = tui::View {
+            mode: args.mode,
+            threads: num_threads,
+            control: Arc::clone(&control),
+            best: Arc::clone(&best_address),
+            top: top.clone(),
+            target: Arc::clone(&target),
+            odds: challenge_scoring && matches!(objective, None | Some(score::Objective::ChallengeScore)) && pattern.is_none(),
+        };
+        tui::start(view, &bus).unwrap_or_else(|e| exit_with_error(&e))
+    });
+    let interrupted = Arc::new(AtomicBool::new(false));
+    {
+        let (control, interrupted) = (Arc::clone(&control), Arc::clone(&interrupted));
+        runtime::on_interrupt(move || {
+            interrupted.store(true, Ordering::Relaxed);
+            control.stopped.store(true, Ordering::Relaxed);
+        });
+    }
+    {
+        let timeout = args.stop_timeout;
+        search::backstop(Arc::clone(&control), timeout, move || {
+            eprintln!("Error: the workers did not finish within {}s of stopping, exiting", timeout.as_secs());
+            report::exit("stop timed out");
+            runtime::exit(exit_code::BACKEND);
+        });
+    }
+    if let Some(dir) = args.report_dir.clone() {
+        std::fs::create_dir_all(&dir).unwrap_or_else(|e| exit_with_error(&format!("cannot create {}: {}", dir.display(), e)));
+        let config = report::Config {
+            command_line: report::redact(std::env::args()),
+            mode: format!("{:?}", args.mode).to_lowercase(),
+            deployer,
+            code_hash,
+            scorer: scorer_name.clone(),
+            threads: num_threads,
+        };
+        let run = report::Run {
+            config,
+            timeline: Arc::clone(&timeline),
+            control: Arc::clone(&control),
+            best: Arc::clone(&best_address),
+            top: top.clone(),
+            hashrates,
+            electricity_price: args.energy.electricity_price,
+        };
+        report::on_exit(move |reason| match run.report(reason).write(&dir) {
+            Ok(path) => println!("Report written to {}", path.display()),
+            Err(e) => eprintln!("Error: {}", e),
+        });
+    }
+
+    let sources = Arc::new(snapshot::Sources {
+        mode: args.mode,
+        control: Arc::clone(&control),
+        coverage,
+        best: Arc::clone(&best_address),
+        started: Instant::now(),
+    });
+    snapshot::spawn(Arc::clone(&sources));
+    #[cfg(unix)]
+    let _running = daemon::is_daemon().then(|| daemon::serve(&args.instance, Arc::clone(&sources)).unwrap_or_else(|e| runtime_error(&e)));
+    if !args.tui {
+        commands::spawn(Arc::clone(&control), Arc::clone(&goal), Arc::clone(&sources));
+    }
+    if args.share_best {
+        let path = args.share_file.clone().unwrap_or_else(|| shared::default_path(share_key));
+        shared::Shared::open(&path)
+            .and_then(|file| shared::spawn(file, Arc::clone(&best_address), Arc::clone(&target)))
+            .unwrap_or_else(|e| runtime_error(&format!("cannot share the best through {}: {}", path.display(), e)));
+    }
+    if !args.schedule.is_empty() {
+        let windows: Vec<String> = args.schedule.iter().map(ToString::to_string).collect();
+        println!("Mining on a schedule: {}", windows.join("; "));
+        schedule::spawn(schedule::Schedule(args.schedule.clone()), Arc::clone(&control), checkpoint.clone());
+    }
+    if args.keep_awake {
+        inhibit::spawn(Arc::clone(&control));
+    }
+    if args.energy.metering() {
+        match (energy::Meter::open(), args.energy.budget()) {
+            (Ok(meter), budget) => {
+                energy::spawn(meter);
+                if let Some(budget) = budget {
+                    energy::watch_budget(budget, Arc::clone(&control));
+                }
+            }
+            (Err(e), Some(_)) => runtime_error(&format!("cannot measure energy use to keep to the budget: {}", e)),
+            (Err(e), None) => eprintln!("Warning: cannot measure energy use: {}", e),
+        }
+    }
+    #[cfg(target_os = "linux")]
+    systemd::spawn(Arc::clone(&control), Arc::clone(&best_address));
+    println!("Running with {} threads", num_threads);
+
+    let search = search::Search {
+        derivation,
+        salts,
+        scorer,
+        pattern,
+        tracker,
+        extra_hashes,
+        histogram,
+        tiebreak: args.tiebreak,
+        zero_nibbles: args.require_leading_zero_nibbles,
+        target: Arc::clone(&target),
+        best: best_address,
+        top: top.clone(),
+        events: Arc::new(bus),
+        on_candidate: (!candidate_logs.is_empty()).then(|| {
+            let record = move |c: &Candidate| candidate_logs.iter().for_each(|log| log.record(c));
+            (args.min_score, Box::new(record) as search::Callback)
+        }),
+        control,
+    };
+    search.run(num_threads);
+    #[cfg(target_os = "linux")]
+    systemd::notify("STOPPING=1");
+    let quit = search.control.stopped.load(Ordering::Relaxed) && !deadline::passed() && !energy::budget_spent();
+    #[cfg(feature = "tui")]
+    if let Some(dashboard) = dashboard {
+        dashboard.finish();
+    }
+    let hashes = search.control.hashes.load(Ordering::Relaxed);
+    for log in &logs {
+        log.write(logfile::Level::Info, &format!("stopped: hashes={}", hashes));
+    }
+    #[cfg(feature = "sqlite")]
+    if let Some(db) = &db {
+        let taken = search.control.taken.lock().unwrap_or_else(PoisonError::into_inner).clone();
+        db.record_peppers(&schema::pepper_ranges(sources.coverage.position(&taken).as_ref()));
+        db.finish(hashes);
+    }
+    // Dropping the search closes the channels of the services following it.
+    drop(search);
+    runtime::shutdown(Duration::from_secs(5));
+    if let Some(writer) = &checkpoint {
+        match writer.save() {
+            Ok(()) => println!("Checkpoint written to {}", writer.path.display()),
+            Err(e) => eprintln!("Error: {}", e),
+        }
+    }
+    // Only a CREATE search runs out of candidates, unless it was quit.
+    match (args.mode, args.salts_file, args.salt_range) {
+        _ if quit => print!("{}", sources.render()),
+        (mode::Mode::Create, _, _) => println!("Searched nonces 0 to {} of every sender", args.max_nonce),
+        (_, Some(path), _) => println!("Searched every salt in {}", path.display()),
+        (_, None, (start, Some(end))) => println!("Searched salt counters {} to {}", start, end - 1),
+        _ => {}
+    }
+    if let Some(top) = &top {
+        top.dump(args.mode);
+    }
+    let points = timeline.points();
+    if points.len() > 1 {
+        println!("Best score over time:");
+        print!("{}", timeline::chart(&points, timeline.elapsed(), 60, 8, color::terminal()));
+    }
+    // A service stopped by the service manager has done what was asked of it.
+    let stopped = runtime::stop_requested();
+    let interrupted = interrupted.load(Ordering::Relaxed) && !stopped;
+    report::exit(match (interrupted, quit) {
+        _ if stopped => "stopped",
+        (true, _) => "interrupted",
+        (false, true) => "quit",
+        _ if deadline::passed() => "competition deadline",
+        _ if energy::budget_spent() => "energy budget used up",
+        (false, false) => "searched everything",
+    });
+    let best = *sources.best.lock().unwrap_or_else(PoisonError::into_inner);
+    if stopped {
+        return;
+    }
+    if interrupted {
+        runtime::exit(exit_code::INTERRUPTED);
+    }
+    if !goal.reached(best.as_ref()) {
+        println!("Stopped without reaching the target");
+        runtime::exit(exit_code::BUDGET_EXHAUSTED);
+    }
+}
+
+// Refuse options that cannot go together, or that this build or platform
+// cannot do, before anything starts.
+fn check_options(args: &Args, rpc: bool, num_threads: usize) {
+    #[cfg(not(feature = "tui"))]
+    if args.tui {
+        exit_with_error("built without the dashboard, rebuild with --features tui");
+    }
+    if args.energy.metering() && !energy::SUPPORTED {
+        exit_with_error("--energy, --max-kwh and --max-cost are only supported on Linux and macOS");
+    }
+    if args.keep_awake && !inhibit::SUPPORTED {
+        exit_with_error("--keep-awake is only supported on Linux, macOS and Windows");
+    }
+    #[cfg(not(feature = "sqlite"))]
+    if args.db.is_some() {
+        exit_with_error("built without SQLite support, rebuild with --features sqlite");
+    }
+    if !rpc {
+        let needs_rpc = [
+            ("--ws-url", args.ws_url.is_some()),
+            ("--verify-scores", args.verify_scores),
+            ("--auto-submit-before-deadline", args.auto_submit_before_deadline.is_some()),
+            ("--auto-resubmit", args.auto_resubmit),
+            ("--secure-below", args.secure_below.is_some()),
+            ("--target beat-chain", args.target == Some(monitor::Target::BeatChain)),
+            ("--initcode-address", args.initcode_address.is_some()),
+            ("--initcode-tx", args.initcode_tx.is_some()),
+        ];
+        if let Some((flag, _)) = needs_rpc.iter().find(|(_, used)| *used) {
+            exit_with_error(&format!("{} needs an RPC endpoint (--rpc-url)", flag));
+        }
+    }
+    if args.mode != mode::Mode::Create2 {
+        if rpc {
+            exit_with_error("the challenge deploys with CREATE2, drop --mode or --rpc-url");
+        }
+        if args.init_code_hash.is_some() || args.initcode_address.is_some() || args.initcode_tx.is_some() || !args.extra_init_code_hash.is_empty() {
+            exit_with_error(&format!("--mode {:?} addresses do not depend on the initcode, drop the initcode options", args.mode).to_lowercase());
+        }
+    }
+    let custom_salts = args.salts != salts::Strategy::Random || args.salt_range != (0, None) || args.salts_file.is_some() || args.pepper_range.is_some() || args.seed_file.is_some();
+    if custom_salts && matches!(args.mode, mode::Mode::Create | mode::Mode::Eoa) {
+        exit_with_error("--mode create and eoa generate their own candidates, drop the salt options");
+    }
+    if args.salt_range != (0, None) && args.salts != salts::Strategy::Sequential {
+        exit_with_error("--salt-range needs --salts sequential");
+    }
+    if args.seed_file.is_some() && (args.salts != salts::Strategy::Random || args.salts_file.is_some()) {
+        exit_with_error("--seed-file needs --salts random");
+    }
+    if let Some(peppers) = &args.pepper_range {
+        if args.salts != salts::Strategy::Random || args.salts_file.is_some() {
+            exit_with_error("--pepper-range needs --salts random");
+        }
+        if peppers.end.saturating_sub(peppers.start) < num_threads as u64 {
+            exit_with_error(&format!("--pepper-range {}..{} has fewer peppers than the {} threads", peppers.start, peppers.end, num_threads));
+        }
+    }
+    if args.mode == mode::Mode::Eoa {
+        // It would hold the keys in the clear, or with --top list keys that
+        // were never saved, as only new bests are.
+        let persisted = [args.top.is_some(), args.top_file.is_some(), args.candidate_log.is_some(), args.csv.is_some(), args.db.is_some(), args.report_dir.is_some()];
+        if persisted.contains(&true) || args.checkpoint.checkpoint.is_some() || args.share_best {
+            exit_with_error("--top, --top-file, --candidate-log, --csv, --db, --report-dir, --checkpoint and --share-best cannot be used with --mode eoa");
+        }
+    }
+    if args.create3_caller.is_some() && args.mode != mode::Mode::Create3 {
+        exit_with_error("--create3-caller needs --mode create3");
+    }
+}
+
+// The salts to search, carrying on from `position` when a checkpoint left one.
+fn salt_generator(args: &Args, senders: Vec<[u8; 20]>, position: Option<salts::SaltPosition>) -> Arc<dyn salts::SaltGenerator> {
+    let pepper_range = args.pepper_range.clone().unwrap_or(0..1 << 32);
+    let seed = args.seed_file.as_deref().map(|path| salts::Seed {
+        key: salts::read_seed(path).unwrap_or_else(|e| fail(e)),
+        worker: args.worker_index.unwrap_or(0),
+    });
+    let random = |random: salts::Random| match seed {
+        Some(seed) => random.seeded(seed),
+        None => random.within(pepper_range.clone()),
+    };
+    match (args.mode, &args.salts_file, args.salts, position) {
+        (mode::Mode::Create, _, _, None) => Arc::new(salts::Nonces { senders, max_nonce: args.max_nonce, start: 0 }),
+        (mode::Mode::Create, _, _, Some(salts::SaltPosition::Nonces { next })) => {
+            Arc::new(salts::Nonces { senders, max_nonce: args.max_nonce, start: next })
+        }
+        (mode::Mode::Eoa, _, _, _) => Arc::new(salts::Keys),
+        (_, Some(path), _, None) => Arc::new(salts::FromIter::new(read_salts(path).unwrap_or_else(|e| exit_with_error(&e)))),
+        (_, None, salts::Strategy::Random, None) => Arc::new(random(salts::Random::new(args.submitter))),
+        (_, None, salts::Strategy::Random, Some(salts::SaltPosition::Random { submitter, streams, retired })) => {
+            // Streams of another worker would repeat its salts.
+            if let Some(seed) = seed {
+                if let Some(stream) = streams.iter().find(|stream| seed.owner(stream.pepper).0 != seed.worker) {
+                    let owner = seed.owner(stream.pepper).0;
+                    exit_with_error(&format!("the checkpoint's pepper {:#010x} is worker {}'s of the seed, not worker {}'s", stream.pepper, owner, seed.worker));
+                }
+            }
+            let random = random(salts::Random::resume(submitter.into_array(), streams, retired));
+            for pepper in random.duplicates() {
+                eprintln!("Warning: pepper {:#010x} is in the checkpoint more than once, its salts are tried twice", pepper);
+            }
+            Arc::new(random)
+        }
+        (_, None, salts::Strategy::Sequential, None) => {
+            let (start, end) = args.salt_range;
+            Arc::new(salts::Sequential { submitter: args.submitter, start, end })
+        }
+        (_, None, salts::Strategy::Sequential, Some(salts::SaltPosition::Sequential { submitter, next, end, .. })) => {
+            Arc::new(salts::Sequential { submitter: submitter.into_array(), start: next, end })
+        }
+        _ => exit_with_error("the checkpoint is of another kind of search, pass --fresh to start over or another --checkpoint"),
+    }
+}
+
+// Check the challenge on `rpc` will take what is mined, and start following
+// it: the leader, the deadline, the odds and whatever submits on its own.
+// Returns the services to tell about every new best.
+fn follow_chain(
+    args: &mut Args,
+    rpc: rpc::RpcClient,
+    deployer: [u8; 20],
+    code_hash: [u8; 32],
+    goal: &Arc<monitor::Goal>,
+    best: &Arc<Mutex<Option<Candidate>>>,
+    control: &Arc<search::Control>,
+) -> Vec<Sender<Candidate>> {
+    let mut followers = Vec::new();
+    if let Err(e) = args.network.check_chain_id(&rpc) {
+        fail(e);
+    }
+    if let Err(e) = challenge::check_submitter(&rpc, &deployer, &args.submitter, &code_hash) {
+        runtime_error(&format!("submitter check failed: {}", e));
+    }
+    println!("Submitter 0x{} is eligible", hex::encode(args.submitter));
+    let leader = monitor::initialize(&rpc, &deployer, goal);
+
+    let auto_submitter = if args.auto_submit_before_deadline.is_some() || args.auto_resubmit || args.secure_below.is_some() {
+        let signer = args.wallet.signer().unwrap_or_else(|e| exit_with_error(&e));
+        let msg_sender = args.relay.relayer.unwrap_or(signer.address());
+        if !challenge::salt_accepts_sender(&salt_prefix(&args.submitter), &msg_sender) {
+            exit_with_error(&format!(
+                "auto-submit would call the challenge as 0x{}, which cannot submit salts mined for 0x{}",
+                hex::encode(msg_sender),
+                hex::encode(args.submitter)
+            ));
+        }
+        if let Some(relayer) = &args.relay.relayer {
+            relay::warn_mismatch(&signer.address(), relayer);
+            if let Err(e) = relay::check_relayer(&rpc, &deployer, relayer, &salt_prefix(&args.submitter)) {
+                runtime_error(&e);
+            }
+        }
+        let options = submit::SubmitOptions {
+            gas: args.gas.clone(),
+            gas_limit: args.gas_limit,
+            nonce: None,
+            wait: true,
+            confirmations: 1,
+            budget: args.budget.clone(),
+            relay: args.relay.clone(),
+        };
+        Some(Arc::new(Mutex::new(submit::Submitter { signer, options, spent: 0 })))
+    } else {
+        None
+    };
+    if let Some(auto_submitter) = auto_submitter.as_ref().filter(|_| args.auto_resubmit) {
+        followers.push(resubmit::spawn(rpc.clone(), deployer, Arc::clone(auto_submitter), args.max_submissions));
+    }
+    let secure = args.secure_below.zip(auto_submitter.clone()).map(|(below, submitter)| odds::Secure {
+        below,
+        rpc: rpc.clone(),
+        contract: deployer,
+        submitter,
+    });
+    let auto_submit = args
+        .auto_submit_before_deadline
+        .zip(auto_submitter)
+        .map(|(margin, submitter)| deadline::AutoSubmit { margin, submitter });
+    if let Err(e) = deadline::spawn(rpc.clone(), deployer, Arc::clone(best), auto_submit, Arc::clone(control)) {
+        runtime_error(&format!("cannot read competition deadline: {}", e));
+    }
+    odds::spawn(args.odds_interval, Arc::clone(control), Arc::clone(&goal.target), Arc::clone(best), secure);
+    if args.verify_scores {
+        let oracle = match args.score_contract {
+            Some(scorer) => verify::ScoreOracle::Library(scorer),
+            None => verify::ScoreOracle::Challenge { contract: deployer, sender: args.submitter },
+        };
+        followers.push(verify::spawn(rpc.clone(), oracle));
+    }
+    monitor::spawn(rpc, deployer, args.watch_interval, Arc::clone(goal), leader);
+    if let Some(ws_url) = args.ws_url.take() {
+        events::spawn(ws_url, deployer, Arc::clone(goal));
+    }
+    followers
+}
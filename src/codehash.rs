@@ -0,0 +1,61 @@
+// Further initcode hashes mined with the same salts as the main one, each with
+// its own best, so one run covers several candidate contracts more cheaply
+// than a run per hash.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Mutex, PoisonError};
+
+use crate::create2_addr;
+use crate::score::{leading_zero_nibbles, Scorer};
+
+struct Extra {
+    code_hash: [u8; 32],
+    // Best score so far, checked without locking before taking `lock`.
+    best: AtomicU32,
+    lock: Mutex<()>,
+}
+
+pub struct ExtraHashes {
+    extras: Vec<Extra>,
+    zero_nibbles: u32,
+}
+
+impl ExtraHashes {
+    // `zero_nibbles` is --require-leading-zero-nibbles, applied to every hash.
+    pub fn new(code_hashes: &[[u8; 32]], zero_nibbles: u32) -> Self {
+        let extras = code_hashes
+            .iter()
+            .map(|code_hash| Extra { code_hash: *code_hash, best: AtomicU32::new(0), lock: Mutex::new(()) })
+            .collect();
+        ExtraHashes { extras, zero_nibbles }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.extras.is_empty()
+    }
+
+    pub fn observe(&self, deployer: &[u8; 20], salt: &[u8; 32], scorer: &dyn Scorer) {
+        for extra in &self.extras {
+            let address = create2_addr(deployer, salt, &extra.code_hash);
+            if leading_zero_nibbles(&address) < self.zero_nibbles {
+                continue;
+            }
+            let best = extra.best.load(Ordering::Relaxed);
+            let score = scorer.score_at_least(&address, best + 1);
+            if score <= best {
+                continue;
+            }
+            let _guard = extra.lock.lock().unwrap_or_else(PoisonError::into_inner);
+            if score > extra.best.load(Ordering::Relaxed) {
+                extra.best.store(score, Ordering::Relaxed);
+                println!(
+                    "Best for initcode 0x{}: 0x{} with score: {}, salt: 0x{}",
+                    hex::encode(extra.code_hash),
+                    hex::encode(address),
+                    score,
+                    hex::encode(salt)
+                );
+            }
+        }
+    }
+}
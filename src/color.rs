@@ -0,0 +1,72 @@
+// Addresses printed with the nibbles the challenge scores picked out in color,
+// so a result shows at a glance why it scored what it did: the leading zeros,
+// the run of 4s after them, the 4s the tail bonus looks at and every other 4.
+// Plain text when stdout is not a terminal or NO_COLOR is set.
+
+use std::io::IsTerminal;
+use std::sync::OnceLock;
+
+use crate::explain_score;
+
+static ENABLED: OnceLock<bool> = OnceLock::new();
+
+const ZEROS: &str = "\x1b[32m";
+const RUN: &str = "\x1b[1;33m";
+const TAIL: &str = "\x1b[1;35m";
+const FOUR: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+// Highlight addresses from now on if `challenge` scoring is in use and stdout
+// is a terminal that wants color.
+pub fn init(challenge: bool) {
+    ENABLED.set(challenge && terminal()).ok();
+}
+
+// Whether stdout is a terminal that wants color.
+pub fn terminal() -> bool {
+    std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+}
+
+// `address` as 0x-prefixed hex, highlighted when enabled.
+pub fn address(address: &[u8; 20]) -> String {
+    match ENABLED.get() {
+        Some(true) => paint(address),
+        _ => format!("0x{}", hex::encode(address)),
+    }
+}
+
+// `address` as 0x-prefixed hex with ANSI colors on the nibbles that score.
+pub fn paint(address: &[u8; 20]) -> String {
+    let hex = hex::encode(address);
+    let zeros = hex.bytes().take_while(|&c| c == b'0').count();
+    let run = hex.bytes().skip(zeros).take_while(|&c| c == b'4').count();
+    let breakdown = explain_score(address);
+    // The tail bonus checks the second and third digits from the end.
+    let tail = breakdown.tail_fours > 0;
+    let style = |i: usize, c: u8| match i {
+        // Nothing counts unless the zeros are followed by a 4.
+        _ if !breakdown.starts_with_four => "",
+        _ if i < zeros => ZEROS,
+        _ if i < zeros + run => RUN,
+        37 | 38 if tail => TAIL,
+        _ if c == b'4' => FOUR,
+        _ => "",
+    };
+    let mut painted = String::from("0x");
+    let mut current = "";
+    for (i, c) in hex.bytes().enumerate() {
+        let wanted = style(i, c);
+        if wanted != current {
+            if !current.is_empty() {
+                painted.push_str(RESET);
+            }
+            painted.push_str(wanted);
+            current = wanted;
+        }
+        painted.push(c as char);
+    }
+    if !current.is_empty() {
+        painted.push_str(RESET);
+    }
+    painted
+}
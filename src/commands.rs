@@ -0,0 +1,79 @@
+// Commands typed into a running miner, one a line, when stdin is a terminal
+// (and not taken by --tui): pause and resume, a status snapshot, a new target
+// and quitting with a summary, all without losing what the run has found.
+
+use std::io::{BufRead, IsTerminal};
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::thread;
+
+use crate::monitor::{Goal, Target};
+use crate::search::Control;
+use crate::snapshot::Sources;
+
+pub const HELP: &str = "Commands: p pause, r resume, s status, t <score|beat-chain> target, q quit";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Pause,
+    Resume,
+    Status,
+    // Report only what reaches this from now on.
+    Target(Target),
+    Quit,
+}
+
+pub fn parse(line: &str) -> Result<Command, String> {
+    let mut words = line.split_whitespace();
+    let command = match (words.next(), words.next()) {
+        (Some("p"), None) => Command::Pause,
+        (Some("r"), None) => Command::Resume,
+        (Some("s"), None) => Command::Status,
+        (Some("q"), None) => Command::Quit,
+        (Some("t"), Some(target)) => Command::Target(target.parse()?),
+        _ => return Err(HELP.to_string()),
+    };
+    match words.next() {
+        Some(_) => Err(HELP.to_string()),
+        None => Ok(command),
+    }
+}
+
+// Follow commands on stdin if it is a terminal. The reading thread is left to
+// end with the process, as a read from stdin cannot be interrupted.
+pub fn spawn(control: Arc<Control>, goal: Arc<Goal>, sources: Arc<Sources>) {
+    if !std::io::stdin().is_terminal() {
+        return;
+    }
+    println!("{}", HELP);
+    thread::spawn(move || {
+        for line in std::io::stdin().lock().lines() {
+            let Ok(line) = line else { break };
+            if line.trim().is_empty() {
+                continue;
+            }
+            match parse(&line) {
+                Ok(Command::Pause) => {
+                    control.paused.store(true, Ordering::Relaxed);
+                    println!("Paused, r to resume");
+                }
+                Ok(Command::Resume) => {
+                    control.paused.store(false, Ordering::Relaxed);
+                    println!("Resumed");
+                }
+                Ok(Command::Status) => print!("{}", sources.render()),
+                Ok(Command::Target(target)) => {
+                    if let Err(e) = goal.set(target) {
+                        println!("{}", e);
+                    }
+                }
+                Ok(Command::Quit) => {
+                    println!("Stopping...");
+                    control.stopped.store(true, Ordering::Relaxed);
+                    break;
+                }
+                Err(e) => println!("{}", e),
+            }
+        }
+    });
+}
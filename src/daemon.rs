@@ -0,0 +1,209 @@
+// Running in the background without systemd or a service manager, on unix:
+// `--daemon` starts the miner again as a detached process with its output in
+// --daemon-log, which writes its PID to --pid-file and listens on a socket
+// next to it. `status` asks it for a status snapshot over that socket, and
+// `stop` has it stop the way Ctrl-C would and waits for it to wrap up.
+
+use std::path::{Path, PathBuf};
+
+use clap::Args;
+
+pub const DEFAULT_PID_FILE: &str = concat!(env!("CARGO_PKG_NAME"), ".pid");
+
+// Set in the environment of the background process.
+pub const ENV: &str = "UNISWAPV4_MINER_DAEMON";
+
+// Which background miner to talk to.
+#[derive(Args, Debug, Clone)]
+pub struct Instance {
+    /// PID file of the --daemon miner; its control socket is next to it, with
+    /// a .sock extension
+    #[arg(long, value_name = "PATH", default_value = DEFAULT_PID_FILE)]
+    pub pid_file: PathBuf,
+}
+
+impl Instance {
+    pub fn socket(&self) -> PathBuf {
+        self.pid_file.with_extension("sock")
+    }
+}
+
+// Whether this process is the background one --daemon started.
+pub fn is_daemon() -> bool {
+    std::env::var_os(ENV).is_some()
+}
+
+// The arguments for the background process: these, without --daemon.
+pub fn child_args(args: impl IntoIterator<Item = std::ffi::OsString>) -> Vec<std::ffi::OsString> {
+    args.into_iter().filter(|arg| arg != "--daemon").collect()
+}
+
+fn read_pid(pid_file: &Path) -> Option<u32> {
+    std::fs::read_to_string(pid_file).ok()?.trim().parse().ok()
+}
+
+#[cfg(unix)]
+pub use unix::{request, serve, start, status, stop, Running};
+
+#[cfg(unix)]
+mod unix {
+    use std::fs::OpenOptions;
+    use std::io::{Read, Write};
+    use std::os::unix::net::UnixStream;
+    use std::os::unix::process::CommandExt;
+    use std::path::PathBuf;
+    use std::process::{Command, Stdio};
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::UnixListener;
+
+    use super::{child_args, read_pid, Instance, ENV};
+    use crate::snapshot::Sources;
+    use crate::{persist, runtime};
+
+    // How long `stop` waits for the miner to wrap up.
+    const STOP_TIMEOUT: Duration = Duration::from_secs(60);
+
+    // Send `command` to the miner and return its answer.
+    pub fn request(instance: &Instance, command: &str) -> Result<String, String> {
+        let socket = instance.socket();
+        let not_running = |e: std::io::Error| match read_pid(&instance.pid_file) {
+            Some(pid) => format!("no miner answers on {} (pid {} in {}): {}", socket.display(), pid, instance.pid_file.display(), e),
+            None => format!("no miner is running with {}", instance.pid_file.display()),
+        };
+        let mut stream = UnixStream::connect(&socket).map_err(not_running)?;
+        let mut answer = String::new();
+        writeln!(stream, "{}", command)
+            .and_then(|_| stream.read_to_string(&mut answer))
+            .map_err(|e| format!("cannot talk to the miner on {}: {}", socket.display(), e))?;
+        Ok(answer)
+    }
+
+    pub fn status(instance: &Instance) -> Result<(), String> {
+        print!("{}", request(instance, "status")?);
+        Ok(())
+    }
+
+    pub fn stop(instance: &Instance) -> Result<(), String> {
+        print!("{}", request(instance, "stop")?);
+        let asked = Instant::now();
+        while instance.pid_file.exists() {
+            if asked.elapsed() > STOP_TIMEOUT {
+                return Err(format!("the miner is still stopping after {}s", STOP_TIMEOUT.as_secs()));
+            }
+            thread::sleep(Duration::from_millis(200));
+        }
+        println!("Stopped");
+        Ok(())
+    }
+
+    // Start the miner again in the background with `args`, its output
+    // appended to `log`, once no other is running with `instance`.
+    pub fn start(instance: &Instance, log: &std::path::Path, args: impl IntoIterator<Item = std::ffi::OsString>) -> Result<(), String> {
+        if let Ok(answer) = request(instance, "pid") {
+            return Err(format!("a miner is already running with {}, pid {}", instance.pid_file.display(), answer.trim()));
+        }
+        let output = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(log)
+            .map_err(|e| format!("cannot open {}: {}", log.display(), e))?;
+        let errors = output.try_clone().map_err(|e| format!("cannot open {}: {}", log.display(), e))?;
+        let exe = std::env::current_exe().map_err(|e| format!("cannot find the miner to start: {}", e))?;
+        let mut child = Command::new(exe)
+            .args(child_args(args))
+            .env(ENV, "1")
+            .stdin(Stdio::null())
+            .stdout(output)
+            .stderr(errors)
+            // Out of the terminal's process group, so its Ctrl-C and hangup
+            // leave it alone.
+            .process_group(0)
+            .spawn()
+            .map_err(|e| format!("cannot start the miner: {}", e))?;
+        // Up once it answers, unless it fails to get there.
+        let started = Instant::now();
+        while started.elapsed() < STOP_TIMEOUT {
+            if let Ok(Some(status)) = child.try_wait() {
+                return Err(format!("the miner exited with {}, see {}", status, log.display()));
+            }
+            if request(instance, "pid").is_ok() {
+                println!("Running in the background as pid {}, output in {}", child.id(), log.display());
+                println!("`status` shows how it is doing and `stop` stops it");
+                return Ok(());
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+        Err(format!("the miner did not come up, see {}", log.display()))
+    }
+
+    // While alive, the PID file and the control socket of the running miner,
+    // removed once it is dropped.
+    pub struct Running {
+        pid_file: PathBuf,
+        socket: PathBuf,
+    }
+
+    impl Drop for Running {
+        fn drop(&mut self) {
+            std::fs::remove_file(&self.socket).ok();
+            std::fs::remove_file(&self.pid_file).ok();
+        }
+    }
+
+    // Write the PID file and answer `status`, `stop` and `pid` on the control
+    // socket until shutdown.
+    pub fn serve(instance: &Instance, sources: Arc<Sources>) -> Result<Running, String> {
+        let socket = instance.socket();
+        // Left behind by a miner that did not get to clean up.
+        std::fs::remove_file(&socket).ok();
+        let listener = std::os::unix::net::UnixListener::bind(&socket)
+            .and_then(|listener| listener.set_nonblocking(true).map(|_| listener))
+            .map_err(|e| format!("cannot listen on {}: {}", socket.display(), e))?;
+        persist::write(&instance.pid_file, format!("{}\n", std::process::id()))
+            .map_err(|e| format!("cannot write {}: {}", instance.pid_file.display(), e))?;
+        let running = Running { pid_file: instance.pid_file.clone(), socket: socket.clone() };
+        {
+            let (pid_file, socket) = (running.pid_file.clone(), running.socket.clone());
+            runtime::before_exit(move |_| {
+                std::fs::remove_file(&socket).ok();
+                std::fs::remove_file(&pid_file).ok();
+            });
+        }
+        runtime::spawn("daemon", move |mut shutdown| async move {
+            let listener = match UnixListener::from_std(listener) {
+                Ok(listener) => listener,
+                Err(e) => return eprintln!("Cannot serve {}: {}", socket.display(), e),
+            };
+            loop {
+                let mut stream = tokio::select! {
+                    accepted = listener.accept() => match accepted {
+                        Ok((stream, _)) => stream,
+                        Err(e) => break eprintln!("Cannot serve {}: {}", socket.display(), e),
+                    },
+                    _ = shutdown.wait() => break,
+                };
+                let mut command = String::new();
+                let (reader, mut writer) = stream.split();
+                if BufReader::new(reader).read_line(&mut command).await.is_err() {
+                    continue;
+                }
+                let answer = match command.trim() {
+                    "status" => sources.render(),
+                    "stop" => {
+                        println!("Stop requested over {}", socket.display());
+                        runtime::request_stop();
+                        "Stopping...\n".to_string()
+                    }
+                    "pid" => format!("{}\n", std::process::id()),
+                    other => format!("unknown command {:?}\n", other),
+                };
+                writer.write_all(answer.as_bytes()).await.ok();
+            }
+        });
+        Ok(running)
+    }
+}
@@ -0,0 +1,292 @@
+// A local SQLite record of every run, compiled in with the `sqlite` feature:
+// what it mined for, each new best and when it was found, the numbers of
+// every status line and the random salt peppers it used. `history` reads it
+// back, for mining on and off for weeks.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use clap::Args;
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::deadline::format_countdown;
+use crate::odds::format_count;
+use crate::provenance;
+use crate::schema::PepperRange;
+use crate::Candidate;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS runs (
+    id INTEGER PRIMARY KEY,
+    started INTEGER NOT NULL,
+    ended INTEGER,
+    host TEXT NOT NULL,
+    mode TEXT NOT NULL,
+    deployer TEXT NOT NULL,
+    code_hash TEXT NOT NULL,
+    scorer TEXT NOT NULL,
+    threads INTEGER NOT NULL,
+    hashes INTEGER NOT NULL DEFAULT 0,
+    backend TEXT,
+    engine TEXT,
+    job TEXT
+);
+CREATE TABLE IF NOT EXISTS bests (
+    run INTEGER NOT NULL REFERENCES runs(id),
+    found INTEGER NOT NULL,
+    score INTEGER NOT NULL,
+    address TEXT NOT NULL,
+    salt TEXT NOT NULL,
+    origin TEXT
+);
+CREATE TABLE IF NOT EXISTS stats (
+    run INTEGER NOT NULL REFERENCES runs(id),
+    timestamp INTEGER NOT NULL,
+    hashes INTEGER NOT NULL,
+    hashrate REAL NOT NULL,
+    best_score INTEGER
+);
+CREATE TABLE IF NOT EXISTS peppers (
+    run INTEGER NOT NULL REFERENCES runs(id),
+    pepper INTEGER NOT NULL,
+    counters INTEGER NOT NULL,
+    PRIMARY KEY (run, pepper)
+);
+CREATE INDEX IF NOT EXISTS bests_by_run ON bests(run);
+CREATE INDEX IF NOT EXISTS stats_by_run ON stats(run);
+";
+
+// Columns added since the first schema, for databases made before them.
+const ADDED_COLUMNS: [(&str, &str); 4] = [("runs", "backend TEXT"), ("runs", "engine TEXT"), ("runs", "job TEXT"), ("bests", "origin TEXT")];
+
+// What a run mines for, as recorded with it.
+pub struct RunInfo {
+    pub mode: String,
+    pub deployer: [u8; 20],
+    pub code_hash: [u8; 32],
+    pub scorer: String,
+    pub threads: usize,
+}
+
+// The database a run records itself to; times are unix seconds.
+pub struct Db {
+    path: PathBuf,
+    connection: Mutex<Connection>,
+    run: i64,
+}
+
+fn now() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |t| t.as_secs() as i64)
+}
+
+fn open(path: &Path) -> Result<Connection, String> {
+    let connection = Connection::open(path).map_err(|e| format!("cannot open {}: {}", path.display(), e))?;
+    // Other runs may read or write the same file.
+    connection
+        .busy_timeout(Duration::from_secs(5))
+        .and_then(|_| connection.pragma_update(None, "journal_mode", "WAL"))
+        .and_then(|_| connection.execute_batch(SCHEMA))
+        .map_err(|e| format!("cannot set up {}: {}", path.display(), e))?;
+    for (table, column) in ADDED_COLUMNS {
+        let name = column.split(' ').next().unwrap();
+        let present: bool = connection
+            .query_row(&format!("SELECT COUNT(*) FROM pragma_table_info('{}') WHERE name = ?1", table), [name], |row| row.get(0))
+            .map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+        if !present {
+            connection
+                .execute_batch(&format!("ALTER TABLE {} ADD COLUMN {}", table, column))
+                .map_err(|e| format!("cannot upgrade {}: {}", path.display(), e))?;
+        }
+    }
+    Ok(connection)
+}
+
+impl Db {
+    // Record a new run in the database at `path`, created if needed.
+    pub fn start(path: &Path, run: &RunInfo) -> Result<Self, String> {
+        let connection = open(path)?;
+        let provenance = provenance::get();
+        connection
+            .execute(
+                "INSERT INTO runs (started, host, mode, deployer, code_hash, scorer, threads, backend, engine, job)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                params![
+                    now(),
+                    provenance.host,
+                    run.mode,
+                    format!("0x{}", hex::encode(run.deployer)),
+                    format!("0x{}", hex::encode(run.code_hash)),
+                    run.scorer,
+                    run.threads as i64,
+                    provenance.backend,
+                    provenance.engine,
+                    provenance.job.map(|job| job.to_string())
+                ],
+            )
+            .map_err(|e| format!("cannot record the run in {}: {}", path.display(), e))?;
+        let run = connection.last_insert_rowid();
+        Ok(Db { path: path.to_path_buf(), connection: Mutex::new(connection), run })
+    }
+
+    fn write(&self, what: &str, sql: &str, params: impl rusqlite::Params) {
+        let connection = self.connection.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Err(e) = connection.execute(sql, params) {
+            eprintln!("Cannot record {} in {}: {}", what, self.path.display(), e);
+        }
+    }
+
+    // A best already recorded with the run, salt or address, is left out.
+    pub fn record_best(&self, c: &Candidate) {
+        self.write(
+            "a new best",
+            "INSERT INTO bests (run, found, score, address, salt, origin) SELECT ?1, ?2, ?3, ?4, ?5, ?6
+             WHERE NOT EXISTS (SELECT 1 FROM bests WHERE run = ?1 AND (address = ?4 OR salt = ?5))",
+            params![
+                self.run,
+                now(),
+                c.score,
+                format!("0x{}", hex::encode(c.address)),
+                format!("0x{}", hex::encode(c.salt)),
+                provenance::stamp(&c.salt).origin.map(|origin| origin.to_string())
+            ],
+        );
+    }
+
+    // A status line's numbers, keeping the run's total up to date as well in
+    // case it never gets to finish.
+    pub fn record_stats(&self, hashes: u64, hashrate: f64, best_score: Option<u32>) {
+        self.write(
+            "the stats",
+            "INSERT INTO stats (run, timestamp, hashes, hashrate, best_score) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![self.run, now(), hashes as i64, hashrate, best_score],
+        );
+        self.write("the stats", "UPDATE runs SET hashes = ?2 WHERE id = ?1", params![self.run, hashes as i64]);
+    }
+
+    // The peppers of random salts the run has used and how many counters it
+    // has tried under each.
+    pub fn record_peppers(&self, ranges: &[PepperRange]) {
+        for range in ranges {
+            let counters = ((range.end - range.start) / range.step.max(1)).min(i64::MAX as u64) as i64;
+            self.write(
+                "the peppers",
+                "INSERT INTO peppers (run, pepper, counters) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (run, pepper) DO UPDATE SET counters = excluded.counters",
+                params![self.run, range.pepper, counters],
+            );
+        }
+    }
+
+    // Peppers other runs of the same job have used, for fresh streams to
+    // stay clear of.
+    pub fn used_peppers(&self) -> Vec<u32> {
+        let Some(job) = provenance::get().job else { return Vec::new() };
+        let connection = self.connection.lock().unwrap_or_else(PoisonError::into_inner);
+        let peppers = connection
+            .prepare("SELECT DISTINCT pepper FROM peppers JOIN runs ON runs.id = peppers.run WHERE runs.job = ?1 AND runs.id != ?2")
+            .and_then(|mut query| query.query_map(params![job.to_string(), self.run], |row| row.get(0))?.collect());
+        peppers.unwrap_or_else(|e| {
+            eprintln!("Cannot read the peppers used before from {}: {}", self.path.display(), e);
+            Vec::new()
+        })
+    }
+
+    pub fn finish(&self, hashes: u64) {
+        self.write("the end of the run", "UPDATE runs SET ended = ?2, hashes = ?3 WHERE id = ?1", params![self.run, now(), hashes as i64]);
+    }
+}
+
+/// List past runs recorded with --db, or the bests of one of them
+#[derive(Args, Debug)]
+pub struct HistoryArgs {
+    /// Database written by --db
+    #[arg(long, value_name = "PATH")]
+    pub db: PathBuf,
+
+    /// Show every best of this run, with when it was found
+    #[arg(long, value_name = "ID")]
+    pub run: Option<i64>,
+}
+
+fn time(unix: i64) -> String {
+    humantime::format_rfc3339_seconds(UNIX_EPOCH + Duration::from_secs(unix.max(0) as u64)).to_string()
+}
+
+pub fn run(args: HistoryArgs) -> Result<(), String> {
+    if !args.db.exists() {
+        return Err(format!("{} does not exist, record runs into it with --db", args.db.display()));
+    }
+    let connection = open(&args.db)?;
+    let failed = |e: rusqlite::Error| format!("cannot read {}: {}", args.db.display(), e);
+    match args.run {
+        Some(run) => {
+            let started: Option<i64> = connection
+                .query_row("SELECT started FROM runs WHERE id = ?1", [run], |row| row.get(0))
+                .optional()
+                .map_err(failed)?;
+            let Some(started) = started else {
+                return Err(format!("no run {} in {}", run, args.db.display()));
+            };
+            let mut bests = connection.prepare("SELECT found, score, address, salt FROM bests WHERE run = ?1 ORDER BY found, score").map_err(failed)?;
+            let bests = bests
+                .query_map([run], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, u32>(1)?, row.get::<_, String>(2)?, row.get::<_, String>(3)?)))
+                .map_err(failed)?;
+            println!("Bests of run {}, started {}:", run, time(started));
+            for best in bests {
+                let (found, score, address, salt) = best.map_err(failed)?;
+                let after = format_countdown(Duration::from_secs((found - started).max(0) as u64));
+                println!("  {} (after {}): score {}, {}, salt: {}", time(found), after, score, address, salt);
+            }
+        }
+        None => {
+            let mut runs = connection
+                .prepare(
+                    "SELECT id, started, COALESCE(ended, (SELECT MAX(timestamp) FROM stats WHERE run = runs.id)), host, mode, scorer, hashes,
+                            (SELECT score FROM bests WHERE run = runs.id ORDER BY score DESC, found LIMIT 1),
+                            (SELECT address FROM bests WHERE run = runs.id ORDER BY score DESC, found LIMIT 1)
+                     FROM runs ORDER BY id",
+                )
+                .map_err(failed)?;
+            let runs = runs
+                .query_map([], |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, i64>(1)?,
+                        row.get::<_, Option<i64>>(2)?,
+                        row.get::<_, String>(3)?,
+                        row.get::<_, String>(4)?,
+                        row.get::<_, String>(5)?,
+                        row.get::<_, i64>(6)?,
+                        row.get::<_, Option<u32>>(7)?,
+                        row.get::<_, Option<String>>(8)?,
+                    ))
+                })
+                .map_err(failed)?;
+            let (mut count, mut total) = (0, 0);
+            for run in runs {
+                let (id, started, ended, host, mode, scorer, hashes, score, address) = run.map_err(failed)?;
+                let ran = ended.map_or("-".to_string(), |ended| format_countdown(Duration::from_secs((ended - started).max(0) as u64)));
+                let best = match (score, address) {
+                    (Some(score), Some(address)) => format!("best {} at {}", score, address),
+                    _ => "nothing found".to_string(),
+                };
+                println!(
+                    "Run {} on {}, {} ({} {}): ran {}, {} hashes, {}",
+                    id,
+                    host,
+                    time(started),
+                    mode,
+                    scorer,
+                    ran,
+                    format_count(hashes as f64),
+                    best
+                );
+                count += 1;
+                total += hashes;
+            }
+            println!("{} runs, {} hashes in all", count, format_count(total as f64));
+        }
+    }
+    Ok(())
+}
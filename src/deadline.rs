@@ -0,0 +1,105 @@
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+use std::time::{Duration, Instant};
+
+use crate::challenge;
+use crate::monitor;
+use crate::rpc::RpcClient;
+use crate::runtime;
+use crate::search::Control;
+use crate::submit::Submitter;
+use crate::Candidate;
+
+static DEADLINE: OnceLock<Instant> = OnceLock::new();
+
+// Time until the competition closes, once the deadline has been fetched.
+pub fn time_left() -> Option<Duration> {
+    DEADLINE
+        .get()
+        .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+}
+
+// Whether the competition is over.
+pub fn passed() -> bool {
+    time_left().is_some_and(|left| left.is_zero())
+}
+
+pub fn format_countdown(left: Duration) -> String {
+    humantime::format_duration(Duration::from_secs(left.as_secs())).to_string()
+}
+
+// Suffix for status lines, empty when the deadline is unknown.
+pub fn countdown() -> String {
+    match time_left() {
+        Some(left) => format!(" (deadline in {})", format_countdown(left)),
+        None => String::new(),
+    }
+}
+
+// Everything needed to submit the best salt unattended.
+pub struct AutoSubmit {
+    pub margin: Duration,
+    pub submitter: Arc<Mutex<Submitter>>,
+}
+
+// Submit the best candidate unless the chain already holds something at least
+// as good.
+pub fn submit_best(
+    rpc: &RpcClient,
+    contract: &[u8; 20],
+    best: &Mutex<Option<Candidate>>,
+    submitter: &Mutex<Submitter>,
+    label: &str,
+) {
+    let candidate = *best.lock().unwrap_or_else(PoisonError::into_inner);
+    match candidate {
+        None => println!("{}: nothing found to submit", label),
+        Some(candidate) => match monitor::fetch_best(rpc, contract) {
+            Ok(chain) if chain.score >= candidate.score => println!(
+                "{}: on-chain best score {} is not beaten by {}, not submitting",
+                label, chain.score, candidate.score
+            ),
+            _ => {
+                println!("{}: submitting best salt with score {}", label, candidate.score);
+                let result = submitter.lock().unwrap_or_else(PoisonError::into_inner).submit(rpc, contract, &candidate.salt);
+                if let Err(e) = result {
+                    eprintln!("{} failed: {}", label, e);
+                }
+            }
+        },
+    }
+}
+
+// Read the deadline from the contract, then submit the best candidate `margin`
+// before it (if requested) and stop the search when the competition is over.
+pub fn spawn(
+    rpc: RpcClient,
+    contract: [u8; 20],
+    best: Arc<Mutex<Option<Candidate>>>,
+    auto_submit: Option<AutoSubmit>,
+    control: Arc<Control>,
+) -> Result<(), String> {
+    let deadline = challenge::competition_deadline(&rpc, &contract)?;
+    let now = rpc.latest_block()?.timestamp;
+    if now >= deadline {
+        return Err(format!("competition ended at timestamp {}", deadline));
+    }
+    let ends = Instant::now() + Duration::from_secs(deadline - now);
+    DEADLINE.set(ends).ok();
+    println!("Competition ends in {}", format_countdown(Duration::from_secs(deadline - now)));
+
+    runtime::spawn("deadline", move |mut shutdown| async move {
+        if let Some(auto) = auto_submit {
+            if !shutdown.sleep_until(ends.checked_sub(auto.margin).unwrap_or(ends)).await {
+                return;
+            }
+            runtime::blocking(move || submit_best(&rpc, &contract, &best, &auto.submitter, "Auto-submit")).await;
+        }
+        if !shutdown.sleep_until(ends).await {
+            return;
+        }
+        println!("Competition deadline reached, stopping");
+        control.stopped.store(true, Ordering::Relaxed);
+    });
+    Ok(())
+}
@@ -0,0 +1,292 @@
+// The energy the machine uses while mining, with --energy, for watts and
+// hashes per joule next to the hashrate in status lines and the report, to
+// compare backends and machines. Linux reads the RAPL package counters under
+// /sys/class/powercap, macOS takes the CPU power from powermetrics; both
+// usually need root. It is the whole package's energy, not the miner's alone.
+// --max-kwh and --max-cost stop the run once it has used that much.
+
+use std::io;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use clap::Args;
+use serde::Serialize;
+
+use crate::runtime;
+use crate::search::Control;
+
+// How often the counters are read, well within the time RAPL's take to wrap.
+const POLL: Duration = Duration::from_secs(2);
+
+pub const SUPPORTED: bool = cfg!(any(target_os = "linux", target_os = "macos"));
+
+const JOULES_PER_KWH: f64 = 3.6e6;
+
+#[derive(Args, Debug, Clone)]
+pub struct EnergyArgs {
+    /// Measure the energy the CPU package uses, for watts and hashes per joule
+    /// in status lines and the report (Linux RAPL and macOS powermetrics,
+    /// usually as root)
+    #[arg(long)]
+    pub energy: bool,
+
+    /// Stop once the run has used this many kWh
+    #[arg(long, value_name = "KWH")]
+    pub max_kwh: Option<f64>,
+
+    /// Stop once the run's electricity has cost this much, at --electricity-price
+    #[arg(long, value_name = "AMOUNT", requires = "electricity_price")]
+    pub max_cost: Option<f64>,
+
+    /// What a kWh costs, for --max-cost and the report
+    #[arg(long, value_name = "PRICE")]
+    pub electricity_price: Option<f64>,
+}
+
+impl EnergyArgs {
+    // Whether to measure energy, asked for or to keep to a budget.
+    pub fn metering(&self) -> bool {
+        self.energy || self.budget().is_some()
+    }
+
+    // The joules the run may use, the lower of --max-kwh and --max-cost.
+    pub fn budget(&self) -> Option<f64> {
+        let cost = self.max_cost.zip(self.electricity_price).map(|(cost, price)| cost / price);
+        [self.max_kwh, cost].into_iter().flatten().reduce(f64::min).map(|kwh| kwh * JOULES_PER_KWH)
+    }
+}
+
+// Energy used since metering started, in microjoules.
+static MICROJOULES: AtomicU64 = AtomicU64::new(0);
+static METERING: AtomicBool = AtomicBool::new(false);
+
+// Joules used since metering started, when it is.
+pub fn joules() -> Option<f64> {
+    METERING.load(Ordering::Relaxed).then(|| MICROJOULES.load(Ordering::Relaxed) as f64 / 1e6)
+}
+
+// Energy use over a run, for the report.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Energy {
+    pub joules: f64,
+    pub watts: f64,
+    pub hashes_per_joule: f64,
+    // At --electricity-price.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost: Option<f64>,
+}
+
+impl Energy {
+    pub fn new(hashes: u64, joules: f64, elapsed: Duration, price: Option<f64>) -> Self {
+        Energy {
+            joules,
+            watts: joules / elapsed.as_secs_f64().max(1e-9),
+            hashes_per_joule: hashes as f64 / joules.max(1e-9),
+            cost: price.map(|price| joules / JOULES_PER_KWH * price),
+        }
+    }
+}
+
+static BUDGET_SPENT: AtomicBool = AtomicBool::new(false);
+
+// Whether the run stopped for having used its energy budget.
+pub fn budget_spent() -> bool {
+    BUDGET_SPENT.load(Ordering::Relaxed)
+}
+
+// Stop the search once `budget` joules are used, or once they can no longer
+// be counted, until shutdown.
+pub fn watch_budget(budget: f64, control: Arc<Control>) {
+    runtime::spawn("energy budget", move |mut shutdown| async move {
+        while shutdown.sleep(POLL).await {
+            match joules() {
+                Some(joules) if joules < budget => continue,
+                Some(_) => println!("The energy budget of {:.3} kWh is used up, stopping", budget / JOULES_PER_KWH),
+                None => eprintln!("Error: the energy budget cannot be kept to without measuring energy, stopping"),
+            }
+            BUDGET_SPENT.store(true, Ordering::Relaxed);
+            control.stopped.store(true, Ordering::Relaxed);
+            break;
+        }
+    });
+}
+
+pub fn format_efficiency(hashes_per_joule: f64) -> String {
+    let units = ["H/J", "kH/J", "MH/J", "GH/J"];
+    let mut efficiency = hashes_per_joule;
+    let mut unit = 0;
+    while efficiency >= 1000.0 && unit + 1 < units.len() {
+        efficiency /= 1000.0;
+        unit += 1;
+    }
+    format!("{:.2} {}", efficiency, units[unit])
+}
+
+pub fn format_joules(joules: f64) -> String {
+    match joules {
+        joules if joules >= 1e6 => format!("{:.2} MJ", joules / 1e6),
+        joules if joules >= 1e3 => format!("{:.2} kJ", joules / 1e3),
+        joules => format!("{:.1} J", joules),
+    }
+}
+
+// A RAPL zone's energy counter, which wraps at `max`.
+struct Zone {
+    energy: std::path::PathBuf,
+    max: u64,
+    last: u64,
+}
+
+fn read_counter(path: &std::path::Path) -> io::Result<u64> {
+    let text = std::fs::read_to_string(path).map_err(|e| io::Error::new(e.kind(), format!("cannot read {}: {}", path.display(), e)))?;
+    text.trim().parse().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", path.display(), e)))
+}
+
+// The package zones of RAPL, intel-rapl:N under a powercap directory, named
+// package-N, on AMD as well as Intel.
+pub struct Rapl {
+    zones: Vec<Zone>,
+}
+
+impl Rapl {
+    pub fn open(powercap: &std::path::Path) -> io::Result<Self> {
+        let entries = std::fs::read_dir(powercap).map_err(|e| io::Error::new(e.kind(), format!("cannot read {}: {}", powercap.display(), e)))?;
+        let mut zones = Vec::new();
+        for entry in entries {
+            let dir = entry?.path();
+            let name = dir.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+            // intel-rapl:N:M are the zones within a package.
+            if name.strip_prefix("intel-rapl:").is_none_or(|zone| zone.contains(':')) {
+                continue;
+            }
+            if !std::fs::read_to_string(dir.join("name")).is_ok_and(|name| name.starts_with("package")) {
+                continue;
+            }
+            let energy = dir.join("energy_uj");
+            let last = read_counter(&energy)?;
+            zones.push(Zone { max: read_counter(&dir.join("max_energy_range_uj"))?, energy, last });
+        }
+        if zones.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::NotFound, format!("no RAPL package zones in {}", powercap.display())));
+        }
+        Ok(Rapl { zones })
+    }
+
+    // Microjoules used since the last sample.
+    pub fn sample(&mut self) -> io::Result<u64> {
+        let mut used = 0;
+        for zone in &mut self.zones {
+            let now = read_counter(&zone.energy)?;
+            used += if now >= zone.last { now - zone.last } else { zone.max - zone.last + now };
+            zone.last = now;
+        }
+        Ok(used)
+    }
+}
+
+// The milliwatts of a powermetrics "CPU Power: 1234 mW" line.
+pub fn parse_power_line(line: &str) -> Option<f64> {
+    line.strip_prefix("CPU Power:")?.trim().strip_suffix("mW")?.trim().parse().ok()
+}
+
+#[cfg(target_os = "linux")]
+pub struct Meter(Rapl);
+
+#[cfg(target_os = "linux")]
+impl Meter {
+    pub fn open() -> io::Result<Self> {
+        Rapl::open(std::path::Path::new("/sys/class/powercap")).map(Meter)
+    }
+
+    pub fn sample(&mut self) -> io::Result<u64> {
+        self.0.sample()
+    }
+}
+
+// powermetrics prints the CPU power every second, added up as it comes.
+#[cfg(target_os = "macos")]
+pub struct Meter {
+    child: std::process::Child,
+    microjoules: std::sync::Arc<AtomicU64>,
+}
+
+#[cfg(target_os = "macos")]
+impl Meter {
+    pub fn open() -> io::Result<Self> {
+        use std::io::BufRead;
+        use std::process::{Command, Stdio};
+
+        let mut child = Command::new("powermetrics")
+            .args(["--samplers", "cpu_power", "-i", "1000"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        let stdout = child.stdout.take().unwrap();
+        let microjoules = std::sync::Arc::new(AtomicU64::new(0));
+        let counted = std::sync::Arc::clone(&microjoules);
+        std::thread::spawn(move || {
+            for line in io::BufReader::new(stdout).lines().map_while(Result::ok) {
+                if let Some(milliwatts) = parse_power_line(&line) {
+                    counted.fetch_add((milliwatts * 1000.0) as u64, Ordering::Relaxed);
+                }
+            }
+        });
+        // It gives up at once unless run as root.
+        std::thread::sleep(Duration::from_millis(500));
+        match child.try_wait()? {
+            Some(status) => Err(io::Error::other(format!("powermetrics exited with {}, it needs root", status))),
+            None => Ok(Meter { child, microjoules }),
+        }
+    }
+
+    pub fn sample(&mut self) -> io::Result<u64> {
+        if let Some(status) = self.child.try_wait()? {
+            return Err(io::Error::other(format!("powermetrics exited with {}", status)));
+        }
+        Ok(self.microjoules.swap(0, Ordering::Relaxed))
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl Drop for Meter {
+    fn drop(&mut self) {
+        self.child.kill().ok();
+        self.child.wait().ok();
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub struct Meter;
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+impl Meter {
+    pub fn open() -> io::Result<Self> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "not supported on this platform"))
+    }
+
+    pub fn sample(&mut self) -> io::Result<u64> {
+        Ok(0)
+    }
+}
+
+// Add up what `meter` reads until shutdown, for joules().
+pub fn spawn(mut meter: Meter) {
+    METERING.store(true, Ordering::Relaxed);
+    runtime::spawn_blocking("energy", move |mut shutdown| {
+        while shutdown.sleep_blocking(POLL) {
+            match meter.sample() {
+                Ok(used) => MICROJOULES.fetch_add(used, Ordering::Relaxed),
+                Err(e) => {
+                    METERING.store(false, Ordering::Relaxed);
+                    return eprintln!("Warning: cannot measure energy use any longer: {}", e);
+                }
+            };
+        }
+        // The last of it, for the report.
+        if let Ok(used) = meter.sample() {
+            MICROJOULES.fetch_add(used, Ordering::Relaxed);
+        }
+    });
+}
@@ -0,0 +1,95 @@
+// Vanity externally owned accounts: candidates are random private keys, and a
+// key is only ever written out encrypted, as a keystore file. Writing them
+// takes the `cli` feature; without it a Miner hands keys to its caller alone.
+
+#[cfg(feature = "cli")]
+use std::path::PathBuf;
+#[cfg(feature = "cli")]
+use std::sync::OnceLock;
+
+use k256::ecdsa::SigningKey;
+use rand::rngs::OsRng;
+use rand::RngCore;
+#[cfg(feature = "cli")]
+use zeroize::Zeroizing;
+
+#[cfg(feature = "cli")]
+use crate::persist;
+use crate::tx::key_address;
+
+#[cfg(feature = "cli")]
+struct KeyOutput {
+    dir: PathBuf,
+    password: Zeroizing<String>,
+}
+
+#[cfg(feature = "cli")]
+static OUTPUT: OnceLock<KeyOutput> = OnceLock::new();
+
+// Where reported keys go, encrypted with the passphrase from `password_env`
+// (prompted for twice when unset).
+#[cfg(feature = "cli")]
+pub fn init(dir: PathBuf, password_env: &str) -> Result<(), String> {
+    let password = match std::env::var(password_env) {
+        Ok(password) => Zeroizing::new(password),
+        Err(_) => {
+            let prompt = |prompt: &str| {
+                rpassword::prompt_password(prompt)
+                    .map(Zeroizing::new)
+                    .map_err(|e| format!("cannot read passphrase: {}", e))
+            };
+            let password = prompt("Passphrase for found keys: ")?;
+            if *prompt("Repeat the passphrase: ")? != *password {
+                return Err("passphrases do not match".to_string());
+            }
+            password
+        }
+    };
+    if password.is_empty() {
+        return Err("refusing to write keys with an empty passphrase".to_string());
+    }
+    std::fs::create_dir_all(&dir).map_err(|e| format!("cannot create {}: {}", dir.display(), e))?;
+    OUTPUT.set(KeyOutput { dir, password }).ok();
+    Ok(())
+}
+
+// A valid private key straight from the operating system's CSPRNG, never a
+// seeded or predictable generator.
+pub fn random_key() -> [u8; 32] {
+    let mut key = [0; 32];
+    loop {
+        OsRng.fill_bytes(&mut key);
+        // Rejects zero and values of at least the group order.
+        if SigningKey::from_slice(&key).is_ok() {
+            return key;
+        }
+    }
+}
+
+pub fn address(key: &[u8; 32]) -> [u8; 20] {
+    key_address(&SigningKey::from_slice(key).expect("keys come from random_key"))
+}
+
+// Encrypt `key` into the key directory and say where it went.
+#[cfg(feature = "cli")]
+pub fn save(key: &[u8; 32]) -> String {
+    let Some(output) = OUTPUT.get() else {
+        return "key not saved (no key directory)".to_string();
+    };
+    let name = format!("0x{}.json", hex::encode(address(key)));
+    match eth_keystore::encrypt_key(&output.dir, &mut OsRng, key, output.password.as_bytes(), Some(&name)) {
+        Ok(_) => {
+            let path = output.dir.join(name);
+            match persist::sync(&path) {
+                Ok(()) => format!("key: {}", path.display()),
+                Err(e) => format!("key saved to {} but NOT synced to disk: {}", path.display(), e),
+            }
+        }
+        Err(e) => format!("key NOT saved: {}", e),
+    }
+}
+
+#[cfg(not(feature = "cli"))]
+pub fn save(_: &[u8; 32]) -> String {
+    "key not saved (no key directory)".to_string()
+}
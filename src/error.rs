@@ -0,0 +1,63 @@
+// Errors of the library API, and the exit codes the command-line miner turns
+// them into. A lock poisoned by a panicking thread is not one of them: what
+// the locks guard is updated in a single step, so every module carries on with
+// whatever the lock holds.
+
+use thiserror::Error;
+
+/// What can go wrong embedding or running the miner.
+#[derive(Debug, Error)]
+pub enum MinerError {
+    /// Settings, options or files that cannot work; nothing was started.
+    #[error("{0}")]
+    Config(String),
+    /// Hex that is not hex, or not the expected number of bytes.
+    #[error("{0}")]
+    InvalidHex(String),
+    /// A search thread panicked, say in a callback. The search is over.
+    #[error("the search thread panicked")]
+    SearchPanicked,
+    /// A failure while running: an RPC endpoint, a submission, a file.
+    #[error("{0}")]
+    Runtime(String),
+    /// Addresses or scores disagreeing with known answers, so whatever would
+    /// be mined cannot be trusted.
+    #[error("self-test failed: {0}")]
+    SelfTest(String),
+}
+
+/// The command-line miner's exit codes, for the scripts and schedulers
+/// running it to act on.
+pub mod exit_code {
+    /// The run ended with its target reached, or had no target to reach.
+    pub const TARGET_REACHED: i32 = 0;
+    /// Something failed while running: an RPC endpoint, a submission, a file.
+    pub const FAILED: i32 = 1;
+    /// The salts, the competition or the user's patience ran out before the
+    /// target was reached.
+    pub const BUDGET_EXHAUSTED: i32 = 2;
+    /// The miner cannot start as invoked.
+    pub const CONFIG: i32 = 3;
+    /// The derivation, scoring or search threads cannot be trusted.
+    pub const BACKEND: i32 = 4;
+    /// Stopped by Ctrl-C or SIGTERM.
+    pub const INTERRUPTED: i32 = 130;
+}
+
+impl MinerError {
+    /// The command-line miner's exit code for this error; see [`exit_code`].
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            MinerError::Config(_) | MinerError::InvalidHex(_) => exit_code::CONFIG,
+            MinerError::Runtime(_) => exit_code::FAILED,
+            MinerError::SearchPanicked | MinerError::SelfTest(_) => exit_code::BACKEND,
+        }
+    }
+}
+
+// The command-line tool's modules report errors as strings.
+impl From<MinerError> for String {
+    fn from(error: MinerError) -> Self {
+        error.to_string()
+    }
+}
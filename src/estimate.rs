@@ -0,0 +1,153 @@
+// How long a score takes to find at a given hashrate, and what score a given
+// amount of mining can be expected to reach, from the exact score distribution.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use clap::Args;
+
+use crate::deadline::format_countdown;
+use crate::odds::{chance_at_least, format_count, format_rate};
+use crate::error::MinerError;
+use crate::{compute_score, create2_addr, DEPLOYER_ADDRESS, INITCODE_HASH};
+
+const BENCHMARK_TIME: Duration = Duration::from_secs(3);
+const AGE_OF_UNIVERSE_SECS: f64 = 4.35e17;
+
+/// Estimate the time to reach a score, or the score reachable in some time
+#[derive(Args, Debug)]
+pub struct EstimateArgs {
+    /// Score to estimate the time for
+    #[arg(long, required_unless_present = "hours", conflicts_with = "hours")]
+    pub score: Option<u32>,
+
+    /// Mining time to estimate the reachable score for
+    #[arg(long, required_unless_present = "score")]
+    pub hours: Option<f64>,
+
+    /// Hashrate to assume, e.g. 50MH/s (default: a quick local benchmark)
+    #[arg(long, value_parser = parse_hashrate)]
+    pub hashrate: Option<f64>,
+
+    /// Number of threads for the benchmark (default: all cores)
+    #[arg(short, long)]
+    pub threads: Option<usize>,
+}
+
+// Hashes per second, with an optional k/M/G/T multiplier and H/s unit.
+fn parse_hashrate(value: &str) -> Result<f64, String> {
+    let number = value.trim();
+    let number = number
+        .strip_suffix("H/s")
+        .or_else(|| number.strip_suffix("h/s"))
+        .unwrap_or(number)
+        .trim_end();
+    let (number, multiplier) = match number.char_indices().last() {
+        Some((i, 'k' | 'K')) => (&number[..i], 1e3),
+        Some((i, 'M')) => (&number[..i], 1e6),
+        Some((i, 'G')) => (&number[..i], 1e9),
+        Some((i, 'T')) => (&number[..i], 1e12),
+        _ => (number, 1.0),
+    };
+    match number.trim_end().parse::<f64>() {
+        Ok(rate) if rate > 0.0 && rate.is_finite() => Ok(rate * multiplier),
+        _ => Err(format!("{:?} is not a hashrate like 50MH/s", value)),
+    }
+}
+
+// Hashes per second of the miner's own loop on this machine.
+fn benchmark(threads: usize) -> Result<f64, MinerError> {
+    let (deployer, code_hash) = (DEPLOYER_ADDRESS, INITCODE_HASH);
+    let hashes = Arc::new(AtomicU64::new(0));
+    let stop = Arc::new(AtomicBool::new(false));
+    let start = Instant::now();
+    let handles: Vec<_> = (0..threads)
+        .map(|thread| {
+            let hashes = Arc::clone(&hashes);
+            let stop = Arc::clone(&stop);
+            thread::spawn(move || {
+                let mut salt = [0; 32];
+                salt[..8].copy_from_slice(&(thread as u64).to_be_bytes());
+                let mut count = 0u64;
+                while !stop.load(Ordering::Relaxed) {
+                    for _ in 0..1024 {
+                        count += 1;
+                        salt[24..].copy_from_slice(&count.to_be_bytes());
+                        std::hint::black_box(compute_score(&create2_addr(&deployer, &salt, &code_hash)));
+                    }
+                }
+                hashes.fetch_add(count, Ordering::Relaxed);
+            })
+        })
+        .collect();
+    thread::sleep(BENCHMARK_TIME);
+    stop.store(true, Ordering::Relaxed);
+    for handle in handles {
+        handle.join().map_err(|_| MinerError::SearchPanicked)?;
+    }
+    Ok(hashes.load(Ordering::Relaxed) as f64 / start.elapsed().as_secs_f64())
+}
+
+fn format_time(seconds: f64) -> String {
+    if seconds > AGE_OF_UNIVERSE_SECS {
+        return "longer than the universe has existed".to_string();
+    }
+    if seconds < 1.0 {
+        return "under a second".to_string();
+    }
+    format_countdown(Duration::from_secs_f64(seconds))
+}
+
+// Probability that the best of `hashes` random addresses scores at least `score`.
+fn chance_of_best(score: u32, hashes: f64) -> f64 {
+    -f64::exp_m1(hashes * f64::ln_1p(-chance_at_least(score)))
+}
+
+// Highest score reached with at least `chance` after `hashes`.
+fn score_with_chance(chance: f64, hashes: f64) -> u32 {
+    (1..).take_while(|score| chance_of_best(*score, hashes) >= chance).last().unwrap_or(0)
+}
+
+pub fn run(args: EstimateArgs) -> Result<(), String> {
+    let rate = match args.hashrate {
+        Some(rate) => rate,
+        None => {
+            let threads = match args.threads.unwrap_or(0) {
+                0 => num_cpus::get(),
+                n => n,
+            };
+            println!("Benchmarking {} threads for {}s...", threads, BENCHMARK_TIME.as_secs());
+            benchmark(threads)?
+        }
+    };
+    println!("Hashrate: {}", format_rate(rate));
+
+    if let Some(score) = args.score {
+        let p = chance_at_least(score);
+        if p == 0.0 {
+            return Err(format!("no address can score {}", score));
+        }
+        // Hashes until the first success are geometric with mean 1/p and median ln 2 / p.
+        println!("Score {} or more: 1 in {} addresses", score, format_count(1.0 / p));
+        println!("Expected time: {}", format_time(1.0 / p / rate));
+        println!("Even odds within: {}", format_time(std::f64::consts::LN_2 / p / rate));
+        println!("90% chance within: {}", format_time(std::f64::consts::LN_10 / p / rate));
+    }
+
+    if let Some(hours) = args.hours {
+        if !(hours > 0.0 && hours.is_finite()) {
+            return Err("--hours must be positive".to_string());
+        }
+        let hashes = rate * hours * 3600.0;
+        // E[best] = sum over s >= 1 of P(best >= s).
+        let expected: f64 = (1..).map(|score| chance_of_best(score, hashes)).take_while(|p| *p > 1e-12).sum();
+        println!("In {} hours: {} hashes", hours, format_count(hashes));
+        println!("Expected best score: {:.1}", expected);
+        println!("Even odds of at least: {}", score_with_chance(0.5, hashes));
+        println!("90% chance of at least: {}", score_with_chance(0.9, hashes));
+        println!("10% chance of at least: {}", score_with_chance(0.1, hashes));
+    }
+    Ok(())
+}
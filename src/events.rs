@@ -0,0 +1,110 @@
+use std::net::TcpStream;
+use std::io::ErrorKind;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Message, WebSocket};
+
+use crate::keccak256;
+use crate::monitor::Goal;
+use crate::rpc::{parse_bytes, to_hex};
+use crate::runtime::{self, Shutdown};
+
+type Socket = WebSocket<MaybeTlsStream<TcpStream>>;
+
+// How long a read blocks before checking for shutdown.
+const READ_TIMEOUT: Duration = Duration::from_secs(1);
+
+// `NewAddressFound(address indexed bestAddress, address indexed submitter, uint256 score)`
+struct NewAddressFound {
+    address: [u8; 20],
+    submitter: [u8; 20],
+    score: u32,
+}
+
+fn topic_address(topic: &Value) -> Option<[u8; 20]> {
+    let word = parse_bytes(topic).ok()?;
+    word.get(12..32)?.try_into().ok()
+}
+
+fn decode_event(log: &Value) -> Option<NewAddressFound> {
+    let topics = log["topics"].as_array()?;
+    let data = parse_bytes(&log["data"]).ok()?;
+    Some(NewAddressFound {
+        address: topic_address(topics.get(1)?)?,
+        submitter: topic_address(topics.get(2)?)?,
+        score: u32::from_be_bytes(data.get(28..32)?.try_into().ok()?),
+    })
+}
+
+fn subscribe(ws_url: &str, contract: &[u8; 20]) -> Result<Socket, String> {
+    let (mut socket, _) = tungstenite::connect(ws_url).map_err(|e| format!("cannot connect to {}: {}", ws_url, e))?;
+    let tcp = match socket.get_ref() {
+        MaybeTlsStream::Plain(tcp) => tcp,
+        MaybeTlsStream::Rustls(tls) => tls.get_ref(),
+        _ => unreachable!("no other TLS backend is enabled"),
+    };
+    tcp.set_read_timeout(Some(READ_TIMEOUT)).map_err(|e| e.to_string())?;
+    let topic = keccak256(b"NewAddressFound(address,address,uint256)");
+    let request = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "eth_subscribe",
+        "params": ["logs", { "address": to_hex(contract), "topics": [to_hex(topic.as_slice())] }]
+    });
+    socket
+        .send(Message::Text(request.to_string()))
+        .map_err(|e| format!("eth_subscribe failed: {}", e))?;
+    Ok(socket)
+}
+
+// Returns Ok once shutdown is requested.
+fn follow(socket: &mut Socket, goal: &Goal, shutdown: &Shutdown) -> Result<(), String> {
+    while !shutdown.requested() {
+        let message = match socket.read() {
+            Ok(message) => message,
+            Err(tungstenite::Error::Io(e)) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => continue,
+            Err(e) => return Err(e.to_string()),
+        };
+        let text = match message {
+            Message::Text(text) => text,
+            Message::Close(_) => return Err("connection closed".to_string()),
+            _ => continue,
+        };
+        let message: Value = serde_json::from_str(&text).map_err(|e| format!("invalid message: {}", e))?;
+        if let Some(error) = message.get("error") {
+            return Err(format!("eth_subscribe failed: {}", error));
+        }
+        if let Some(event) = decode_event(&message["params"]["result"]) {
+            println!(
+                "NewAddressFound: 0x{} with score: {} by 0x{}",
+                hex::encode(event.address),
+                event.score,
+                hex::encode(event.submitter)
+            );
+            goal.raise(event.score);
+        }
+    }
+    socket.close(None).ok();
+    Ok(())
+}
+
+// Subscribe to the contract's NewAddressFound events over WebSocket and raise
+// the target as soon as someone takes the lead. Reconnects on failure.
+pub fn spawn(ws_url: String, contract: [u8; 20], goal: Arc<Goal>) {
+    runtime::spawn_blocking("event subscription", move |mut shutdown| loop {
+        match subscribe(&ws_url, &contract) {
+            Ok(mut socket) => {
+                if let Err(e) = follow(&mut socket, &goal, &shutdown) {
+                    eprintln!("Warning: event subscription: {}", e);
+                }
+            }
+            Err(e) => eprintln!("Warning: event subscription: {}", e),
+        }
+        if !shutdown.sleep_blocking(Duration::from_secs(5)) {
+            break;
+        }
+    });
+}
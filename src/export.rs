@@ -0,0 +1,83 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use clap::ValueEnum;
+use serde_json::json;
+
+use crate::rpc::to_hex;
+use crate::tx::to_checksum;
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum ExportFormat {
+    /// Foundry script to run with `forge script --broadcast`
+    Foundry,
+    /// Safe Transaction Builder batch JSON
+    Safe,
+}
+
+pub fn render(format: ExportFormat, contract: &[u8; 20], salt: &[u8; 32], chain_id: u64) -> String {
+    match format {
+        ExportFormat::Foundry => foundry_script(contract, salt),
+        ExportFormat::Safe => safe_batch(contract, salt, chain_id),
+    }
+}
+
+fn foundry_script(contract: &[u8; 20], salt: &[u8; 32]) -> String {
+    format!(
+        r#"// SPDX-License-Identifier: UNLICENSED
+pragma solidity ^0.8.0;
+
+import {{Script}} from "forge-std/Script.sol";
+
+interface IUniswapV4DeployerCompetition {{
+    function updateBestAddress(bytes32 salt) external;
+}}
+
+// The broadcasting account must be {submitter}
+// (or the salt must start with 20 zero bytes).
+contract SubmitBestAddress is Script {{
+    IUniswapV4DeployerCompetition constant COMPETITION = IUniswapV4DeployerCompetition({contract});
+    bytes32 constant SALT = {salt};
+
+    function run() external {{
+        vm.startBroadcast();
+        COMPETITION.updateBestAddress(SALT);
+        vm.stopBroadcast();
+    }}
+}}
+"#,
+        submitter = to_checksum(&salt[..20].try_into().unwrap()),
+        contract = to_checksum(contract),
+        salt = to_hex(salt),
+    )
+}
+
+// https://help.safe.global/en/articles/40841-transaction-builder
+fn safe_batch(contract: &[u8; 20], salt: &[u8; 32], chain_id: u64) -> String {
+    let created_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    let safe = to_checksum(&salt[..20].try_into().unwrap());
+    let batch = json!({
+        "version": "1.0",
+        "chainId": chain_id.to_string(),
+        "createdAt": created_at,
+        "meta": {
+            "name": "Uniswap v4 address challenge submission",
+            "description": format!("updateBestAddress({}); must be executed by Safe {}", to_hex(salt), safe),
+            "createdFromSafeAddress": safe,
+        },
+        "transactions": [{
+            "to": to_checksum(contract),
+            "value": "0",
+            "data": null,
+            "contractMethod": {
+                "inputs": [{ "internalType": "bytes32", "name": "salt", "type": "bytes32" }],
+                "name": "updateBestAddress",
+                "payable": false
+            },
+            "contractInputsValues": { "salt": to_hex(salt) }
+        }]
+    });
+    serde_json::to_string_pretty(&batch).unwrap()
+}
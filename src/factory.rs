@@ -0,0 +1,49 @@
+// Well-known CREATE2 factories to mine salts for instead of the challenge
+// contract, with the rules they put on salts.
+
+use clap::ValueEnum;
+
+use crate::error::MinerError;
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Factory {
+    /// 0age's ImmutableCreate2Factory (safeCreate2), at the same address on most chains
+    ImmutableCreate2,
+}
+
+impl Factory {
+    pub fn address(self) -> [u8; 20] {
+        match self {
+            Factory::ImmutableCreate2 => crate::const_hex("0000000000FFe8B47B3e2130213B802212439497"),
+        }
+    }
+
+    // Whether `caller` may deploy with `salt`. ImmutableCreate2Factory requires
+    // the salt to start with the caller's address or with 20 zero bytes
+    // (anyone may use it).
+    pub fn allows(self, salt: &[u8; 32], caller: &[u8; 20]) -> bool {
+        match self {
+            Factory::ImmutableCreate2 => salt[..20] == caller[..] || salt[..20] == [0; 20],
+        }
+    }
+
+    pub fn check_salt(self, salt: &[u8; 32], caller: &[u8; 20]) -> Result<(), MinerError> {
+        if self.allows(salt, caller) {
+            return Ok(());
+        }
+        Err(MinerError::Config(format!(
+            "salt 0x{} cannot be used by 0x{}: it must start with the caller's address or 20 zero bytes",
+            hex::encode(salt),
+            hex::encode(caller)
+        )))
+    }
+
+    pub fn deploy_hint(self, caller: &[u8; 20]) -> String {
+        let from = if *caller == [0; 20] { "any account".to_string() } else { format!("0x{}", hex::encode(caller)) };
+        match self {
+            Factory::ImmutableCreate2 => {
+                format!("Deploy with safeCreate2(salt, initcode) on 0x{} from {}", hex::encode(self.address()), from)
+            }
+        }
+    }
+}
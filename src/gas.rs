@@ -0,0 +1,111 @@
+use std::time::Duration;
+
+use clap::Args;
+
+const GWEI: f64 = 1e9;
+
+/// EIP-1559 fee configuration for submission transactions
+#[derive(Args, Debug, Clone)]
+pub struct GasStrategy {
+    /// Hard cap on maxFeePerGas, in gwei
+    #[arg(long, value_name = "GWEI")]
+    pub max_fee: Option<f64>,
+
+    /// Fixed maxPriorityFeePerGas in gwei (default: the node's suggestion)
+    #[arg(long, value_name = "GWEI")]
+    pub priority_fee: Option<f64>,
+
+    /// maxFeePerGas is this multiple of the current base fee, plus the priority fee
+    #[arg(long, default_value_t = 2.0)]
+    pub base_fee_multiplier: f64,
+
+    /// Escalate fees when the competition deadline is closer than this (e.g. 10m)
+    #[arg(long, value_name = "DURATION", value_parser = humantime::parse_duration)]
+    pub urgent_within: Option<Duration>,
+
+    /// Fee multiplier reached at the deadline in urgent mode, ramping up linearly
+    #[arg(long, default_value_t = 3.0)]
+    pub urgent_multiplier: f64,
+
+    /// Replace a pending submission with higher fees after this long (e.g. 1m)
+    #[arg(long, value_name = "DURATION", default_value = "1m", value_parser = humantime::parse_duration)]
+    pub replace_after: Duration,
+
+    /// Maximum number of fee-bumping replacements per submission
+    #[arg(long, default_value_t = 5)]
+    pub max_replacements: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fees {
+    pub max_fee_per_gas: u128,
+    pub max_priority_fee_per_gas: u128,
+}
+
+impl GasStrategy {
+    // Fees for a transaction given the current base fee, the node's suggested
+    // tip, and the time left until the deadline (if known).
+    pub fn fees(&self, base_fee: u128, suggested_tip: u128, time_left: Option<Duration>) -> Fees {
+        let escalation = self.escalation(time_left);
+        let tip = self
+            .priority_fee
+            .map(|gwei| (gwei * GWEI) as u128)
+            .unwrap_or(suggested_tip);
+        let tip = (tip as f64 * escalation) as u128;
+        let mut max_fee = (base_fee as f64 * self.base_fee_multiplier * escalation) as u128 + tip;
+        if let Some(cap) = self.max_fee {
+            max_fee = max_fee.min((cap * GWEI) as u128);
+        }
+        Fees {
+            max_fee_per_gas: max_fee,
+            max_priority_fee_per_gas: tip.min(max_fee),
+        }
+    }
+
+    // 1.0 outside the urgent window, rising linearly to `urgent_multiplier` at the deadline.
+    pub fn escalation(&self, time_left: Option<Duration>) -> f64 {
+        match (self.urgent_within, time_left) {
+            (Some(window), Some(left)) if left < window && !window.is_zero() => {
+                let progress = 1.0 - left.as_secs_f64() / window.as_secs_f64();
+                1.0 + (self.urgent_multiplier - 1.0).max(0.0) * progress
+            }
+            _ => 1.0,
+        }
+    }
+
+    // Fees for a replacement: at least 12.5% above the previous ones (nodes
+    // require 10%), or the current strategy fees if higher. None if the
+    // --max-fee cap leaves no room.
+    pub fn bump(&self, previous: Fees, current: Fees) -> Option<Fees> {
+        let bumped = |fee: u128| fee + fee / 8 + 1;
+        let tip = bumped(previous.max_priority_fee_per_gas).max(current.max_priority_fee_per_gas);
+        let max_fee = bumped(previous.max_fee_per_gas).max(current.max_fee_per_gas).max(tip);
+        if let Some(cap) = self.max_fee {
+            if max_fee > (cap * GWEI) as u128 {
+                return None;
+            }
+        }
+        Some(Fees { max_fee_per_gas: max_fee, max_priority_fee_per_gas: tip })
+    }
+
+    // A --max-fee below the base fee leaves the transaction unincludable
+    // until the base fee falls, its salt public meanwhile.
+    pub fn check_cap(&self, base_fee: u128) -> Result<(), String> {
+        match self.max_fee {
+            Some(cap) if ((cap * GWEI) as u128) < base_fee => Err(format!(
+                "--max-fee {} is below the base fee of {}, the transaction could not be included",
+                format_gwei((cap * GWEI) as u128),
+                format_gwei(base_fee)
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn is_urgent(&self, time_left: Option<Duration>) -> bool {
+        self.escalation(time_left) > 1.0
+    }
+}
+
+pub fn format_gwei(wei: u128) -> String {
+    format!("{:.3} gwei", wei as f64 / GWEI)
+}
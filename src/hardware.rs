@@ -0,0 +1,198 @@
+// Ledger and Trezor signers, compiled in with the `ledger` and `trezor` features.
+
+// Parse a BIP-32 derivation path such as m/44'/60'/0'/0/0.
+pub fn parse_hd_path(path: &str) -> Result<Vec<u32>, String> {
+    let mut parts = path.split('/');
+    if parts.next() != Some("m") {
+        return Err(format!("derivation path must start with m/: {}", path));
+    }
+    parts
+        .map(|part| {
+            let (index, hardened) = match part.strip_suffix('\'') {
+                Some(index) => (index, true),
+                None => (part, false),
+            };
+            let index: u32 = index
+                .parse()
+                .map_err(|_| format!("invalid derivation path component: {}", part))?;
+            if index >= 0x8000_0000 {
+                return Err(format!("derivation path component out of range: {}", part));
+            }
+            Ok(if hardened { index | 0x8000_0000 } else { index })
+        })
+        .collect()
+}
+
+#[cfg(feature = "ledger")]
+pub mod ledger {
+    use coins_ledger::transports::native::hid::TransportNativeHID;
+    use coins_ledger::APDUCommand;
+
+    use crate::tx::{eip191_hash, encode_signature, recover_parity, Eip1559Tx};
+
+    // Ethereum app instructions, see
+    // https://github.com/LedgerHQ/app-ethereum/blob/develop/doc/ethapp.adoc
+    const CLA: u8 = 0xE0;
+    const INS_GET_ADDRESS: u8 = 0x02;
+    const INS_SIGN_TRANSACTION: u8 = 0x04;
+    const INS_SIGN_PERSONAL_MESSAGE: u8 = 0x08;
+    const P1_FIRST_CHUNK: u8 = 0x00;
+    const P1_MORE_CHUNKS: u8 = 0x80;
+    const MAX_CHUNK: usize = 255;
+
+    pub struct Ledger {
+        transport: TransportNativeHID,
+        path: Vec<u8>,
+        address: [u8; 20],
+    }
+
+    impl Ledger {
+        pub fn connect(hd_path: &[u32]) -> Result<Self, String> {
+            let transport = TransportNativeHID::new()
+                .map_err(|e| format!("cannot open Ledger (is it unlocked with the Ethereum app open?): {}", e))?;
+            let mut path = vec![hd_path.len() as u8];
+            for index in hd_path {
+                path.extend_from_slice(&index.to_be_bytes());
+            }
+            let mut ledger = Ledger { transport, path, address: [0; 20] };
+            ledger.address = ledger.fetch_address()?;
+            Ok(ledger)
+        }
+
+        pub fn address(&self) -> [u8; 20] {
+            self.address
+        }
+
+        fn exchange(&self, ins: u8, p1: u8, data: &[u8]) -> Result<Vec<u8>, String> {
+            let command = APDUCommand {
+                cla: CLA,
+                ins,
+                p1,
+                p2: 0x00,
+                data: data.into(),
+                response_len: None,
+            };
+            let answer = self
+                .transport
+                .exchange(&command)
+                .map_err(|e| format!("Ledger error: {}", e))?;
+            answer
+                .data()
+                .map(<[u8]>::to_vec)
+                .ok_or_else(|| format!("Ledger returned status {:#06x}", answer.retcode()))
+        }
+
+        // Response layout: pubkey length, pubkey, address length, ASCII hex address.
+        fn fetch_address(&self) -> Result<[u8; 20], String> {
+            let response = self.exchange(INS_GET_ADDRESS, 0x00, &self.path)?;
+            let address = response
+                .first()
+                .map(|len| 1 + *len as usize)
+                .and_then(|offset| {
+                    let len = *response.get(offset)? as usize;
+                    response.get(offset + 1..offset + 1 + len)
+                })
+                .ok_or("malformed Ledger address response")?;
+            let address = std::str::from_utf8(address).map_err(|e| e.to_string())?;
+            crate::decode_hex::<20>(address).map_err(String::from)
+        }
+
+        // Send `payload` in chunks and return the (r, s) of the final response,
+        // which is laid out as v, r, s.
+        fn sign(&self, ins: u8, payload: &[u8]) -> Result<([u8; 32], [u8; 32]), String> {
+            let mut response = Vec::new();
+            for (i, chunk) in payload.chunks(MAX_CHUNK).enumerate() {
+                let p1 = if i == 0 { P1_FIRST_CHUNK } else { P1_MORE_CHUNKS };
+                response = self.exchange(ins, p1, chunk)?;
+            }
+            if response.len() < 65 {
+                return Err("malformed Ledger signature".to_string());
+            }
+            Ok((response[1..33].try_into().unwrap(), response[33..65].try_into().unwrap()))
+        }
+
+        pub fn sign_transaction(&self, tx: &Eip1559Tx) -> Result<Vec<u8>, String> {
+            let mut payload = self.path.clone();
+            payload.extend(tx.unsigned_payload());
+            let (r, s) = self.sign(INS_SIGN_TRANSACTION, &payload)?;
+            let y_odd = recover_parity(&tx.signing_hash(), &r, &s, &self.address)?;
+            Ok(tx.encode_signed(y_odd, &r, &s))
+        }
+
+        pub fn sign_message(&self, message: &[u8]) -> Result<[u8; 65], String> {
+            let mut payload = self.path.clone();
+            payload.extend_from_slice(&(message.len() as u32).to_be_bytes());
+            payload.extend_from_slice(message);
+            let (r, s) = self.sign(INS_SIGN_PERSONAL_MESSAGE, &payload)?;
+            let y_odd = recover_parity(&eip191_hash(message), &r, &s, &self.address)?;
+            Ok(encode_signature(y_odd, &r, &s))
+        }
+    }
+}
+
+#[cfg(feature = "trezor")]
+pub mod trezor {
+    use trezor_client::client::Trezor as Client;
+
+    use crate::rpc::to_hex;
+    use crate::tx::{eip191_hash, encode_signature, recover_parity, Eip1559Tx};
+
+    pub struct Trezor {
+        client: Client,
+        path: Vec<u32>,
+        address: [u8; 20],
+    }
+
+    fn be_trimmed(value: u128) -> Vec<u8> {
+        let bytes = value.to_be_bytes();
+        let skip = bytes.iter().take_while(|b| **b == 0).count();
+        bytes[skip..].to_vec()
+    }
+
+    impl Trezor {
+        pub fn connect(hd_path: &[u32]) -> Result<Self, String> {
+            let mut client = trezor_client::unique(false).map_err(|e| format!("cannot open Trezor: {}", e))?;
+            client
+                .init_device(None)
+                .map_err(|e| format!("cannot initialize Trezor: {}", e))?;
+            let address = client
+                .ethereum_get_address(hd_path.to_vec())
+                .map_err(|e| format!("Trezor error: {}", e))?;
+            let address = crate::decode_hex::<20>(&address)?;
+            Ok(Trezor { client, path: hd_path.to_vec(), address })
+        }
+
+        pub fn address(&self) -> [u8; 20] {
+            self.address
+        }
+
+        pub fn sign_transaction(&mut self, tx: &Eip1559Tx) -> Result<Vec<u8>, String> {
+            let signature = self
+                .client
+                .ethereum_sign_eip1559_tx(
+                    self.path.clone(),
+                    be_trimmed(tx.nonce as u128),
+                    be_trimmed(tx.gas_limit as u128),
+                    to_hex(&tx.to),
+                    be_trimmed(tx.value),
+                    tx.data.clone(),
+                    Some(tx.chain_id),
+                    be_trimmed(tx.max_fee_per_gas),
+                    be_trimmed(tx.max_priority_fee_per_gas),
+                    Vec::new(),
+                )
+                .map_err(|e| format!("Trezor error: {}", e))?;
+            let y_odd = recover_parity(&tx.signing_hash(), &signature.r, &signature.s, &self.address)?;
+            Ok(tx.encode_signed(y_odd, &signature.r, &signature.s))
+        }
+
+        pub fn sign_message(&mut self, message: &[u8]) -> Result<[u8; 65], String> {
+            let signature = self
+                .client
+                .ethereum_sign_message(message.to_vec(), self.path.clone())
+                .map_err(|e| format!("Trezor error: {}", e))?;
+            let y_odd = recover_parity(&eip191_hash(message), &signature.r, &signature.s, &self.address)?;
+            Ok(encode_signature(y_odd, &signature.r, &signature.s))
+        }
+    }
+}
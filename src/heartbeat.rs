@@ -0,0 +1,99 @@
+// A status line every --status-interval, so a long run shows it is making
+// progress even when hours pass without a new best, with the same numbers
+// handed to whatever records them (--stats-csv, --db).
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use crate::odds;
+use crate::salts::{format_coverage, Coverage};
+use crate::search::{self, Control};
+use crate::{color, deadline, energy, runtime, Candidate};
+
+// Where the search stands at a status line.
+pub struct Status {
+    pub hashes: u64,
+    // Hashes a second since the last line.
+    pub hashrate: f64,
+    pub best: Option<Candidate>,
+}
+
+pub type Recorder = Box<dyn Fn(&Status) + Send>;
+
+// Print elapsed time, hashes, the hashrate since the last line and the best so
+// far every `interval`, and hand them to each of `recorders`. Given the
+// `target` of challenge scoring, also the time something beating it is
+// expected to take, and the share of the salts tried where `coverage` knows
+// it. With --energy, the watts and hashes per joule since the last line too.
+// With several threads, their hashrates follow, flagging the slow ones.
+pub fn spawn(
+    interval: Duration,
+    best: Arc<Mutex<Option<Candidate>>>,
+    target: Option<Arc<AtomicU32>>,
+    coverage: Arc<Coverage>,
+    control: Arc<Control>,
+    recorders: Vec<Recorder>,
+) {
+    runtime::spawn("heartbeat", move |mut shutdown| async move {
+        let started = Instant::now();
+        let mut last = (started, control.hashes.load(Ordering::Relaxed));
+        let mut last_joules = energy::joules();
+        while shutdown.sleep(interval).await {
+            let now = (Instant::now(), control.hashes.load(Ordering::Relaxed));
+            let rate = (now.1 - last.1) as f64 / now.0.duration_since(last.0).as_secs_f64();
+            let joules = energy::joules();
+            let power = match (last_joules, joules) {
+                (Some(before), Some(joules)) if joules > before => {
+                    let used = joules - before;
+                    let watts = used / now.0.duration_since(last.0).as_secs_f64();
+                    format!(", {:.1} W for {}", watts, energy::format_efficiency((now.1 - last.1) as f64 / used))
+                }
+                _ => String::new(),
+            };
+            (last, last_joules) = (now, joules);
+            let status = Status { hashes: now.1, hashrate: rate, best: *best.lock().unwrap_or_else(PoisonError::into_inner) };
+            for record in &recorders {
+                record(&status);
+            }
+            let best = match status.best {
+                Some(c) => format!("best score {} at {}", c.score, color::address(&c.address)),
+                None => "nothing found yet".to_string(),
+            };
+            let eta = match &target {
+                Some(target) => format!(", next better expected in {}", odds::format_eta(target.load(Ordering::Relaxed), rate)),
+                None => String::new(),
+            };
+            let taken = control.taken.lock().unwrap_or_else(PoisonError::into_inner).clone();
+            let covered = match coverage.fraction(&taken) {
+                Some(fraction) => format!(" ({} of the salts)", format_coverage(fraction)),
+                None => String::new(),
+            };
+            println!(
+                "Status: {} elapsed, {} hashes at {}{}{}, {}{}",
+                deadline::format_countdown(started.elapsed()),
+                odds::format_count(now.1 as f64),
+                odds::format_rate(rate),
+                covered,
+                power,
+                best,
+                eta
+            );
+            let rates = control.rates.lock().unwrap_or_else(PoisonError::into_inner).clone();
+            if rates.len() > 1 {
+                println!("  Threads: {}", threads(&rates));
+            }
+        }
+    });
+}
+
+// Each of `rates`, with the slow ones marked.
+pub fn threads(rates: &[f64]) -> String {
+    let slow = search::slow(rates);
+    rates
+        .iter()
+        .zip(slow)
+        .map(|(&rate, slow)| if slow { format!("{} (slow)", odds::format_rate(rate)) } else { odds::format_rate(rate) })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
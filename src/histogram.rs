@@ -0,0 +1,90 @@
+// Counts of every score computed, written out as JSON next to what the exact
+// distribution predicts, to check the scoring against theory and to see how
+// quickly higher scores thin out.
+
+#[cfg(feature = "cli")]
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(feature = "cli")]
+use std::sync::Arc;
+#[cfg(feature = "cli")]
+use std::time::Duration;
+
+use serde_json::json;
+
+use crate::odds;
+#[cfg(feature = "cli")]
+use crate::{persist, runtime};
+
+// Scores from this value up share the last bucket. The challenge's maximum is 400.
+pub const BUCKETS: usize = 401;
+
+pub struct Histogram {
+    buckets: Vec<AtomicU64>,
+    // Whether the scores follow the challenge's distribution.
+    theory: bool,
+}
+
+impl Histogram {
+    pub fn new(theory: bool) -> Self {
+        Histogram { buckets: (0..BUCKETS).map(|_| AtomicU64::new(0)).collect(), theory }
+    }
+
+    // Index into a worker's local counts for `score`.
+    pub fn bucket(score: u32) -> usize {
+        (score as usize).min(BUCKETS - 1)
+    }
+
+    // Add a worker's local counts (BUCKETS long) and reset them.
+    pub fn flush(&self, counts: &mut [u64]) {
+        for (bucket, count) in self.buckets.iter().zip(counts.iter_mut()) {
+            if *count > 0 {
+                bucket.fetch_add(*count, Ordering::Relaxed);
+                *count = 0;
+            }
+        }
+    }
+
+    // The counts so far, and what theory expects of them.
+    pub fn to_json(&self) -> serde_json::Value {
+        let counts: Vec<u64> = self.buckets.iter().map(|b| b.load(Ordering::Relaxed)).collect();
+        let total: u64 = counts.iter().sum();
+        let scores: Vec<_> = counts
+            .iter()
+            .enumerate()
+            .filter_map(|(score, &count)| {
+                let expected = self.theory.then(|| total as f64 * odds::chance_of(score as u32));
+                // Scores neither seen nor expected at least once are left out.
+                if count == 0 && !matches!(expected, Some(expected) if expected >= 1.0) {
+                    return None;
+                }
+                let mut entry = json!({ "score": score, "count": count });
+                if let Some(expected) = expected {
+                    entry["expected"] = json!(expected);
+                }
+                Some(entry)
+            })
+            .collect();
+        json!({ "hashes": total, "last_bucket_from": BUCKETS - 1, "scores": scores })
+    }
+}
+
+#[cfg(feature = "cli")]
+fn write(path: &Path, histogram: &Histogram) {
+    let json = serde_json::to_string_pretty(&histogram.to_json()).unwrap();
+    if let Err(e) = persist::write(path, json + "\n") {
+        eprintln!("Cannot write histogram to {}: {}", path.display(), e);
+    }
+}
+
+// Rewrite `path` with the histogram every `interval`, and a last time on
+// shutdown.
+#[cfg(feature = "cli")]
+pub fn spawn(path: PathBuf, interval: Duration, histogram: Arc<Histogram>) {
+    runtime::spawn("histogram", move |mut shutdown| async move {
+        while shutdown.sleep(interval).await {
+            write(&path, &histogram);
+        }
+        write(&path, &histogram);
+    });
+}
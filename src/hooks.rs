@@ -0,0 +1,42 @@
+// What a search has to tell the outside world, handed to every subscribed
+// sink in turn: stdout, files, webhooks, a coordinator, a TUI. The search
+// itself only ever emits.
+
+use std::sync::{PoisonError, RwLock};
+
+use crate::error::MinerError;
+use crate::miner::Stats;
+use crate::Candidate;
+
+/// Something that happened in a search.
+#[derive(Debug)]
+pub enum Event<'a> {
+    /// A candidate beating every one before it (and the target).
+    NewBest(&'a Candidate),
+    /// Where the search stands, every progress interval.
+    Progress(&'a Stats),
+    /// Something failed, the search itself or a service around it.
+    Error(&'a MinerError),
+}
+
+/// Called with every event, from whichever thread emits it: workers for new
+/// bests, which wait meanwhile, so keep it quick.
+pub type Sink = Box<dyn Fn(&Event) + Send + Sync>;
+
+/// Sinks subscribed to a search's events.
+#[derive(Default)]
+pub struct EventBus {
+    sinks: RwLock<Vec<Sink>>,
+}
+
+impl EventBus {
+    pub fn subscribe(&self, sink: impl Fn(&Event) + Send + Sync + 'static) {
+        self.sinks.write().unwrap_or_else(PoisonError::into_inner).push(Box::new(sink));
+    }
+
+    pub fn emit(&self, event: Event) {
+        for sink in self.sinks.read().unwrap_or_else(PoisonError::into_inner).iter() {
+            sink(&event);
+        }
+    }
+}
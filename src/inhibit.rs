@@ -0,0 +1,130 @@
+// Keeping the machine from going to sleep while the search runs, with
+// --keep-awake: systemd-inhibit on Linux, caffeinate (an IOKit assertion) on
+// macOS and SetThreadExecutionState on Windows. The hold is let go while the
+// search is paused and when the run ends, and with the process should it die.
+
+use std::io;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::runtime;
+use crate::search::Control;
+
+// How often a pause or resume is picked up.
+const POLL: Duration = Duration::from_secs(5);
+
+pub const SUPPORTED: bool = cfg!(any(target_os = "linux", target_os = "macos", windows));
+
+const WHY: &str = "Mining a salt";
+
+// Keeps the machine awake until dropped.
+pub struct Hold {
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    child: std::process::Child,
+}
+
+#[cfg(target_os = "linux")]
+impl Hold {
+    // The inhibitor lasts as long as systemd-inhibit's `cat` reads its stdin,
+    // which ends when the pipe to it closes, however this process ends.
+    pub fn new() -> io::Result<Self> {
+        use std::process::{Command, Stdio};
+
+        let child = Command::new("systemd-inhibit")
+            .args(["--what=sleep:idle", "--mode=block", "--who", env!("CARGO_PKG_NAME"), "--why", WHY, "cat"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .spawn()?;
+        let mut hold = Hold { child };
+        // It gives up at once without logind to talk to.
+        std::thread::sleep(Duration::from_millis(200));
+        match hold.child.try_wait()? {
+            Some(status) => Err(io::Error::other(format!("systemd-inhibit exited with {}", status))),
+            None => Ok(hold),
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl Hold {
+    // caffeinate holds a PreventUserIdleSystemSleep assertion until killed,
+    // or until this process exits.
+    pub fn new() -> io::Result<Self> {
+        use std::process::{Command, Stdio};
+
+        let child = Command::new("caffeinate")
+            .args(["-i", "-w", &std::process::id().to_string()])
+            .stdin(Stdio::null())
+            .spawn()?;
+        Ok(Hold { child })
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+impl Drop for Hold {
+    fn drop(&mut self) {
+        // Closing stdin lets systemd-inhibit go; caffeinate has to be killed.
+        drop(self.child.stdin.take());
+        if cfg!(target_os = "macos") {
+            self.child.kill().ok();
+        }
+        self.child.wait().ok();
+    }
+}
+
+// SetThreadExecutionState is per thread, so a Hold is only made and dropped
+// on the one thread watching the search.
+#[cfg(windows)]
+impl Hold {
+    pub fn new() -> io::Result<Self> {
+        use windows_sys::Win32::System::Power::{SetThreadExecutionState, ES_CONTINUOUS, ES_SYSTEM_REQUIRED};
+
+        if unsafe { SetThreadExecutionState(ES_CONTINUOUS | ES_SYSTEM_REQUIRED) } == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Hold {})
+    }
+}
+
+#[cfg(windows)]
+impl Drop for Hold {
+    fn drop(&mut self) {
+        use windows_sys::Win32::System::Power::{SetThreadExecutionState, ES_CONTINUOUS};
+
+        unsafe { SetThreadExecutionState(ES_CONTINUOUS) };
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+impl Hold {
+    pub fn new() -> io::Result<Self> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "not supported on this platform"))
+    }
+}
+
+// Whether the machine should be held awake: while the search runs unpaused.
+pub fn wanted(control: &Control) -> bool {
+    !control.paused.load(Ordering::Relaxed) && !control.stopped.load(Ordering::Relaxed)
+}
+
+// Keep the machine awake while the search runs, until shutdown.
+pub fn spawn(control: Arc<Control>) {
+    // Blocking, so the hold is taken and let go on the same thread.
+    runtime::spawn_blocking("keep awake", move |mut shutdown| {
+        let mut hold = None;
+        loop {
+            match (wanted(&control), hold.is_some()) {
+                (true, false) => match Hold::new() {
+                    Ok(new) => hold = Some(new),
+                    Err(e) => return eprintln!("Warning: cannot keep the system awake: {}", e),
+                },
+                (false, true) => hold = None,
+                _ => {}
+            }
+            if !shutdown.sleep_blocking(POLL) {
+                break;
+            }
+        }
+    });
+}
@@ -0,0 +1,61 @@
+// Derive the initcode hash from the initcode itself instead of trusting a
+// hardcoded or typed-in value: mining against the wrong hash wastes the whole run.
+
+use std::fmt;
+
+use crate::rpc::{parse_bytes, RpcClient};
+use crate::keccak256;
+
+pub enum InitcodeSource {
+    // A contract whose runtime code is the initcode (e.g. an SSTORE2 data
+    // contract, minus its leading STOP byte).
+    Code { address: [u8; 20], skip: usize },
+    // A contract-creation transaction whose input is the initcode.
+    CreationTx(String),
+}
+
+impl fmt::Display for InitcodeSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InitcodeSource::Code { address, .. } => write!(f, "the code of 0x{}", hex::encode(address)),
+            InitcodeSource::CreationTx(hash) => write!(f, "transaction {}", hash),
+        }
+    }
+}
+
+pub fn fetch(rpc: &RpcClient, source: &InitcodeSource) -> Result<Vec<u8>, String> {
+    let initcode = match source {
+        InitcodeSource::Code { address, skip } => {
+            let code = rpc.get_code(address)?;
+            code.get(*skip..).map(<[u8]>::to_vec).unwrap_or_default()
+        }
+        InitcodeSource::CreationTx(hash) => {
+            let tx = rpc.transaction_by_hash(hash)?.ok_or_else(|| format!("transaction {} not found", hash))?;
+            if !tx["to"].is_null() {
+                return Err(format!("transaction {} is not a contract creation", hash));
+            }
+            parse_bytes(&tx["input"])?
+        }
+    };
+    if initcode.is_empty() {
+        return Err(format!("{} holds no initcode", source));
+    }
+    Ok(initcode)
+}
+
+// Fetch and hash the initcode, refusing if it disagrees with the hash the user
+// (or the network preset) expects.
+pub fn verified_hash(rpc: &RpcClient, source: &InitcodeSource, expected: Option<[u8; 32]>) -> Result<[u8; 32], String> {
+    let initcode = fetch(rpc, source)?;
+    let hash = keccak256(&initcode).0;
+    println!("Initcode from {} ({} bytes) hashes to 0x{}", source, initcode.len(), hex::encode(hash));
+    match expected {
+        Some(expected) if expected != hash => Err(format!(
+            "initcode hash mismatch: expected 0x{} but {} hashes to 0x{}",
+            hex::encode(expected),
+            source,
+            hex::encode(hash)
+        )),
+        _ => Ok(hash),
+    }
+}
@@ -0,0 +1,466 @@
+//! Mining CREATE2 salts for the Uniswap v4 address challenge, as a library.
+//!
+//! The primitives, [`create2_addr`] and [`compute_score`] (and the CREATE and
+//! CREATE3 derivations next to them), are what the challenge contract itself
+//! computes. [`miner::Miner`] runs the multi-threaded search in the background,
+//! with callbacks for new bests; [`search::Search`] is the search itself, as
+//! the command-line miner runs it, and [`score`], [`mode`] and [`odds`] hold the
+//! scoring rules, address derivations and score statistics it builds on. The
+//! remaining modules back the command-line tool (RPC, signing, submission),
+//! come with the default `cli` feature and are public so it can use them, not
+//! as a stable interface.
+//!
+//! With `std` alone, the search and [`miner::Miner`] are built without the
+//! command line's dependencies (clap, tokio, the RPC and websocket clients and
+//! keystores). Without the default `std` feature only the items at the crate
+//! root are built, `no_std` and without allocating, for embedded verifiers and
+//! zkVM guests.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use tiny_keccak::{Hasher, Keccak};
+
+// The Ethereum types the std API speaks. The derivations below take raw
+// arrays, which `&Address` and `&B256` deref to.
+#[cfg(feature = "std")]
+pub use alloy_primitives::{keccak256, Address, B256};
+
+#[cfg(feature = "cli")]
+pub mod budget;
+#[cfg(feature = "cli")]
+pub mod challenge;
+#[cfg(feature = "cli")]
+pub mod checkpoint;
+#[cfg(feature = "std")]
+pub mod codehash;
+#[cfg(feature = "cli")]
+pub mod cli;
+#[cfg(feature = "std")]
+pub mod color;
+#[cfg(feature = "cli")]
+pub mod commands;
+#[cfg(feature = "cli")]
+pub mod daemon;
+#[cfg(feature = "sqlite")]
+pub mod db;
+#[cfg(feature = "cli")]
+pub mod deadline;
+#[cfg(feature = "cli")]
+pub mod energy;
+#[cfg(feature = "std")]
+pub mod eoa;
+#[cfg(feature = "std")]
+pub mod error;
+#[cfg(feature = "cli")]
+pub mod estimate;
+#[cfg(feature = "cli")]
+pub mod events;
+#[cfg(feature = "cli")]
+pub mod export;
+#[cfg(feature = "cli")]
+pub mod factory;
+#[cfg(feature = "cli")]
+pub mod gas;
+#[cfg(feature = "cli")]
+pub mod heartbeat;
+#[cfg(feature = "std")]
+pub mod histogram;
+#[cfg(any(feature = "ledger", feature = "trezor"))]
+pub mod hardware;
+#[cfg(feature = "std")]
+pub mod hooks;
+#[cfg(feature = "cli")]
+pub mod inhibit;
+#[cfg(feature = "cli")]
+pub mod initcode;
+#[cfg(feature = "cli")]
+pub mod logfile;
+#[cfg(feature = "std")]
+pub mod miner;
+#[cfg(feature = "std")]
+pub mod mode;
+#[cfg(feature = "cli")]
+pub mod monitor;
+#[cfg(feature = "cli")]
+pub mod network;
+#[cfg(feature = "std")]
+pub mod odds;
+#[cfg(feature = "cli")]
+pub mod pattern;
+#[cfg(feature = "std")]
+pub mod persist;
+#[cfg(feature = "std")]
+pub mod profile;
+#[cfg(feature = "cli")]
+pub mod prove;
+#[cfg(feature = "std")]
+pub mod provenance;
+#[cfg(feature = "cli")]
+pub mod relay;
+#[cfg(feature = "cli")]
+pub mod report;
+#[cfg(feature = "cli")]
+pub mod resubmit;
+#[cfg(feature = "std")]
+pub mod results;
+#[cfg(feature = "cli")]
+pub mod rpc;
+#[cfg(feature = "cli")]
+pub mod runtime;
+#[cfg(feature = "std")]
+pub mod salts;
+#[cfg(feature = "cli")]
+pub mod schedule;
+#[cfg(feature = "std")]
+pub mod schema;
+#[cfg(feature = "std")]
+pub mod score;
+#[cfg(feature = "std")]
+pub mod search;
+#[cfg(feature = "std")]
+pub mod selftest;
+#[cfg(feature = "cli")]
+pub mod service;
+#[cfg(feature = "cli")]
+pub mod shared;
+#[cfg(feature = "script")]
+pub mod script;
+#[cfg(feature = "cli")]
+pub mod snapshot;
+#[cfg(feature = "cli")]
+pub mod submit;
+#[cfg(all(feature = "cli", target_os = "linux"))]
+pub mod syslog;
+#[cfg(all(feature = "cli", target_os = "linux"))]
+pub mod systemd;
+#[cfg(feature = "cli")]
+pub mod timeline;
+#[cfg(feature = "std")]
+pub mod top;
+#[cfg(feature = "std")]
+pub mod track;
+#[cfg(feature = "tui")]
+pub mod tui;
+#[cfg(feature = "std")]
+pub mod tx;
+#[cfg(feature = "cli")]
+pub mod verify;
+#[cfg(feature = "cli")]
+pub mod wallet;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+/// Derive the address of a contract created using the CREATE2 opcode.
+///
+/// `address` is the deployer, `salt` the deployment salt and `code_hash` the
+/// keccak256 hash of the initcode.
+///
+/// ```
+/// use uniswapv4_challenge_miner::create2_addr;
+///
+/// // Example 0 of EIP-1014: initcode 0x00.
+/// let code_hash = hex::decode("bc36789e7a1e281436464229828f817d6612f7b477d66591ff96a9e064bcc98a").unwrap();
+/// let address = create2_addr(&[0; 20], &[0; 32], &code_hash.try_into().unwrap());
+/// assert_eq!(hex::encode(address), "4d1a2e2bb4f88f0250f26ffff098b0b30b26bf38");
+/// ```
+pub fn create2_addr(address: &[u8; 20], salt: &[u8; 32], code_hash: &[u8; 32]) -> [u8; 20] {
+    let mut buf = [0; 85];
+
+    buf[0] = 0xFF;
+    buf[1..21].copy_from_slice(address);
+    buf[21..53].copy_from_slice(salt);
+    buf[53..85].copy_from_slice(code_hash);
+
+    let mut hasher = Keccak::v256();
+    hasher.update(&buf[..]);
+
+    let mut out = [0; 32];
+    hasher.finalize(&mut out);
+    let mut result = [0; 20];
+    result.copy_from_slice(&out[12..32]);
+    result
+}
+
+/// keccak256 of the CREATE3 proxy initcode (0x67363d3d37363d34f03d5260086018f3),
+/// shared by Solady's and 0xSequence's CREATE3 and the factories built on them.
+pub const CREATE3_PROXY_HASH: [u8; 32] = [
+    0x21, 0xc3, 0x5d, 0xbe, 0x1b, 0x34, 0x4a, 0x24, 0x88, 0xcf, 0x33, 0x21, 0xd6, 0xce, 0x54, 0x2f,
+    0x8e, 0x9f, 0x30, 0x55, 0x44, 0xff, 0x09, 0xe4, 0x99, 0x3a, 0x62, 0x31, 0x9a, 0x49, 0x7c, 0x1f,
+];
+
+/// Derive the address of a contract created through CREATE3: the deployer
+/// CREATE2s the proxy with the salt, and the proxy's first CREATE (nonce 1)
+/// deploys the contract, so the initcode does not matter.
+pub fn create3_addr(address: &[u8; 20], salt: &[u8; 32]) -> [u8; 20] {
+    create_addr(&create2_addr(address, salt, &CREATE3_PROXY_HASH), 1)
+}
+
+/// Derive the address of a contract created using the CREATE opcode, from
+/// keccak256(rlp([sender, nonce])).
+pub fn create_addr(sender: &[u8; 20], nonce: u64) -> [u8; 20] {
+    let mut buf = [0; 31];
+    buf[1] = 0x80 + 20;
+    buf[2..22].copy_from_slice(sender);
+    let nonce_len = match nonce {
+        0 => {
+            buf[22] = 0x80;
+            1
+        }
+        1..=0x7f => {
+            buf[22] = nonce as u8;
+            1
+        }
+        _ => {
+            let bytes = nonce.to_be_bytes();
+            let skip = nonce.leading_zeros() as usize / 8;
+            buf[22] = 0x80 + (8 - skip) as u8;
+            buf[23..31 - skip].copy_from_slice(&bytes[skip..]);
+            9 - skip
+        }
+    };
+    buf[0] = 0xc0 + (21 + nonce_len) as u8;
+
+    let mut hasher = Keccak::v256();
+    hasher.update(&buf[..22 + nonce_len]);
+
+    let mut out = [0; 32];
+    hasher.finalize(&mut out);
+    let mut result = [0; 20];
+    result.copy_from_slice(&out[12..32]);
+    result
+}
+
+/// Compute address score according to Uniswap V4 Address Challenge Rules
+/// (<https://github.com/Uniswap/v4-periphery/blob/0bbf0dc09889e3bc34c7aa08962160a27ba4b340/src/libraries/VanityAddressLib.sol#L18>):
+///
+/// - 10 points for every leading 0 nibble
+/// - 40 points if the first 4 is followed by 3 more 4s
+/// - 20 points if the first nibble after the four 4s is NOT a 4
+/// - 20 points if the last 4 nibbles are 4s
+/// - 1 point for every 4
+///
+/// Addresses whose first nonzero nibble is not a 4 score 0.
+///
+/// ```
+/// use uniswapv4_challenge_miner::compute_score;
+///
+/// let address = hex::decode("0000000044449dbd6b1b8e5dcac0e2ce0ec9ef2e").unwrap();
+/// assert_eq!(compute_score(&address.try_into().unwrap()), 144);
+/// ```
+pub fn compute_score(address: &[u8; 20]) -> u32 {
+    compute_score_at_least(address, 0)
+}
+
+/// Same as [`compute_score`] for addresses scoring at least `min_score`; for
+/// the rest it stops once the score can no longer get there and returns
+/// something lower.
+pub fn compute_score_at_least(address: &[u8; 20], min_score: u32) -> u32 {
+    let mut calculated_score = 0;
+    let mut starting_zeros = true;
+    let mut starting_fours = true;
+    let mut first_four = true;
+    let mut four_counts = 0;
+
+    for i in 0..40 {
+        // Past the leading runs only a point per 4 and the final bonus are left.
+        if !starting_fours && calculated_score + (40 - i) as u32 + 20 < min_score {
+            return calculated_score;
+        }
+        let current_nibble = if i % 2 == 0 {
+            (address[i / 2] >> 4) & 0x0F
+        } else {
+            address[i / 2] & 0x0F
+        };
+
+        if starting_zeros && current_nibble == 0 {
+            calculated_score += 10;
+            continue;
+        } else {
+            starting_zeros = false;
+        }
+
+        if starting_fours {
+            if first_four && current_nibble != 4 {
+                return 0;
+            }
+
+            if current_nibble == 4 {
+                four_counts += 1;
+                if four_counts == 4 {
+                    calculated_score += 40;
+                    if i == 39 {
+                        calculated_score += 20;
+                    }
+                }
+            } else {
+                if four_counts == 4 {
+                    calculated_score += 20;
+                }
+                starting_fours = false;
+            }
+            first_four = false;
+        }
+
+        if current_nibble == 4 {
+            calculated_score += 1;
+        }
+    }
+
+    if address[18] & 0x0F == 0x04 && address[19] & 0xF0 == 0x40 {
+        calculated_score += 20;
+    }
+
+    calculated_score
+}
+
+/// How [`compute_score`] adds up for an address, points per rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScoreBreakdown {
+    /// Whether the first nonzero nibble is a 4; addresses where it is not
+    /// score 0 whatever the rules below say.
+    pub starts_with_four: bool,
+    /// 10 for every leading 0 nibble.
+    pub leading_zeros: u32,
+    /// 40 if the first 4 is followed by 3 more.
+    pub four_fours: u32,
+    /// 20 if those four 4s are followed by something other than a 4 (or end
+    /// the address).
+    pub not_four_after: u32,
+    /// 20 if the address ends in 4s, which the contract checks at the second
+    /// and third nibbles from the end.
+    pub tail_fours: u32,
+    /// 1 for every 4.
+    pub fours: u32,
+}
+
+impl ScoreBreakdown {
+    /// The score, equal to [`compute_score`]'s.
+    pub fn total(&self) -> u32 {
+        if !self.starts_with_four {
+            return 0;
+        }
+        self.leading_zeros + self.four_fours + self.not_four_after + self.tail_fours + self.fours
+    }
+}
+
+impl core::fmt::Display for ScoreBreakdown {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        if !self.starts_with_four {
+            return write!(f, "first nonzero nibble is not a 4: 0");
+        }
+        write!(f, "{} leading zeros: {}", self.leading_zeros / 10, self.leading_zeros)?;
+        if self.four_fours > 0 {
+            write!(f, ", four 4s: {}", self.four_fours)?;
+        }
+        if self.not_four_after > 0 {
+            write!(f, ", no 4 after them: {}", self.not_four_after)?;
+        }
+        if self.tail_fours > 0 {
+            write!(f, ", 4s at the end: {}", self.tail_fours)?;
+        }
+        write!(f, ", {} 4s: {}, total {}", self.fours, self.fours, self.total())
+    }
+}
+
+/// The rules behind [`compute_score`], one by one, to check a score by.
+///
+/// ```
+/// use uniswapv4_challenge_miner::{compute_score, explain_score};
+///
+/// let address: [u8; 20] = hex::decode("0000000044449dbd6b1b8e5dcac0e2ce0ec9ef2e").unwrap().try_into().unwrap();
+/// let breakdown = explain_score(&address);
+/// assert_eq!((breakdown.leading_zeros, breakdown.four_fours, breakdown.not_four_after, breakdown.fours), (80, 40, 20, 4));
+/// assert_eq!(breakdown.total(), compute_score(&address));
+/// ```
+pub fn explain_score(address: &[u8; 20]) -> ScoreBreakdown {
+    let nibble = |i: usize| (address[i / 2] >> (4 * (1 - i % 2))) & 0x0F;
+    let zeros = (0..40).take_while(|&i| nibble(i) == 0).count();
+    let run = (zeros..40).take_while(|&i| nibble(i) == 4).count();
+    ScoreBreakdown {
+        starts_with_four: zeros == 40 || run > 0,
+        leading_zeros: 10 * zeros as u32,
+        four_fours: if run >= 4 { 40 } else { 0 },
+        // The contract only looks once the run ends, having counted up to
+        // exactly four.
+        not_four_after: if run == 4 { 20 } else { 0 },
+        tail_fours: if nibble(37) == 4 && nibble(38) == 4 { 20 } else { 0 },
+        fours: (0..40).filter(|&i| nibble(i) == 4).count() as u32,
+    }
+}
+
+/// The challenge contract on mainnet.
+pub const DEPLOYER_ADDRESS_HEX: &str = "48E516B34A1274f49457b9C6182097796D0498Cb";
+/// Hash of the PoolManager initcode the challenge deploys.
+pub const INITCODE_HASH_HEX: &str = "94d114296a5af85c1fd2dc039cdaa32f1ed4b0fe0868f02d888bfc91feb645d9";
+/// Default `--submitter` of the command-line miner.
+pub const SUBMITTER_ADDRESS_HEX: &str = "b46B370a1A16B959bFF7d47010E256C50Db8330F";
+
+/// [`DEPLOYER_ADDRESS_HEX`] as bytes.
+pub const DEPLOYER_ADDRESS: [u8; 20] = const_hex(DEPLOYER_ADDRESS_HEX);
+/// [`INITCODE_HASH_HEX`] as bytes.
+pub const INITCODE_HASH: [u8; 32] = const_hex(INITCODE_HASH_HEX);
+/// [`SUBMITTER_ADDRESS_HEX`] as bytes.
+pub const SUBMITTER_ADDRESS: [u8; 20] = const_hex(SUBMITTER_ADDRESS_HEX);
+
+// Decode a hex constant at compile time, so a typo fails the build rather
+// than a run.
+pub(crate) const fn const_hex<const N: usize>(hex: &str) -> [u8; N] {
+    const fn nibble(c: u8) -> u8 {
+        match c {
+            b'0'..=b'9' => c - b'0',
+            b'a'..=b'f' => c - b'a' + 10,
+            b'A'..=b'F' => c - b'A' + 10,
+            _ => panic!("not a hex digit"),
+        }
+    }
+    let hex = hex.as_bytes();
+    assert!(hex.len() == 2 * N, "wrong number of hex digits");
+    let mut bytes = [0; N];
+    let mut i = 0;
+    while i < N {
+        bytes[i] = (nibble(hex[2 * i]) << 4) | nibble(hex[2 * i + 1]);
+        i += 1;
+    }
+    bytes
+}
+
+/// A mined salt together with the address it deploys to and its score.
+/// Serialized (with `std`) as `{"salt": "0x..", "address": "0x..", "score": n}`.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct Candidate {
+    #[cfg_attr(feature = "std", serde(with = "schema::hex"))]
+    pub salt: [u8; 32],
+    #[cfg_attr(feature = "std", serde(with = "schema::hex"))]
+    pub address: [u8; 20],
+    pub score: u32,
+}
+
+#[cfg(feature = "std")]
+impl Candidate {
+    /// The address, as alloy-primitives' type.
+    pub fn address(&self) -> Address {
+        Address::from(self.address)
+    }
+
+    /// The salt, as alloy-primitives' type.
+    pub fn salt(&self) -> B256 {
+        B256::from(self.salt)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<Candidate> for Address {
+    fn from(candidate: Candidate) -> Self {
+        candidate.address()
+    }
+}
+
+/// Parse a fixed-length hex string, with or without 0x prefix.
+#[cfg(feature = "std")]
+pub fn decode_hex<const N: usize>(value: &str) -> Result<[u8; N], error::MinerError> {
+    let bytes = hex::decode(value.trim_start_matches("0x")).map_err(|e| error::MinerError::InvalidHex(e.to_string()))?;
+    bytes
+        .try_into()
+        .map_err(|bytes: Vec<u8>| error::MinerError::InvalidHex(format!("expected {} bytes, got {}", N, bytes.len())))
+}
@@ -0,0 +1,227 @@
+// A log of the run kept in a file, apart from whatever stdout shows: one
+// timestamped line per event (start, new bests, status lines, errors, the
+// end), rotated by size and age with only the last few old files kept, so an
+// unattended miner neither fills its disk nor loses how the run began.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, SystemTime};
+
+use clap::{Args, ValueEnum};
+
+use crate::heartbeat::Status;
+use crate::hooks::Event;
+use crate::mode::Mode;
+use crate::Candidate;
+
+/// Logging to a file
+#[derive(Args, Debug, Clone)]
+pub struct LogArgs {
+    /// Also log new bests, status lines and errors to this file, with timestamps
+    #[arg(long, value_name = "PATH")]
+    pub log_file: Option<PathBuf>,
+
+    /// Start a new --log-file once it reaches this size, e.g. 10M (0 for no limit)
+    #[arg(long, value_name = "SIZE", default_value = "10M", value_parser = parse_size, requires = "log_file")]
+    pub log_max_size: u64,
+
+    /// Start a new --log-file once it is this old, e.g. 1d
+    #[arg(long, value_name = "DURATION", value_parser = humantime::parse_duration, requires = "log_file")]
+    pub log_max_age: Option<Duration>,
+
+    /// Rotated log files kept next to --log-file, as <file>.1 (newest) to <file>.N
+    #[arg(long, value_name = "N", default_value_t = 5, requires = "log_file")]
+    pub log_keep: usize,
+
+    /// Also log to journald or syslog (Linux only), journald if it runs when no target is given
+    #[arg(long, value_name = "TARGET", num_args = 0..=1, default_missing_value = "auto")]
+    pub syslog: Option<SyslogTarget>,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyslogTarget {
+    /// journald if it runs, else syslog
+    Auto,
+    /// journald's native protocol, with score, address and salt fields on new bests
+    Journald,
+    /// The local syslog daemon, through /dev/log
+    Syslog,
+}
+
+impl LogArgs {
+    // Every log asked for.
+    pub fn open(&self) -> Result<Vec<Arc<dyn Log>>, String> {
+        let mut logs: Vec<Arc<dyn Log>> = Vec::new();
+        if let Some(path) = &self.log_file {
+            let rotation = Rotation { max_size: (self.log_max_size > 0).then_some(self.log_max_size), max_age: self.log_max_age, keep: self.log_keep };
+            logs.push(Arc::new(LogFile::open(path, rotation)?));
+        }
+        if let Some(target) = self.syslog {
+            #[cfg(target_os = "linux")]
+            logs.push(Arc::new(crate::syslog::Syslog::open(target)?));
+            #[cfg(not(target_os = "linux"))]
+            return Err(format!("--syslog {:?} is only supported on Linux", target).to_lowercase());
+        }
+        #[cfg(windows)]
+        if crate::service::running() {
+            logs.push(Arc::new(crate::service::EventLog::open()?));
+        }
+        Ok(logs)
+    }
+}
+
+// Somewhere the run is logged to.
+pub trait Log: Send + Sync {
+    fn write(&self, level: Level, message: &str);
+
+    // `event` of a search in `mode`, with whatever more of it the log can
+    // hold than its line.
+    fn event(&self, event: &Event, mode: Mode) {
+        let (level, message) = describe(event, mode);
+        self.write(level, &message);
+    }
+}
+
+// A byte count with an optional K, M or G (powers of 1024).
+pub fn parse_size(value: &str) -> Result<u64, String> {
+    let number = value.trim();
+    let number = number.strip_suffix(['B', 'b']).unwrap_or(number);
+    let (number, multiplier) = match number.char_indices().last() {
+        Some((i, 'k' | 'K')) => (&number[..i], 1 << 10),
+        Some((i, 'm' | 'M')) => (&number[..i], 1 << 20),
+        Some((i, 'g' | 'G')) => (&number[..i], 1 << 30),
+        _ => (number, 1),
+    };
+    number
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(multiplier))
+        .ok_or_else(|| format!("{:?} is not a size like 10M", value))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Level {
+    pub fn name(self) -> &'static str {
+        match self {
+            Level::Info => "INFO",
+            Level::Warning => "WARN",
+            Level::Error => "ERROR",
+        }
+    }
+}
+
+// When to start a new file and how many old ones to keep.
+#[derive(Debug, Clone, Copy)]
+pub struct Rotation {
+    pub max_size: Option<u64>,
+    pub max_age: Option<Duration>,
+    pub keep: usize,
+}
+
+struct Current {
+    file: File,
+    size: u64,
+    opened: SystemTime,
+}
+
+pub struct LogFile {
+    path: PathBuf,
+    rotation: Rotation,
+    current: Mutex<Current>,
+}
+
+// The line for `event` of a search in `mode`, as key=value fields after a
+// short message.
+pub fn describe(event: &Event, mode: Mode) -> (Level, String) {
+    match event {
+        Event::NewBest(c) => (Level::Info, format!("new best: {}", fields(c, mode))),
+        Event::Progress(stats) => (Level::Info, format!("progress: hashes={} best_score={}", stats.hashes, score_field(stats.best.map(|c| c.score)))),
+        Event::Error(e) => (Level::Error, format!("error: {}", e)),
+    }
+}
+
+pub fn describe_status(status: &Status) -> String {
+    format!("status: hashes={} hashrate={:.0} best_score={}", status.hashes, status.hashrate, score_field(status.best.map(|c| c.score)))
+}
+
+fn score_field(score: Option<u32>) -> String {
+    score.map_or("none".to_string(), |score| score.to_string())
+}
+
+// How to reproduce a candidate, never a --mode eoa key.
+pub fn fields(c: &Candidate, mode: Mode) -> String {
+    let reproduce = match mode {
+        Mode::Eoa => "key=saved-to-key-dir".to_string(),
+        _ => format!("salt=0x{}", hex::encode(c.salt)),
+    };
+    format!("score={} address=0x{} {}", c.score, hex::encode(c.address), reproduce)
+}
+
+impl LogFile {
+    // Append to `path`; its age counts from when it was last started.
+    pub fn open(path: &Path, rotation: Rotation) -> Result<Self, String> {
+        let current = Self::start(path).map_err(|e| format!("cannot open {}: {}", path.display(), e))?;
+        Ok(LogFile { path: path.to_path_buf(), rotation, current: Mutex::new(current) })
+    }
+
+    fn start(path: &Path) -> std::io::Result<Current> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let metadata = file.metadata()?;
+        let opened = metadata.created().or_else(|_| metadata.modified()).unwrap_or_else(|_| SystemTime::now());
+        // An empty file is as good as new, whatever its creation time.
+        let opened = if metadata.len() == 0 { SystemTime::now() } else { opened };
+        Ok(Current { file, size: metadata.len(), opened })
+    }
+
+    // <file>.N-1 becomes <file>.N, ..., <file> becomes <file>.1, dropping
+    // whatever is beyond `keep`, and a new <file> is started.
+    fn rotate(&self) -> std::io::Result<Current> {
+        let numbered = |n: usize| {
+            let mut name = self.path.clone().into_os_string();
+            name.push(format!(".{}", n));
+            PathBuf::from(name)
+        };
+        match self.rotation.keep {
+            0 => fs::remove_file(&self.path)?,
+            keep => {
+                fs::remove_file(numbered(keep)).ok();
+                for n in (1..keep).rev() {
+                    if numbered(n).exists() {
+                        fs::rename(numbered(n), numbered(n + 1))?;
+                    }
+                }
+                fs::rename(&self.path, numbered(1))?;
+            }
+        }
+        Self::start(&self.path)
+    }
+}
+
+impl Log for LogFile {
+    fn write(&self, level: Level, message: &str) {
+        let now = SystemTime::now();
+        let line = format!("{} {} {}\n", humantime::format_rfc3339_seconds(now), level.name(), message);
+        let mut current = self.current.lock().unwrap_or_else(PoisonError::into_inner);
+        let too_big = self.rotation.max_size.is_some_and(|max| current.size > 0 && current.size + line.len() as u64 > max);
+        let too_old = self.rotation.max_age.is_some_and(|max| now.duration_since(current.opened).unwrap_or_default() >= max);
+        if too_big || too_old {
+            match self.rotate() {
+                Ok(started) => *current = started,
+                Err(e) => eprintln!("Cannot rotate {}: {}", self.path.display(), e),
+            }
+        }
+        match current.file.write_all(line.as_bytes()) {
+            Ok(()) => current.size += line.len() as u64,
+            Err(e) => eprintln!("Cannot write to {}: {}", self.path.display(), e),
+        }
+    }
+}
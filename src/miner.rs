@@ -0,0 +1,524 @@
+//! Embedding the miner in another program.
+//!
+//! ```no_run
+//! use uniswapv4_challenge_miner::miner::Miner;
+//!
+//! let miner = Miner::builder()
+//!     .threads(4)
+//!     .on_new_best(|c| println!("0x{} scores {}", hex::encode(c.address), c.score))
+//!     .run()
+//!     .unwrap();
+//! std::thread::sleep(std::time::Duration::from_secs(60));
+//! println!("{} hashes so far", miner.stats().hashes);
+//! miner.stop();
+//! ```
+
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use alloy_primitives::{Address, B256};
+use serde::{Deserialize, Serialize};
+
+use crate::error::MinerError;
+use crate::hooks::{Event, EventBus};
+use crate::mode::{Derivation, Mode};
+use crate::salts::{Keys, Nonces, Random, SaltGenerator, SaltPosition, Sequential};
+use crate::schema::{self, Checkpoint, CounterRange, Version};
+use crate::score::{self, Scorer, Tiebreak};
+use crate::search::{self, Callback, Control, Search};
+use crate::top::Top;
+use crate::{Candidate, DEPLOYER_ADDRESS, INITCODE_HASH, SUBMITTER_ADDRESS};
+
+// Candidates a CandidateStream holds before the workers wait for it.
+const STREAM_BUFFER: usize = 64;
+
+// How often the progress thread looks for the end of the search.
+const PROGRESS_POLL: Duration = Duration::from_millis(50);
+
+/// Settings for a [`Miner`], defaulting to those of the command-line miner:
+/// the challenge's deployer and initcode, its scoring and one thread per core.
+pub struct MinerBuilder {
+    mode: Mode,
+    deployer: [u8; 20],
+    code_hash: [u8; 32],
+    create3_caller: Option<[u8; 20]>,
+    senders: Vec<[u8; 20]>,
+    max_nonce: u64,
+    submitter: [u8; 20],
+    salts: Option<Box<dyn SaltGenerator>>,
+    scorer: Box<dyn Scorer>,
+    tiebreak: Vec<Tiebreak>,
+    min_score: u32,
+    top: Option<usize>,
+    threads: usize,
+    events: EventBus,
+    progress_interval: Option<Duration>,
+    checkpoint: Option<Checkpoint>,
+}
+
+impl MinerBuilder {
+    /// How salts become addresses; see [`Mode`].
+    pub fn mode(mut self, mode: Mode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// The CREATE2 or CREATE3 factory.
+    pub fn deployer(mut self, deployer: impl Into<Address>) -> Self {
+        self.deployer = deployer.into().into_array();
+        self
+    }
+
+    /// keccak256 of the initcode deployed with CREATE2.
+    pub fn code_hash(mut self, code_hash: impl Into<B256>) -> Self {
+        self.code_hash = code_hash.into().0;
+        self
+    }
+
+    /// For CREATE3 factories that hash the caller into the salt.
+    pub fn create3_caller(mut self, caller: impl Into<Address>) -> Self {
+        self.create3_caller = Some(caller.into().into_array());
+        self
+    }
+
+    /// Accounts searched in [`Mode::Create`], with nonces up to `max_nonce`.
+    pub fn senders<A: Into<Address>>(mut self, senders: impl IntoIterator<Item = A>, max_nonce: u64) -> Self {
+        self.senders = senders.into_iter().map(|sender| sender.into().into_array()).collect();
+        self.max_nonce = max_nonce;
+        self
+    }
+
+    /// Address in the first 20 bytes of every salt. The challenge only takes
+    /// a salt from this address (or from anyone, for the zero address).
+    pub fn submitter(mut self, submitter: impl Into<Address>) -> Self {
+        self.submitter = submitter.into().into_array();
+        self
+    }
+
+    /// Where CREATE2 and CREATE3 salts come from, instead of the submitter, a
+    /// random pepper per thread and a counter.
+    pub fn salts(mut self, salts: impl SaltGenerator + 'static) -> Self {
+        self.salts = Some(Box::new(salts));
+        self
+    }
+
+    pub fn scorer(mut self, scorer: impl Scorer + 'static) -> Self {
+        self.scorer = Box::new(scorer);
+        self
+    }
+
+    /// Keep equal scores that win these tiebreaks, in order.
+    pub fn tiebreak(mut self, tiebreak: Vec<Tiebreak>) -> Self {
+        self.tiebreak = tiebreak;
+        self
+    }
+
+    /// Only report candidates scoring above `score`, say the on-chain best.
+    pub fn min_score(mut self, score: u32) -> Self {
+        self.min_score = score;
+        self
+    }
+
+    /// Keep the best `size` candidates as well as the best, for
+    /// [`Miner::top`] and snapshots.
+    pub fn top(mut self, size: usize) -> Self {
+        self.top = Some(size);
+        self
+    }
+
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    /// Called from a worker thread with every new best, which workers wait
+    /// on, so keep it quick. Can be given more than once.
+    pub fn on_new_best(self, callback: impl Fn(&Candidate) + Send + Sync + 'static) -> Self {
+        self.subscribe(move |event| {
+            if let Event::NewBest(c) = event {
+                callback(c);
+            }
+        })
+    }
+
+    /// Called with the stats every [`progress_interval`](Self::progress_interval),
+    /// and once more when the search is over.
+    pub fn on_progress(mut self, callback: impl Fn(&Stats) + Send + Sync + 'static) -> Self {
+        self.progress_interval.get_or_insert(Duration::from_secs(1));
+        self.subscribe(move |event| {
+            if let Event::Progress(stats) = event {
+                callback(stats);
+            }
+        })
+    }
+
+    /// Called when the search fails, before [`Miner::stop`] or
+    /// [`Miner::wait`] report it.
+    pub fn on_error(self, callback: impl Fn(&MinerError) + Send + Sync + 'static) -> Self {
+        self.subscribe(move |event| {
+            if let Event::Error(e) = event {
+                callback(e);
+            }
+        })
+    }
+
+    /// Called with every event of the search; see [`Event`].
+    pub fn subscribe(self, sink: impl Fn(&Event) + Send + Sync + 'static) -> Self {
+        self.events.subscribe(sink);
+        self
+    }
+
+    /// How often progress events are emitted, every second by default. There
+    /// are none unless this or [`on_progress`](Self::on_progress) is given.
+    pub fn progress_interval(mut self, interval: Duration) -> Self {
+        self.progress_interval = Some(interval);
+        self
+    }
+
+    /// Carry on from where a [`Miner::snapshot`] left off, here or in
+    /// another process: with its salt streams, best candidates and hash count.
+    /// The other settings are this builder's and should match the original
+    /// search's.
+    pub fn resume_from(mut self, checkpoint: Checkpoint) -> Self {
+        self.checkpoint = Some(checkpoint);
+        self
+    }
+
+    /// Start mining in the background. Fails with [`MinerError::Config`] on
+    /// settings that do not go together.
+    pub fn run(self) -> Result<Miner, MinerError> {
+        self.start(None)
+    }
+
+    /// Start mining in the background and iterate over every candidate
+    /// scoring at least `threshold`, in the order they are found. Workers wait
+    /// while a few candidates are left unread, and stop with the stream.
+    pub fn stream(self, threshold: u32) -> Result<CandidateStream, MinerError> {
+        let (sender, receiver) = mpsc::sync_channel(STREAM_BUFFER);
+        let callback: Callback = Box::new(move |c: &Candidate| {
+            sender.send(*c).ok();
+        });
+        let miner = self.start(Some((threshold, callback)))?;
+        Ok(CandidateStream { receiver, miner })
+    }
+
+    fn start(self, on_candidate: Option<(u32, Callback)>) -> Result<Miner, MinerError> {
+        let config = |message: &str| Err(MinerError::Config(message.to_string()));
+        if self.threads == 0 {
+            return config("cannot mine on zero threads");
+        }
+        if (self.mode == Mode::Create) == self.senders.is_empty() {
+            return config("senders are needed for, and only used by, Mode::Create");
+        }
+        if self.create3_caller.is_some() && self.mode != Mode::Create3 {
+            return config("a CREATE3 caller is only used by Mode::Create3");
+        }
+        if self.top == Some(0) {
+            return config("cannot keep the top zero candidates");
+        }
+        let position = self.checkpoint.as_ref().and_then(|checkpoint| checkpoint.salts.clone());
+        let salts: Arc<dyn SaltGenerator> = match (self.mode, position) {
+            (Mode::Create | Mode::Eoa, _) if self.salts.is_some() => {
+                return config("only Mode::Create2 and Mode::Create3 take a salt generator")
+            }
+            (Mode::Create, None) => Arc::new(Nonces { senders: self.senders, max_nonce: self.max_nonce, start: 0 }),
+            (Mode::Create, Some(SaltPosition::Nonces { next })) => {
+                Arc::new(Nonces { senders: self.senders, max_nonce: self.max_nonce, start: next })
+            }
+            (Mode::Eoa, None) => Arc::new(Keys),
+            (Mode::Create2 | Mode::Create3, None) => match self.salts {
+                Some(salts) => salts.into(),
+                None => Arc::new(Random::new(self.submitter)),
+            },
+            (Mode::Create2 | Mode::Create3, Some(SaltPosition::Random { submitter, streams, retired })) => {
+                Arc::new(Random::resume(submitter.into_array(), streams, retired))
+            }
+            (Mode::Create2 | Mode::Create3, Some(SaltPosition::Sequential { submitter, next, end, .. })) => {
+                Arc::new(Sequential { submitter: submitter.into_array(), start: next, end })
+            }
+            _ => return config("the checkpoint is of a search in another mode"),
+        };
+        salts.check_peppers(self.threads)?;
+        let code_hash = match self.mode {
+            Mode::Create2 => self.code_hash,
+            Mode::Create3 => crate::CREATE3_PROXY_HASH,
+            Mode::Create | Mode::Eoa => [0; 32],
+        };
+        let derivation = Derivation {
+            mode: self.mode,
+            deployer: self.deployer,
+            code_hash,
+            create3_caller: self.create3_caller,
+        };
+        let checkpoint = self.checkpoint.unwrap_or_default();
+        let best = Arc::new(Mutex::new(checkpoint.best));
+        let top = self.top.map(|size| {
+            let top = Top::new(size, None, false);
+            checkpoint.top.iter().for_each(|c| top.insert(*c));
+            Arc::new(top)
+        });
+        let min_score = checkpoint.best.map(|best| best.score.max(self.min_score)).unwrap_or(self.min_score);
+        let control = Arc::new(Control { hashes: checkpoint.hashes.into(), ..Control::default() });
+        let search = Search {
+            derivation,
+            salts: Arc::clone(&salts),
+            scorer: self.scorer,
+            #[cfg(feature = "cli")]
+            pattern: None,
+            tracker: None,
+            extra_hashes: None,
+            histogram: None,
+            tiebreak: self.tiebreak,
+            zero_nibbles: 0,
+            target: Arc::new(AtomicU32::new(min_score)),
+            best: Arc::clone(&best),
+            top: top.clone(),
+            events: Arc::new(self.events),
+            on_candidate,
+            control: Arc::clone(&control),
+        };
+        let events = Arc::clone(&search.events);
+        let shared = Arc::new(Shared {
+            control,
+            best,
+            top,
+            started: Instant::now(),
+            elapsed_before: checkpoint.elapsed,
+            finished: AtomicBool::new(false),
+        });
+        let threads = self.threads;
+        let thread = {
+            let (shared, events) = (Arc::clone(&shared), Arc::clone(&events));
+            thread::spawn(move || {
+                let result = panic::catch_unwind(AssertUnwindSafe(|| search.run(threads)));
+                shared.finished.store(true, Ordering::Relaxed);
+                if let Err(payload) = result {
+                    events.emit(Event::Error(&MinerError::SearchPanicked));
+                    panic::resume_unwind(payload);
+                }
+            })
+        };
+        let progress = self.progress_interval.map(|interval| {
+            let (shared, events) = (Arc::clone(&shared), Arc::clone(&events));
+            thread::spawn(move || {
+                let mut next = Instant::now() + interval;
+                loop {
+                    let finished = shared.finished.load(Ordering::Relaxed);
+                    if finished || Instant::now() >= next {
+                        events.emit(Event::Progress(&shared.stats()));
+                        next += interval;
+                    }
+                    if finished {
+                        break;
+                    }
+                    thread::sleep(PROGRESS_POLL.min(next.saturating_duration_since(Instant::now())));
+                }
+            })
+        });
+        Ok(Miner { shared, salts, covered: checkpoint.covered, events, thread: Some(thread), progress })
+    }
+}
+
+/// A running search. Dropping it stops the search.
+pub struct Miner {
+    shared: Arc<Shared>,
+    salts: Arc<dyn SaltGenerator>,
+    // Counter ranges covered before resuming from a checkpoint.
+    covered: Vec<CounterRange>,
+    events: Arc<EventBus>,
+    thread: Option<JoinHandle<()>>,
+    progress: Option<JoinHandle<()>>,
+}
+
+// What the search thread, the progress thread and the Miner all look at.
+struct Shared {
+    control: Arc<Control>,
+    best: Arc<Mutex<Option<Candidate>>>,
+    top: Option<Arc<Top>>,
+    started: Instant,
+    elapsed_before: Duration,
+    finished: AtomicBool,
+}
+
+impl Shared {
+    fn stats(&self) -> Stats {
+        Stats {
+            hashes: self.control.hashes.load(Ordering::Relaxed),
+            elapsed: self.elapsed_before + self.started.elapsed(),
+            // A Candidate is written whole, so a callback panicking while
+            // the lock is held cannot leave it torn.
+            best: *self.best.lock().unwrap_or_else(PoisonError::into_inner),
+            paused: self.control.paused.load(Ordering::Relaxed),
+            finished: self.finished.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Progress of a [`Miner`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Stats {
+    pub hashes: u64,
+    #[serde(rename = "elapsed_secs", with = "crate::schema::secs")]
+    pub elapsed: Duration,
+    pub best: Option<Candidate>,
+    pub paused: bool,
+    /// Whether the search is over, stopped or out of candidates (only
+    /// [`Mode::Create`] runs out).
+    pub finished: bool,
+}
+
+impl Stats {
+    /// Average hashes per second since the start, pauses included.
+    pub fn hashrate(&self) -> f64 {
+        self.hashes as f64 / self.elapsed.as_secs_f64().max(1e-9)
+    }
+}
+
+/// Progress of one worker thread of a [`Miner`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ThreadStats {
+    /// Salts it has tried so far (of this run, not one resumed from).
+    pub hashes: u64,
+    /// Hashes per second over about the last ten seconds, pauses left out.
+    pub hashrate: f64,
+    /// Whether it hashes at under three quarters of the median thread's
+    /// rate, say throttled or sharing its core with something else.
+    pub slow: bool,
+}
+
+impl Miner {
+    pub fn builder() -> MinerBuilder {
+        MinerBuilder {
+            mode: Mode::Create2,
+            deployer: DEPLOYER_ADDRESS,
+            code_hash: INITCODE_HASH,
+            create3_caller: None,
+            senders: Vec::new(),
+            max_nonce: 0,
+            submitter: SUBMITTER_ADDRESS,
+            salts: None,
+            scorer: Box::new(score::UniswapV4),
+            tiebreak: Vec::new(),
+            min_score: 0,
+            top: None,
+            threads: num_cpus::get(),
+            events: EventBus::default(),
+            progress_interval: None,
+            checkpoint: None,
+        }
+    }
+
+    /// Workers stop hashing within a few thousand hashes of this.
+    pub fn pause(&self) {
+        self.shared.control.paused.store(true, Ordering::Relaxed);
+    }
+
+    pub fn resume(&self) {
+        self.shared.control.paused.store(false, Ordering::Relaxed);
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.shared.stats()
+    }
+
+    /// The best candidates kept with [`MinerBuilder::top`], best first.
+    pub fn top(&self) -> Vec<Candidate> {
+        self.shared.top.as_ref().map_or_else(Vec::new, |top| top.entries())
+    }
+
+    /// Each worker thread's progress, in thread order.
+    pub fn thread_stats(&self) -> Vec<ThreadStats> {
+        let hashes = self.shared.control.taken.lock().unwrap_or_else(PoisonError::into_inner).clone();
+        let rates = self.shared.control.rates.lock().unwrap_or_else(PoisonError::into_inner).clone();
+        let slow = search::slow(&rates);
+        hashes.into_iter().zip(rates).zip(slow).map(|((hashes, hashrate), slow)| ThreadStats { hashes, hashrate, slow }).collect()
+    }
+
+    /// The search's events, to subscribe more sinks while it runs.
+    pub fn events(&self) -> &EventBus {
+        &self.events
+    }
+
+    /// The search's state, to resume it later with
+    /// [`MinerBuilder::resume_from`]. Salts tried in the last few thousand
+    /// hashes of each thread are tried again on resuming.
+    pub fn snapshot(&self) -> Checkpoint {
+        let taken = self.shared.control.taken.lock().unwrap_or_else(PoisonError::into_inner).clone();
+        let salts = self.salts.position(&taken);
+        let mut covered = self.covered.clone();
+        schema::extend_covered(&mut covered, salts.as_ref());
+        let peppers = schema::pepper_ranges(salts.as_ref());
+        let stats = self.stats();
+        let top = self.top();
+        Checkpoint { version: Version, salts, covered, peppers, best: stats.best, top, hashes: stats.hashes, elapsed: stats.elapsed }
+    }
+
+    /// Stop the search and return the best candidate found. Fails with
+    /// [`MinerError::SearchPanicked`] if a worker or callback panicked.
+    pub fn stop(mut self) -> Result<Option<Candidate>, MinerError> {
+        self.shared.control.stopped.store(true, Ordering::Relaxed);
+        self.join()
+    }
+
+    /// Wait for the search to end on its own, which only [`Mode::Create`]
+    /// does, and return the best candidate found.
+    pub fn wait(mut self) -> Result<Option<Candidate>, MinerError> {
+        self.join()
+    }
+
+    fn join(&mut self) -> Result<Option<Candidate>, MinerError> {
+        let searched = self.thread.take().map(JoinHandle::join);
+        // The last progress event is out before stop or wait return.
+        if let Some(progress) = self.progress.take() {
+            progress.join().ok();
+        }
+        if let Some(Err(_)) = searched {
+            return Err(MinerError::SearchPanicked);
+        }
+        Ok(*self.shared.best.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+}
+
+impl Drop for Miner {
+    fn drop(&mut self) {
+        self.shared.control.stopped.store(true, Ordering::Relaxed);
+        self.join().ok();
+    }
+}
+
+/// Candidates from [`MinerBuilder::stream`], ending when the search does.
+/// Dropping it stops the search.
+///
+/// ```no_run
+/// use uniswapv4_challenge_miner::miner::Miner;
+///
+/// let stream = Miner::builder().stream(100).unwrap();
+/// for c in stream.filter(|c| c.address[19] == 0x44).take(3) {
+///     println!("0x{} scores {}", hex::encode(c.address), c.score);
+/// }
+/// ```
+pub struct CandidateStream {
+    // Dropped first, so workers waiting to send give up before the miner
+    // waits for them.
+    receiver: Receiver<Candidate>,
+    miner: Miner,
+}
+
+impl CandidateStream {
+    /// The running search, to pause it or read its stats.
+    pub fn miner(&self) -> &Miner {
+        &self.miner
+    }
+}
+
+impl Iterator for CandidateStream {
+    type Item = Candidate;
+
+    fn next(&mut self) -> Option<Candidate> {
+        self.receiver.recv().ok()
+    }
+}
@@ -0,0 +1,72 @@
+// How a salt becomes the address being scored.
+
+use serde::{Deserialize, Serialize};
+
+use crate::eoa;
+use crate::{create2_addr, create3_addr, create_addr, keccak256};
+
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Mode {
+    /// The deployer CREATE2s the initcode itself, as the challenge does
+    Create2,
+    /// The deployer deploys through a CREATE3 proxy; the initcode does not matter
+    Create3,
+    /// Plain CREATE from each --sender, searching nonces up to --max-nonce
+    Create,
+    /// Random private keys, for vanity accounts; found keys are saved encrypted to --key-dir
+    Eoa,
+}
+
+pub struct Derivation {
+    pub mode: Mode,
+    pub deployer: [u8; 20],
+    pub code_hash: [u8; 32],
+    // CREATE3 factories commonly mix the caller into the salt,
+    // keccak256(caller ++ salt), so callers cannot take each other's addresses.
+    pub create3_caller: Option<[u8; 20]>,
+}
+
+impl Derivation {
+    pub fn address(&self, salt: &[u8; 32]) -> [u8; 20] {
+        match self.mode {
+            Mode::Create2 => create2_addr(&self.deployer, salt, &self.code_hash),
+            Mode::Create3 => match &self.create3_caller {
+                Some(caller) => {
+                    let mut buf = [0; 52];
+                    buf[..20].copy_from_slice(caller);
+                    buf[20..].copy_from_slice(salt);
+                    create3_addr(&self.deployer, &keccak256(buf))
+                }
+                None => create3_addr(&self.deployer, salt),
+            },
+            Mode::Create => {
+                let (sender, nonce) = sender_nonce(salt);
+                create_addr(&sender, nonce)
+            }
+            Mode::Eoa => eoa::address(salt),
+        }
+    }
+}
+
+fn sender_nonce(salt: &[u8; 32]) -> ([u8; 20], u64) {
+    let mut sender = [0; 20];
+    sender.copy_from_slice(&salt[..20]);
+    (sender, u64::from_be_bytes(salt[24..].try_into().unwrap()))
+}
+
+impl Mode {
+    // How to reproduce a candidate, for the lines reporting it.
+    pub fn describe(self, salt: &[u8; 32]) -> String {
+        match self {
+            Mode::Create => {
+                let (sender, nonce) = sender_nonce(salt);
+                format!("sender: 0x{}, nonce: {}", hex::encode(sender), nonce)
+            }
+            // Never the key itself, which is saved as it is first reported.
+            Mode::Eoa => "key saved to --key-dir".to_string(),
+            Mode::Create2 | Mode::Create3 => format!("salt: 0x{}", hex::encode(salt)),
+        }
+    }
+}
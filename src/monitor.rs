@@ -0,0 +1,182 @@
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::challenge;
+use crate::error::MinerError;
+use crate::{compute_score, Candidate};
+use crate::odds;
+use crate::rpc::RpcClient;
+use crate::runtime;
+
+// What candidates have to score to be reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    // Whatever strictly beats the on-chain best, following it as it changes
+    // (the default with --rpc-url).
+    BeatChain,
+    // A fixed score, reached or beaten, whatever the chain holds.
+    Score(u32),
+}
+
+impl FromStr for Target {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "beat-chain" => Ok(Target::BeatChain),
+            // Only scores above 0 are ever reported.
+            _ => match s.parse() {
+                Ok(0) => Err("a --target score has to be at least 1".to_string()),
+                Ok(score) => Ok(Target::Score(score)),
+                Err(_) => Err(format!("expected beat-chain or a score, got {:?}", s)),
+            },
+        }
+    }
+}
+
+// What one search has to reach, and the target its workers report above.
+pub struct Goal {
+    // Score a candidate has to beat to be worth reporting: the higher of the
+    // local best and the on-chain best, or just below a fixed target.
+    pub target: Arc<AtomicU32>,
+    // Whether there is an on-chain best to follow.
+    chain: bool,
+    // Cleared for a fixed target, which the on-chain best then leaves alone.
+    follow_chain: AtomicBool,
+    // The fixed target's score.
+    fixed: AtomicU32,
+    // The on-chain best last seen, to go back to when following it again.
+    chain_best: AtomicU32,
+}
+
+impl Goal {
+    // Following the on-chain best if `chain`.
+    pub fn new(chain: bool) -> Self {
+        Goal {
+            target: Arc::new(AtomicU32::new(0)),
+            chain,
+            follow_chain: AtomicBool::new(true),
+            fixed: AtomicU32::new(0),
+            chain_best: AtomicU32::new(0),
+        }
+    }
+
+    // Apply `spec`, before the workers start or from a command while they run.
+    pub fn set(&self, spec: Target) -> Result<(), MinerError> {
+        match spec {
+            Target::Score(score) => {
+                self.follow_chain.store(false, Ordering::Relaxed);
+                self.fixed.store(score, Ordering::Relaxed);
+                self.target.store(score.saturating_sub(1), Ordering::Relaxed);
+                println!("Target: only scores of at least {} will be reported", score);
+            }
+            Target::BeatChain if !self.chain => return Err(MinerError::Config("beat-chain needs an on-chain best to follow".to_string())),
+            Target::BeatChain => {
+                // The workers still only report what beats the local best.
+                self.follow_chain.store(true, Ordering::Relaxed);
+                let score = self.chain_best.load(Ordering::Relaxed);
+                self.target.store(score, Ordering::Relaxed);
+                println!("Target: only scores above the on-chain best of {} will be reported", score);
+            }
+        }
+        Ok(())
+    }
+
+    // Raise the target to an on-chain score and tell the user about the new
+    // bar, unless the target is fixed.
+    pub fn raise(&self, score: u32) {
+        self.chain_best.fetch_max(score, Ordering::Relaxed);
+        if !self.follow_chain.load(Ordering::Relaxed) {
+            return;
+        }
+        if self.target.fetch_max(score, Ordering::Relaxed) < score {
+            println!("Target raised: only scores above {} will be reported", score);
+        }
+    }
+
+    // Whether `best` reached the target: a fixed one, or, when there is one to
+    // follow, the on-chain best, which the target then is at least. A run with
+    // neither has nothing to reach.
+    pub fn reached(&self, best: Option<&Candidate>) -> bool {
+        let score = best.map(|c| c.score);
+        if !self.follow_chain.load(Ordering::Relaxed) {
+            score.is_some_and(|score| score >= self.fixed.load(Ordering::Relaxed))
+        } else if self.chain {
+            score.is_some_and(|score| score >= self.target.load(Ordering::Relaxed))
+        } else {
+            true
+        }
+    }
+}
+
+// The leading entry of the competition as stored on-chain.
+pub struct ChainBest {
+    pub address: [u8; 20],
+    pub submitter: [u8; 20],
+    pub score: u32,
+}
+
+pub fn fetch_best(rpc: &RpcClient, contract: &[u8; 20]) -> Result<ChainBest, String> {
+    let address = challenge::best_address(rpc, contract)?;
+    let submitter = challenge::best_address_submitter(rpc, contract)?;
+    Ok(ChainBest { address, submitter, score: compute_score(&address) })
+}
+
+fn print_best(best: &ChainBest) {
+    println!(
+        "On-chain best: 0x{} with score: {} ({}), submitted by 0x{}",
+        hex::encode(best.address),
+        best.score,
+        odds::rarity(best.score),
+        hex::encode(best.submitter)
+    );
+    println!(
+        "Beating it takes {} hashes on average",
+        odds::format_count(1.0 / odds::chance_above(best.score))
+    );
+}
+
+// Start from the current on-chain leader, before any worker runs, so nothing
+// already beaten gets reported. Returns the leading address.
+pub fn initialize(rpc: &RpcClient, contract: &[u8; 20], goal: &Goal) -> Option<[u8; 20]> {
+    match fetch_best(rpc, contract) {
+        Ok(best) => {
+            print_best(&best);
+            goal.raise(best.score);
+            Some(best.address)
+        }
+        Err(e) => {
+            eprintln!("Warning: cannot read the on-chain best, starting from zero: {}", e);
+            None
+        }
+    }
+}
+
+// Poll the challenge contract and raise the target to the on-chain best score,
+// so workers stop reporting candidates that could no longer win. `leader` is
+// the leading address already reported, if any.
+pub fn spawn(
+    rpc: RpcClient,
+    contract: [u8; 20],
+    interval: Duration,
+    goal: Arc<Goal>,
+    mut leader: Option<[u8; 20]>,
+) {
+    runtime::spawn("chain monitor", move |mut shutdown| async move {
+        while shutdown.sleep(interval).await {
+            let rpc = rpc.clone();
+            match runtime::blocking(move || fetch_best(&rpc, &contract)).await {
+                Ok(best) => {
+                    if leader != Some(best.address) {
+                        print_best(&best);
+                        leader = Some(best.address);
+                    }
+                    goal.raise(best.score);
+                }
+                Err(e) => eprintln!("Warning: chain monitor: {}", e),
+            }
+        }
+    });
+}
@@ -0,0 +1,109 @@
+// Per-network defaults, so the whole mine -> verify -> submit flow can be
+// rehearsed against a local fork before running it on mainnet.
+
+use std::fmt;
+
+use clap::{Args, ValueEnum};
+
+use crate::rpc::RpcClient;
+use crate::error::MinerError;
+use crate::{decode_hex, DEPLOYER_ADDRESS, INITCODE_HASH};
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Network {
+    /// Ethereum mainnet, where the competition runs
+    Mainnet,
+    /// Sepolia testnet (needs --contract pointing at your own deployment)
+    Sepolia,
+    /// Local anvil node, e.g. `anvil --fork-url <mainnet RPC>`
+    Anvil,
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.to_possible_value().unwrap().get_name())
+    }
+}
+
+// What a network preset provides. Missing values have to be given on the
+// command line or read from the chain.
+pub struct NetworkConfig {
+    // None when any chain id is acceptable (anvil reports the forked chain's).
+    pub chain_id: Option<u64>,
+    pub contract: Option<[u8; 20]>,
+    pub init_code_hash: Option<[u8; 32]>,
+    pub rpc_url: Option<&'static str>,
+}
+
+impl Network {
+    pub fn config(self) -> NetworkConfig {
+        match self {
+            Network::Mainnet => NetworkConfig {
+                chain_id: Some(1),
+                contract: Some(DEPLOYER_ADDRESS),
+                init_code_hash: Some(INITCODE_HASH),
+                rpc_url: None,
+            },
+            Network::Sepolia => NetworkConfig {
+                chain_id: Some(11155111),
+                contract: None,
+                init_code_hash: None,
+                rpc_url: None,
+            },
+            Network::Anvil => NetworkConfig {
+                chain_id: None,
+                contract: Some(DEPLOYER_ADDRESS),
+                init_code_hash: Some(INITCODE_HASH),
+                rpc_url: Some("http://127.0.0.1:8545"),
+            },
+        }
+    }
+}
+
+/// Network selection
+#[derive(Args, Debug, Clone)]
+pub struct NetworkArgs {
+    /// Network preset supplying the chain id, contract and default endpoint
+    #[arg(long, value_enum, default_value_t = Network::Mainnet)]
+    pub network: Network,
+
+    /// Challenge contract address (default: the network's)
+    #[arg(long, value_parser = decode_hex::<20>)]
+    pub contract: Option<[u8; 20]>,
+
+    /// Expected chain id (default: the network's)
+    #[arg(long)]
+    pub chain_id: Option<u64>,
+}
+
+impl NetworkArgs {
+    pub fn contract(&self) -> Result<[u8; 20], String> {
+        self.contract
+            .or(self.network.config().contract)
+            .ok_or_else(|| format!("no known challenge contract on {}, pass --contract", self.network))
+    }
+
+    pub fn chain_id(&self) -> Option<u64> {
+        self.chain_id.or(self.network.config().chain_id)
+    }
+
+    // The explicit endpoints if any, else the network's default one.
+    pub fn rpc_urls(&self, explicit: Vec<String>) -> Vec<String> {
+        if !explicit.is_empty() {
+            return explicit;
+        }
+        self.network.config().rpc_url.map(str::to_string).into_iter().collect()
+    }
+
+    // Make sure the node is on the chain we expect to be talking to.
+    pub fn check_chain_id(&self, rpc: &RpcClient) -> Result<u64, MinerError> {
+        let actual = rpc.chain_id().map_err(MinerError::Runtime)?;
+        match self.chain_id() {
+            Some(expected) if expected != actual => Err(MinerError::Config(format!(
+                "RPC endpoint is on chain {} but chain {} was expected for {}",
+                actual, expected, self.network
+            ))),
+            _ => Ok(actual),
+        }
+    }
+}
@@ -0,0 +1,214 @@
+// How likely mining is to improve on the current best before the deadline,
+// from the exact score distribution of a random address and the measured
+// hashrate.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+// The live odds, and securing the best on them, take the chain.
+#[cfg(feature = "cli")]
+use std::sync::atomic::{AtomicU32, Ordering};
+#[cfg(feature = "cli")]
+use std::sync::{Arc, Mutex, PoisonError};
+#[cfg(feature = "cli")]
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "cli")]
+use crate::deadline;
+#[cfg(feature = "cli")]
+use crate::rpc::RpcClient;
+#[cfg(feature = "cli")]
+use crate::runtime;
+#[cfg(feature = "cli")]
+use crate::search::Control;
+#[cfg(feature = "cli")]
+use crate::submit::Submitter;
+#[cfg(feature = "cli")]
+use crate::Candidate;
+
+// Where compute_score is in its scan of the address.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Phase {
+    Zeros,
+    // Run of 4s after the leading zeros, counting up to 5 (meaning "more than 4").
+    Fours(u8),
+    Rest,
+    // First nibble after the zeros was not a 4: the score is 0.
+    Failed,
+}
+
+// Probability of each score for a uniformly random address, following
+// compute_score nibble by nibble.
+fn compute_distribution() -> Vec<f64> {
+    // (phase, score so far, whether nibble 37 is a 4)
+    let mut states: HashMap<(Phase, u32, bool), f64> = HashMap::new();
+    states.insert((Phase::Zeros, 0, false), 1.0);
+    for i in 0..40 {
+        let mut next = HashMap::new();
+        for (&(phase, score, four_at_37), &p) in &states {
+            for nibble in 0..16u8 {
+                let is_four = nibble == 4;
+                let (phase, score) = match phase {
+                    Phase::Zeros if nibble == 0 => (Phase::Zeros, score + 10),
+                    Phase::Zeros if is_four => (Phase::Fours(1), score + 1),
+                    Phase::Zeros | Phase::Failed => (Phase::Failed, 0),
+                    Phase::Fours(count) if is_four => {
+                        let mut score = score + 1;
+                        if count == 3 {
+                            score += 40;
+                            if i == 39 {
+                                score += 20;
+                            }
+                        }
+                        (Phase::Fours((count + 1).min(5)), score)
+                    }
+                    Phase::Fours(4) => (Phase::Rest, score + 20),
+                    Phase::Fours(_) => (Phase::Rest, score),
+                    Phase::Rest => (Phase::Rest, score + is_four as u32),
+                };
+                let four_at_37 = if i == 37 { is_four } else { four_at_37 };
+                let score = if i == 38 && four_at_37 && is_four && phase != Phase::Failed { score + 20 } else { score };
+                *next.entry((phase, score, four_at_37)).or_insert(0.0) += p / 16.0;
+            }
+        }
+        states = next;
+    }
+
+    let mut distribution = Vec::new();
+    for ((_, score, _), p) in states {
+        let score = score as usize;
+        if distribution.len() <= score {
+            distribution.resize(score + 1, 0.0);
+        }
+        distribution[score] += p;
+    }
+    distribution
+}
+
+fn distribution() -> &'static [f64] {
+    static DISTRIBUTION: OnceLock<Vec<f64>> = OnceLock::new();
+    DISTRIBUTION.get_or_init(compute_distribution)
+}
+
+// Probability that a random address scores above `score`.
+pub fn chance_above(score: u32) -> f64 {
+    // Summed from the rarest scores up to keep tiny probabilities accurate,
+    // from +0: an empty sum is -0, which would make impossible scores 1 in -inf.
+    distribution().iter().skip(score as usize + 1).rev().fold(0.0, |sum, p| sum + p)
+}
+
+// Probability that a random address scores exactly `score`.
+pub fn chance_of(score: u32) -> f64 {
+    distribution().get(score as usize).copied().unwrap_or(0.0)
+}
+
+// Probability that a random address scores `score` or more.
+pub fn chance_at_least(score: u32) -> f64 {
+    match score.checked_sub(1) {
+        Some(below) => chance_above(below),
+        None => 1.0,
+    }
+}
+
+// Large counts in scientific notation.
+pub fn format_count(count: f64) -> String {
+    if count < 1e5 {
+        format!("{:.0}", count)
+    } else {
+        format!("{:.2e}", count)
+    }
+}
+
+// "1 in N" for the share of addresses scoring at least `score`, which is also
+// the expected number of hashes to find one.
+pub fn rarity(score: u32) -> String {
+    format!("1 in {}", format_count(1.0 / chance_at_least(score)))
+}
+
+// Probability of at least one of `hashes` random addresses scoring above `score`.
+pub fn chance_to_beat(score: u32, hashes: f64) -> f64 {
+    -f64::exp_m1(hashes * f64::ln_1p(-chance_above(score)))
+}
+
+// Expected time until something scores above `score` at `rate` hashes a
+// second: "never" past the highest score, "-" while nothing is hashed.
+#[cfg(feature = "cli")]
+pub fn format_eta(score: u32, rate: f64) -> String {
+    let chance = chance_above(score);
+    if chance == 0.0 {
+        return "never".to_string();
+    }
+    if rate <= 0.0 {
+        return "-".to_string();
+    }
+    let seconds = (1.0 / (chance * rate)).min(1e15);
+    format!("~{}", deadline::format_countdown(Duration::from_secs_f64(seconds)))
+}
+
+#[cfg(feature = "cli")]
+fn format_percent(chance: f64) -> String {
+    match chance {
+        c if c > 0.0 && c < 0.01 => "<0.01%".to_string(),
+        c => format!("{:.2}%", c),
+    }
+}
+
+pub fn format_rate(per_second: f64) -> String {
+    let units = ["H/s", "kH/s", "MH/s", "GH/s", "TH/s"];
+    let mut rate = per_second;
+    let mut unit = 0;
+    while rate >= 1000.0 && unit + 1 < units.len() {
+        rate /= 1000.0;
+        unit += 1;
+    }
+    format!("{:.2} {}", rate, units[unit])
+}
+
+// Submitting the best salt once improving on it has become unlikely.
+#[cfg(feature = "cli")]
+pub struct Secure {
+    // Win probability in percent below which to submit.
+    pub below: f64,
+    pub rpc: RpcClient,
+    pub contract: [u8; 20],
+    pub submitter: Arc<Mutex<Submitter>>,
+}
+
+// Measure the hashrate and prints the chance of beating
+// the current target before the deadline every `interval`, switching to
+// securing the best salt when `secure` is given and the chance drops too low.
+#[cfg(feature = "cli")]
+pub fn spawn(interval: Duration, control: Arc<Control>, target: Arc<AtomicU32>, best: Arc<Mutex<Option<Candidate>>>, secure: Option<Secure>) {
+    runtime::spawn("odds", move |mut shutdown| async move {
+        let mut securing = false;
+        let mut secured: Option<[u8; 32]> = None;
+        let mut last = (Instant::now(), control.hashes.load(Ordering::Relaxed));
+        while shutdown.sleep(interval).await {
+            let now = (Instant::now(), control.hashes.load(Ordering::Relaxed));
+            let rate = (now.1 - last.1) as f64 / now.0.duration_since(last.0).as_secs_f64();
+            last = now;
+            let Some(left) = deadline::time_left() else { continue };
+            let score = target.load(Ordering::Relaxed);
+            let chance = 100.0 * chance_to_beat(score, rate * left.as_secs_f64());
+            println!(
+                "Odds: {} of beating score {} in the {} left at {}",
+                format_percent(chance),
+                score,
+                deadline::format_countdown(left),
+                format_rate(rate)
+            );
+            let Some(s) = &secure else { continue };
+            if !securing && chance < s.below {
+                println!("Odds below {}%, securing the best salt found", s.below);
+                securing = true;
+            }
+            // Once securing, submit each new best the first time it is seen.
+            let salt = best.lock().unwrap_or_else(PoisonError::into_inner).map(|c| c.salt);
+            if securing && salt.is_some() && salt != secured {
+                let (rpc, contract, best, submitter) = (s.rpc.clone(), s.contract, Arc::clone(&best), Arc::clone(&s.submitter));
+                runtime::blocking(move || deadline::submit_best(&rpc, &contract, &best, &submitter, "Secure")).await;
+                secured = salt;
+            }
+        }
+    });
+}
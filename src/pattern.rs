@@ -0,0 +1,326 @@
+// Pattern matching mode: instead of maximizing a score, report every address
+// whose hex form matches what the user asked for.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+use clap::{Args, Parser};
+use regex::bytes::Regex;
+
+use crate::eoa;
+use crate::error::exit_code;
+use crate::mode::Mode;
+use crate::runtime;
+use crate::tx::{checksum_to_slice, to_checksum};
+
+/// Pattern matching (replaces scoring)
+#[derive(Args, Debug, Clone)]
+#[group(id = "pattern", multiple = true)]
+pub struct PatternArgs {
+    /// File of named targets, one per line: a name followed by the options
+    /// below, e.g. `dead --suffix dead --matches 3`
+    #[arg(long, value_name = "FILE")]
+    pub targets: Option<PathBuf>,
+
+    /// Report addresses starting with these hex digits, e.g. 00000000
+    #[arg(long, value_name = "HEX", value_parser = parse_nibbles)]
+    pub prefix: Option<Nibbles>,
+
+    /// Report addresses ending with these hex digits, e.g. dead
+    #[arg(long, value_name = "HEX", value_parser = parse_nibbles)]
+    pub suffix: Option<Nibbles>,
+
+    /// Report addresses containing these hex digits anywhere (repeatable)
+    #[arg(long, value_name = "HEX", value_parser = parse_nibbles)]
+    pub contains: Vec<Nibbles>,
+
+    /// Report addresses whose lowercase hex (40 digits, no 0x) matches this
+    /// regex, e.g. '^0{8}4{4}.*4444$'
+    #[arg(long, value_name = "REGEX", value_parser = parse_regex)]
+    pub pattern_regex: Option<Regex>,
+
+    /// Report addresses whose low 14 bits carry these Uniswap v4 hook
+    /// permissions, e.g. BEFORE_SWAP|AFTER_SWAP or 0xc0
+    #[arg(long, value_name = "MASK", value_parser = parse_hook_flags)]
+    pub hook_flags: Option<u16>,
+
+    /// Require the other permission bits to be clear, as PoolManager does
+    /// when validating a hook's address against its permissions
+    #[arg(long, requires = "hook_flags")]
+    pub exact_hook_flags: bool,
+
+    /// Match --prefix, --suffix, --contains and --pattern-regex case-sensitively
+    /// against the EIP-55 checksummed address, e.g. --prefix dEaD
+    #[arg(long)]
+    pub checksummed: bool,
+
+    /// Stop after this many matches (0 for never)
+    #[arg(long, default_value_t = 1)]
+    pub matches: u64,
+}
+
+// Hex digits as nibble values (an odd number of digits is fine), along with
+// the digits as given.
+#[derive(Debug, Clone)]
+pub struct Nibbles(Vec<u8>, String);
+
+fn parse_nibbles(value: &str) -> Result<Nibbles, String> {
+    let digits = value.strip_prefix("0x").unwrap_or(value);
+    if digits.is_empty() || digits.len() > 40 {
+        return Err("expected 1 to 40 hex digits".to_string());
+    }
+    digits
+        .chars()
+        .map(|c| c.to_digit(16).map(|d| d as u8).ok_or_else(|| format!("{:?} is not a hex digit", c)))
+        .collect::<Result<_, _>>()
+        .map(|nibbles| Nibbles(nibbles, digits.to_string()))
+}
+
+// Hook permission bits from v4-core's Hooks library.
+const HOOK_FLAGS: [(&str, u16); 14] = [
+    ("BEFORE_INITIALIZE", 1 << 13),
+    ("AFTER_INITIALIZE", 1 << 12),
+    ("BEFORE_ADD_LIQUIDITY", 1 << 11),
+    ("AFTER_ADD_LIQUIDITY", 1 << 10),
+    ("BEFORE_REMOVE_LIQUIDITY", 1 << 9),
+    ("AFTER_REMOVE_LIQUIDITY", 1 << 8),
+    ("BEFORE_SWAP", 1 << 7),
+    ("AFTER_SWAP", 1 << 6),
+    ("BEFORE_DONATE", 1 << 5),
+    ("AFTER_DONATE", 1 << 4),
+    ("BEFORE_SWAP_RETURNS_DELTA", 1 << 3),
+    ("AFTER_SWAP_RETURNS_DELTA", 1 << 2),
+    ("AFTER_ADD_LIQUIDITY_RETURNS_DELTA", 1 << 1),
+    ("AFTER_REMOVE_LIQUIDITY_RETURNS_DELTA", 1 << 0),
+];
+const ALL_HOOK_FLAGS: u16 = (1 << 14) - 1;
+
+// Flag names joined by `|` (with or without the _FLAG suffix), or a number.
+pub fn parse_hook_flags(value: &str) -> Result<u16, String> {
+    let mask = if let Some(hex) = value.strip_prefix("0x") {
+        u16::from_str_radix(hex, 16).map_err(|e| e.to_string())?
+    } else if let Ok(mask) = value.parse::<u16>() {
+        mask
+    } else {
+        let mut mask = 0;
+        for name in value.split('|').map(str::trim) {
+            let name = name.to_uppercase();
+            let name = name.strip_suffix("_FLAG").unwrap_or(&name);
+            let (_, bit) = HOOK_FLAGS
+                .iter()
+                .find(|(flag, _)| *flag == name)
+                .ok_or_else(|| format!("unknown hook flag {:?}", name))?;
+            mask |= bit;
+        }
+        mask
+    };
+    if mask & !ALL_HOOK_FLAGS != 0 {
+        return Err(format!("0x{:x} sets bits above the 14 hook permission bits", mask));
+    }
+    Ok(mask)
+}
+
+fn parse_regex(value: &str) -> Result<Regex, String> {
+    Regex::new(value).map_err(|e| e.to_string())
+}
+
+// Case-sensitive conditions on the checksummed address, checked only once the
+// case-insensitive ones hold, so the extra keccak runs for very few addresses.
+struct Checksummed {
+    prefix: Vec<u8>,
+    suffix: Vec<u8>,
+    contains: Vec<Vec<u8>>,
+    // The regex ignoring case, which the lowercase hex has to match first.
+    folded: Option<Regex>,
+}
+
+// All conditions given on the command line; an address has to meet every one.
+pub struct Pattern {
+    prefix: Vec<u8>,
+    suffix: Vec<u8>,
+    // As lowercase hex, to search the encoded address.
+    contains: Vec<Vec<u8>>,
+    regex: Option<Regex>,
+    checksummed: Option<Checksummed>,
+    // Permission bits that must be set, and the bits compared.
+    hook_flags: u16,
+    hook_mask: u16,
+    max_matches: u64,
+    found: AtomicU64,
+}
+
+impl PatternArgs {
+    // The pattern to match, if matching mode was asked for.
+    pub fn pattern(&self) -> Option<Pattern> {
+        if self.prefix.is_none()
+            && self.suffix.is_none()
+            && self.contains.is_empty()
+            && self.pattern_regex.is_none()
+            && self.hook_flags.is_none()
+        {
+            return None;
+        }
+        let hex_digits = |nibbles: &Nibbles| nibbles.0.iter().map(|n| b"0123456789abcdef"[*n as usize]).collect();
+        Some(Pattern {
+            prefix: self.prefix.clone().map_or(Vec::new(), |n| n.0),
+            suffix: self.suffix.clone().map_or(Vec::new(), |n| n.0),
+            contains: self.contains.iter().map(hex_digits).collect(),
+            regex: self.pattern_regex.clone(),
+            checksummed: self.checksummed.then(|| Checksummed {
+                prefix: self.prefix.as_ref().map_or(Vec::new(), |n| n.1.clone().into_bytes()),
+                suffix: self.suffix.as_ref().map_or(Vec::new(), |n| n.1.clone().into_bytes()),
+                contains: self.contains.iter().map(|n| n.1.clone().into_bytes()).collect(),
+                folded: self.pattern_regex.as_ref().map(|regex| Regex::new(&format!("(?i){}", regex)).unwrap()),
+            }),
+            hook_flags: self.hook_flags.unwrap_or(0),
+            hook_mask: match self.exact_hook_flags {
+                true => ALL_HOOK_FLAGS,
+                false => self.hook_flags.unwrap_or(0),
+            },
+            max_matches: self.matches,
+            found: AtomicU64::new(0),
+        })
+    }
+}
+
+fn contains_all(hex: &[u8], needles: &[Vec<u8>]) -> bool {
+    needles.iter().all(|needle| hex.windows(needle.len()).any(|window| window == needle.as_slice()))
+}
+
+fn nibble(address: &[u8; 20], i: usize) -> u8 {
+    (address[i / 2] >> (4 * (1 - i % 2))) & 0x0F
+}
+
+impl Pattern {
+    pub fn matches(&self, address: &[u8; 20]) -> bool {
+        if u16::from_be_bytes([address[18], address[19]]) & self.hook_mask != self.hook_flags {
+            return false;
+        }
+        // Prefix and suffix are compared on the raw nibbles, rejecting nearly
+        // every address before anything is hex-encoded.
+        if !self.prefix.iter().enumerate().all(|(i, n)| nibble(address, i) == *n) {
+            return false;
+        }
+        let offset = 40 - self.suffix.len();
+        if !self.suffix.iter().enumerate().all(|(i, n)| nibble(address, offset + i) == *n) {
+            return false;
+        }
+        if self.contains.is_empty() && self.regex.is_none() && self.checksummed.is_none() {
+            return true;
+        }
+        let mut buf = [0; 40];
+        hex::encode_to_slice(address, &mut buf).unwrap();
+        if !contains_all(&buf, &self.contains) {
+            return false;
+        }
+        let Some(checksummed) = &self.checksummed else {
+            return self.regex.iter().all(|regex| regex.is_match(&buf));
+        };
+        if !checksummed.folded.iter().all(|regex| regex.is_match(&buf)) {
+            return false;
+        }
+        checksum_to_slice(address, &mut buf);
+        buf.starts_with(&checksummed.prefix)
+            && buf.ends_with(&checksummed.suffix)
+            && contains_all(&buf, &checksummed.contains)
+            && self.regex.iter().all(|regex| regex.is_match(&buf))
+    }
+
+    // Print a match under the target's name, if it has one. Returns true for
+    // the match completing the target's --matches.
+    fn report(&self, name: Option<&str>, mode: Mode, salt: &[u8; 32], address: &[u8; 20]) -> bool {
+        let found = self.found.fetch_add(1, Ordering::Relaxed) + 1;
+        if self.max_matches != 0 && found > self.max_matches {
+            return false;
+        }
+        let address = match self.checksummed {
+            Some(_) => to_checksum(address),
+            None => format!("0x{}", hex::encode(address)),
+        };
+        // Keys are saved as they are reported.
+        let reproduce = match mode {
+            Mode::Eoa => eoa::save(salt),
+            mode => mode.describe(salt),
+        };
+        match name {
+            Some(name) => println!("Match [{}]: {}, {}", name, address, reproduce),
+            None => println!("Match: {}, {}", address, reproduce),
+        }
+        found == self.max_matches
+    }
+}
+
+// One line of a --targets file.
+#[derive(Parser)]
+#[command(no_binary_name = true)]
+struct TargetLine {
+    name: String,
+    #[command(flatten)]
+    pattern: PatternArgs,
+}
+
+// Every pattern to match, all checked against each address.
+pub struct Targets {
+    targets: Vec<(Option<String>, Pattern)>,
+    // Exit once this many targets are complete (None when some never are).
+    finish_after: Option<usize>,
+    complete: AtomicUsize,
+}
+
+impl Targets {
+    // The targets asked for on the command line, if matching mode was.
+    pub fn from_args(args: &PatternArgs) -> Result<Option<Self>, String> {
+        let targets = match &args.targets {
+            Some(path) => {
+                if args.pattern().is_some() {
+                    return Err("put every pattern in the --targets file".to_string());
+                }
+                read_targets(path)?
+            }
+            None => match args.pattern() {
+                Some(pattern) => vec![(None, pattern)],
+                None => return Ok(None),
+            },
+        };
+        let finish_after = targets.iter().all(|(_, p)| p.max_matches != 0).then_some(targets.len());
+        Ok(Some(Targets { targets, finish_after, complete: AtomicUsize::new(0) }))
+    }
+
+    // Report `address` to every target it matches, derived from `salt` in `mode`.
+    pub fn check(&self, mode: Mode, salt: &[u8; 32], address: &[u8; 20]) {
+        for (name, pattern) in &self.targets {
+            if pattern.matches(address) && pattern.report(name.as_deref(), mode, salt, address) {
+                let complete = self.complete.fetch_add(1, Ordering::Relaxed) + 1;
+                if Some(complete) == self.finish_after {
+                    runtime::exit(exit_code::TARGET_REACHED);
+                }
+            }
+        }
+    }
+}
+
+fn read_targets(path: &Path) -> Result<Vec<(Option<String>, Pattern)>, String> {
+    let contents = std::fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+    let mut targets = Vec::new();
+    for (number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let at = |e: String| format!("{}:{}: {}", path.display(), number + 1, e);
+        let target = TargetLine::try_parse_from(line.split_whitespace()).map_err(|e| {
+            // Only clap's first line; the rest is usage help for the command line.
+            let message = e.to_string();
+            at(message.lines().next().unwrap_or_default().trim_start_matches("error: ").to_string())
+        })?;
+        if target.pattern.targets.is_some() {
+            return Err(at("targets cannot include other --targets files".to_string()));
+        }
+        let pattern = target.pattern.pattern().ok_or_else(|| at(format!("target {} has no pattern", target.name)))?;
+        targets.push((Some(target.name), pattern));
+    }
+    if targets.is_empty() {
+        return Err(format!("{} has no targets", path.display()));
+    }
+    Ok(targets)
+}
@@ -0,0 +1,59 @@
+// Writing files that may hold a winning salt so a crash, power loss or OOM
+// kill can never leave them truncated: the contents go to a temporary file
+// next to the target, are synced to disk, and only then renamed over it, so
+// the file always holds either the old contents or the new ones in full.
+
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Replace the file at `path` with `contents` atomically and durably.
+pub fn write(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    let temporary = temporary_path(path);
+    let written = File::create(&temporary).and_then(|mut file| {
+        file.write_all(contents.as_ref())?;
+        file.sync_all()
+    });
+    let renamed = written.and_then(|_| fs::rename(&temporary, path));
+    if renamed.is_err() {
+        fs::remove_file(&temporary).ok();
+    }
+    renamed?;
+    sync_directory(path);
+    Ok(())
+}
+
+/// Make sure a file written some other way, say by a library, is on disk.
+pub fn sync(path: &Path) -> io::Result<()> {
+    File::open(path)?.sync_all()?;
+    sync_directory(path);
+    Ok(())
+}
+
+// Counts the writes of this process, for their temporary files.
+static WRITES: AtomicU64 = AtomicU64::new(0);
+
+// `.<name>.tmp-<pid>-<n>` in the same directory, so the rename stays on one
+// filesystem and neither concurrent runs nor threads of one run, say the
+// checkpoint saver and a schedule closing, share it.
+fn temporary_path(path: &Path) -> PathBuf {
+    let name = path.file_name().map_or("file".into(), |name| name.to_string_lossy());
+    let write = WRITES.fetch_add(1, Ordering::Relaxed);
+    path.with_file_name(format!(".{}.tmp-{}-{}", name, std::process::id(), write))
+}
+
+// A rename or new file only survives a power loss once its directory entry
+// is synced too. Windows has no such thing to sync, nor a way to open a
+// directory for it.
+fn sync_directory(path: &Path) {
+    #[cfg(unix)]
+    if let Some(directory) = path.parent() {
+        let directory = if directory.as_os_str().is_empty() { Path::new(".") } else { directory };
+        if let Ok(directory) = File::open(directory) {
+            directory.sync_all().ok();
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+}
@@ -0,0 +1,220 @@
+// Data-driven scoring: point weights for runs of nibbles at the start of the
+// address, for nibble values anywhere and for patterns at fixed positions,
+// read from a JSON profile.
+//
+// Profiles are named and versioned (`uniswap-v4-challenge@1`), so a change to
+// the rules becomes a new version next to the old one and earlier results stay
+// reproducible. Shipped presets live in profiles/.
+
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::error::MinerError;
+use crate::score::Scorer;
+
+// (name, version, profile); a new version of the rules gets a new entry.
+const PRESETS: [(&str, u32, &str); 1] = [(
+    "uniswap-v4-challenge",
+    1,
+    include_str!("../profiles/uniswap-v4-challenge@1.json"),
+)];
+
+// Older names still accepted for presets.
+const ALIASES: [(&str, &str); 1] = [("uniswap-v4", "uniswap-v4-challenge")];
+
+// The preset called `name` or `name@version`, the latest version when none is given.
+fn preset(name: &str) -> Option<(String, &'static str)> {
+    let (name, version) = match name.split_once('@') {
+        Some((name, version)) => (name, Some(version.parse::<u32>().ok()?)),
+        None => (name, None),
+    };
+    let name = ALIASES.iter().find(|(alias, _)| *alias == name).map_or(name, |(_, name)| name);
+    PRESETS
+        .iter()
+        .filter(|(preset, v, _)| *preset == name && version.iter().all(|version| version == v))
+        .max_by_key(|(_, v, _)| *v)
+        .map(|(name, version, json)| (format!("{}@{}", name, version), *json))
+}
+
+// A run of one nibble value, scanned in order from the start of the address
+// after the previous runs.
+struct Run {
+    nibble: u8,
+    each: u32,
+    // The address scores 0 unless the run is at least one nibble long (when
+    // any nibbles are left).
+    required: bool,
+    // (length, points) for runs at least / exactly this long.
+    at_least: Vec<(usize, u32)>,
+    exactly: Vec<(usize, u32)>,
+}
+
+// Nibbles expected at a position (negative in the file to count from the end).
+struct PatternBonus {
+    at: usize,
+    nibbles: Vec<u8>,
+    points: u32,
+}
+
+pub struct Profile {
+    // `name@version`, when the profile declares them.
+    pub id: Option<String>,
+    runs: Vec<Run>,
+    each: [u32; 16],
+    patterns: Vec<PatternBonus>,
+}
+
+fn preset_names() -> String {
+    PRESETS.iter().map(|(name, version, _)| format!("{}@{}", name, version)).collect::<Vec<_>>().join(", ")
+}
+
+fn nibble_value(value: &str) -> Result<u8, String> {
+    match value.chars().collect::<Vec<_>>()[..] {
+        [c] => c.to_digit(16).map(|d| d as u8).ok_or_else(|| format!("{:?} is not a hex digit", value)),
+        _ => Err(format!("expected a single hex digit, got {:?}", value)),
+    }
+}
+
+fn points(value: &Value, what: &str) -> Result<u32, String> {
+    value
+        .as_u64()
+        .and_then(|points| u32::try_from(points).ok())
+        .ok_or_else(|| format!("{} must be a non-negative integer", what))
+}
+
+// {"<length>": points, ...}
+fn length_bonuses(value: &Value, what: &str) -> Result<Vec<(usize, u32)>, String> {
+    let Some(map) = value.as_object() else {
+        return match value {
+            Value::Null => Ok(Vec::new()),
+            _ => Err(format!("{} must be an object of length: points", what)),
+        };
+    };
+    map.iter()
+        .map(|(length, value)| {
+            let length = length.parse().map_err(|_| format!("{}: {:?} is not a length", what, length))?;
+            Ok((length, points(value, what)?))
+        })
+        .collect()
+}
+
+impl Profile {
+    // A preset name (optionally `@version`) or the path of a profile file.
+    pub fn load(name: &str) -> Result<Self, MinerError> {
+        let json = match preset(name) {
+            Some((_, json)) => json.to_string(),
+            None if name.contains('@') && !Path::new(name).exists() => {
+                return Err(MinerError::Config(format!("no preset profile {} (available: {})", name, preset_names())));
+            }
+            None => std::fs::read_to_string(Path::new(name)).map_err(|e| MinerError::Config(format!("cannot read profile {}: {}", name, e)))?,
+        };
+        let profile = Self::parse(&json).map_err(|e| MinerError::Config(format!("profile {}: {}", name, e)))?;
+        if let Some(id) = &profile.id {
+            println!("Scoring with profile {}", id);
+        }
+        Ok(profile)
+    }
+
+    pub fn parse(json: &str) -> Result<Self, MinerError> {
+        Self::read(json).map_err(MinerError::Config)
+    }
+
+    fn read(json: &str) -> Result<Self, String> {
+        let profile: Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        let id = match (profile["name"].as_str(), profile["version"].as_u64()) {
+            (Some(name), Some(version)) => Some(format!("{}@{}", name, version)),
+            (Some(name), None) => Some(name.to_string()),
+            _ => None,
+        };
+        let mut runs = Vec::new();
+        for run in profile["runs"].as_array().into_iter().flatten() {
+            let nibble = nibble_value(run["nibble"].as_str().ok_or("every run needs a \"nibble\"")?)?;
+            runs.push(Run {
+                nibble,
+                each: if run["each"].is_null() { 0 } else { points(&run["each"], "each")? },
+                required: run["required"].as_bool().unwrap_or(false),
+                at_least: length_bonuses(&run["at_least"], "at_least")?,
+                exactly: length_bonuses(&run["exactly"], "exactly")?,
+            });
+        }
+
+        let mut each = [0; 16];
+        if let Some(map) = profile["each"].as_object() {
+            for (nibble, value) in map {
+                each[nibble_value(nibble)? as usize] = points(value, "each")?;
+            }
+        }
+
+        let mut patterns = Vec::new();
+        for pattern in profile["patterns"].as_array().into_iter().flatten() {
+            let hex = pattern["hex"].as_str().ok_or("every pattern needs \"hex\" digits")?;
+            let nibbles = hex
+                .chars()
+                .map(|c| nibble_value(&c.to_string()))
+                .collect::<Result<Vec<_>, _>>()?;
+            let at = pattern["at"].as_i64().ok_or("every pattern needs a position \"at\"")?;
+            let at = if at < 0 { 40 + at } else { at };
+            if nibbles.is_empty() || at < 0 || at as usize + nibbles.len() > 40 {
+                return Err(format!("pattern {:?} at {} does not fit in an address", hex, pattern["at"]));
+            }
+            patterns.push(PatternBonus { at: at as usize, nibbles, points: points(&pattern["points"], "points")? });
+        }
+        let profile = Profile { id, runs, each, patterns };
+        if profile.max_score().is_none() {
+            return Err(format!("its points can add up to more than {}", u32::MAX));
+        }
+        Ok(profile)
+    }
+
+    // The most any address could score, if it fits in a u32, so that scoring
+    // never overflows: the runs together are at most the whole address, and
+    // one exact length of each run at most applies.
+    fn max_score(&self) -> Option<u32> {
+        let runs = self.runs.iter().map(|run| run.each).max().unwrap_or(0).checked_mul(40)?;
+        let mut max = runs.checked_add(self.each.iter().max().unwrap().checked_mul(40)?)?;
+        for run in &self.runs {
+            for (_, points) in &run.at_least {
+                max = max.checked_add(*points)?;
+            }
+            max = max.checked_add(run.exactly.iter().map(|(_, points)| *points).max().unwrap_or(0))?;
+        }
+        self.patterns.iter().try_fold(max, |max, pattern| max.checked_add(pattern.points))
+    }
+}
+
+impl Scorer for Profile {
+    fn score(&self, address: &[u8; 20]) -> u32 {
+        let mut nibbles = [0; 40];
+        for (i, byte) in address.iter().enumerate() {
+            nibbles[2 * i] = byte >> 4;
+            nibbles[2 * i + 1] = byte & 0x0F;
+        }
+
+        let mut score = 0;
+        let mut i = 0;
+        for run in &self.runs {
+            if i == nibbles.len() {
+                break;
+            }
+            if run.required && nibbles[i] != run.nibble {
+                return 0;
+            }
+            let start = i;
+            while i < nibbles.len() && nibbles[i] == run.nibble {
+                i += 1;
+            }
+            let length = i - start;
+            score += run.each * length as u32;
+            score += run.at_least.iter().filter(|(l, _)| length >= *l).map(|(_, p)| p).sum::<u32>();
+            score += run.exactly.iter().filter(|(l, _)| length == *l).map(|(_, p)| p).sum::<u32>();
+        }
+        score += nibbles.iter().map(|n| self.each[*n as usize]).sum::<u32>();
+        for pattern in &self.patterns {
+            if nibbles[pattern.at..pattern.at + pattern.nibbles.len()] == pattern.nibbles[..] {
+                score += pattern.points;
+            }
+        }
+        score
+    }
+}
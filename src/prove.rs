@@ -0,0 +1,98 @@
+// Signed proof that a salt was found by the submitter at a given time, for
+// establishing priority without submitting on-chain yet.
+
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use clap::Args;
+use serde_json::json;
+
+use crate::network::NetworkArgs;
+use crate::persist;
+use crate::rpc::to_hex;
+use crate::wallet::WalletArgs;
+use crate::{compute_score, create2_addr, decode_hex, INITCODE_HASH_HEX};
+
+/// Sign an EIP-191 message binding a found salt to the submitter
+#[derive(Args, Debug)]
+pub struct ProveArgs {
+    /// Salt to prove (32 bytes, hex)
+    #[arg(long, value_parser = decode_hex::<32>)]
+    pub salt: [u8; 32],
+
+    #[command(flatten)]
+    pub network: NetworkArgs,
+
+    /// Hash of the initcode the salt was mined for
+    #[arg(long, default_value = INITCODE_HASH_HEX, value_parser = decode_hex::<32>)]
+    pub init_code_hash: [u8; 32],
+
+    /// Unix timestamp to attest (default: now)
+    #[arg(long)]
+    pub timestamp: Option<u64>,
+
+    /// Write the proof as JSON to this file (default: stdout)
+    #[arg(long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub wallet: WalletArgs,
+}
+
+fn message(salt: &[u8; 32], address: &[u8; 20], score: u32, deployer: &[u8; 20], code_hash: &[u8; 32], timestamp: u64) -> String {
+    let time = humantime::format_rfc3339_seconds(UNIX_EPOCH + Duration::from_secs(timestamp));
+    format!(
+        "Uniswap v4 address challenge discovery\n\
+         Salt: {}\n\
+         Address: {}\n\
+         Score: {}\n\
+         Deployer: {}\n\
+         Initcode hash: {}\n\
+         Timestamp: {} ({})",
+        to_hex(salt),
+        to_hex(address),
+        score,
+        to_hex(deployer),
+        to_hex(code_hash),
+        timestamp,
+        time
+    )
+}
+
+pub fn run(mut args: ProveArgs) -> Result<(), String> {
+    let deployer = args.network.contract()?;
+    let address = create2_addr(&deployer, &args.salt, &args.init_code_hash);
+    let score = compute_score(&address);
+    let timestamp = match args.timestamp {
+        Some(timestamp) => timestamp,
+        None => SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| e.to_string())?
+            .as_secs(),
+    };
+    let message = message(&args.salt, &address, score, &deployer, &args.init_code_hash, timestamp);
+
+    let mut signer = args.wallet.signer()?;
+    if signer.is_hardware() {
+        println!("{}", message);
+        println!("Confirm the message on your device...");
+    }
+    let signature = signer.sign_message(message.as_bytes())?;
+    let proof = json!({
+        "message": message,
+        "signature": to_hex(&signature),
+        "signer": to_hex(&signer.address()),
+        "salt": to_hex(&args.salt),
+        "address": to_hex(&address),
+        "score": score,
+        "timestamp": timestamp,
+    });
+    let rendered = serde_json::to_string_pretty(&proof).unwrap() + "\n";
+    match &args.output {
+        Some(path) => persist::write(path, rendered).map_err(|e| format!("cannot write {}: {}", path.display(), e)),
+        None => {
+            print!("{}", rendered);
+            Ok(())
+        }
+    }
+}
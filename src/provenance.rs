@@ -0,0 +1,87 @@
+// Where each result came from, stamped on everything written out: the
+// machine, the hashing backend and miner version, a hash of the job, and the
+// state of the salt generator that produced the salt. Results gathered from
+// many machines can then be audited, and any one of them reproduced.
+
+use std::sync::{Arc, OnceLock};
+
+use alloy_primitives::B256;
+use serde::Serialize;
+
+use crate::{keccak256, Candidate};
+use crate::results::hostname;
+use crate::salts::{Origin, SaltGenerator};
+
+// What every result of this run shares.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Provenance {
+    pub host: String,
+    pub backend: String,
+    pub engine: String,
+    // Hash of the job mined for; None when it was never given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub job: Option<B256>,
+}
+
+impl Provenance {
+    pub fn new(job: Option<B256>) -> Self {
+        Provenance {
+            host: hostname(),
+            backend: format!("cpu-{}", std::env::consts::ARCH),
+            engine: concat!(env!("CARGO_PKG_NAME"), " ", env!("CARGO_PKG_VERSION")).to_string(),
+            job,
+        }
+    }
+}
+
+// keccak256 of `job` as compact JSON, the same for the same job on every
+// machine.
+pub fn job_hash(job: &impl Serialize) -> B256 {
+    keccak256(serde_json::to_vec(job).unwrap())
+}
+
+// A result's stamp: the run's provenance and the generator state, if known.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Stamp {
+    #[serde(flatten)]
+    pub provenance: Provenance,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub origin: Option<Origin>,
+}
+
+struct Run {
+    provenance: Provenance,
+    salts: Arc<dyn SaltGenerator>,
+}
+
+static RUN: OnceLock<Run> = OnceLock::new();
+
+// Stamp results with `provenance` and origins from `salts`; without it they
+// get the host, backend and engine alone.
+pub fn init(provenance: Provenance, salts: Arc<dyn SaltGenerator>) {
+    RUN.set(Run { provenance, salts }).ok();
+}
+
+pub fn get() -> Provenance {
+    RUN.get().map_or_else(|| Provenance::new(None), |run| run.provenance.clone())
+}
+
+pub fn stamp(salt: &[u8; 32]) -> Stamp {
+    let origin = RUN.get().and_then(|run| run.salts.origin(salt));
+    Stamp { provenance: get(), origin }
+}
+
+// A result as written out, with its stamp alongside.
+#[derive(Debug, Clone, Serialize)]
+pub struct Stamped {
+    #[serde(flatten)]
+    pub candidate: Candidate,
+    #[serde(flatten)]
+    pub stamp: Stamp,
+}
+
+impl From<Candidate> for Stamped {
+    fn from(candidate: Candidate) -> Self {
+        Stamped { candidate, stamp: stamp(&candidate.salt) }
+    }
+}
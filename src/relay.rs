@@ -0,0 +1,99 @@
+// Submitting through a relayer or contract wallet, where the account signing the
+// transaction is not the msg.sender the challenge sees.
+
+use clap::Args;
+
+use crate::challenge::{self, Simulation};
+use crate::decode_hex;
+use crate::rpc::RpcClient;
+
+/// Relayed submission
+#[derive(Args, Debug, Clone)]
+pub struct RelayArgs {
+    /// Submit through this contract wallet, which calls the challenge via
+    /// `execute(address,uint256,bytes)`; salts must be bound to it
+    #[arg(long, value_name = "ADDRESS", value_parser = decode_hex::<20>, requires = "allow_sender_mismatch")]
+    pub relayer: Option<[u8; 20]>,
+
+    /// Acknowledge that the transaction sender differs from the salt's address
+    #[arg(long, requires = "relayer")]
+    pub allow_sender_mismatch: bool,
+}
+
+// Calldata for `execute(address dest, uint256 value, bytes data)`, the call
+// forwarding entry point of ERC-4337 style smart accounts.
+pub fn execute_calldata(target: &[u8; 20], data: &[u8]) -> Vec<u8> {
+    let mut calldata = challenge::selector("execute(address,uint256,bytes)").to_vec();
+    calldata.extend_from_slice(&[0; 12]);
+    calldata.extend_from_slice(target);
+    calldata.extend_from_slice(&[0; 32]);
+    let mut offset = [0; 32];
+    offset[31] = 0x60;
+    calldata.extend_from_slice(&offset);
+    let mut length = [0; 32];
+    length[24..].copy_from_slice(&(data.len() as u64).to_be_bytes());
+    calldata.extend_from_slice(&length);
+    calldata.extend_from_slice(data);
+    calldata.resize(calldata.len() + (32 - data.len() % 32) % 32, 0);
+    calldata
+}
+
+// A call made through `execute`, as decoded from its calldata.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Execute<'a> {
+    pub target: [u8; 20],
+    pub value: u128,
+    pub data: &'a [u8],
+}
+
+// The reverse of execute_calldata, for showing what a relayed transaction
+// does; None when `calldata` is not a call of `execute`.
+pub fn decode_execute(calldata: &[u8]) -> Option<Execute<'_>> {
+    let (selector, words) = calldata.split_first_chunk::<4>()?;
+    if *selector != challenge::selector("execute(address,uint256,bytes)") || words.len() < 128 {
+        return None;
+    }
+    let word = |i: usize| &words[32 * i..32 * (i + 1)];
+    if word(0)[..12] != [0; 12]
+        || word(1)[..16] != [0; 16]
+        || word(2)[..24] != [0; 24]
+        || word(3)[..24] != [0; 24]
+    {
+        return None;
+    }
+    // Both come from the calldata, so adding them up may overflow.
+    let offset = u64::from_be_bytes(word(2)[24..].try_into().unwrap()) as usize;
+    let start = offset.checked_add(32)?;
+    let length = u64::from_be_bytes(words.get(offset..start)?[24..].try_into().unwrap()) as usize;
+    Some(Execute {
+        target: word(0)[12..].try_into().unwrap(),
+        value: u128::from_be_bytes(word(1)[16..].try_into().unwrap()),
+        data: words.get(start..start.checked_add(length)?)?,
+    })
+}
+
+pub fn warn_mismatch(sender: &[u8; 20], relayer: &[u8; 20]) {
+    eprintln!(
+        "WARNING: the transaction is sent by 0x{} but the challenge will see 0x{} as msg.sender. \
+         Make sure 0x{} forwards the call unchanged, or the submission fails and the salt is revealed.",
+        hex::encode(sender),
+        hex::encode(relayer),
+        hex::encode(relayer)
+    );
+}
+
+// Ask the contract itself whether it accepts the salt from the relayer, rather
+// than relying on our reading of its sender rules.
+pub fn check_relayer(rpc: &RpcClient, contract: &[u8; 20], relayer: &[u8; 20], salt: &[u8; 32]) -> Result<(), String> {
+    if rpc.get_code(relayer)?.is_empty() {
+        return Err(format!("relayer 0x{} has no code, it cannot forward calls", hex::encode(relayer)));
+    }
+    match challenge::simulate_update(rpc, contract, relayer, salt)? {
+        Simulation::InvalidSender => Err(format!(
+            "the challenge rejects this salt when called by relayer 0x{}",
+            hex::encode(relayer)
+        )),
+        Simulation::CompetitionOver => Err("the competition is over".to_string()),
+        _ => Ok(()),
+    }
+}
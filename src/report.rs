@@ -0,0 +1,224 @@
+// A report of the whole run written to --report-dir when it ends, whatever
+// ends it: what it mined for, how long it ran, its hashes and hashrates, the
+// best results with their salts and the best score over time. One JSON file
+// and the same as text, named after the time the run ended. With --energy,
+// the energy used and hashes per joule as well.
+
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use crate::schema::Version;
+use crate::provenance::{self, Provenance, Stamped};
+use crate::timeline::{self, Point, Timeline};
+use crate::energy::{self, Energy};
+use crate::search::Control;
+use crate::top::Top;
+use crate::{deadline, odds, persist, Candidate};
+
+// What the run mined for.
+#[derive(Debug, Clone, Serialize)]
+pub struct Config {
+    pub command_line: Vec<String>,
+    pub mode: String,
+    #[serde(with = "crate::schema::hex")]
+    pub deployer: [u8; 20],
+    #[serde(with = "crate::schema::hex")]
+    pub code_hash: [u8; 32],
+    pub scorer: String,
+    pub threads: usize,
+}
+
+// Flags whose values stay out of reports: keys, and URLs that often carry
+// an API key.
+const SECRET_FLAGS: [&str; 3] = ["--private-key", "--rpc-url", "--ws-url"];
+
+// `args` with the values of SECRET_FLAGS replaced, for Config::command_line.
+pub fn redact(args: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut redact_next = false;
+    args.into_iter()
+        .map(|arg| {
+            let secret = std::mem::replace(&mut redact_next, SECRET_FLAGS.contains(&arg.as_str()));
+            match SECRET_FLAGS.iter().find(|flag| arg.starts_with(&format!("{}=", flag))) {
+                Some(flag) => format!("{}=<redacted>", flag),
+                None if secret => "<redacted>".to_string(),
+                None => arg,
+            }
+        })
+        .collect()
+}
+
+// Hashes a second: the run's average, and the highest and lowest of the
+// status lines when there were any.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Hashrate {
+    pub average: f64,
+    pub peak: Option<f64>,
+    pub lowest: Option<f64>,
+    pub samples: usize,
+}
+
+impl Hashrate {
+    pub fn new(hashes: u64, elapsed: Duration, samples: &[f64]) -> Self {
+        Hashrate {
+            average: hashes as f64 / elapsed.as_secs_f64().max(1e-9),
+            peak: samples.iter().copied().reduce(f64::max),
+            lowest: samples.iter().copied().reduce(f64::min),
+            samples: samples.len(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    pub version: Version,
+    // Why the run ended.
+    pub reason: String,
+    #[serde(flatten)]
+    pub provenance: Provenance,
+    // Unix times.
+    pub started: u64,
+    pub ended: u64,
+    #[serde(rename = "elapsed_secs", with = "crate::schema::secs")]
+    pub elapsed: Duration,
+    pub config: Config,
+    pub hashes: u64,
+    pub hashrate: Hashrate,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub energy: Option<Energy>,
+    pub best: Option<Stamped>,
+    // The --top results, or the best alone without --top.
+    pub top: Vec<Stamped>,
+    pub best_over_time: Vec<Point>,
+}
+
+fn time(unix: u64) -> String {
+    humantime::format_rfc3339_seconds(UNIX_EPOCH + Duration::from_secs(unix)).to_string()
+}
+
+impl Report {
+    pub fn text(&self) -> String {
+        let mut out = String::new();
+        let c = &self.config;
+        writeln!(out, "{} report", env!("CARGO_PKG_NAME")).ok();
+        writeln!(out).ok();
+        let p = &self.provenance;
+        writeln!(out, "Host:       {}", p.host).ok();
+        writeln!(out, "Engine:     {} on {}", p.engine, p.backend).ok();
+        if let Some(job) = p.job {
+            writeln!(out, "Job hash:   {}", job).ok();
+        }
+        writeln!(out, "Command:    {}", c.command_line.join(" ")).ok();
+        writeln!(out, "Mode:       {}, deployer 0x{}, init code hash 0x{}", c.mode, hex::encode(c.deployer), hex::encode(c.code_hash)).ok();
+        writeln!(out, "Scorer:     {}", c.scorer).ok();
+        writeln!(out, "Threads:    {}", c.threads).ok();
+        writeln!(out, "Started:    {}", time(self.started)).ok();
+        writeln!(out, "Ended:      {} ({})", time(self.ended), self.reason).ok();
+        writeln!(out, "Duration:   {}", deadline::format_countdown(self.elapsed)).ok();
+        writeln!(out, "Hashes:     {}", odds::format_count(self.hashes as f64)).ok();
+        let rate = &self.hashrate;
+        write!(out, "Hashrate:   {} average", odds::format_rate(rate.average)).ok();
+        if let (Some(peak), Some(lowest)) = (rate.peak, rate.lowest) {
+            write!(out, ", {} peak, {} lowest over {} status lines", odds::format_rate(peak), odds::format_rate(lowest), rate.samples).ok();
+        }
+        writeln!(out).ok();
+        if let Some(e) = &self.energy {
+            let efficiency = energy::format_efficiency(e.hashes_per_joule);
+            write!(out, "Energy:     {}, {:.1} W average, {}", energy::format_joules(e.joules), e.watts, efficiency).ok();
+            match e.cost {
+                Some(cost) => writeln!(out, ", costing {:.4}", cost).ok(),
+                None => writeln!(out).ok(),
+            };
+        }
+        match self.best.as_ref().map(|best| &best.candidate) {
+            Some(best) => writeln!(out, "Best:       score {} at 0x{}, salt 0x{}", best.score, hex::encode(best.address), hex::encode(best.salt)).ok(),
+            None => writeln!(out, "Best:       nothing found").ok(),
+        };
+        if !self.top.is_empty() {
+            writeln!(out, "\nTop {} results:", self.top.len()).ok();
+            for (rank, Stamped { candidate: c, stamp }) in self.top.iter().enumerate() {
+                write!(out, "{:>3}. score {} at 0x{}, salt 0x{}", rank + 1, c.score, hex::encode(c.address), hex::encode(c.salt)).ok();
+                match stamp.origin {
+                    Some(origin) => writeln!(out, " ({})", origin).ok(),
+                    None => writeln!(out).ok(),
+                };
+            }
+        }
+        if !self.best_over_time.is_empty() {
+            writeln!(out, "\nBest score over time:").ok();
+            out += &timeline::chart(&self.best_over_time, self.elapsed, 60, 8, false);
+            for p in &self.best_over_time {
+                writeln!(out, "  {:>12} score {:>3} after {} hashes", deadline::format_countdown(p.elapsed), p.score, odds::format_count(p.hashes as f64)).ok();
+            }
+        }
+        out
+    }
+
+    // Write report-<time>.json and .txt into `dir`, returning the JSON's path.
+    pub fn write(&self, dir: &Path) -> Result<PathBuf, String> {
+        let stamp = time(self.ended).replace(':', "");
+        let json = dir.join(format!("report-{}.json", stamp));
+        let text = dir.join(format!("report-{}.txt", stamp));
+        let written = persist::write(&json, serde_json::to_string_pretty(self).unwrap() + "\n")
+            .and_then(|_| persist::write(&text, self.text()));
+        written.map(|_| json).map_err(|e| format!("cannot write the report to {}: {}", dir.display(), e))
+    }
+}
+
+// What a report is made from as the run goes.
+pub struct Run {
+    pub config: Config,
+    pub timeline: Arc<Timeline>,
+    pub control: Arc<Control>,
+    pub best: Arc<Mutex<Option<Candidate>>>,
+    pub top: Option<Arc<Top>>,
+    // The hashrate of each status line.
+    pub hashrates: Arc<Mutex<Vec<f64>>>,
+    pub electricity_price: Option<f64>,
+}
+
+impl Run {
+    pub fn report(&self, reason: &str) -> Report {
+        let hashes = self.control.hashes.load(Ordering::Relaxed);
+        let elapsed = self.timeline.elapsed();
+        let ended = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |t| t.as_secs());
+        let best = *self.best.lock().unwrap_or_else(PoisonError::into_inner);
+        let hashrate = Hashrate::new(hashes, elapsed, &self.hashrates.lock().unwrap_or_else(PoisonError::into_inner));
+        Report {
+            version: Version,
+            reason: reason.to_string(),
+            provenance: provenance::get(),
+            started: ended.saturating_sub(elapsed.as_secs()),
+            ended,
+            elapsed,
+            config: self.config.clone(),
+            hashes,
+            hashrate,
+            energy: energy::joules().map(|joules| Energy::new(hashes, joules, elapsed, self.electricity_price)),
+            best: best.map(Stamped::from),
+            top: self.top.as_ref().map_or(best.into_iter().collect(), |top| top.entries()).into_iter().map(Stamped::from).collect(),
+            best_over_time: self.timeline.points(),
+        }
+    }
+}
+
+type OnExit = Box<dyn FnOnce(&str) + Send>;
+
+static ON_EXIT: Mutex<Option<OnExit>> = Mutex::new(None);
+
+// Have `write` called with the reason once the run ends, however it ends.
+pub fn on_exit(write: impl FnOnce(&str) + Send + 'static) {
+    *ON_EXIT.lock().unwrap_or_else(PoisonError::into_inner) = Some(Box::new(write));
+}
+
+// The run is ending for `reason`; only the first call does anything.
+pub fn exit(reason: &str) {
+    let write = ON_EXIT.lock().unwrap_or_else(PoisonError::into_inner).take();
+    if let Some(write) = write {
+        write(reason);
+    }
+}
@@ -0,0 +1,52 @@
+// Closed-loop submission: whenever mining beats the on-chain best while someone
+// else holds the lead, submit the new salt right away instead of waiting for
+// the deadline.
+
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::monitor;
+use crate::rpc::RpcClient;
+use crate::runtime;
+use crate::submit::Submitter;
+use crate::Candidate;
+
+// Submit candidates sent to it, at most `max_submissions` times, until the
+// sender is dropped. Candidates queued up while a submission is in flight are
+// collapsed into the best of them.
+pub fn spawn(rpc: RpcClient, contract: [u8; 20], submitter: Arc<Mutex<Submitter>>, max_submissions: u32) -> Sender<Candidate> {
+    let (sender, receiver) = mpsc::channel::<Candidate>();
+    runtime::spawn_blocking("re-submit", move |_| {
+        let mut submissions = 0;
+        while let Ok(first) = receiver.recv() {
+            let candidate = receiver.try_iter().fold(first, |best, c| if c.score > best.score { c } else { best });
+            if submissions >= max_submissions {
+                println!("Re-submit: limit of {} submissions reached, not submitting score {}", max_submissions, candidate.score);
+                continue;
+            }
+            // The nonce comes from the node and the spending is counted
+            // before sending, so a submission that panicked left nothing
+            // half-done.
+            let mut submitter = submitter.lock().unwrap_or_else(PoisonError::into_inner);
+            match monitor::fetch_best(&rpc, &contract) {
+                Ok(chain) if chain.score >= candidate.score => continue,
+                Ok(chain) if chain.submitter == submitter.msg_sender() => continue,
+                Ok(chain) => println!(
+                    "Re-submit: 0x{} leads with score {}, submitting score {}",
+                    hex::encode(chain.submitter),
+                    chain.score,
+                    candidate.score
+                ),
+                Err(e) => {
+                    eprintln!("Warning: re-submit: cannot read on-chain best: {}", e);
+                    continue;
+                }
+            }
+            submissions += 1;
+            if let Err(e) = submitter.submit(&rpc, &contract, &candidate.salt) {
+                eprintln!("Re-submit failed: {}", e);
+            }
+        }
+    });
+    sender
+}
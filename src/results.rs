@@ -0,0 +1,181 @@
+// Every candidate scoring at least --min-score, appended to a JSON Lines or
+// CSV file as it is found, so the near-best results of a long run are still
+// there once a better one has taken over as the best. A candidate already in
+// the file, rediscovered after a restart, is not appended again. Each line is
+// stamped with where it came from; see provenance. Also the CSV of run
+// statistics written with each status line.
+
+use std::collections::HashSet;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use crate::provenance::{self, Provenance, Stamp};
+use crate::{decode_hex, Candidate};
+
+// One line of the file.
+#[derive(Serialize)]
+struct Entry<'a> {
+    #[serde(flatten)]
+    candidate: &'a Candidate,
+    // Unix time it was found at.
+    timestamp: u64,
+    #[serde(flatten)]
+    stamp: Stamp,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    JsonLines,
+    Csv,
+}
+
+const CANDIDATES_HEADER: &str = "timestamp,host,score,address,salt,backend,engine,job,origin";
+const STATS_HEADER: &str = "timestamp,hashes,hashrate,best_score";
+
+// Salts and addresses met so far, to keep results from being recorded twice.
+#[derive(Default)]
+pub struct Seen {
+    salts: HashSet<[u8; 32]>,
+    addresses: HashSet<[u8; 20]>,
+}
+
+impl Seen {
+    // Whether neither `candidate`'s salt nor its address has been met before.
+    pub fn insert(&mut self, candidate: &Candidate) -> bool {
+        let new_salt = self.salts.insert(candidate.salt);
+        let new_address = self.addresses.insert(candidate.address);
+        new_salt && new_address
+    }
+}
+
+pub struct CandidateLog {
+    format: Format,
+    file: Appender,
+    seen: Mutex<Seen>,
+}
+
+impl CandidateLog {
+    // Append to `path`, creating it if needed.
+    pub fn open(path: &Path, format: Format) -> Result<Self, String> {
+        let header = (format == Format::Csv).then_some(CANDIDATES_HEADER);
+        let seen = Mutex::new(read_seen(path, format));
+        Ok(CandidateLog { format, file: Appender::open(path, header)?, seen })
+    }
+
+    // Write `candidate` out unless it already is, synced to disk right away
+    // so a crash or power loss loses nothing.
+    pub fn record(&self, candidate: &Candidate) {
+        if !self.seen.lock().unwrap_or_else(PoisonError::into_inner).insert(candidate) {
+            return;
+        }
+        let timestamp = now();
+        let stamp = provenance::stamp(&candidate.salt);
+        let line = match self.format {
+            Format::JsonLines => serde_json::to_string(&Entry { candidate, timestamp, stamp }).unwrap(),
+            Format::Csv => {
+                let Provenance { host, backend, engine, job } = &stamp.provenance;
+                format!(
+                    "{},{},{},0x{},0x{},{},{},{},{}",
+                    timestamp,
+                    host,
+                    candidate.score,
+                    hex::encode(candidate.address),
+                    hex::encode(candidate.salt),
+                    backend,
+                    engine,
+                    job.map_or(String::new(), |job| job.to_string()),
+                    stamp.origin.map_or(String::new(), |origin| origin.to_string())
+                )
+            }
+        };
+        self.file.append(&line);
+    }
+}
+
+// The candidates already in the file at `path`, skipping lines it cannot read.
+fn read_seen(path: &Path, format: Format) -> Seen {
+    let mut seen = Seen::default();
+    let Ok(contents) = std::fs::read_to_string(path) else { return seen };
+    for line in contents.lines() {
+        let fields = match format {
+            Format::JsonLines => serde_json::from_str::<serde_json::Value>(line).ok().map(|entry| (entry["salt"].clone(), entry["address"].clone())),
+            Format::Csv => {
+                let columns: Vec<&str> = line.split(',').collect();
+                (columns.len() >= 5).then(|| (columns[4].into(), columns[3].into()))
+            }
+        };
+        let Some((serde_json::Value::String(salt), serde_json::Value::String(address))) = fields else { continue };
+        if let (Ok(salt), Ok(address)) = (decode_hex(&salt), decode_hex(&address)) {
+            seen.insert(&Candidate { salt, address, score: 0 });
+        }
+    }
+    seen
+}
+
+// The hashes done, the hashrate and the best score at each status line.
+pub struct StatsCsv(Appender);
+
+impl StatsCsv {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        Appender::open(path, Some(STATS_HEADER)).map(StatsCsv)
+    }
+
+    pub fn record(&self, hashes: u64, hashrate: f64, best_score: Option<u32>) {
+        let best_score = best_score.map_or(String::new(), |score| score.to_string());
+        self.0.append(&format!("{},{},{:.0},{}", now(), hashes, hashrate, best_score));
+    }
+}
+
+// A file lines are appended to, starting with `header` when it is new.
+struct Appender {
+    path: PathBuf,
+    file: Mutex<BufWriter<File>>,
+}
+
+impl Appender {
+    fn open(path: &Path, header: Option<&str>) -> Result<Self, String> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| format!("cannot open {}: {}", path.display(), e))?;
+        let empty = file.metadata().map(|m| m.len() == 0).unwrap_or(false);
+        let appender = Appender { path: path.to_path_buf(), file: Mutex::new(BufWriter::new(file)) };
+        if let Some(header) = header.filter(|_| empty) {
+            appender.append(header);
+        }
+        Ok(appender)
+    }
+
+    fn append(&self, line: &str) {
+        let mut file = self.file.lock().unwrap_or_else(PoisonError::into_inner);
+        let written = writeln!(file, "{}", line)
+            .and_then(|_| file.flush())
+            .and_then(|_| file.get_ref().sync_data());
+        if let Err(e) = written {
+            eprintln!("Cannot write to {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |t| t.as_secs())
+}
+
+// The name of this machine, for telling apart the files of several.
+pub fn hostname() -> String {
+    let name = std::env::var("HOSTNAME")
+        .or_else(|_| std::env::var("COMPUTERNAME"))
+        .ok()
+        .or_else(|| std::fs::read_to_string("/proc/sys/kernel/hostname").ok())
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok());
+    match name.as_deref().map(str::trim) {
+        Some(name) if !name.is_empty() => name.to_string(),
+        _ => "unknown".to_string(),
+    }
+}
@@ -0,0 +1,242 @@
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+
+const BASE_BACKOFF: Duration = Duration::from_secs(5);
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+// Minimal blocking JSON-RPC client for an Ethereum node, failing over between
+// several endpoints. Clones share the endpoints' health.
+#[derive(Clone)]
+pub struct RpcClient {
+    endpoints: Arc<Vec<Endpoint>>,
+    agent: ureq::Agent,
+}
+
+struct Endpoint {
+    url: String,
+    health: Mutex<Health>,
+}
+
+// Consecutive failures of an endpoint and when it may be tried again.
+#[derive(Default)]
+struct Health {
+    failures: u32,
+    retry_at: Option<Instant>,
+}
+
+impl Endpoint {
+    fn is_healthy(&self, now: Instant) -> bool {
+        !matches!(self.health.lock().unwrap_or_else(PoisonError::into_inner).retry_at, Some(at) if now < at)
+    }
+
+    fn record_success(&self) {
+        *self.health.lock().unwrap_or_else(PoisonError::into_inner) = Health::default();
+    }
+
+    // Exponential backoff: 5s after the first failure, doubling up to 5 minutes.
+    fn record_failure(&self) -> Duration {
+        let mut health = self.health.lock().unwrap_or_else(PoisonError::into_inner);
+        let backoff = BASE_BACKOFF
+            .saturating_mul(1 << health.failures.min(6))
+            .min(MAX_BACKOFF);
+        health.failures += 1;
+        health.retry_at = Some(Instant::now() + backoff);
+        backoff
+    }
+}
+
+// Header fields of a block that the submission logic cares about.
+pub struct Block {
+    pub timestamp: u64,
+    pub base_fee: u128,
+}
+
+// Scheme and host of an endpoint, since the rest of the URL often carries an API key.
+pub fn redact(url: &str) -> String {
+    match url.split_once("://") {
+        Some((scheme, rest)) => format!("{}://{}", scheme, rest.split(['/', '?']).next().unwrap_or_default()),
+        None => url.split(['/', '?']).next().unwrap_or_default().to_string(),
+    }
+}
+
+// Errors that say nothing about the request itself, so another endpoint may do better.
+fn is_provider_error(error: &Value) -> bool {
+    let code = error["code"].as_i64().unwrap_or_default();
+    let message = error["message"].as_str().unwrap_or_default().to_lowercase();
+    code == 429 || code == -32005 || message.contains("rate limit") || message.contains("too many requests")
+}
+
+impl RpcClient {
+    pub fn new(urls: &[String]) -> Self {
+        let agent = ureq::AgentBuilder::new()
+            .timeout(Duration::from_secs(30))
+            .build();
+        let endpoints = urls
+            .iter()
+            .map(|url| Endpoint { url: url.clone(), health: Mutex::default() })
+            .collect();
+        RpcClient { endpoints: Arc::new(endpoints), agent }
+    }
+
+    fn send(&self, url: &str, body: &Value) -> Result<Value, String> {
+        self.agent
+            .post(url)
+            .send_json(body)
+            .map_err(|e| match e {
+                ureq::Error::Status(code, _) => format!("HTTP {}", code),
+                ureq::Error::Transport(transport) => match transport.message() {
+                    Some(message) => format!("{}: {}", transport.kind(), message),
+                    None => transport.kind().to_string(),
+                },
+            })?
+            .into_json()
+            .map_err(|e| format!("invalid JSON: {}", e))
+    }
+
+    // Send a request, returning the node's error object as the inner `Err`.
+    // Endpoints are tried in order, skipping those backing off after recent
+    // failures; transport errors and rate limits move on to the next one.
+    pub fn request_raw(&self, method: &str, params: Value) -> Result<Result<Value, Value>, String> {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let now = Instant::now();
+        let (healthy, backing_off): (Vec<_>, Vec<_>) = self.endpoints.iter().partition(|e| e.is_healthy(now));
+        let mut last_error = "no RPC endpoint configured".to_string();
+        for endpoint in healthy.into_iter().chain(backing_off) {
+            let outcome = self.send(&endpoint.url, &body).and_then(|response| {
+                match response.get("error") {
+                    Some(error) if is_provider_error(error) => Err(error.to_string()),
+                    Some(error) => Ok(Err(error.clone())),
+                    None => response
+                        .get("result")
+                        .cloned()
+                        .map(Ok)
+                        .ok_or_else(|| "no result".to_string()),
+                }
+            });
+            match outcome {
+                Ok(result) => {
+                    endpoint.record_success();
+                    return Ok(result);
+                }
+                Err(e) => {
+                    let backoff = endpoint.record_failure();
+                    if self.endpoints.len() > 1 {
+                        eprintln!(
+                            "Warning: {} failed on {} ({}), backing off for {}",
+                            method,
+                            redact(&endpoint.url),
+                            e,
+                            humantime::format_duration(backoff)
+                        );
+                    }
+                    last_error = format!("{} request failed: {}", method, e);
+                }
+            }
+        }
+        Err(last_error)
+    }
+
+    pub fn request(&self, method: &str, params: Value) -> Result<Value, String> {
+        self.request_raw(method, params)?
+            .map_err(|error| format!("{} failed: {}", method, error))
+    }
+
+    pub fn chain_id(&self) -> Result<u64, String> {
+        Ok(parse_quantity(&self.request("eth_chainId", json!([]))?)? as u64)
+    }
+
+    pub fn block_number(&self) -> Result<u64, String> {
+        Ok(parse_quantity(&self.request("eth_blockNumber", json!([]))?)? as u64)
+    }
+
+    pub fn latest_block(&self) -> Result<Block, String> {
+        let block = self.request("eth_getBlockByNumber", json!(["latest", false]))?;
+        Ok(Block {
+            timestamp: parse_quantity(&block["timestamp"])? as u64,
+            base_fee: parse_quantity(&block["baseFeePerGas"])?,
+        })
+    }
+
+    pub fn max_priority_fee(&self) -> Result<u128, String> {
+        parse_quantity(&self.request("eth_maxPriorityFeePerGas", json!([]))?)
+    }
+
+    // Transaction count at `block`, "latest" for mined or "pending" to include the mempool.
+    pub fn nonce(&self, address: &[u8; 20], block: &str) -> Result<u64, String> {
+        let params = json!([to_hex(address), block]);
+        Ok(parse_quantity(&self.request("eth_getTransactionCount", params)?)? as u64)
+    }
+
+    pub fn estimate_gas(&self, from: &[u8; 20], to: &[u8; 20], data: &[u8]) -> Result<u64, String> {
+        let params = json!([{ "from": to_hex(from), "to": to_hex(to), "data": to_hex(data) }]);
+        Ok(parse_quantity(&self.request("eth_estimateGas", params)?)? as u64)
+    }
+
+    pub fn call(&self, to: &[u8; 20], data: &[u8]) -> Result<Vec<u8>, String> {
+        let params = json!([{ "to": to_hex(to), "data": to_hex(data) }, "latest"]);
+        parse_bytes(&self.request("eth_call", params)?)
+    }
+
+    // eth_call from a given sender; a revert comes back as `Ok(Err(revert_data))`.
+    pub fn call_from(&self, from: &[u8; 20], to: &[u8; 20], data: &[u8]) -> Result<Result<Vec<u8>, Vec<u8>>, String> {
+        let params = json!([{ "from": to_hex(from), "to": to_hex(to), "data": to_hex(data) }, "latest"]);
+        match self.request_raw("eth_call", params)? {
+            Ok(output) => Ok(Ok(parse_bytes(&output)?)),
+            Err(error) => match revert_data(&error) {
+                Some(data) => Ok(Err(data)),
+                None => Err(format!("eth_call failed: {}", error)),
+            },
+        }
+    }
+
+    pub fn get_code(&self, address: &[u8; 20]) -> Result<Vec<u8>, String> {
+        parse_bytes(&self.request("eth_getCode", json!([to_hex(address), "latest"]))?)
+    }
+
+    pub fn transaction_receipt(&self, hash: &str) -> Result<Option<Value>, String> {
+        let receipt = self.request("eth_getTransactionReceipt", json!([hash]))?;
+        Ok((!receipt.is_null()).then_some(receipt))
+    }
+
+    pub fn transaction_by_hash(&self, hash: &str) -> Result<Option<Value>, String> {
+        let tx = self.request("eth_getTransactionByHash", json!([hash]))?;
+        Ok((!tx.is_null()).then_some(tx))
+    }
+
+    pub fn send_raw_transaction(&self, raw: &[u8]) -> Result<String, String> {
+        let hash = self.request("eth_sendRawTransaction", json!([to_hex(raw)]))?;
+        hash.as_str()
+            .map(str::to_string)
+            .ok_or_else(|| format!("unexpected transaction hash: {}", hash))
+    }
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+// Parse a hex-encoded JSON-RPC quantity such as "0x1a".
+pub fn parse_quantity(value: &Value) -> Result<u128, String> {
+    let text = value
+        .as_str()
+        .ok_or_else(|| format!("expected hex quantity, got {}", value))?;
+    u128::from_str_radix(text.trim_start_matches("0x"), 16)
+        .map_err(|e| format!("invalid quantity {}: {}", text, e))
+}
+
+pub fn parse_bytes(value: &Value) -> Result<Vec<u8>, String> {
+    let text = value
+        .as_str()
+        .ok_or_else(|| format!("expected hex data, got {}", value))?;
+    hex::decode(text.trim_start_matches("0x")).map_err(|e| format!("invalid hex data {}: {}", text, e))
+}
+
+// Revert data from an eth_call error. Nodes put it in `error.data`, some
+// providers one level deeper.
+fn revert_data(error: &Value) -> Option<Vec<u8>> {
+    let data = &error["data"];
+    let data = data.get("data").unwrap_or(data);
+    parse_bytes(data).ok()
+}
@@ -0,0 +1,185 @@
+// The one tokio runtime shared by everything that talks to the network or
+// waits on a timer: chain monitoring, event subscriptions, odds, deadline and
+// submissions. Hashing stays on its own plain threads. Services are handed a
+// Shutdown to watch, and shutdown() asks them all to stop and waits for them.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock, PoisonError};
+use std::time::{Duration, Instant};
+
+use tokio::runtime::{Handle, Runtime};
+use tokio::sync::{watch, Notify};
+use tokio::task::JoinHandle;
+
+use crate::error::exit_code;
+
+struct Services {
+    runtime: Runtime,
+    shutdown: watch::Sender<bool>,
+    tasks: Mutex<Vec<(&'static str, JoinHandle<()>)>>,
+}
+
+static SERVICES: OnceLock<Services> = OnceLock::new();
+
+fn services() -> &'static Services {
+    SERVICES.get_or_init(|| Services {
+        // Services mostly wait; blocking RPC calls go to the blocking pool.
+        runtime: tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .thread_name("services")
+            .enable_all()
+            .build()
+            .expect("cannot start the tokio runtime"),
+        shutdown: watch::channel(false).0,
+        tasks: Mutex::new(Vec::new()),
+    })
+}
+
+// For services to find out when to stop.
+#[derive(Clone)]
+pub struct Shutdown(watch::Receiver<bool>);
+
+impl Shutdown {
+    pub fn requested(&self) -> bool {
+        *self.0.borrow()
+    }
+
+    // Resolves once shutdown has been requested.
+    pub async fn wait(&mut self) {
+        self.0.wait_for(|requested| *requested).await.ok();
+    }
+
+    // Sleep for `duration`, or until shutdown; false when it was shutdown.
+    pub async fn sleep(&mut self, duration: Duration) -> bool {
+        self.sleep_until(Instant::now() + duration).await
+    }
+
+    pub async fn sleep_until(&mut self, when: Instant) -> bool {
+        tokio::select! {
+            _ = tokio::time::sleep_until(when.into()) => true,
+            _ = self.wait() => false,
+        }
+    }
+
+    // The same from a blocking service thread.
+    pub fn sleep_blocking(&mut self, duration: Duration) -> bool {
+        Handle::current().block_on(self.sleep(duration))
+    }
+}
+
+fn register(name: &'static str, task: JoinHandle<()>) {
+    services().tasks.lock().unwrap_or_else(PoisonError::into_inner).push((name, task));
+}
+
+fn shutdown_signal() -> Shutdown {
+    Shutdown(services().shutdown.subscribe())
+}
+
+// Run an async service on the shared runtime.
+pub fn spawn<F>(name: &'static str, service: impl FnOnce(Shutdown) -> F)
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    let task = services().runtime.spawn(service(shutdown_signal()));
+    register(name, task);
+}
+
+// Stops asked for other than by a signal, say by the Windows service manager.
+static STOP_REQUESTED: Notify = Notify::const_new();
+static STOPPED_ON_REQUEST: AtomicBool = AtomicBool::new(false);
+
+// Stop the run as the first Ctrl-C would.
+pub fn request_stop() {
+    STOPPED_ON_REQUEST.store(true, Ordering::Relaxed);
+    STOP_REQUESTED.notify_one();
+}
+
+// Whether request_stop() was called: a stop asked for, not an interruption.
+pub fn stop_requested() -> bool {
+    STOPPED_ON_REQUEST.load(Ordering::Relaxed)
+}
+
+type BeforeExit = Box<dyn Fn(i32) + Send + Sync>;
+
+static BEFORE_EXIT: Mutex<Vec<BeforeExit>> = Mutex::new(Vec::new());
+
+// Have `hook` called with the exit code whenever exit() ends the process.
+pub fn before_exit(hook: impl Fn(i32) + Send + Sync + 'static) {
+    BEFORE_EXIT.lock().unwrap_or_else(PoisonError::into_inner).push(Box::new(hook));
+}
+
+// End the process with `code`, letting whatever runs it know first.
+pub fn exit(code: i32) -> ! {
+    for hook in BEFORE_EXIT.lock().unwrap_or_else(PoisonError::into_inner).iter() {
+        hook(code);
+    }
+    std::process::exit(code)
+}
+
+// Call `stop` on the first Ctrl-C (or SIGTERM on unix) or request_stop() so
+// the run can wrap up as usual, and exit at once on the second.
+pub fn on_interrupt(stop: impl Fn() + Send + 'static) {
+    spawn("interrupt", move |mut shutdown| async move {
+        let mut stopping = false;
+        loop {
+            tokio::select! {
+                _ = interrupted() => {}
+                _ = STOP_REQUESTED.notified() => {}
+                _ = shutdown.wait() => break,
+            }
+            if stopping {
+                eprintln!("Interrupted again, exiting");
+                exit(exit_code::INTERRUPTED);
+            }
+            eprintln!("Stopping, interrupt again to exit at once");
+            stopping = true;
+            stop();
+        }
+    });
+}
+
+async fn interrupted() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let Ok(mut terminate) = signal(SignalKind::terminate()) else {
+            return tokio::signal::ctrl_c().await.unwrap_or(());
+        };
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {}
+            _ = terminate.recv() => {}
+        }
+    }
+    #[cfg(not(unix))]
+    tokio::signal::ctrl_c().await.ok();
+}
+
+// Run a service built on blocking calls on the runtime's blocking pool.
+pub fn spawn_blocking(name: &'static str, service: impl FnOnce(Shutdown) + Send + 'static) {
+    let shutdown = shutdown_signal();
+    let task = services().runtime.spawn_blocking(move || service(shutdown));
+    register(name, task);
+}
+
+// Run a blocking call, say an RPC request, from an async service.
+pub async fn blocking<T: Send + 'static>(call: impl FnOnce() -> T + Send + 'static) -> T {
+    tokio::task::spawn_blocking(call).await.expect("blocking call panicked")
+}
+
+// Ask every service to stop and wait up to `grace` for them, say for a
+// submission in flight to go through.
+pub fn shutdown(grace: Duration) {
+    let Some(services) = SERVICES.get() else { return };
+    services.shutdown.send_replace(true);
+    let tasks = std::mem::take(&mut *services.tasks.lock().unwrap_or_else(PoisonError::into_inner));
+    let deadline = Instant::now() + grace;
+    services.runtime.block_on(async {
+        for (name, task) in tasks {
+            if tokio::time::timeout_at(deadline.into(), task).await.is_err() {
+                eprintln!("Warning: {} did not stop within {}s", name, grace.as_secs());
+            }
+        }
+    });
+}
@@ -0,0 +1,558 @@
+// Where the candidates come from. Each worker thread asks for its own stream
+// of salts, and together the streams should try every salt once.
+
+use std::ops::Range;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use rand::rngs::OsRng;
+use rand::Rng;
+use alloy_primitives::{keccak256, Address};
+use serde::{Deserialize, Serialize};
+
+use crate::eoa;
+use crate::error::MinerError;
+use crate::schema::{CounterRange, PepperRange};
+
+pub trait SaltGenerator: Send + Sync {
+    // Salts for worker `thread` of `threads`; the worker stops when they run out.
+    fn salts(&self, thread: usize, threads: usize) -> Box<dyn Iterator<Item = [u8; 32]> + Send + '_>;
+
+    // Where the streams stand once each worker has taken `taken[thread]` of
+    // its salts, to carry on from there later. None when the salts cannot be
+    // picked up again, say from a file or random keys.
+    fn position(&self, _taken: &[u64]) -> Option<SaltPosition> {
+        None
+    }
+
+    // The generator state `salt` came from, to produce it again. None when
+    // the salt is all there is, say from a file or a random key.
+    fn origin(&self, _salt: &[u8; 32]) -> Option<Origin> {
+        None
+    }
+
+    // The share of every salt these could be that has been tried once each
+    // worker has taken `taken[thread]`, counting the `before` counter ranges
+    // of the runs before it was resumed. None when that is not kept track of.
+    fn coverage(&self, _taken: &[u64], _before: &[CounterRange]) -> Option<f64> {
+        None
+    }
+
+    // Never give a fresh stream one of `peppers`, used by other runs; for
+    // salt generators with peppers, before the search starts.
+    fn avoid_peppers(&self, _peppers: &[u32]) {}
+
+    // Whether there are fresh peppers enough for whichever of `threads`
+    // workers have no stream to carry on, once the peppers to avoid are in.
+    // A worker whose stream runs out later and finds none left stops there.
+    fn check_peppers(&self, _threads: usize) -> Result<(), MinerError> {
+        Ok(())
+    }
+}
+
+// There are 2^32 peppers.
+const PEPPERS: u64 = 1 << 32;
+
+// Pepper ranges small enough to pick from what is left of them.
+const SMALL_PEPPER_RANGE: u64 = 1 << 16;
+
+// Parse --pepper-range, START..END or START.. for up to the last pepper.
+pub fn parse_pepper_range(s: &str) -> Result<Range<u64>, String> {
+    let (start, end) = parse_range(s)?;
+    let end = end.unwrap_or(PEPPERS);
+    if end > PEPPERS {
+        return Err(format!("peppers only go up to {}", PEPPERS));
+    }
+    Ok(start..end)
+}
+
+// How much of the salts a search has tried, earlier runs included, for the
+// status output.
+pub struct Coverage {
+    pub salts: Arc<dyn SaltGenerator>,
+    pub before: Vec<CounterRange>,
+}
+
+impl Coverage {
+    pub fn position(&self, taken: &[u64]) -> Option<SaltPosition> {
+        self.salts.position(taken)
+    }
+
+    pub fn fraction(&self, taken: &[u64]) -> Option<f64> {
+        self.salts.coverage(taken, &self.before)
+    }
+}
+
+// `fraction` as a percentage, in scientific notation once it gets tiny, as
+// it does for random salts.
+pub fn format_coverage(fraction: f64) -> String {
+    match fraction * 100.0 {
+        0.0 => "0%".to_string(),
+        percent if percent >= 0.01 => format!("{:.2}%", percent),
+        percent => format!("{:.2e}%", percent),
+    }
+}
+
+/// The state of a salt generator that produced a salt, stamped on the results
+/// written out with it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "strategy", rename_all = "kebab-case")]
+pub enum Origin {
+    Random { pepper: u32, counter: u64 },
+    Sequential { counter: u64 },
+    Nonces { sender: Address, nonce: u64 },
+}
+
+impl std::fmt::Display for Origin {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Origin::Random { pepper, counter } => write!(f, "random pepper {:#010x} counter {}", pepper, counter),
+            Origin::Sequential { counter } => write!(f, "sequential counter {}", counter),
+            Origin::Nonces { sender, nonce } => write!(f, "sender {} nonce {}", sender, nonce),
+        }
+    }
+}
+
+// The counter in the last eight bytes shared by the CREATE2 salt layouts.
+fn counter(salt: &[u8; 32]) -> u64 {
+    u64::from_be_bytes(salt[24..].try_into().unwrap())
+}
+
+/// How far a search has got through its salts, as saved in a checkpoint.
+/// Everything before the position has been tried; a little after it may have
+/// been too, and is tried again on resuming.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "strategy", rename_all = "kebab-case")]
+pub enum SaltPosition {
+    /// Peppers whose counters ran out are `retired`, never to be used again.
+    Random {
+        submitter: Address,
+        streams: Vec<RandomStream>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        retired: Vec<PepperRange>,
+    },
+    /// Every counter from `start` up to `next` has been tried.
+    Sequential { submitter: Address, start: u64, next: u64, end: Option<u64> },
+    /// Every sender and nonce before index `next` has been tried.
+    Nonces { next: u64 },
+}
+
+/// One thread's salts under [`Random`]: its pepper and the counters
+/// `next`, `next + step`, ...
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RandomStream {
+    pub pepper: u32,
+    pub next: u64,
+    pub step: u64,
+}
+
+// Workers stride through a range: thread t takes first + t, then every
+// `threads`th counter after it. Once each has done `taken[t]`, every counter
+// below the returned one has been tried.
+fn low_watermark(first: u64, taken: &[u64]) -> u64 {
+    let threads = taken.len() as u64;
+    (0..threads)
+        .zip(taken)
+        .map(|(thread, taken)| first.saturating_add(thread).saturating_add(taken.saturating_mul(threads)))
+        .min()
+        .unwrap_or(first)
+}
+
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// --submitter, a random pepper per thread and a counter: never repeats
+    /// work across runs, but cannot be replayed
+    Random,
+    /// --submitter, four zero bytes and a counter over --salt-range: the same
+    /// salts on every run, so ranges can be split between machines
+    Sequential,
+}
+
+// A fleet's master seed and this machine's index in the fleet. Worker `w`
+// takes the peppers at positions w * 2^16 to w * 2^16 + 2^16 - 1 of a
+// shuffle of every pepper only the seed reproduces, so no two workers ever
+// get the same pepper. Anyone holding the seed can work out which worker
+// tried a salt, and no coordinator is needed while the campaign runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Seed {
+    pub key: [u8; 32],
+    pub worker: u16,
+}
+
+// How many peppers each worker of a seed has.
+pub const SEED_PEPPERS: u32 = 1 << 16;
+
+// Rounds of the Feistel network that orders the peppers.
+const SEED_ROUNDS: u8 = 4;
+
+impl Seed {
+    // The `index`th of this worker's peppers.
+    pub fn pepper(&self, index: u16) -> u32 {
+        let (mut left, mut right) = (self.worker, index);
+        for round in 0..SEED_ROUNDS {
+            (left, right) = (right, left ^ self.round(round, right));
+        }
+        (left as u32) << 16 | right as u32
+    }
+
+    // The worker `pepper` belongs to and its index among that worker's peppers.
+    pub fn owner(&self, pepper: u32) -> (u16, u16) {
+        let (mut left, mut right) = ((pepper >> 16) as u16, pepper as u16);
+        for round in (0..SEED_ROUNDS).rev() {
+            (left, right) = (right ^ self.round(round, left), left);
+        }
+        (left, right)
+    }
+
+    fn round(&self, round: u8, half: u16) -> u16 {
+        let mut input = [0; 35];
+        input[..32].copy_from_slice(&self.key);
+        input[32] = round;
+        input[33..].copy_from_slice(&half.to_be_bytes());
+        u16::from_be_bytes(keccak256(input)[..2].try_into().unwrap())
+    }
+}
+
+// Read a --seed-file, a 32-byte hex seed.
+pub fn read_seed(path: &std::path::Path) -> Result<[u8; 32], MinerError> {
+    let text = std::fs::read_to_string(path).map_err(|e| MinerError::Config(format!("cannot read {}: {}", path.display(), e)))?;
+    crate::decode_hex::<32>(text.trim()).map_err(|e| MinerError::Config(format!("{} does not hold a 32-byte hex seed: {}", path.display(), e)))
+}
+
+// What the miner has always done: the submitter, a random 4-byte pepper per
+// thread and a 64-bit counter. Peppers come from the OS's secure generator,
+// out of `peppers`, and never repeat one used before, so no two streams try
+// the same salts. A thread whose counters run out goes on with a fresh pepper
+// rather than wrap around to salts it has tried. With a `seed`, the peppers
+// are the worker's share of it instead, taken in order.
+pub struct Random {
+    submitter: [u8; 20],
+    peppers: Range<u64>,
+    seed: Option<Seed>,
+    // Streams carried on from a checkpoint, by thread.
+    resumed: Vec<RandomStream>,
+    // Peppers other runs have used, say as recorded by --db.
+    known: Mutex<Vec<u32>>,
+    // Peppers whose counters ran out, in this run or before.
+    retired: Mutex<Vec<PepperRange>>,
+    // Where each thread's stream started and how many salts the thread had
+    // taken by then, for checkpoints.
+    started: Mutex<Vec<Option<(RandomStream, u64)>>>,
+}
+
+impl Random {
+    pub fn new(submitter: [u8; 20]) -> Self {
+        Random::resume(submitter, Vec::new(), Vec::new())
+    }
+
+    // Carry on with `streams`; threads beyond them get fresh peppers, and
+    // none gets one of the `retired`.
+    pub fn resume(submitter: [u8; 20], streams: Vec<RandomStream>, retired: Vec<PepperRange>) -> Self {
+        Random {
+            submitter,
+            peppers: 0..PEPPERS,
+            seed: None,
+            resumed: streams,
+            known: Mutex::new(Vec::new()),
+            retired: Mutex::new(retired),
+            started: Mutex::new(Vec::new()),
+        }
+    }
+
+    // Draw fresh peppers from `peppers` alone, say the share of a fleet
+    // assigned to this machine.
+    pub fn within(mut self, peppers: Range<u64>) -> Self {
+        self.peppers = peppers.start.min(PEPPERS)..peppers.end.min(PEPPERS);
+        self
+    }
+
+    // Take fresh peppers from the worker's share of `seed` alone.
+    pub fn seeded(mut self, seed: Seed) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    // Peppers given to more than one of the resumed and retired streams,
+    // whose work is duplicated.
+    pub fn duplicates(&self) -> Vec<u32> {
+        let retired = self.retired.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut peppers: Vec<u32> = self.resumed.iter().map(|s| s.pepper).chain(retired.iter().map(|r| r.pepper)).collect();
+        peppers.sort_unstable();
+        let mut duplicates: Vec<u32> = peppers.windows(2).filter(|w| w[0] == w[1]).map(|w| w[0]).collect();
+        duplicates.dedup();
+        duplicates
+    }
+
+    // Every pepper some stream has or had, resumed or not, or another run used.
+    fn used_peppers(&self, started: &[Option<(RandomStream, u64)>]) -> Vec<u32> {
+        let retired = self.retired.lock().unwrap_or_else(PoisonError::into_inner);
+        let streams = self.resumed.iter().chain(started.iter().flatten().map(|(s, _)| s));
+        let known = self.known.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut used: Vec<u32> = streams.map(|s| s.pepper).chain(retired.iter().map(|r| r.pepper)).chain(known.iter().copied()).collect();
+        used.sort_unstable();
+        used.dedup();
+        used
+    }
+
+    // How many of the peppers fresh streams may take are not `used`.
+    fn free_peppers(&self, used: &[u32]) -> u64 {
+        let (all, taken) = match &self.seed {
+            Some(seed) => (SEED_PEPPERS as u64, used.iter().filter(|&&pepper| seed.owner(pepper).0 == seed.worker).count()),
+            None => (self.peppers.end - self.peppers.start, used.iter().filter(|&&pepper| self.peppers.contains(&(pepper as u64))).count()),
+        };
+        all - taken as u64
+    }
+
+    // A pepper no other stream has or had, so no two streams, resumed or not,
+    // ever try the same salt. None once every one of them has been used.
+    fn fresh_pepper(&self, started: &[Option<(RandomStream, u64)>]) -> Option<u32> {
+        let used = self.used_peppers(started);
+        if let Some(seed) = &self.seed {
+            return (0..=u16::MAX).map(|index| seed.pepper(index)).find(|pepper| used.binary_search(pepper).is_err());
+        }
+        // Sampling could take forever once most of a small range is used.
+        if self.peppers.end - self.peppers.start <= SMALL_PEPPER_RANGE {
+            let free: Vec<u32> = self.peppers.clone().map(|pepper| pepper as u32).filter(|pepper| used.binary_search(pepper).is_err()).collect();
+            return (!free.is_empty()).then(|| free[OsRng.gen_range(0..free.len())]);
+        }
+        if self.free_peppers(&used) == 0 {
+            return None;
+        }
+        loop {
+            let pepper = OsRng.gen_range(self.peppers.clone()) as u32;
+            if used.binary_search(&pepper).is_err() {
+                return Some(pepper);
+            }
+        }
+    }
+
+    // Retire `stream`, whose counters have run out after `thread` took
+    // `taken` salts, for the same counters under a fresh pepper. None when
+    // there is none left, the stream staying where it ended.
+    fn rotate(&self, thread: usize, stream: RandomStream, taken: u64) -> Option<RandomStream> {
+        let mut started = self.started.lock().unwrap_or_else(PoisonError::into_inner);
+        let first = stream.next % stream.step.max(1);
+        let fresh = RandomStream { pepper: self.fresh_pepper(&started)?, next: first, step: stream.step };
+        self.retired.lock().unwrap_or_else(PoisonError::into_inner).push(PepperRange { pepper: stream.pepper, start: first, end: u64::MAX, step: stream.step });
+        started[thread] = Some((fresh, taken));
+        Some(fresh)
+    }
+}
+
+impl SaltGenerator for Random {
+    fn salts(&self, thread: usize, threads: usize) -> Box<dyn Iterator<Item = [u8; 32]> + Send + '_> {
+        let mut started = self.started.lock().unwrap_or_else(PoisonError::into_inner);
+        let stream = self.resumed.get(thread).copied().or_else(|| {
+            Some(RandomStream { pepper: self.fresh_pepper(&started)?, next: thread as u64, step: threads as u64 })
+        });
+        // Every pepper is taken; the others carry on without this worker.
+        let Some(mut stream) = stream else { return Box::new(std::iter::empty()) };
+        if started.len() <= thread {
+            started.resize(thread + 1, None);
+        }
+        started[thread] = Some((stream, 0));
+        drop(started);
+        let mut salt = [0; 32];
+        salt[..20].copy_from_slice(&self.submitter);
+        salt[20..24].copy_from_slice(&stream.pepper.to_be_bytes());
+        let (mut next, mut taken) = (Some(stream.next), 0);
+        Box::new(std::iter::from_fn(move || {
+            let counter = match next {
+                Some(counter) => counter,
+                None => {
+                    stream = self.rotate(thread, stream, taken)?;
+                    salt[20..24].copy_from_slice(&stream.pepper.to_be_bytes());
+                    stream.next
+                }
+            };
+            // Counters stay below u64::MAX, the end of a retired range.
+            next = counter.checked_add(stream.step).filter(|&next| next < u64::MAX);
+            taken += 1;
+            salt[24..].copy_from_slice(&counter.to_be_bytes());
+            Some(salt)
+        }))
+    }
+
+    fn position(&self, taken: &[u64]) -> Option<SaltPosition> {
+        let started = self.started.lock().unwrap_or_else(PoisonError::into_inner);
+        // Threads yet to start keep the stream they were resumed with.
+        let streams = (0..started.len().max(self.resumed.len()))
+            .filter_map(|thread| match started.get(thread).copied().flatten() {
+                Some((s, before)) => {
+                    let taken = taken.get(thread).copied().unwrap_or(0).saturating_sub(before);
+                    Some(RandomStream { next: s.next.saturating_add(taken.saturating_mul(s.step)), ..s })
+                }
+                None => self.resumed.get(thread).copied(),
+            })
+            .collect();
+        let retired = self.retired.lock().unwrap_or_else(PoisonError::into_inner).clone();
+        Some(SaltPosition::Random { submitter: self.submitter.into(), streams, retired })
+    }
+
+    // Each stream has tried every counter below `next` in its residue class,
+    // and each retired pepper every one in its range.
+    fn coverage(&self, taken: &[u64], _before: &[CounterRange]) -> Option<f64> {
+        let Some(SaltPosition::Random { streams, retired, .. }) = self.position(taken) else { return None };
+        let tried: f64 = streams.iter().map(|s| (s.next / s.step.max(1)) as f64).sum();
+        let exhausted: f64 = retired.iter().map(|r| ((r.end - r.start) / r.step.max(1)) as f64).sum();
+        Some((tried + exhausted) / 2f64.powi(96))
+    }
+
+    fn avoid_peppers(&self, peppers: &[u32]) {
+        self.known.lock().unwrap_or_else(PoisonError::into_inner).extend_from_slice(peppers);
+    }
+
+    fn check_peppers(&self, threads: usize) -> Result<(), MinerError> {
+        let fresh = threads.saturating_sub(self.resumed.len()) as u64;
+        let free = self.free_peppers(&self.used_peppers(&self.started.lock().unwrap_or_else(PoisonError::into_inner)));
+        if free >= fresh {
+            return Ok(());
+        }
+        let peppers = match &self.seed {
+            Some(seed) => format!("worker {}'s peppers of the seed", seed.worker),
+            None => format!("peppers {}..{}", self.peppers.start, self.peppers.end),
+        };
+        Err(MinerError::Config(format!(
+            "{} fresh peppers are needed but only {} of the {} are left unused, say by --db's earlier runs",
+            fresh, free, peppers
+        )))
+    }
+
+    fn origin(&self, salt: &[u8; 32]) -> Option<Origin> {
+        let pepper = u32::from_be_bytes(salt[20..24].try_into().unwrap());
+        (salt[..20] == self.submitter).then(|| Origin::Random { pepper, counter: counter(salt) })
+    }
+}
+
+// The submitter, four zero bytes and every counter from `start` up to (not
+// including) `end`, shared out between the threads.
+pub struct Sequential {
+    pub submitter: [u8; 20],
+    pub start: u64,
+    pub end: Option<u64>,
+}
+
+impl Sequential {
+    pub fn salt(&self, counter: u64) -> [u8; 32] {
+        let mut salt = [0; 32];
+        salt[..20].copy_from_slice(&self.submitter);
+        salt[24..].copy_from_slice(&counter.to_be_bytes());
+        salt
+    }
+}
+
+impl SaltGenerator for Sequential {
+    fn salts(&self, thread: usize, threads: usize) -> Box<dyn Iterator<Item = [u8; 32]> + Send + '_> {
+        let end = self.end.unwrap_or(u64::MAX);
+        Box::new((self.start.saturating_add(thread as u64)..end).step_by(threads).map(|counter| self.salt(counter)))
+    }
+
+    fn position(&self, taken: &[u64]) -> Option<SaltPosition> {
+        let next = low_watermark(self.start, taken).min(self.end.unwrap_or(u64::MAX));
+        Some(SaltPosition::Sequential { submitter: self.submitter.into(), start: self.start, next, end: self.end })
+    }
+
+    fn coverage(&self, taken: &[u64], before: &[CounterRange]) -> Option<f64> {
+        let end = self.end.unwrap_or(u64::MAX);
+        let next = low_watermark(self.start, taken).min(end);
+        let first = before.first().map_or(self.start, |range| range.start.min(self.start));
+        let tried: u64 = before.iter().map(|range| range.end - range.start).sum::<u64>() + (next - self.start);
+        Some(tried as f64 / (end - first).max(1) as f64)
+    }
+
+    fn origin(&self, salt: &[u8; 32]) -> Option<Origin> {
+        (*salt == self.salt(counter(salt))).then(|| Origin::Sequential { counter: counter(salt) })
+    }
+}
+
+// Parse --salt-range, START..END or START.. for no end.
+pub fn parse_range(s: &str) -> Result<(u64, Option<u64>), String> {
+    let (start, end) = s.split_once("..").ok_or("expected START..END or START..")?;
+    let start = start.parse().map_err(|e| format!("bad start {:?}: {}", start, e))?;
+    let end = match end {
+        "" => None,
+        end => Some(end.parse().map_err(|e| format!("bad end {:?}: {}", end, e))?),
+    };
+    if end.is_some_and(|end| end <= start) {
+        return Err("the range is empty".to_string());
+    }
+    Ok((start, end))
+}
+
+// Salts from any iterator, say a file of them, handed out to the threads a
+// chunk at a time.
+pub struct FromIter {
+    iter: Mutex<Box<dyn Iterator<Item = [u8; 32]> + Send>>,
+}
+
+// Salts a thread takes from a FromIter per lock.
+const CHUNK: usize = 1 << 10;
+
+impl FromIter {
+    pub fn new(iter: impl Iterator<Item = [u8; 32]> + Send + 'static) -> Self {
+        FromIter { iter: Mutex::new(Box::new(iter)) }
+    }
+}
+
+impl SaltGenerator for FromIter {
+    fn salts(&self, _thread: usize, _threads: usize) -> Box<dyn Iterator<Item = [u8; 32]> + Send + '_> {
+        let mut chunk = Vec::with_capacity(CHUNK);
+        Box::new(std::iter::from_fn(move || {
+            if chunk.is_empty() {
+                chunk.extend(self.iter.lock().unwrap_or_else(PoisonError::into_inner).by_ref().take(CHUNK));
+                chunk.reverse();
+            }
+            chunk.pop()
+        }))
+    }
+}
+
+// CREATE candidates: each sender with every nonce up to `max_nonce`, encoded
+// as the sender, four zero bytes and the nonce. Candidates are numbered
+// sender by sender within each nonce, and the search starts at `start`.
+pub struct Nonces {
+    pub senders: Vec<[u8; 20]>,
+    pub max_nonce: u64,
+    pub start: u64,
+}
+
+impl SaltGenerator for Nonces {
+    fn salts(&self, thread: usize, threads: usize) -> Box<dyn Iterator<Item = [u8; 32]> + Send + '_> {
+        let senders = self.senders.len() as u64;
+        Box::new((self.start.saturating_add(thread as u64)..).step_by(threads).map_while(move |index| {
+            let nonce = index / senders;
+            if nonce > self.max_nonce {
+                return None;
+            }
+            let mut salt = [0; 32];
+            salt[..20].copy_from_slice(&self.senders[(index % senders) as usize]);
+            salt[24..].copy_from_slice(&nonce.to_be_bytes());
+            Some(salt)
+        }))
+    }
+
+    fn position(&self, taken: &[u64]) -> Option<SaltPosition> {
+        let end = (self.max_nonce.saturating_add(1)).saturating_mul(self.senders.len() as u64);
+        Some(SaltPosition::Nonces { next: low_watermark(self.start, taken).min(end) })
+    }
+
+    // Resumed searches start at the index they got to, so it counts them too.
+    fn coverage(&self, taken: &[u64], _before: &[CounterRange]) -> Option<f64> {
+        let end = (self.max_nonce.saturating_add(1)).saturating_mul(self.senders.len() as u64);
+        Some(low_watermark(self.start, taken).min(end) as f64 / end.max(1) as f64)
+    }
+
+    fn origin(&self, salt: &[u8; 32]) -> Option<Origin> {
+        let sender: [u8; 20] = salt[..20].try_into().unwrap();
+        self.senders.contains(&sender).then(|| Origin::Nonces { sender: sender.into(), nonce: counter(salt) })
+    }
+}
+
+// EOA candidates: fresh private keys.
+pub struct Keys;
+
+impl SaltGenerator for Keys {
+    fn salts(&self, _thread: usize, _threads: usize) -> Box<dyn Iterator<Item = [u8; 32]> + Send + '_> {
+        Box::new(std::iter::repeat_with(eoa::random_key))
+    }
+}
@@ -0,0 +1,157 @@
+// Mining only at set times of the week, with --schedule: say on weeknights
+// and all weekend, for a borrowed office machine or off-peak electricity. The
+// search is paused as a window closes and resumed as the next one opens, in
+// local time, with a checkpoint written at each pause. A pause or resume typed
+// in between is left alone until the next boundary.
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{Datelike, Local, NaiveDateTime, NaiveTime, Timelike, Weekday};
+
+use crate::checkpoint::Writer;
+use crate::runtime;
+use crate::search::Control;
+
+// How often the time is checked against the schedule.
+const POLL: Duration = Duration::from_secs(15);
+
+// Some days of the week, from `start` to `end`, every day of them when there
+// are no times. A window ending at or before its start runs past midnight
+// into the next day, so "fri 22:00-07:00" is Friday night to Saturday morning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Window {
+    days: [bool; 7],
+    hours: Option<(NaiveTime, NaiveTime)>,
+}
+
+impl Window {
+    pub fn contains(&self, now: NaiveDateTime) -> bool {
+        let day = |weekday: Weekday| self.days[weekday.num_days_from_monday() as usize];
+        let (today, time) = (now.weekday(), now.time());
+        match self.hours {
+            None => day(today),
+            Some((start, end)) if start < end => day(today) && start <= time && time < end,
+            Some((start, end)) => (day(today) && start <= time) || (day(today.pred()) && time < end),
+        }
+    }
+}
+
+fn parse_day(s: &str) -> Result<Weekday, String> {
+    s.parse().map_err(|_| format!("{:?} is not a day of the week", s))
+}
+
+fn parse_time(s: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(s, "%H:%M").map_err(|_| format!("{:?} is not a time, expected HH:MM", s))
+}
+
+// DAYS, HH:MM-HH:MM or DAYS HH:MM-HH:MM, DAYS being a day (mon), a run of
+// them (mon-fri, fri-mon), weekdays, weekends or daily.
+impl FromStr for Window {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        let mut words = s.split_whitespace().collect::<Vec<_>>();
+        let hours = match words.last() {
+            Some(last) if last.contains(':') => {
+                let (start, end) = last.split_once('-').ok_or_else(|| format!("expected HH:MM-HH:MM, got {:?}", last))?;
+                words.pop();
+                Some((parse_time(start)?, parse_time(end)?))
+            }
+            _ => None,
+        };
+        let (first, last) = match words[..] {
+            [] | ["daily"] => (Weekday::Mon, Weekday::Sun),
+            ["weekdays"] => (Weekday::Mon, Weekday::Fri),
+            ["weekends"] => (Weekday::Sat, Weekday::Sun),
+            [days] => match days.split_once('-') {
+                Some((first, last)) => (parse_day(first)?, parse_day(last)?),
+                None => (parse_day(days)?, parse_day(days)?),
+            },
+            _ => return Err(format!("expected DAYS HH:MM-HH:MM, got {:?}", s)),
+        };
+        if words.is_empty() && hours.is_none() {
+            return Err("the window is empty".to_string());
+        }
+        let mut days = [false; 7];
+        let mut day = first;
+        loop {
+            days[day.num_days_from_monday() as usize] = true;
+            if day == last {
+                break;
+            }
+            day = day.succ();
+        }
+        Ok(Window { days, hours })
+    }
+}
+
+// The windows of time to mine in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule(pub Vec<Window>);
+
+impl Schedule {
+    pub fn contains(&self, now: NaiveDateTime) -> bool {
+        self.0.iter().any(|window| window.contains(now))
+    }
+
+    // A minute at a time, the first time from `now` on it changes whether
+    // to mine, if it does within the week.
+    pub fn next_change(&self, now: NaiveDateTime) -> Option<NaiveDateTime> {
+        let now = now.with_second(0)?.with_nanosecond(0)?;
+        let mining = self.contains(now);
+        (1..=7 * 24 * 60).map(|minutes| now + chrono::Duration::minutes(minutes)).find(|&when| self.contains(when) != mining)
+    }
+}
+
+impl fmt::Display for Window {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let days: Vec<String> = (0..7).filter(|&day| self.days[day]).map(|day| Weekday::try_from(day as u8).unwrap().to_string()).collect();
+        write!(f, "{}", days.join(","))?;
+        if let Some((start, end)) = self.hours {
+            write!(f, " {}-{}", start.format("%H:%M"), end.format("%H:%M"))?;
+        }
+        Ok(())
+    }
+}
+
+fn describe(when: Option<NaiveDateTime>) -> String {
+    match when {
+        Some(when) => format!("until {}", when.format("%a %-d %b %H:%M")),
+        None => "for good".to_string(),
+    }
+}
+
+// Pause and resume the search with `schedule` until shutdown, checkpointing
+// with `checkpoint` as each window closes. The search starts paused when it
+// starts outside the schedule.
+pub fn spawn(schedule: Schedule, control: Arc<Control>, checkpoint: Option<Arc<Writer>>) {
+    let now = Local::now().naive_local();
+    let mut mining = schedule.contains(now);
+    if !mining {
+        control.paused.store(true, Ordering::Relaxed);
+        println!("Outside the schedule, paused {}", describe(schedule.next_change(now)));
+    }
+    runtime::spawn_blocking("schedule", move |mut shutdown| {
+        while shutdown.sleep_blocking(POLL) {
+            let now = Local::now().naive_local();
+            if schedule.contains(now) == mining {
+                continue;
+            }
+            mining = !mining;
+            if mining {
+                control.paused.store(false, Ordering::Relaxed);
+                println!("The schedule opens, mining {}", describe(schedule.next_change(now)));
+                continue;
+            }
+            control.paused.store(true, Ordering::Relaxed);
+            println!("The schedule closes, paused {}", describe(schedule.next_change(now)));
+            if let Some(Err(e)) = checkpoint.as_ref().map(|writer| writer.save()) {
+                eprintln!("Error: {}", e);
+            }
+        }
+    });
+}
@@ -0,0 +1,219 @@
+// The serialized forms of jobs and results, shared by files, APIs and the
+// network protocol. Addresses and hashes are alloy's, bytes 0x-prefixed hex
+// strings, durations seconds, and every top-level document carries a version.
+
+use std::time::Duration;
+
+use alloy_primitives::{Address, B256};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::MinerError;
+use crate::miner::{Miner, MinerBuilder, Stats};
+use crate::mode::Mode;
+use crate::salts::{SaltPosition, Sequential};
+use crate::score::ScorerSpec;
+use crate::{decode_hex, Candidate, DEPLOYER_ADDRESS, INITCODE_HASH, SUBMITTER_ADDRESS};
+
+/// The schema version written into documents, and the only one read back.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// The `version` field of a document: always [`SCHEMA_VERSION`], so readers
+/// reject documents from an incompatible miner instead of misreading them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Version;
+
+impl Serialize for Version {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(SCHEMA_VERSION)
+    }
+}
+
+impl<'de> Deserialize<'de> for Version {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match u32::deserialize(deserializer)? {
+            SCHEMA_VERSION => Ok(Version),
+            other => Err(D::Error::custom(format!("schema version {} is not supported (expected {})", other, SCHEMA_VERSION))),
+        }
+    }
+}
+
+// Fixed-size byte arrays as hex strings, for `#[serde(with = ..)]`.
+pub(crate) mod hex {
+    use super::*;
+
+    pub fn serialize<S: Serializer, const N: usize>(bytes: &[u8; N], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("0x{}", ::hex::encode(bytes)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, const N: usize>(deserializer: D) -> Result<[u8; N], D::Error> {
+        decode_hex(&String::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+// Durations as fractional seconds.
+pub(crate) mod secs {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(duration.as_secs_f64())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        Duration::try_from_secs_f64(f64::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+/// Where a job's CREATE2 and CREATE3 salts come from; see [`crate::salts`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "strategy", rename_all = "kebab-case")]
+pub enum SaltSpec {
+    Random,
+    Sequential {
+        start: u64,
+        #[serde(default)]
+        end: Option<u64>,
+    },
+}
+
+/// A search as data, to be written to a file or sent to another machine and
+/// run there with [`JobSpec::builder`]. Defaults are the command-line miner's.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JobSpec {
+    pub version: Version,
+    pub mode: Mode,
+    pub deployer: Address,
+    pub code_hash: B256,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub create3_caller: Option<Address>,
+    /// Only for [`Mode::Create`], with nonces up to `max_nonce`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub senders: Vec<Address>,
+    #[serde(default)]
+    pub max_nonce: u64,
+    pub submitter: Address,
+    pub salts: SaltSpec,
+    pub scorer: ScorerSpec,
+    pub min_score: u32,
+}
+
+impl Default for JobSpec {
+    fn default() -> Self {
+        JobSpec {
+            version: Version,
+            mode: Mode::Create2,
+            deployer: DEPLOYER_ADDRESS.into(),
+            code_hash: INITCODE_HASH.into(),
+            create3_caller: None,
+            senders: Vec::new(),
+            max_nonce: 0,
+            submitter: SUBMITTER_ADDRESS.into(),
+            salts: SaltSpec::Random,
+            scorer: ScorerSpec::UniswapV4,
+            min_score: 0,
+        }
+    }
+}
+
+impl JobSpec {
+    /// A miner set up to run this job; threads and callbacks are left to the
+    /// machine running it.
+    pub fn builder(&self) -> Result<MinerBuilder, MinerError> {
+        let mut builder = Miner::builder()
+            .mode(self.mode)
+            .deployer(self.deployer)
+            .code_hash(self.code_hash)
+            .submitter(self.submitter)
+            .scorer(self.scorer.build()?)
+            .min_score(self.min_score);
+        if let Some(caller) = self.create3_caller {
+            builder = builder.create3_caller(caller);
+        }
+        if !self.senders.is_empty() {
+            builder = builder.senders(self.senders.iter().copied(), self.max_nonce);
+        }
+        if let SaltSpec::Sequential { start, end } = self.salts {
+            builder = builder.salts(Sequential { submitter: self.submitter.into_array(), start, end });
+        }
+        Ok(builder)
+    }
+}
+
+/// What a job came to: the best candidate found and the work it took.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BestResult {
+    pub version: Version,
+    pub job: JobSpec,
+    pub best: Option<Candidate>,
+    pub hashes: u64,
+    #[serde(rename = "elapsed_secs", with = "secs")]
+    pub elapsed: Duration,
+}
+
+impl BestResult {
+    pub fn new(job: JobSpec, stats: &Stats) -> Self {
+        BestResult { version: Version, job, best: stats.best, hashes: stats.hashes, elapsed: stats.elapsed }
+    }
+}
+
+/// A range of sequential salt counters, from `start` up to (not including)
+/// `end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CounterRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+/// Add the counters a sequential search at `salts` has tried to `covered`,
+/// the ranges tried before it was resumed.
+pub fn extend_covered(covered: &mut Vec<CounterRange>, salts: Option<&SaltPosition>) {
+    if let Some(&SaltPosition::Sequential { start, next, .. }) = salts {
+        match covered.last_mut() {
+            Some(last) if last.end == start => last.end = next,
+            _ if next > start => covered.push(CounterRange { start, end: next }),
+            _ => {}
+        }
+    }
+}
+
+/// The counters `start`, `start + step`, ... up to (not including) `end`
+/// tried under one pepper of random salts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PepperRange {
+    pub pepper: u32,
+    pub start: u64,
+    pub end: u64,
+    pub step: u64,
+}
+
+/// The counters each pepper of random salts at `salts` has tried, retired
+/// ones included. Streams carry on from where they got to and are never
+/// dropped, so these cover every earlier run too.
+pub fn pepper_ranges(salts: Option<&SaltPosition>) -> Vec<PepperRange> {
+    let Some(SaltPosition::Random { streams, retired, .. }) = salts else { return Vec::new() };
+    let current = streams.iter().map(|s| PepperRange { pepper: s.pepper, start: s.next % s.step.max(1), end: s.next, step: s.step });
+    retired.iter().copied().chain(current).collect()
+}
+
+/// The state of a running search, from [`Miner::snapshot`], to carry on from
+/// with [`MinerBuilder::resume_from`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub version: Version,
+    /// Where the salts stand; None for salts that cannot be picked up again.
+    pub salts: Option<SaltPosition>,
+    /// Sequential salt counters tried so far, across every resume.
+    #[serde(default)]
+    pub covered: Vec<CounterRange>,
+    /// Random salt counters tried so far under each pepper, across every
+    /// resume.
+    #[serde(default)]
+    pub peppers: Vec<PepperRange>,
+    pub best: Option<Candidate>,
+    /// The best few results, best first, when they are kept.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub top: Vec<Candidate>,
+    pub hashes: u64,
+    #[serde(rename = "elapsed_secs", with = "secs")]
+    pub elapsed: Duration,
+}
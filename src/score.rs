@@ -0,0 +1,223 @@
+// Pluggable scoring, so the miner can grind for rules other than the Uniswap
+// v4 challenge's.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::MinerError;
+use crate::{compute_score, compute_score_at_least, Candidate};
+
+pub trait Scorer: Send + Sync {
+    fn score(&self, address: &[u8; 20]) -> u32;
+
+    // The score when it is at least `min_score`, otherwise anything lower, for
+    // scorers that can give up early on hopeless addresses.
+    fn score_at_least(&self, address: &[u8; 20], _min_score: u32) -> u32 {
+        self.score(address)
+    }
+}
+
+impl Scorer for Box<dyn Scorer> {
+    fn score(&self, address: &[u8; 20]) -> u32 {
+        (**self).score(address)
+    }
+
+    fn score_at_least(&self, address: &[u8; 20], min_score: u32) -> u32 {
+        (**self).score_at_least(address, min_score)
+    }
+}
+
+// The challenge's own rules (VanityAddressLib).
+pub struct UniswapV4;
+
+impl Scorer for UniswapV4 {
+    fn score(&self, address: &[u8; 20]) -> u32 {
+        compute_score(address)
+    }
+
+    fn score_at_least(&self, address: &[u8; 20], min_score: u32) -> u32 {
+        compute_score_at_least(address, min_score)
+    }
+}
+
+// Number of leading zero bytes, which make an address cheaper to pass in calldata.
+pub struct LeadingZeroBytes;
+
+impl Scorer for LeadingZeroBytes {
+    fn score(&self, address: &[u8; 20]) -> u32 {
+        address.iter().take_while(|b| **b == 0).count() as u32
+    }
+}
+
+// Number of 4 nibbles anywhere in the address.
+pub struct Fours;
+
+impl Scorer for Fours {
+    fn score(&self, address: &[u8; 20]) -> u32 {
+        address.iter().map(|b| (b >> 4 == 4) as u32 + (b & 0x0F == 4) as u32).sum()
+    }
+}
+
+// How many of the 14 hook permission bits agree with a wanted set; 14 is an
+// address deployable as a hook with exactly those permissions.
+pub struct HookFlagBits(pub u16);
+
+impl Scorer for HookFlagBits {
+    fn score(&self, address: &[u8; 20]) -> u32 {
+        let flags = u16::from_be_bytes([address[18], address[19]]);
+        14 - ((flags ^ self.0) & ((1 << 14) - 1)).count_ones()
+    }
+}
+
+// Well-known objectives, to maximize instead of a score or to track alongside it.
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Objective {
+    /// The Uniswap v4 challenge's score
+    ChallengeScore,
+    /// Most leading zero bytes
+    LeadingZeros,
+    /// Most 4 nibbles anywhere
+    Fours,
+    /// Most hook permission bits agreeing with --objective-hook-flags
+    HookFlags,
+}
+
+impl Objective {
+    pub fn scorer(self, hook_flags: Option<u16>) -> Result<Box<dyn Scorer>, MinerError> {
+        Ok(match self {
+            Objective::ChallengeScore => Box::new(UniswapV4),
+            Objective::LeadingZeros => Box::new(LeadingZeroBytes),
+            Objective::Fours => Box::new(Fours),
+            Objective::HookFlags => Box::new(HookFlagBits(
+                hook_flags.ok_or_else(|| MinerError::Config("the hook-flags objective needs --objective-hook-flags".to_string()))?,
+            )),
+        })
+    }
+
+    // How a best value is reported.
+    pub fn describe(self, value: u32) -> String {
+        let plural = if value == 1 { "" } else { "s" };
+        match self {
+            Objective::ChallengeScore => format!("challenge score {}", value),
+            Objective::LeadingZeros => format!("{} leading zero byte{}", value, plural),
+            Objective::Fours => format!("{} four{}", value, plural),
+            Objective::HookFlags => format!("{} of 14 hook flag bits matching", value),
+        }
+    }
+}
+
+// How to order candidates with equal scores, so the best is the same however
+// the search was split up or resumed.
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tiebreak {
+    /// More leading zero nibbles wins
+    LeadingZeros,
+    /// The lexicographically smaller address wins
+    LowestAddress,
+    /// The smaller salt wins, an order of discovery independent of timing
+    LowestSalt,
+    /// The candidate found first wins (the default)
+    Earliest,
+}
+
+pub fn leading_zero_nibbles(address: &[u8; 20]) -> u32 {
+    let bytes = address.iter().take_while(|b| **b == 0).count();
+    let half = address.get(bytes).is_some_and(|b| b >> 4 == 0) as usize;
+    (2 * bytes + half) as u32
+}
+
+// Whether `new` beats `old`, which has the same score, under `rules` applied
+// in order. Ties left after all rules keep `old`.
+pub fn wins_tie(rules: &[Tiebreak], new: &Candidate, old: &Candidate) -> bool {
+    for rule in rules {
+        let order = match rule {
+            Tiebreak::LeadingZeros => leading_zero_nibbles(&new.address).cmp(&leading_zero_nibbles(&old.address)),
+            Tiebreak::LowestAddress => old.address.cmp(&new.address),
+            Tiebreak::LowestSalt => old.salt.cmp(&new.salt),
+            Tiebreak::Earliest => return false,
+        };
+        if order != Ordering::Equal {
+            return order == Ordering::Greater;
+        }
+    }
+    false
+}
+
+// A scorer as named on the command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScorerSpec {
+    UniswapV4,
+    // `script:<path>`, a Rhai script.
+    Script(PathBuf),
+    // `wasm:<path>`, a WebAssembly module (binary or text format).
+    Wasm(PathBuf),
+    // `profile:<preset or path>`, a JSON scoring profile.
+    Profile(String),
+}
+
+impl ScorerSpec {
+    pub fn build(&self) -> Result<Box<dyn Scorer>, MinerError> {
+        match self {
+            ScorerSpec::UniswapV4 => Ok(Box::new(UniswapV4)),
+            #[cfg(feature = "script")]
+            ScorerSpec::Script(path) => Ok(Box::new(crate::script::ScriptScorer::load(path)?)),
+            #[cfg(not(feature = "script"))]
+            ScorerSpec::Script(_) => Err(MinerError::Config("built without scripting support, rebuild with --features script".to_string())),
+            #[cfg(feature = "wasm")]
+            ScorerSpec::Wasm(path) => Ok(Box::new(crate::wasm::WasmScorer::load(path)?)),
+            #[cfg(not(feature = "wasm"))]
+            ScorerSpec::Wasm(_) => Err(MinerError::Config("built without WebAssembly support, rebuild with --features wasm".to_string())),
+            ScorerSpec::Profile(name) => Ok(Box::new(crate::profile::Profile::load(name)?)),
+        }
+    }
+}
+
+impl FromStr for ScorerSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        if let Some(path) = s.strip_prefix("script:") {
+            return Ok(ScorerSpec::Script(PathBuf::from(path)));
+        }
+        if let Some(path) = s.strip_prefix("wasm:") {
+            return Ok(ScorerSpec::Wasm(PathBuf::from(path)));
+        }
+        if let Some(name) = s.strip_prefix("profile:") {
+            return Ok(ScorerSpec::Profile(name.to_string()));
+        }
+        match s {
+            "uniswap-v4" => Ok(ScorerSpec::UniswapV4),
+            _ => Err(format!("unknown scorer {:?} (available: uniswap-v4, profile:<preset or path>, script:<path>, wasm:<path>)", s)),
+        }
+    }
+}
+
+// Serialized as named on the command line.
+impl Serialize for ScorerSpec {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ScorerSpec {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl fmt::Display for ScorerSpec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScorerSpec::UniswapV4 => f.write_str("uniswap-v4"),
+            ScorerSpec::Script(path) => write!(f, "script:{}", path.display()),
+            ScorerSpec::Wasm(path) => write!(f, "wasm:{}", path.display()),
+            ScorerSpec::Profile(name) => write!(f, "profile:{}", name),
+        }
+    }
+}
@@ -0,0 +1,75 @@
+// User-defined scoring in Rhai, compiled in with the `script` feature.
+//
+// The script defines `fn score(address)`, called with the lowercase hex address
+// (40 characters, no 0x) and returning an integer. It may also define
+// `fn prefix()` returning a hex prefix every interesting address starts with;
+// other addresses then score 0 without running the script at all, which is
+// what keeps scripted scoring usable.
+
+use std::path::Path;
+
+use rhai::{CallFnOptions, Engine, OptimizationLevel, Scope, AST};
+
+use crate::error::MinerError;
+use crate::score::Scorer;
+
+// Guards against scripts that loop forever.
+const MAX_OPERATIONS: u64 = 100_000;
+
+pub struct ScriptScorer {
+    engine: Engine,
+    ast: AST,
+    prefix: Vec<u8>,
+}
+
+impl ScriptScorer {
+    pub fn load(path: &Path) -> Result<Self, MinerError> {
+        let mut engine = Engine::new();
+        engine.set_optimization_level(OptimizationLevel::Full);
+        engine.set_max_operations(MAX_OPERATIONS);
+        let ast = engine
+            .compile_file(path.to_path_buf())
+            .map_err(|e| MinerError::Config(format!("cannot compile {}: {}", path.display(), e)))?;
+        let defines = |name: &str| ast.iter_functions().any(|f| f.name == name && f.params.is_empty());
+        let prefix = if defines("prefix") {
+            engine
+                .call_fn::<String>(&mut Scope::new(), &ast, "prefix", ())
+                .map_err(|e| MinerError::Config(format!("{}: prefix(): {}", path.display(), e)))?
+                .to_lowercase()
+        } else {
+            String::new()
+        };
+        if !prefix.bytes().all(|b| b.is_ascii_hexdigit()) || prefix.len() > 40 {
+            return Err(MinerError::Config(format!("{}: prefix() must return at most 40 hex digits, got {:?}", path.display(), prefix)));
+        }
+        // Only the functions are needed from here on; top-level statements ran once above.
+        let ast = ast.clone_functions_only();
+        let scorer = ScriptScorer { engine, ast, prefix: prefix.into_bytes() };
+        scorer
+            .call(&"0".repeat(40))
+            .map_err(|e| MinerError::Config(format!("{}: score(): {}", path.display(), e)))?;
+        Ok(scorer)
+    }
+
+    fn call(&self, address: &str) -> Result<u32, String> {
+        let options = CallFnOptions::new().eval_ast(false);
+        let score = self
+            .engine
+            .call_fn_with_options::<i64>(options, &mut Scope::new(), &self.ast, "score", (address.to_string(),))
+            .map_err(|e| e.to_string())?;
+        Ok(score.clamp(0, u32::MAX as i64) as u32)
+    }
+}
+
+impl Scorer for ScriptScorer {
+    fn score(&self, address: &[u8; 20]) -> u32 {
+        let mut buf = [0; 40];
+        hex::encode_to_slice(address, &mut buf).unwrap();
+        if !buf.starts_with(&self.prefix) {
+            return 0;
+        }
+        // A failing call (including running out of operations) scores nothing.
+        self.call(std::str::from_utf8(&buf).unwrap()).unwrap_or(0)
+    }
+}
+
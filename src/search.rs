@@ -0,0 +1,226 @@
+// The search itself: worker threads deriving and scoring candidates, keeping
+// the best and handing new bests to whoever follows them.
+
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::codehash::ExtraHashes;
+use crate::histogram::{self, Histogram};
+use crate::hooks::{Event, EventBus};
+use crate::mode::Derivation;
+#[cfg(feature = "cli")]
+use crate::pattern::Targets;
+use crate::salts::SaltGenerator;
+use crate::score::{self, Scorer, Tiebreak};
+use crate::top::Top;
+use crate::track::Tracker;
+#[cfg(feature = "cli")]
+use crate::runtime;
+use crate::Candidate;
+
+// Hashes each worker counts locally before adding them to the shared total.
+const HASH_BATCH: u64 = 1 << 12;
+
+// How far back a worker's hashrate looks, roughly.
+const RATE_WINDOW: Duration = Duration::from_secs(10);
+
+// A worker this much slower than the median one is flagged, as throttled or
+// sharing its core.
+pub const SLOW: f64 = 0.75;
+
+// Told about candidates while the best is still locked.
+pub type Callback = Box<dyn Fn(&Candidate) + Send + Sync>;
+
+// Shared with whoever controls a running search. Workers look at it once per
+// batch of hashes.
+#[derive(Default)]
+pub struct Control {
+    pub paused: AtomicBool,
+    pub stopped: AtomicBool,
+    // Hashes of this search, and of the one resumed for a Miner.
+    pub hashes: AtomicU64,
+    // Salts each worker has been through, for checkpoints.
+    pub taken: Mutex<Vec<u64>>,
+    // Each worker's hashrate over about the last RATE_WINDOW, pauses left out.
+    pub rates: Mutex<Vec<f64>>,
+}
+
+// Once `control` is stopped, give the workers `timeout` to finish their
+// batches, and call `give_up` if the search has not ended by then.
+#[cfg(feature = "cli")]
+pub fn backstop(control: Arc<Control>, timeout: Duration, give_up: impl FnOnce() + Send + 'static) {
+    runtime::spawn("stop timeout", move |mut shutdown| async move {
+        while !control.stopped.load(Ordering::Relaxed) {
+            if !shutdown.sleep(Duration::from_millis(100)).await {
+                return;
+            }
+        }
+        // The search ending shuts every service down.
+        if shutdown.sleep(timeout).await {
+            give_up();
+        }
+    });
+}
+
+// Which of `rates` are SLOW next to the median, when there are several.
+pub fn slow(rates: &[f64]) -> Vec<bool> {
+    let mut sorted = rates.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let median = sorted.get(sorted.len() / 2).copied().unwrap_or(0.0);
+    rates.iter().map(|&rate| rates.len() > 1 && rate < SLOW * median).collect()
+}
+
+pub struct Search {
+    pub derivation: Derivation,
+    pub salts: Arc<dyn SaltGenerator>,
+    pub scorer: Box<dyn Scorer>,
+    // Report matches instead of maximizing the score.
+    #[cfg(feature = "cli")]
+    pub pattern: Option<Targets>,
+    pub tracker: Option<Tracker>,
+    pub extra_hashes: Option<ExtraHashes>,
+    pub histogram: Option<Arc<Histogram>>,
+    pub tiebreak: Vec<Tiebreak>,
+    // Addresses with fewer leading zero nibbles are skipped.
+    pub zero_nibbles: u32,
+    // Score a candidate has to beat to be worth reporting: the higher of the
+    // local best and the on-chain best.
+    pub target: Arc<AtomicU32>,
+    pub best: Arc<Mutex<Option<Candidate>>>,
+    // The best few results, kept alongside the best.
+    pub top: Option<Arc<Top>>,
+    // Told about every new best, while the best is still locked.
+    pub events: Arc<EventBus>,
+    // Told about every candidate scoring at least this much, best or not.
+    pub on_candidate: Option<(u32, Callback)>,
+    pub control: Arc<Control>,
+}
+
+impl Search {
+    // Run on `threads` threads until stopped or the candidates run out, which
+    // only a CREATE search does.
+    pub fn run(&self, threads: usize) {
+        *self.control.taken.lock().unwrap_or_else(PoisonError::into_inner) = vec![0; threads];
+        *self.control.rates.lock().unwrap_or_else(PoisonError::into_inner) = vec![0.0; threads];
+        thread::scope(|scope| {
+            for i in 0..threads {
+                scope.spawn(move || self.worker(i, threads));
+            }
+        });
+    }
+
+    fn wait_while_paused(&self) {
+        while self.control.paused.load(Ordering::Relaxed) && !self.control.stopped.load(Ordering::Relaxed) {
+            thread::sleep(Duration::from_millis(50));
+        }
+    }
+
+    fn worker(&self, i: usize, threads: usize) {
+        let mut hashed = 0;
+        let mut taken = 0;
+        let mut flushed = Instant::now();
+        // Counted locally and flushed with the hash count, to keep the atomics cheap.
+        let mut counts = vec![0u64; if self.histogram.is_some() { histogram::BUCKETS } else { 0 }];
+        let salts = self.salts.salts(i, threads);
+        // Paused from the start, say outside a --schedule.
+        self.wait_while_paused();
+        if self.control.stopped.load(Ordering::Relaxed) {
+            return;
+        }
+        for salt in salts {
+            // A stop only ever lands between batches, so every salt hashed is
+            // also scored and reported.
+            if hashed == HASH_BATCH {
+                self.control.hashes.fetch_add(hashed, Ordering::Relaxed);
+                // Everything before this salt is done with.
+                self.control.taken.lock().unwrap_or_else(PoisonError::into_inner)[i] = taken;
+                self.record_rate(i, flushed.elapsed());
+                hashed = 0;
+                if let Some(histogram) = &self.histogram {
+                    histogram.flush(&mut counts);
+                }
+                self.wait_while_paused();
+                flushed = Instant::now();
+                if self.control.stopped.load(Ordering::Relaxed) {
+                    break;
+                }
+            }
+            let address = self.derivation.address(&salt);
+            hashed += 1;
+            taken += 1;
+            if let Some(tracker) = &self.tracker {
+                tracker.observe(self.derivation.mode, &salt, &address);
+            }
+            if let Some(extra_hashes) = &self.extra_hashes {
+                extra_hashes.observe(&self.derivation.deployer, &salt, &*self.scorer);
+            }
+            #[cfg(feature = "cli")]
+            if let Some(pattern) = &self.pattern {
+                pattern.check(self.derivation.mode, &salt, &address);
+                continue;
+            }
+            if score::leading_zero_nibbles(&address) < self.zero_nibbles {
+                continue;
+            }
+            let target_score = self.target.load(Ordering::Relaxed);
+            // Ties only matter with a tiebreak; anything lower is never reported.
+            let min_score = target_score.saturating_add(self.tiebreak.is_empty() as u32);
+            let min_score = self.top.as_ref().map_or(min_score, |top| min_score.min(top.floor()));
+            let min_score = self.on_candidate.as_ref().map_or(min_score, |(threshold, _)| min_score.min(*threshold));
+            let score = match &self.histogram {
+                Some(_) => self.scorer.score(&address),
+                None => self.scorer.score_at_least(&address, min_score),
+            };
+            if self.histogram.is_some() {
+                counts[Histogram::bucket(score)] += 1;
+            }
+            if let Some(top) = self.top.as_ref().filter(|top| score >= top.floor()) {
+                top.insert(Candidate { salt, address, score });
+            }
+            if let Some((_, callback)) = self.on_candidate.as_ref().filter(|(threshold, _)| score >= *threshold) {
+                callback(&Candidate { salt, address, score });
+            }
+            if score > target_score || (score == target_score && !self.tiebreak.is_empty()) {
+                self.offer(Candidate { salt, address, score });
+            }
+        }
+        // The rest of the last batch, when the salts run out part way.
+        self.control.hashes.fetch_add(hashed, Ordering::Relaxed);
+        if let Some(histogram) = &self.histogram {
+            histogram.flush(&mut counts);
+        }
+        self.control.taken.lock().unwrap_or_else(PoisonError::into_inner)[i] = taken;
+    }
+
+    // Fold a batch hashed in `took` into worker `i`'s hashrate, weighting it
+    // by how much of RATE_WINDOW it covers.
+    fn record_rate(&self, i: usize, took: Duration) {
+        let rate = HASH_BATCH as f64 / took.as_secs_f64().max(1e-9);
+        let weight = 1.0 - (-took.as_secs_f64() / RATE_WINDOW.as_secs_f64()).exp();
+        let mut rates = self.control.rates.lock().unwrap_or_else(PoisonError::into_inner);
+        rates[i] = if rates[i] == 0.0 { rate } else { rates[i] + weight * (rate - rates[i]) };
+    }
+
+    // Keep `candidate` if it beats the best so far and tell the callbacks.
+    fn offer(&self, candidate: Candidate) {
+        let mut best = self.best.lock().unwrap_or_else(PoisonError::into_inner);
+        let score = candidate.score;
+        let target_score = self.target.load(Ordering::Relaxed);
+        // A tie only counts against our own best, never the on-chain one.
+        let better = match *best {
+            // Found again, by a resumed search or another worker.
+            Some(best) if best.salt == candidate.salt || best.address == candidate.address => false,
+            None => score > target_score,
+            Some(best) if score == best.score => score >= target_score && score::wins_tie(&self.tiebreak, &candidate, &best),
+            Some(best) => score > best.score && score > target_score,
+        };
+        if !better {
+            return;
+        }
+        *best = Some(candidate);
+        self.target.fetch_max(score, Ordering::Relaxed);
+        self.events.emit(Event::NewBest(&candidate));
+    }
+}
@@ -0,0 +1,136 @@
+// Known answers checked before every search. A miner quietly deriving wrong
+// addresses or scores is the worst way to fail, since nothing looks wrong
+// until a submission is rejected hours later, so it refuses to start instead.
+
+use crate::error::MinerError;
+use crate::mode::{Derivation, Mode};
+use crate::score::Scorer;
+use crate::{const_hex, keccak256, CREATE3_PROXY_HASH};
+
+// Deployer, salt, initcode hash and the address CREATE2 puts it at: EIP-1014's
+// examples. A mainnet deployment follows.
+const CREATE2: [(&str, &str, &str, &str); 3] = [
+    (
+        "0000000000000000000000000000000000000000",
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        "bc36789e7a1e281436464229828f817d6612f7b477d66591ff96a9e064bcc98a",
+        "4d1a2e2bb4f88f0250f26ffff098b0b30b26bf38",
+    ),
+    (
+        "deadbeef00000000000000000000000000000000",
+        "000000000000000000000000feed000000000000000000000000000000000000",
+        "bc36789e7a1e281436464229828f817d6612f7b477d66591ff96a9e064bcc98a",
+        "d04116cdd17bebe565eb2422f2497e06cc1c9833",
+    ),
+    (
+        "0000000000000000000000000000000000000000",
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470",
+        "e33c0c7f7df4809055c3eba6c09cfe4baf1bd9e0",
+    ),
+];
+
+// Uniswap v2's USDC/WETH pair on mainnet, salted with keccak256(token0 ++ token1).
+const V2_FACTORY: &str = "5c69bee701ef814a2b6a3edd4b1652cb9cc5aa6f";
+const V2_PAIR_HASH: &str = "96e8ac4277198ff8b6f785478aa9a39f403cb768dd02cbee326c3e7da348845f";
+const USDC: &str = "a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48";
+const WETH: &str = "c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2";
+const USDC_WETH_PAIR: &str = "b4e16d0168e52d35cacd2c6185b44281ec28c9dc";
+
+// Sender, nonce and the address CREATE gives, across RLP's nonce encodings.
+const CREATE: [(&str, u64, &str); 3] = [
+    ("6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0", 0, "cd234a471b72ba2f1ccf0a70fcaba648a5eecd8d"),
+    ("6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0", 1, "343c43a37d37dff08ae8c4a11544c718abb4fcf8"),
+    ("6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0", 3, "fffd933a0bc612844eaf0c6fe3e5b8e9b6c1d19c"),
+];
+
+// The proxy CREATE3 deploys first, hashing to CREATE3_PROXY_HASH.
+const CREATE3_PROXY: &str = "67363d3d37363d34f03d5260086018f3";
+
+// Private key 1, whose account is as well known as any.
+const EOA: (&str, &str) = (
+    "0000000000000000000000000000000000000000000000000000000000000001",
+    "7e5f4552091a69125d5dfcb7b8c2659029395bdf",
+);
+
+// Addresses and their challenge scores, worked out by hand from the rules.
+const SCORES: [(&str, u32); 7] = [
+    // 8 leading zeros, four 4s, no 4 after them, 4 4s.
+    ("0000000044449dbd6b1b8e5dcac0e2ce0ec9ef2e", 144),
+    // The PoolManager's own address: 11 zeros, four 4s, no 4 after them, 4 4s.
+    ("000000000004444c5dc75cb358380d2e3de08a90", 174),
+    ("0000000000000000000000000000000000000000", 400),
+    // 36 zeros, four 4s ending the address, 4s at the end, 4 4s.
+    ("0000000000000000000000000000000000004444", 444),
+    // Forty 4s: four 4s but more after them, 4s at the end.
+    ("4444444444444444444444444444444444444444", 100),
+    ("1444444444444444444444444444444444444444", 0),
+    ("0000000514444444444444444444444444444444", 0),
+];
+
+// A --mode create salt: the sender, then the nonce in the last 8 bytes.
+fn sender_salt(sender: &[u8; 20], nonce: u64) -> [u8; 32] {
+    let mut salt = [0; 32];
+    salt[..20].copy_from_slice(sender);
+    salt[24..].copy_from_slice(&nonce.to_be_bytes());
+    salt
+}
+
+fn failed(what: &str, expected: &str, got: &[u8]) -> MinerError {
+    MinerError::SelfTest(format!("{}: expected 0x{}, got 0x{}", what, expected, hex::encode(got)))
+}
+
+fn check_address(what: &str, expected: &str, got: [u8; 20]) -> Result<(), MinerError> {
+    match got == const_hex::<20>(expected) {
+        true => Ok(()),
+        false => Err(failed(what, expected, &got)),
+    }
+}
+
+// Derive every vector through the same code the workers run for `mode`, and
+// score every vector with `scorer` when it should follow the challenge's rules.
+pub fn check(mode: Mode, scorer: Option<&dyn Scorer>) -> Result<(), MinerError> {
+    let create2 = |deployer: &str, code_hash: &str| Derivation { mode: Mode::Create2, deployer: const_hex(deployer), code_hash: const_hex(code_hash), create3_caller: None };
+    for (deployer, salt, code_hash, address) in CREATE2 {
+        check_address("CREATE2 derivation", address, create2(deployer, code_hash).address(&const_hex(salt)))?;
+    }
+    let salt = keccak256([const_hex::<20>(USDC), const_hex(WETH)].concat());
+    check_address("CREATE2 of the Uniswap v2 USDC/WETH pair", USDC_WETH_PAIR, create2(V2_FACTORY, V2_PAIR_HASH).address(&salt))?;
+
+    let create = Derivation { mode: Mode::Create, deployer: [0; 20], code_hash: [0; 32], create3_caller: None };
+    for (sender, nonce, address) in CREATE {
+        check_address("CREATE derivation", address, create.address(&sender_salt(&const_hex(sender), nonce)))?;
+    }
+
+    match mode {
+        Mode::Create2 | Mode::Create => {}
+        Mode::Create3 => {
+            if keccak256(const_hex::<16>(CREATE3_PROXY)) != CREATE3_PROXY_HASH {
+                return Err(failed("hash of the CREATE3 proxy", CREATE3_PROXY, &CREATE3_PROXY_HASH));
+            }
+            // CREATE2 of the proxy, then CREATE from it with nonce 1.
+            let (deployer, salt) = (const_hex(CREATE2[1].0), const_hex(CREATE2[1].1));
+            let proxy = create2(CREATE2[1].0, &hex::encode(CREATE3_PROXY_HASH)).address(&salt);
+            let expected = create.address(&sender_salt(&proxy, 1));
+            let derivation = Derivation { mode: Mode::Create3, deployer, code_hash: [0; 32], create3_caller: None };
+            check_address("CREATE3 derivation", &hex::encode(expected), derivation.address(&salt))?;
+        }
+        Mode::Eoa => {
+            let derivation = Derivation { mode: Mode::Eoa, deployer: [0; 20], code_hash: [0; 32], create3_caller: None };
+            check_address("account of a private key", EOA.1, derivation.address(&const_hex(EOA.0)))?;
+        }
+    }
+
+    if let Some(scorer) = scorer {
+        for (address, expected) in SCORES {
+            let address = const_hex(address);
+            // The early exit must not cut short an address reaching the bar.
+            for got in [scorer.score(&address), scorer.score_at_least(&address, expected)] {
+                if got != expected {
+                    return Err(MinerError::SelfTest(format!("score of 0x{}: expected {}, got {}", hex::encode(address), expected, got)));
+                }
+            }
+        }
+    }
+    Ok(())
+}
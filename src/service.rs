@@ -0,0 +1,282 @@
+// Running as a Windows service. `--service install` registers the miner, with
+// the rest of its command line, to start on boot; the service manager then
+// starts it with `--service run`. Stopping the service (or shutting down)
+// stops the search the way Ctrl-C does, so the run wraps up as usual, and its
+// start, new bests and end go to the Application event log. `--service
+// uninstall` stops and removes it.
+
+use std::ffi::OsString;
+
+use clap::ValueEnum;
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// Register the service, to start on boot with the rest of the command line
+    Install,
+    /// Run as the service, as the service manager does
+    Run,
+    /// Stop and remove the service
+    Uninstall,
+}
+
+pub const NAME: &str = env!("CARGO_PKG_NAME");
+
+// The service's command line: the miner at `exe` with `args`, the flags it
+// was installed with, minus `--service install` and plus `--service run`.
+pub fn command_line(exe: OsString, args: impl IntoIterator<Item = OsString>) -> String {
+    let mut kept = vec![exe];
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.to_str() {
+            Some("--service") => {
+                args.next();
+            }
+            Some(arg) if arg.starts_with("--service=") => {}
+            _ => kept.push(arg),
+        }
+    }
+    kept.extend(["--service".into(), "run".into()]);
+    kept.iter().map(|arg| quote(&arg.to_string_lossy())).collect::<Vec<_>>().join(" ")
+}
+
+// `arg` as CommandLineToArgvW reads it back: quoted when it has spaces, tabs
+// or quotes, with quotes and the backslashes before them escaped.
+fn quote(arg: &str) -> String {
+    if !arg.is_empty() && !arg.contains([' ', '\t', '"']) {
+        return arg.to_string();
+    }
+    let mut quoted = String::from('"');
+    let mut backslashes = 0;
+    for c in arg.chars() {
+        match c {
+            '\\' => backslashes += 1,
+            '"' => {
+                quoted += &"\\".repeat(2 * backslashes + 1);
+                backslashes = 0;
+            }
+            _ => backslashes = 0,
+        }
+        quoted.push(c);
+    }
+    quoted += &"\\".repeat(backslashes);
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(windows)]
+pub use windows::{install, run, running, uninstall, EventLog};
+
+#[cfg(windows)]
+mod windows {
+    use std::ffi::c_void;
+    use std::io;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::ptr;
+    use std::sync::{Mutex, OnceLock, PoisonError};
+
+    use windows_sys::Win32::Foundation::{
+        ERROR_CALL_NOT_IMPLEMENTED, ERROR_FAILED_SERVICE_CONTROLLER_CONNECT, ERROR_SERVICE_SPECIFIC_ERROR, HANDLE, NO_ERROR,
+    };
+    use windows_sys::Win32::Storage::FileSystem::DELETE;
+    use windows_sys::Win32::System::EventLog::{
+        DeregisterEventSource, RegisterEventSourceW, ReportEventW, EVENTLOG_ERROR_TYPE, EVENTLOG_INFORMATION_TYPE, EVENTLOG_WARNING_TYPE,
+    };
+    use windows_sys::Win32::System::Services::*;
+
+    use super::{command_line, NAME};
+    use crate::error::exit_code;
+    use crate::logfile::{Level, Log};
+    use crate::runtime;
+
+    fn wide(s: &str) -> Vec<u16> {
+        s.encode_utf16().chain([0]).collect()
+    }
+
+    // A service manager handle, closed when dropped.
+    struct ScHandle(SC_HANDLE);
+
+    impl ScHandle {
+        fn new(handle: SC_HANDLE) -> io::Result<Self> {
+            if handle.is_null() {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(ScHandle(handle))
+        }
+
+        fn manager(access: u32) -> io::Result<Self> {
+            Self::new(unsafe { OpenSCManagerW(ptr::null(), ptr::null(), access) })
+        }
+    }
+
+    impl Drop for ScHandle {
+        fn drop(&mut self) {
+            unsafe { CloseServiceHandle(self.0) };
+        }
+    }
+
+    pub fn install() -> Result<(), String> {
+        let failed = |e: io::Error| format!("cannot install the {} service: {}", NAME, e);
+        let exe = std::env::current_exe().map_err(failed)?;
+        let command_line = wide(&command_line(exe.into_os_string(), std::env::args_os().skip(1)));
+        let manager = ScHandle::manager(SC_MANAGER_CREATE_SERVICE).map_err(failed)?;
+        let (name, display) = (wide(NAME), wide("Uniswap v4 challenge miner"));
+        let service = ScHandle::new(unsafe {
+            CreateServiceW(
+                manager.0,
+                name.as_ptr(),
+                display.as_ptr(),
+                SERVICE_ALL_ACCESS,
+                SERVICE_WIN32_OWN_PROCESS,
+                SERVICE_AUTO_START,
+                SERVICE_ERROR_NORMAL,
+                command_line.as_ptr(),
+                ptr::null(),
+                ptr::null_mut(),
+                ptr::null(),
+                ptr::null(),
+                ptr::null(),
+            )
+        })
+        .map_err(failed)?;
+        let mut text = wide("Mines salts for the Uniswap v4 address challenge");
+        let description = SERVICE_DESCRIPTIONW { lpDescription: text.as_mut_ptr() };
+        unsafe { ChangeServiceConfig2W(service.0, SERVICE_CONFIG_DESCRIPTION, &description as *const _ as *const c_void) };
+        println!("Installed the {} service, starting on boot; start it now with `sc start {}`", NAME, NAME);
+        Ok(())
+    }
+
+    pub fn uninstall() -> Result<(), String> {
+        let failed = |e: io::Error| format!("cannot uninstall the {} service: {}", NAME, e);
+        let manager = ScHandle::manager(SC_MANAGER_CONNECT).map_err(failed)?;
+        let name = wide(NAME);
+        let service = ScHandle::new(unsafe { OpenServiceW(manager.0, name.as_ptr(), SERVICE_STOP | SERVICE_QUERY_STATUS | DELETE) })
+            .map_err(failed)?;
+        // Failing when it is not running, which is fine.
+        let mut status = unsafe { std::mem::zeroed::<SERVICE_STATUS>() };
+        unsafe { ControlService(service.0, SERVICE_CONTROL_STOP, &mut status) };
+        if unsafe { DeleteService(service.0) } == 0 {
+            return Err(failed(io::Error::last_os_error()));
+        }
+        println!("Uninstalled the {} service", NAME);
+        Ok(())
+    }
+
+    struct StatusHandle(SERVICE_STATUS_HANDLE);
+
+    // Only ever passed to SetServiceStatus, from any thread.
+    unsafe impl Send for StatusHandle {}
+    unsafe impl Sync for StatusHandle {}
+
+    static STATUS: OnceLock<StatusHandle> = OnceLock::new();
+
+    type Mine = Box<dyn FnOnce() + Send>;
+
+    static MINE: Mutex<Option<Mine>> = Mutex::new(None);
+
+    // Whether this process is running as the service.
+    pub fn running() -> bool {
+        STATUS.get().is_some()
+    }
+
+    // Run `mine` as the service, returning once it has stopped.
+    pub fn run(mine: impl FnOnce() + Send + 'static) -> Result<(), String> {
+        *MINE.lock().unwrap_or_else(PoisonError::into_inner) = Some(Box::new(mine));
+        let mut name = wide(NAME);
+        let table = [
+            SERVICE_TABLE_ENTRYW { lpServiceName: name.as_mut_ptr(), lpServiceProc: Some(service_main) },
+            SERVICE_TABLE_ENTRYW { lpServiceName: ptr::null_mut(), lpServiceProc: None },
+        ];
+        if unsafe { StartServiceCtrlDispatcherW(table.as_ptr()) } != 0 {
+            return Ok(());
+        }
+        match io::Error::last_os_error() {
+            e if e.raw_os_error() == Some(ERROR_FAILED_SERVICE_CONTROLLER_CONNECT as i32) => {
+                Err("--service run is for the service manager, install the service with --service install".to_string())
+            }
+            e => Err(format!("cannot run as a service: {}", e)),
+        }
+    }
+
+    fn set_state(state: SERVICE_STATUS_CURRENT_STATE, code: i32) {
+        let Some(handle) = STATUS.get() else { return };
+        let status = SERVICE_STATUS {
+            dwServiceType: SERVICE_WIN32_OWN_PROCESS,
+            dwCurrentState: state,
+            dwControlsAccepted: if state == SERVICE_RUNNING { SERVICE_ACCEPT_STOP | SERVICE_ACCEPT_SHUTDOWN } else { 0 },
+            dwWin32ExitCode: if code == 0 { NO_ERROR } else { ERROR_SERVICE_SPECIFIC_ERROR },
+            dwServiceSpecificExitCode: code as u32,
+            dwCheckPoint: 0,
+            // Long enough for the search to wrap up and the report to be written.
+            dwWaitHint: if state == SERVICE_STOP_PENDING { 30_000 } else { 0 },
+        };
+        unsafe { SetServiceStatus(handle.0, &status) };
+    }
+
+    unsafe extern "system" fn service_main(_: u32, _: *mut windows_sys::core::PWSTR) {
+        let name = wide(NAME);
+        let handle = RegisterServiceCtrlHandlerExW(name.as_ptr(), Some(handler), ptr::null());
+        if handle.is_null() {
+            return;
+        }
+        STATUS.set(StatusHandle(handle)).ok();
+        set_state(SERVICE_RUNNING, 0);
+        // Exits from anywhere in the run are a stop, not a crash.
+        runtime::before_exit(|code| set_state(SERVICE_STOPPED, code));
+        let mine = MINE.lock().unwrap_or_else(PoisonError::into_inner).take();
+        let finished = panic::catch_unwind(AssertUnwindSafe(|| {
+            if let Some(mine) = mine {
+                mine();
+            }
+        }));
+        set_state(SERVICE_STOPPED, if finished.is_ok() { 0 } else { exit_code::FAILED });
+    }
+
+    unsafe extern "system" fn handler(control: u32, _: u32, _: *mut c_void, _: *mut c_void) -> u32 {
+        match control {
+            SERVICE_CONTROL_STOP | SERVICE_CONTROL_SHUTDOWN => {
+                set_state(SERVICE_STOP_PENDING, 0);
+                runtime::request_stop();
+                NO_ERROR
+            }
+            SERVICE_CONTROL_INTERROGATE => NO_ERROR,
+            _ => ERROR_CALL_NOT_IMPLEMENTED,
+        }
+    }
+
+    // The Application event log, with the service's name as the source.
+    pub struct EventLog(HANDLE);
+
+    // Event sources may be reported to from any thread.
+    unsafe impl Send for EventLog {}
+    unsafe impl Sync for EventLog {}
+
+    impl EventLog {
+        pub fn open() -> Result<Self, String> {
+            let name = wide(NAME);
+            let handle = unsafe { RegisterEventSourceW(ptr::null(), name.as_ptr()) };
+            if handle.is_null() {
+                return Err(format!("cannot open the event log: {}", io::Error::last_os_error()));
+            }
+            Ok(EventLog(handle))
+        }
+    }
+
+    impl Log for EventLog {
+        fn write(&self, level: Level, message: &str) {
+            let kind = match level {
+                Level::Info => EVENTLOG_INFORMATION_TYPE,
+                Level::Warning => EVENTLOG_WARNING_TYPE,
+                Level::Error => EVENTLOG_ERROR_TYPE,
+            };
+            let message = wide(message);
+            let strings = [message.as_ptr()];
+            unsafe { ReportEventW(self.0, kind, 0, 1, ptr::null_mut(), 1, 0, strings.as_ptr(), ptr::null()) };
+        }
+    }
+
+    impl Drop for EventLog {
+        fn drop(&mut self) {
+            unsafe { DeregisterEventSource(self.0) };
+        }
+    }
+}
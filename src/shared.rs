@@ -0,0 +1,211 @@
+// Miners on one machine sharing their best, with --share-best: say one per
+// NUMA node, or a CPU and a GPU process. Each publishes its best to a small
+// file they all lock before touching, and raises its target to the best any
+// of them has, so a score is only reported by the one that beat everyone.
+// Miners of the same mode, deployer, initcode and scorer find the same file.
+// It lists the miners using it: the best is dropped once none of them is
+// running, and the last to exit deletes the file, so a later run, or one
+// after a crash, starts afresh.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+use alloy_primitives::B256;
+use serde::{Deserialize, Serialize};
+
+use crate::{runtime, Candidate};
+
+// How often each miner catches up with the others.
+const POLL: Duration = Duration::from_secs(1);
+
+// The file miners of `key` share, in the temporary directory.
+pub fn default_path(key: B256) -> PathBuf {
+    std::env::temp_dir().join(format!("{}-{}.best", env!("CARGO_PKG_NAME"), hex::encode(&key[..8])))
+}
+
+// The best of all the miners, and which process found it.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Entry {
+    pub best: Candidate,
+    pub pid: u32,
+}
+
+// What the file holds: the best, and the pids of the miners sharing it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Board {
+    pub best: Option<Entry>,
+    pub miners: Vec<u32>,
+}
+
+// Whether process `pid` still runs, as far as can be told: on Linux from
+// /proc, on other unixes from kill -0, and elsewhere it is taken to.
+fn alive(pid: u32) -> bool {
+    if pid == std::process::id() {
+        true
+    } else if cfg!(target_os = "linux") {
+        Path::new(&format!("/proc/{}", pid)).exists()
+    } else if cfg!(unix) {
+        let kill = std::process::Command::new("kill").args(["-0", &pid.to_string()]).stderr(std::process::Stdio::null()).status();
+        kill.is_ok_and(|status| status.success())
+    } else {
+        true
+    }
+}
+
+// Whether `file` is still the one at `path`, not one deleted by the last
+// miner to leave it between opening and locking.
+#[cfg(unix)]
+fn linked(file: &File, path: &Path) -> io::Result<bool> {
+    use std::os::unix::fs::MetadataExt;
+    let (open, named) = (file.metadata()?, std::fs::metadata(path));
+    Ok(named.is_ok_and(|named| (named.dev(), named.ino()) == (open.dev(), open.ino())))
+}
+
+#[cfg(not(unix))]
+fn linked(_: &File, path: &Path) -> io::Result<bool> {
+    Ok(path.exists())
+}
+
+// Open `path`, creating it for this user alone. The temporary directory is
+// everyone's, so a file left there by another user, or a link to one of ours,
+// is refused rather than trusted: its best would hold every miner back.
+#[cfg(unix)]
+fn open_own(path: &Path) -> io::Result<File> {
+    use std::os::unix::fs::{MetadataExt, OpenOptionsExt};
+    let file = OpenOptions::new().read(true).write(true).create(true).truncate(false).mode(0o600).custom_flags(libc::O_NOFOLLOW).open(path)?;
+    // SAFETY: geteuid takes nothing and cannot fail.
+    let uid = unsafe { libc::geteuid() };
+    match file.metadata()?.uid() == uid {
+        true => Ok(file),
+        false => Err(io::Error::new(io::ErrorKind::PermissionDenied, format!("{} belongs to another user", path.display()))),
+    }
+}
+
+#[cfg(not(unix))]
+fn open_own(path: &Path) -> io::Result<File> {
+    OpenOptions::new().read(true).write(true).create(true).truncate(false).open(path)
+}
+
+pub struct Shared {
+    path: PathBuf,
+    file: File,
+}
+
+impl Shared {
+    // Open `path` and join the miners listed in it.
+    pub fn open(path: &Path) -> io::Result<Self> {
+        loop {
+            let file = open_own(path)?;
+            file.lock()?;
+            if !linked(&file, path)? {
+                continue;
+            }
+            let mut shared = Shared { path: path.to_path_buf(), file };
+            let joined = shared.read().and_then(|mut board| {
+                board.miners.push(std::process::id());
+                shared.write(&board)
+            });
+            shared.file.unlock()?;
+            return joined.map(|_| shared);
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    // Publish `ours` when it beats the shared best, and return the shared
+    // best after. Ties stay with whoever got there first.
+    pub fn exchange(&mut self, ours: Option<Candidate>) -> io::Result<Option<Entry>> {
+        self.file.lock()?;
+        let exchanged = self.exchange_locked(ours);
+        self.file.unlock()?;
+        exchanged
+    }
+
+    fn exchange_locked(&mut self, ours: Option<Candidate>) -> io::Result<Option<Entry>> {
+        let mut board = self.read()?;
+        match ours.filter(|ours| board.best.is_none_or(|shared| ours.score > shared.best.score)) {
+            Some(best) => {
+                board.best = Some(Entry { best, pid: std::process::id() });
+                self.write(&board)?;
+                Ok(board.best)
+            }
+            None => Ok(board.best),
+        }
+    }
+
+    // Publish `ours` one last time and leave, deleting the file when no other
+    // miner is left using it.
+    pub fn leave(mut self, ours: Option<Candidate>) -> io::Result<()> {
+        self.file.lock()?;
+        let left = self.exchange_locked(ours).and_then(|_| self.read()).and_then(|mut board| {
+            if let Some(at) = board.miners.iter().position(|pid| *pid == std::process::id()) {
+                board.miners.remove(at);
+            }
+            match board.miners.is_empty() {
+                true => std::fs::remove_file(&self.path),
+                false => self.write(&board),
+            }
+        });
+        self.file.unlock()?;
+        left
+    }
+
+    // The board with the miners no longer running taken off it, and the best
+    // too when none is.
+    fn read(&mut self) -> io::Result<Board> {
+        let mut json = String::new();
+        self.file.seek(SeekFrom::Start(0))?;
+        self.file.read_to_string(&mut json)?;
+        // Empty for the first miner, and never half written under the lock.
+        let mut board: Board = serde_json::from_str(&json).unwrap_or_default();
+        board.miners.retain(|pid| alive(*pid));
+        if board.miners.is_empty() {
+            board.best = None;
+        }
+        Ok(board)
+    }
+
+    fn write(&mut self, board: &Board) -> io::Result<()> {
+        self.file.set_len(0)?;
+        self.file.seek(SeekFrom::Start(0))?;
+        self.file.write_all(serde_json::to_string(board).unwrap().as_bytes())
+    }
+}
+
+// Publish `best` and raise `target` to the best of the other miners.
+fn catch_up(shared: &mut Shared, best: &Mutex<Option<Candidate>>, target: &AtomicU32) -> io::Result<()> {
+    let ours = *best.lock().unwrap_or_else(PoisonError::into_inner);
+    match shared.exchange(ours)? {
+        Some(entry) if entry.pid != std::process::id() && target.fetch_max(entry.best.score, Ordering::Relaxed) < entry.best.score => {
+            let (best, pid) = (entry.best, entry.pid);
+            println!("Shared best: score {} at 0x{} from pid {}, only scores above it will be reported", best.score, hex::encode(best.address), pid);
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+// Keep `shared` and `target` up to date with `best` and the other miners'
+// until shutdown, starting before the search does.
+pub fn spawn(mut shared: Shared, best: Arc<Mutex<Option<Candidate>>>, target: Arc<AtomicU32>) -> io::Result<()> {
+    catch_up(&mut shared, &best, &target)?;
+    runtime::spawn_blocking("shared best", move |mut shutdown| {
+        let shared_path = shared.path().to_path_buf();
+        while shutdown.sleep_blocking(POLL) {
+            if let Err(e) = catch_up(&mut shared, &best, &target) {
+                return eprintln!("Warning: cannot share the best through {}: {}", shared.path().display(), e);
+            }
+        }
+        // What was found last, for the miners carrying on.
+        if let Err(e) = shared.leave(*best.lock().unwrap_or_else(PoisonError::into_inner)) {
+            eprintln!("Warning: cannot leave {}: {}", shared_path.display(), e);
+        }
+    });
+    Ok(())
+}
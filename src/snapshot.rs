@@ -0,0 +1,126 @@
+// A full status snapshot on demand, for a quick look at a headless miner
+// without stopping it: SIGUSR1 on unix (`kill -USR1 <pid>`), and on Windows
+// reading the named pipe printed at startup (`type \\.\pipe\...`). The
+// snapshot goes to stdout, and on Windows to the pipe's reader as well.
+
+use std::fmt::Write;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Instant;
+
+use crate::logfile::fields;
+use crate::mode::Mode;
+use crate::salts::{format_coverage, Coverage, SaltPosition};
+use crate::search::{self, Control};
+use crate::{deadline, odds, runtime, Candidate};
+
+// What a snapshot is taken of.
+pub struct Sources {
+    pub mode: Mode,
+    pub control: Arc<Control>,
+    pub coverage: Arc<Coverage>,
+    pub best: Arc<Mutex<Option<Candidate>>>,
+    pub started: Instant,
+}
+
+impl Sources {
+    pub fn render(&self) -> String {
+        let elapsed = self.started.elapsed();
+        let hashes = self.control.hashes.load(Ordering::Relaxed);
+        let taken = self.control.taken.lock().unwrap_or_else(PoisonError::into_inner).clone();
+        let rates = self.control.rates.lock().unwrap_or_else(PoisonError::into_inner).clone();
+        let slow = search::slow(&rates);
+        let best = *self.best.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut out = String::new();
+        writeln!(out, "Status snapshot (pid {}):", std::process::id()).ok();
+        writeln!(
+            out,
+            "  Uptime {}, {} hashes at {} on average",
+            deadline::format_countdown(elapsed),
+            odds::format_count(hashes as f64),
+            odds::format_rate(hashes as f64 / elapsed.as_secs_f64().max(1e-9))
+        )
+        .ok();
+        let total = taken.iter().sum::<u64>().max(1);
+        for (thread, &count) in taken.iter().enumerate() {
+            let share = 100.0 * count as f64 / total as f64;
+            write!(out, "  Thread {}: {} salts ({:.1}%)", thread, odds::format_count(count as f64), share).ok();
+            if let Some(rate) = rates.get(thread) {
+                write!(out, " at {}", odds::format_rate(*rate)).ok();
+            }
+            writeln!(out, "{}", if slow.get(thread) == Some(&true) { ", slow" } else { "" }).ok();
+        }
+        match best {
+            Some(c) => writeln!(out, "  Best: {}", fields(&c, self.mode)).ok(),
+            None => writeln!(out, "  Best: nothing found yet").ok(),
+        };
+        match (self.coverage.position(&taken), self.coverage.fraction(&taken)) {
+            (Some(position), Some(fraction)) => writeln!(out, "  Covered: {} ({} of the salts)", describe(&position), format_coverage(fraction)).ok(),
+            (Some(position), None) => writeln!(out, "  Covered: {}", describe(&position)).ok(),
+            (None, _) => writeln!(out, "  Covered: not tracked for these salts").ok(),
+        };
+        out
+    }
+}
+
+// What has been tried up to `position`.
+pub fn describe(position: &SaltPosition) -> String {
+    match position {
+        SaltPosition::Random { streams, .. } => streams
+            .iter()
+            .map(|s| format!("pepper {:#010x} below counter {}", s.pepper, s.next))
+            .collect::<Vec<_>>()
+            .join(", "),
+        SaltPosition::Sequential { start, next, end, .. } => match end {
+            Some(end) => format!("counters {}..{} of {}..{}", start, next, start, end),
+            None => format!("counters {}..{}", start, next),
+        },
+        SaltPosition::Nonces { next } => format!("the first {} senders and nonces", next),
+    }
+}
+
+// Print a snapshot whenever one is asked for, until shutdown.
+pub fn spawn(sources: Arc<Sources>) {
+    #[cfg(unix)]
+    runtime::spawn("snapshot", move |mut shutdown| async move {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut requests = match signal(SignalKind::user_defined1()) {
+            Ok(requests) => requests,
+            Err(e) => return eprintln!("Cannot handle SIGUSR1: {}", e),
+        };
+        loop {
+            tokio::select! {
+                _ = requests.recv() => print!("{}", sources.render()),
+                _ = shutdown.wait() => break,
+            }
+        }
+    });
+    #[cfg(windows)]
+    runtime::spawn("snapshot", move |mut shutdown| async move {
+        use tokio::io::AsyncWriteExt;
+        use tokio::net::windows::named_pipe::ServerOptions;
+
+        let name = format!(r"\\.\pipe\{}-{}", env!("CARGO_PKG_NAME"), std::process::id());
+        println!("Status snapshots: type {}", name);
+        let mut first = true;
+        loop {
+            let mut pipe = match ServerOptions::new().first_pipe_instance(first).create(&name) {
+                Ok(pipe) => pipe,
+                Err(e) => return eprintln!("Cannot create {}: {}", name, e),
+            };
+            first = false;
+            tokio::select! {
+                connected = pipe.connect() => if let Err(e) = connected {
+                    return eprintln!("Cannot serve {}: {}", name, e);
+                },
+                _ = shutdown.wait() => break,
+            }
+            let snapshot = sources.render();
+            print!("{}", snapshot);
+            pipe.write_all(snapshot.as_bytes()).await.ok();
+        }
+    });
+    #[cfg(not(any(unix, windows)))]
+    drop(sources);
+}
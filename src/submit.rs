@@ -0,0 +1,542 @@
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use clap::{ArgGroup, Args};
+
+use crate::budget::Budget;
+use crate::challenge::{self, Simulation};
+use crate::export::{self, ExportFormat};
+use crate::gas::{format_gwei, Fees, GasStrategy};
+use crate::monitor;
+use crate::network::NetworkArgs;
+use crate::persist;
+use crate::relay::{self, RelayArgs};
+use crate::rpc::{parse_quantity, to_hex, RpcClient};
+use crate::tx::Eip1559Tx;
+use crate::wallet::{Signer, WalletArgs};
+use crate::decode_hex;
+
+/// Submit a mined salt to the challenge contract
+#[derive(Args, Debug)]
+#[command(group(
+    ArgGroup::new("signer")
+        .required(true)
+        .args(["private_key", "keystore", "ledger", "trezor", "dry_run", "export"])
+))]
+pub struct SubmitArgs {
+    /// Salt to submit (32 bytes, hex)
+    #[arg(long, value_parser = decode_hex::<32>)]
+    pub salt: [u8; 32],
+
+    /// JSON-RPC endpoint of an Ethereum node (default: the network's, if any).
+    /// Repeat or separate with commas to fail over between several providers
+    #[arg(long, env = "RPC_URL", value_delimiter = ',')]
+    pub rpc_url: Vec<String>,
+
+    #[command(flatten)]
+    pub network: NetworkArgs,
+
+    /// Print the calldata and a `cast send` command instead of signing and sending
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Write the submission as a Foundry script or Safe batch instead of sending it
+    #[arg(long, value_enum, value_name = "FORMAT")]
+    pub export: Option<ExportFormat>,
+
+    /// File to write the export to (default: stdout)
+    #[arg(long, value_name = "PATH", requires = "export")]
+    pub output: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub wallet: WalletArgs,
+
+    /// Gas limit (default: estimate plus 20%)
+    #[arg(long)]
+    pub gas_limit: Option<u64>,
+
+    /// Nonce to use, e.g. to replace a stuck transaction (default: next pending nonce)
+    #[arg(long)]
+    pub nonce: Option<u64>,
+
+    /// Return once the transaction is sent instead of following it until it is mined
+    #[arg(long)]
+    pub no_wait: bool,
+
+    /// Blocks to wait for after the transaction is mined before reporting the outcome
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..))]
+    pub confirmations: u64,
+
+    #[command(flatten)]
+    pub gas: GasStrategy,
+
+    #[command(flatten)]
+    pub budget: Budget,
+
+    #[command(flatten)]
+    pub relay: RelayArgs,
+}
+
+pub fn run(mut args: SubmitArgs) -> Result<(), String> {
+    let contract = args.network.contract()?;
+    if args.dry_run {
+        return print_dry_run(&args, &contract);
+    }
+    if let Some(format) = args.export {
+        let chain_id = args
+            .network
+            .chain_id()
+            .ok_or_else(|| format!("{} has no fixed chain id, pass --chain-id", args.network.network))?;
+        let rendered = export::render(format, &contract, &args.salt, chain_id);
+        return match &args.output {
+            Some(path) => persist::write(path, rendered).map_err(|e| format!("cannot write {}: {}", path.display(), e)),
+            None => {
+                print!("{}", rendered);
+                Ok(())
+            }
+        };
+    }
+    let rpc_urls = args.network.rpc_urls(std::mem::take(&mut args.rpc_url));
+    if rpc_urls.is_empty() {
+        return Err("no RPC endpoint, pass --rpc-url".to_string());
+    }
+    let rpc = RpcClient::new(&rpc_urls);
+    args.network.check_chain_id(&rpc)?;
+    let mut signer = args.wallet.signer()?;
+    let options = SubmitOptions {
+        gas: args.gas.clone(),
+        gas_limit: args.gas_limit,
+        nonce: args.nonce,
+        wait: !args.no_wait,
+        confirmations: args.confirmations,
+        budget: args.budget.clone(),
+        relay: args.relay.clone(),
+    };
+    submit_salt(&rpc, &contract, &args.salt, &mut signer, &options, &mut 0)?;
+    Ok(())
+}
+
+// Everything needed to broadcast the submission from another machine. With
+// --relayer that is a call of the relayer's `execute`, as submit would send.
+fn print_dry_run(args: &SubmitArgs, contract: &[u8; 20]) -> Result<(), String> {
+    let data = challenge::update_best_address_calldata(&args.salt);
+    let rpc_url = args
+        .network
+        .rpc_urls(args.rpc_url.clone())
+        .first()
+        .cloned()
+        .unwrap_or_else(|| "$RPC_URL".to_string());
+    let mut command = match &args.relay.relayer {
+        Some(relayer) => {
+            if !challenge::salt_accepts_sender(&args.salt, relayer) {
+                return Err(format!(
+                    "salt is bound to 0x{} but the challenge would be called by {}",
+                    hex::encode(&args.salt[..20]),
+                    to_hex(relayer)
+                ));
+            }
+            println!("To:       {} (the relayer)", to_hex(relayer));
+            println!("Value:    0");
+            println!(
+                "Calldata: {}",
+                to_hex(&relay::execute_calldata(contract, &data))
+            );
+            println!(
+                "Calls:    updateBestAddress(bytes32) on {} with {}",
+                to_hex(contract),
+                to_hex(&data)
+            );
+            println!("Sender:   any account the relayer takes calls from; the challenge sees the relayer as msg.sender");
+            format!(
+                "cast send {} 'execute(address,uint256,bytes)' {} 0 {} --rpc-url {}",
+                to_hex(relayer),
+                to_hex(contract),
+                to_hex(&data),
+                rpc_url
+            )
+        }
+        None => {
+            println!("To:       {}", to_hex(contract));
+            println!("Value:    0");
+            println!("Calldata: {}", to_hex(&data));
+            if args.salt[..20] == [0; 20] {
+                println!("Sender:   any (salt is not bound to a submitter)");
+            } else {
+                println!("Sender:   must be 0x{}", hex::encode(&args.salt[..20]));
+            }
+            format!(
+                "cast send {} 'updateBestAddress(bytes32)' {} --rpc-url {}",
+                to_hex(contract),
+                to_hex(&args.salt),
+                rpc_url
+            )
+        }
+    };
+    if let Some(limit) = args.gas_limit {
+        command += &format!(" --gas-limit {}", limit);
+    }
+    if let Some(max_fee) = args.gas.max_fee {
+        command += &format!(" --gas-price {}gwei", max_fee);
+    }
+    if let Some(priority_fee) = args.gas.priority_fee {
+        command += &format!(" --priority-gas-price {}gwei", priority_fee);
+    }
+    println!();
+    println!("{}", command);
+    Ok(())
+}
+
+const POLL_INTERVAL: Duration = Duration::from_secs(4);
+
+// Polls a used nonce gets for the receipt of one of ours to turn up, since a
+// node can count the nonce before it serves the receipt, or a load-balanced
+// endpoint answer from a node behind the one that saw the block.
+const RECEIPT_POLLS: u32 = 5;
+
+// How a submission is fee'd, nonced and followed up.
+#[derive(Debug, Clone)]
+pub struct SubmitOptions {
+    pub gas: GasStrategy,
+    pub gas_limit: Option<u64>,
+    pub nonce: Option<u64>,
+    pub wait: bool,
+    // Blocks (including the one it is mined in) to wait for after inclusion.
+    pub confirmations: u64,
+    pub budget: Budget,
+    pub relay: RelayArgs,
+}
+
+// A signer with its submission settings, shared by the automatic submitters.
+pub struct Submitter {
+    pub signer: Signer,
+    pub options: SubmitOptions,
+    // Worst-case cost of everything sent so far, in wei.
+    pub spent: u128,
+}
+
+impl Submitter {
+    // Address the challenge will record as the submitter.
+    pub fn msg_sender(&self) -> [u8; 20] {
+        self.options.relay.relayer.unwrap_or(self.signer.address())
+    }
+
+    pub fn submit(&mut self, rpc: &RpcClient, contract: &[u8; 20], salt: &[u8; 32]) -> Result<String, String> {
+        submit_salt(rpc, contract, salt, &mut self.signer, &self.options, &mut self.spent)
+    }
+}
+
+// What a transaction can cost at most.
+fn max_cost(tx: &Eip1559Tx) -> u128 {
+    tx.gas_limit as u128 * tx.max_fee_per_gas
+}
+
+fn time_left(rpc: &RpcClient, contract: &[u8; 20], now: u64) -> Result<Option<Duration>, String> {
+    match challenge::competition_deadline(rpc, contract) {
+        Ok(deadline) if now > deadline => Err(format!("competition ended at timestamp {}", deadline)),
+        Ok(deadline) => Ok(Some(Duration::from_secs(deadline - now))),
+        Err(e) => {
+            eprintln!("Warning: could not read competition deadline: {}", e);
+            Ok(None)
+        }
+    }
+}
+
+// Fees according to the strategy at the current block, plus its base fee.
+fn current_fees(rpc: &RpcClient, contract: &[u8; 20], gas: &GasStrategy) -> Result<(Fees, u128), String> {
+    let block = rpc.latest_block()?;
+    let time_left = time_left(rpc, contract, block.timestamp)?;
+    let suggested_tip = match gas.priority_fee {
+        Some(_) => 0,
+        None => rpc.max_priority_fee()?,
+    };
+    if gas.is_urgent(time_left) {
+        println!("Deadline is near, escalating fees by {:.2}x", gas.escalation(time_left));
+    }
+    Ok((gas.fees(block.base_fee, suggested_tip, time_left), block.base_fee))
+}
+
+fn sign_and_send(rpc: &RpcClient, signer: &mut Signer, tx: &Eip1559Tx) -> Result<(Vec<u8>, String), String> {
+    if signer.is_hardware() {
+        print_signing_summary(tx);
+        println!("Confirm the transaction on your device...");
+    }
+    let raw = signer.sign_transaction(tx)?;
+    let hash = rpc.send_raw_transaction(&raw)?;
+    Ok((raw, hash))
+}
+
+// Build, sign and broadcast `updateBestAddress(salt)`. Unless `wait` is off,
+// follow the transaction until it is mined and confirmed, check that the salt
+// took the lead, and return the hash that made it. `spent` tracks the
+// worst-case cost of submissions against the budget.
+pub fn submit_salt(
+    rpc: &RpcClient,
+    contract: &[u8; 20],
+    salt: &[u8; 32],
+    signer: &mut Signer,
+    options: &SubmitOptions,
+    spent: &mut u128,
+) -> Result<String, String> {
+    let sender = signer.address();
+    // The account the challenge sees calling it.
+    let msg_sender = options.relay.relayer.unwrap_or(sender);
+    if !challenge::salt_accepts_sender(salt, &msg_sender) {
+        return Err(format!(
+            "salt is bound to 0x{} but the challenge would be called by {}",
+            hex::encode(&salt[..20]),
+            to_hex(&msg_sender)
+        ));
+    }
+    let calldata = challenge::update_best_address_calldata(salt);
+    let (to, data) = match &options.relay.relayer {
+        Some(relayer) => {
+            relay::warn_mismatch(&sender, relayer);
+            relay::check_relayer(rpc, contract, relayer, salt)?;
+            (*relayer, relay::execute_calldata(contract, &calldata))
+        }
+        None => (*contract, calldata),
+    };
+
+    let chain_id = rpc.chain_id()?;
+    let (fees, base_fee) = current_fees(rpc, contract, &options.gas)?;
+    options.gas.check_cap(base_fee).map_err(|e| format!("{}, not submitting", e))?;
+    let gas_limit = match options.gas_limit {
+        Some(limit) => limit,
+        None => rpc.estimate_gas(&sender, &to, &data)? * 6 / 5,
+    };
+    let nonce = match options.nonce {
+        Some(nonce) => nonce,
+        None => rpc.nonce(&sender, "pending")?,
+    };
+
+    let tx = Eip1559Tx {
+        chain_id,
+        nonce,
+        max_priority_fee_per_gas: fees.max_priority_fee_per_gas,
+        max_fee_per_gas: fees.max_fee_per_gas,
+        gas_limit,
+        to,
+        value: 0,
+        data,
+    };
+    println!(
+        "Submitting salt 0x{} from {} (nonce {}, base fee {}, max fee {}, priority fee {})",
+        hex::encode(salt),
+        to_hex(&sender),
+        tx.nonce,
+        format_gwei(base_fee),
+        format_gwei(tx.max_fee_per_gas),
+        format_gwei(tx.max_priority_fee_per_gas)
+    );
+    options.budget.check(rpc, max_cost(&tx), *spent)?;
+    let (raw, hash) = sign_and_send(rpc, signer, &tx)?;
+    *spent += max_cost(&tx);
+    println!("Transaction sent: {}", hash);
+    if !options.wait {
+        return Ok(hash);
+    }
+    let pending = Pending { tx, raw, hash };
+    let hash = match wait_for_inclusion(rpc, signer, contract, options, pending, spent)? {
+        Inclusion::Succeeded(hash) => hash,
+        Inclusion::Reverted(hash) => {
+            let reason = match challenge::simulate_update(rpc, contract, &msg_sender, salt) {
+                Ok(Simulation::WorseAddress { score, .. }) => {
+                    format!("the salt no longer beats the best address (its score is {})", score)
+                }
+                Ok(Simulation::CompetitionOver) => "the competition is over".to_string(),
+                Ok(Simulation::InvalidSender) => "the contract rejected the sender".to_string(),
+                _ => "unknown reason".to_string(),
+            };
+            return Err(format!("transaction {} reverted: {}", hash, reason));
+        }
+    };
+    report_outcome(rpc, contract, salt, &msg_sender);
+    Ok(hash)
+}
+
+// Re-read the contract to tell whether the submission actually holds the lead.
+fn report_outcome(rpc: &RpcClient, contract: &[u8; 20], salt: &[u8; 32], sender: &[u8; 20]) {
+    let leader = challenge::best_address_salt(rpc, contract).and_then(|best_salt| {
+        let best = monitor::fetch_best(rpc, contract)?;
+        Ok((best_salt, best))
+    });
+    match leader {
+        Ok((best_salt, best)) if best_salt == *salt && best.submitter == *sender => println!(
+            "Submission confirmed: 0x{} with score {} now holds the lead",
+            hex::encode(best.address),
+            best.score
+        ),
+        Ok((_, best)) => println!(
+            "Submission mined, but the lead is held by 0x{} with score {} (submitted by 0x{})",
+            hex::encode(best.address),
+            best.score,
+            hex::encode(best.submitter)
+        ),
+        Err(e) => eprintln!("Warning: could not read the best address after submitting: {}", e),
+    }
+}
+
+// The latest transaction sent for a nonce.
+struct Pending {
+    tx: Eip1559Tx,
+    raw: Vec<u8>,
+    hash: String,
+}
+
+enum Inclusion {
+    Succeeded(String),
+    Reverted(String),
+}
+
+// Poll until one of our transactions for the nonce is mined and has enough
+// confirmations. Re-broadcast it if the node forgets it, and replace it with
+// higher fees if it lingers. A receipt vanishing in a reorg puts us back to
+// waiting for inclusion.
+fn wait_for_inclusion(
+    rpc: &RpcClient,
+    signer: &mut Signer,
+    contract: &[u8; 20],
+    options: &SubmitOptions,
+    pending: Pending,
+    spent: &mut u128,
+) -> Result<Inclusion, String> {
+    let Pending { mut tx, mut raw, mut hash } = pending;
+    let gas = &options.gas;
+    let sender = signer.address();
+    let mut sent = vec![hash.clone()];
+    let mut replacements = 0;
+    let mut last_broadcast = Instant::now();
+    let mut mined_in = None;
+    let mut without_receipt = 0;
+    'poll: loop {
+        thread::sleep(POLL_INTERVAL);
+        let mined_nonce = rpc.nonce(&sender, "latest")?;
+        for candidate in &sent {
+            let Some(receipt) = rpc.transaction_receipt(candidate)? else {
+                continue;
+            };
+            let block = parse_quantity(&receipt["blockNumber"])? as u64;
+            if parse_quantity(&receipt["status"])? != 1 {
+                println!("Transaction {} reverted in block {}", candidate, block);
+                return Ok(Inclusion::Reverted(candidate.clone()));
+            }
+            let depth = (rpc.block_number()? + 1).saturating_sub(block);
+            if depth >= options.confirmations {
+                println!("Transaction {} mined in block {} ({} confirmations)", candidate, block, depth);
+                return Ok(Inclusion::Succeeded(candidate.clone()));
+            }
+            if mined_in != Some(block) {
+                println!(
+                    "Transaction {} mined in block {}, waiting for {} confirmations",
+                    candidate, block, options.confirmations
+                );
+                mined_in = Some(block);
+            }
+            continue 'poll;
+        }
+        if mined_in.take().is_some() {
+            println!("Transaction is no longer in the chain (reorg), waiting for it to be mined again");
+            last_broadcast = Instant::now();
+        }
+        if mined_nonce > tx.nonce {
+            without_receipt += 1;
+            if without_receipt < RECEIPT_POLLS {
+                continue;
+            }
+            return Err(format!("nonce {} was used by a different transaction", tx.nonce));
+        }
+
+        if rpc.transaction_by_hash(&hash)?.is_none() {
+            println!("Transaction {} was dropped, re-broadcasting", hash);
+            if let Err(e) = rpc.send_raw_transaction(&raw) {
+                eprintln!("Warning: re-broadcast failed: {}", e);
+            }
+            continue;
+        }
+
+        if replacements < gas.max_replacements && last_broadcast.elapsed() >= gas.replace_after {
+            let previous = Fees { max_fee_per_gas: tx.max_fee_per_gas, max_priority_fee_per_gas: tx.max_priority_fee_per_gas };
+            let (current, base_fee) = current_fees(rpc, contract, gas)?;
+            if let Err(e) = gas.check_cap(base_fee) {
+                println!("Not speeding up nonce {}: {}", tx.nonce, e);
+                replacements = gas.max_replacements;
+                continue;
+            }
+            let bumped = gas.bump(previous, current).map(|fees| Eip1559Tx {
+                max_fee_per_gas: fees.max_fee_per_gas,
+                max_priority_fee_per_gas: fees.max_priority_fee_per_gas,
+                ..tx.clone()
+            });
+            // Only one transaction per nonce can land, so a replacement adds
+            // just its extra worst-case cost.
+            let already_spent = *spent - max_cost(&tx);
+            match bumped.map(|bumped| (options.budget.check(rpc, max_cost(&bumped), already_spent), bumped)) {
+                Some((Err(e), _)) => {
+                    println!("Not speeding up nonce {}: {}", tx.nonce, e);
+                    replacements = gas.max_replacements;
+                }
+                Some((Ok(()), bumped)) => {
+                    *spent = already_spent + max_cost(&bumped);
+                    tx = bumped;
+                    println!(
+                        "Speeding up nonce {}: max fee {}, priority fee {}",
+                        tx.nonce,
+                        format_gwei(tx.max_fee_per_gas),
+                        format_gwei(tx.max_priority_fee_per_gas)
+                    );
+                    (raw, hash) = sign_and_send(rpc, signer, &tx)?;
+                    println!("Replacement sent: {}", hash);
+                    sent.push(hash.clone());
+                    replacements += 1;
+                }
+                None => {
+                    println!("Cannot speed up nonce {} further without exceeding --max-fee", tx.nonce);
+                    replacements = gas.max_replacements;
+                }
+            }
+            last_broadcast = Instant::now();
+        }
+    }
+}
+
+// Decoded view of the transaction, laid out so each field can be checked
+// against what the hardware wallet shows before approving.
+fn print_signing_summary(tx: &Eip1559Tx) {
+    println!("Transaction to sign:");
+    println!("  chain id:     {}", tx.chain_id);
+    println!("  to:           {}", to_hex(&tx.to));
+    println!("  value:        {} wei", tx.value);
+    println!("  nonce:        {}", tx.nonce);
+    println!("  gas limit:    {}", tx.gas_limit);
+    println!("  max fee:      {}", format_gwei(tx.max_fee_per_gas));
+    println!(
+        "  priority fee: {}",
+        format_gwei(tx.max_priority_fee_per_gas)
+    );
+    // Through a relayer, the inner call is what reaches the challenge.
+    let call = match relay::decode_execute(&tx.data) {
+        Some(execute) => {
+            println!(
+                "  function:     execute(address,uint256,bytes) [0x{}]",
+                hex::encode(&tx.data[..4])
+            );
+            println!("  target:       {}", to_hex(&execute.target));
+            println!("  forwarded:    {} wei", execute.value);
+            execute.data
+        }
+        None => &tx.data[..],
+    };
+    let Some((selector, args)) = call.split_first_chunk::<4>() else {
+        // Too short to be a call of anything.
+        println!("  data:         0x{}", hex::encode(call));
+        return;
+    };
+    println!(
+        "  function:     updateBestAddress(bytes32) [0x{}]",
+        hex::encode(selector)
+    );
+    for (i, word) in args.chunks(32).enumerate() {
+        println!("  data[{}]:      0x{}", i, hex::encode(word));
+    }
+}
@@ -0,0 +1,124 @@
+// Logging to journald or the local syslog daemon on Linux, for fleets that
+// already collect those. journald gets its native protocol, with the score,
+// address and salt of a new best as their own fields; syslog gets the same
+// key=value lines --log-file has, at the same priorities.
+
+use std::io;
+use std::os::unix::net::UnixDatagram;
+use std::path::{Path, PathBuf};
+
+use crate::hooks::Event;
+use crate::logfile::{describe, Level, Log, SyslogTarget};
+use crate::mode::Mode;
+
+pub const JOURNALD_SOCKET: &str = "/run/systemd/journal/socket";
+pub const SYSLOG_SOCKET: &str = "/dev/log";
+const IDENTIFIER: &str = env!("CARGO_PKG_NAME");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Protocol {
+    Journald,
+    Syslog,
+}
+
+pub struct Syslog {
+    protocol: Protocol,
+    path: PathBuf,
+    socket: UnixDatagram,
+}
+
+// The syslog severity of `level`, which journald's PRIORITY shares.
+fn priority(level: Level) -> u8 {
+    match level {
+        Level::Info => 6,
+        Level::Warning => 4,
+        Level::Error => 3,
+    }
+}
+
+// One entry in journald's native protocol: KEY=value lines, or the key, a
+// little-endian length and the raw value for values spanning lines.
+pub fn journal_entry(fields: &[(&str, &str)]) -> Vec<u8> {
+    let mut entry = Vec::new();
+    for (key, value) in fields {
+        entry.extend_from_slice(key.as_bytes());
+        if value.contains('\n') {
+            entry.push(b'\n');
+            entry.extend_from_slice(&(value.len() as u64).to_le_bytes());
+        } else {
+            entry.push(b'=');
+        }
+        entry.extend_from_slice(value.as_bytes());
+        entry.push(b'\n');
+    }
+    entry
+}
+
+impl Syslog {
+    // Log to whichever `target` asks for, journald first if either will do.
+    pub fn open(target: SyslogTarget) -> Result<Self, String> {
+        let journald = Path::new(JOURNALD_SOCKET).exists();
+        match target {
+            SyslogTarget::Journald => Self::connect(Protocol::Journald, Path::new(JOURNALD_SOCKET)),
+            SyslogTarget::Syslog => Self::connect(Protocol::Syslog, Path::new(SYSLOG_SOCKET)),
+            SyslogTarget::Auto if journald => Self::connect(Protocol::Journald, Path::new(JOURNALD_SOCKET)),
+            SyslogTarget::Auto if Path::new(SYSLOG_SOCKET).exists() => Self::connect(Protocol::Syslog, Path::new(SYSLOG_SOCKET)),
+            SyslogTarget::Auto => Err(format!("neither journald ({}) nor syslog ({}) is listening", JOURNALD_SOCKET, SYSLOG_SOCKET)),
+        }
+    }
+
+    // Log with `protocol` to the socket at `path`, checking it is there.
+    pub fn connect(protocol: Protocol, path: &Path) -> Result<Self, String> {
+        let unbound = || UnixDatagram::unbound().map_err(|e| format!("cannot create a socket for {}: {}", path.display(), e));
+        unbound()?.connect(path).map_err(|e| format!("cannot log to {}: {}", path.display(), e))?;
+        // Entries are sent to the path each time rather than over a connected
+        // socket, so a restarted daemon is picked up.
+        let socket = unbound()?;
+        Ok(Syslog { protocol, path: path.to_path_buf(), socket })
+    }
+
+    fn send(&self, level: Level, message: &str, extra: &[(&str, &str)]) {
+        let sent = match self.protocol {
+            Protocol::Journald => {
+                let priority = priority(level).to_string();
+                let mut fields = vec![("MESSAGE", message), ("PRIORITY", &priority), ("SYSLOG_IDENTIFIER", IDENTIFIER)];
+                fields.extend_from_slice(extra);
+                self.send_datagram(&journal_entry(&fields))
+            }
+            // The user facility, as RFC 3164 local sockets expect.
+            Protocol::Syslog => {
+                let line = format!("<{}>{}[{}]: {}", 8 + priority(level), IDENTIFIER, std::process::id(), message);
+                self.send_datagram(line.as_bytes())
+            }
+        };
+        if let Err(e) = sent {
+            eprintln!("Cannot log to {}: {}", self.path.display(), e);
+        }
+    }
+
+    fn send_datagram(&self, datagram: &[u8]) -> io::Result<()> {
+        self.socket.send_to(datagram, &self.path).map(|_| ())
+    }
+}
+
+impl Log for Syslog {
+    fn write(&self, level: Level, message: &str) {
+        self.send(level, message, &[]);
+    }
+
+    fn event(&self, event: &Event, mode: Mode) {
+        let (level, message) = describe(event, mode);
+        match event {
+            Event::NewBest(c) => {
+                let (score, address, salt) = (c.score.to_string(), format!("0x{}", hex::encode(c.address)), format!("0x{}", hex::encode(c.salt)));
+                let mut fields = vec![("SCORE", score.as_str()), ("ADDRESS", address.as_str())];
+                // A --mode eoa salt is a private key.
+                if mode != Mode::Eoa {
+                    fields.push(("SALT", salt.as_str()));
+                }
+                self.send(level, &message, &fields);
+            }
+            _ => self.send(level, &message, &[]),
+        }
+    }
+}
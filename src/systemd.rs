@@ -0,0 +1,112 @@
+// Telling systemd how the miner is doing when it runs as a Type=notify unit:
+// READY once the search starts, a STATUS of the best score and hashrate for
+// `systemctl status`, STOPPING as it wraps up, and, with WatchdogSec=, a
+// WATCHDOG ping at half the timeout for as long as the hash count keeps
+// going up, so a hung miner is restarted. Nothing happens outside systemd.
+
+use std::io;
+use std::os::unix::net::{SocketAddr, UnixDatagram};
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+use std::time::{Duration, Instant};
+
+use crate::odds;
+use crate::search::Control;
+use crate::{runtime, Candidate};
+
+// How often STATUS is updated when there is no watchdog to keep up with.
+const STATUS_INTERVAL: Duration = Duration::from_secs(10);
+
+pub struct Notifier {
+    socket: UnixDatagram,
+    address: SocketAddr,
+}
+
+impl Notifier {
+    // The socket at `path`, an abstract one when it starts with `@`.
+    pub fn connect(path: &str) -> io::Result<Self> {
+        let address = match path.strip_prefix('@') {
+            Some(name) => {
+                use std::os::linux::net::SocketAddrExt;
+                SocketAddr::from_abstract_name(name)?
+            }
+            None => SocketAddr::from_pathname(path)?,
+        };
+        Ok(Notifier { socket: UnixDatagram::unbound()?, address })
+    }
+
+    // Send `state`, newline-separated VARIABLE=value assignments.
+    pub fn notify(&self, state: &str) -> io::Result<()> {
+        self.socket.send_to_addr(state.as_bytes(), &self.address).map(|_| ())
+    }
+}
+
+static NOTIFIER: OnceLock<Option<Notifier>> = OnceLock::new();
+
+// The NOTIFY_SOCKET systemd passed, if it did.
+fn notifier() -> Option<&'static Notifier> {
+    NOTIFIER
+        .get_or_init(|| {
+            let path = std::env::var("NOTIFY_SOCKET").ok()?;
+            Notifier::connect(&path).map_err(|e| eprintln!("Cannot notify systemd at {}: {}", path, e)).ok()
+        })
+        .as_ref()
+}
+
+// Send `state` to systemd, if running under it.
+pub fn notify(state: &str) {
+    if let Some(notifier) = notifier() {
+        notifier.notify(state).ok();
+    }
+}
+
+// The watchdog timeout from WATCHDOG_USEC, when meant for process `pid`
+// (WATCHDOG_PID, if set).
+pub fn watchdog(usec: Option<&str>, watchdog_pid: Option<&str>, pid: u32) -> Option<Duration> {
+    if watchdog_pid.is_some_and(|watchdog_pid| watchdog_pid.parse() != Ok(pid)) {
+        return None;
+    }
+    usec?.parse().ok().filter(|&usec| usec > 0).map(Duration::from_micros)
+}
+
+// The STATUS shown by `systemctl status`.
+pub fn status(best: Option<&Candidate>, hashrate: f64, hashes: u64, paused: bool) -> String {
+    let best = match best {
+        Some(c) => format!("best score {} at 0x{}", c.score, hex::encode(c.address)),
+        None => "nothing found yet".to_string(),
+    };
+    let state = if paused { "Paused" } else { "Mining" };
+    format!("{}: {}, {} hashes at {}", state, best, odds::format_count(hashes as f64), odds::format_rate(hashrate))
+}
+
+// Tell systemd the search is starting, then keep its status and watchdog up
+// to date until shutdown.
+pub fn spawn(control: Arc<Control>, best: Arc<Mutex<Option<Candidate>>>) {
+    if notifier().is_none() {
+        return;
+    }
+    let timeout = watchdog(
+        std::env::var("WATCHDOG_USEC").ok().as_deref(),
+        std::env::var("WATCHDOG_PID").ok().as_deref(),
+        std::process::id(),
+    );
+    let interval = timeout.map_or(STATUS_INTERVAL, |timeout| (timeout / 2).min(STATUS_INTERVAL));
+    notify(&format!("READY=1\nSTATUS={}", status(None, 0.0, 0, false)));
+    runtime::spawn("systemd", move |mut shutdown| async move {
+        let mut last = (Instant::now(), control.hashes.load(Ordering::Relaxed));
+        while shutdown.sleep(interval).await {
+            let now = (Instant::now(), control.hashes.load(Ordering::Relaxed));
+            let rate = (now.1 - last.1) as f64 / now.0.duration_since(last.0).as_secs_f64();
+            let paused = control.paused.load(Ordering::Relaxed);
+            // Workers that stopped hashing without being paused are hung.
+            let alive = paused || now.1 > last.1;
+            last = now;
+            let best = *best.lock().unwrap_or_else(PoisonError::into_inner);
+            let mut state = format!("STATUS={}", status(best.as_ref(), rate, now.1, paused));
+            if timeout.is_some() && alive {
+                state += "\nWATCHDOG=1";
+            }
+            notify(&state);
+        }
+    });
+}
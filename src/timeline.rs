@@ -0,0 +1,97 @@
+// The best score over the run: every time it went up, with the time and the
+// hashes done so far. Charted on exit and kept in the --report-dir report, to
+// show how fast the returns diminish and help decide when to stop or add
+// hardware.
+
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use crate::deadline;
+
+// The best score going up to `score`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Point {
+    #[serde(rename = "elapsed_secs", with = "crate::schema::secs")]
+    pub elapsed: Duration,
+    // Unix time.
+    pub timestamp: u64,
+    pub hashes: u64,
+    pub score: u32,
+}
+
+pub struct Timeline {
+    started: Instant,
+    points: Mutex<Vec<Point>>,
+}
+
+impl Default for Timeline {
+    fn default() -> Self {
+        Timeline { started: Instant::now(), points: Mutex::new(Vec::new()) }
+    }
+}
+
+impl Timeline {
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    // A new best scoring `score`, after `hashes`; ties and worse are no
+    // transition.
+    pub fn record(&self, score: u32, hashes: u64) {
+        let mut points = self.points.lock().unwrap_or_else(PoisonError::into_inner);
+        if points.last().is_some_and(|last| score <= last.score) {
+            return;
+        }
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |t| t.as_secs());
+        points.push(Point { elapsed: self.started.elapsed(), timestamp, hashes, score });
+    }
+
+    pub fn points(&self) -> Vec<Point> {
+        self.points.lock().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+}
+
+// The best score as bars over `total`, scaled between the lowest and highest
+// score, `width` columns by up to `height` rows. In 24-bit color, going from
+// green to red with the score, if `rgb`, else in plain ASCII.
+pub fn chart(points: &[Point], total: Duration, width: usize, height: usize, rgb: bool) -> String {
+    let (Some(low), Some(high)) = (points.first().map(|p| p.score), points.last().map(|p| p.score)) else {
+        return String::new();
+    };
+    let height = if high == low { 1 } else { height.clamp(2, (high - low + 1) as usize) };
+    // The best at the end of each column, as a row count (0 before anything).
+    let bars: Vec<usize> = (1..=width)
+        .map(|column| {
+            let at = total.mul_f64(column as f64 / width as f64);
+            match points.iter().rev().find(|p| p.elapsed <= at) {
+                Some(_) if high == low => 1,
+                Some(p) => 1 + ((p.score - low) as f64 / (high - low) as f64 * (height - 1) as f64).round() as usize,
+                None => 0,
+            }
+        })
+        .collect();
+    let label = high.to_string().len();
+    let mut out = String::new();
+    for row in (1..=height).rev() {
+        let score = match row {
+            _ if row == height => high.to_string(),
+            1 => low.to_string(),
+            _ => String::new(),
+        };
+        out += &format!("{:>label$} |", score);
+        let level = if height == 1 { 1.0 } else { (row - 1) as f64 / (height - 1) as f64 };
+        if rgb {
+            let (red, green) = ((510.0 * level).min(255.0) as u8, (510.0 * (1.0 - level)).min(255.0) as u8);
+            out += &format!("\x1b[38;2;{};{};60m", red, green);
+        }
+        let block = if rgb { "█" } else { "#" };
+        out += bars.iter().map(|&bar| if bar >= row { block } else { " " }).collect::<String>().trim_end();
+        out += if rgb { "\x1b[0m\n" } else { "\n" };
+    }
+    out += &format!("{:>label$} +{}\n", "", "-".repeat(width));
+    let end = deadline::format_countdown(total);
+    out += &format!("{:>label$}  0s{:>pad$}\n", "", end, pad = width.saturating_sub(2));
+    out
+}
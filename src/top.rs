@@ -0,0 +1,75 @@
+// The best few results rather than only the single best, as fallbacks in case
+// the winner turns out to be unusable (say it fails on-chain validation).
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Mutex, PoisonError};
+
+use crate::mode::Mode;
+use crate::{color, persist};
+use crate::provenance::Stamped;
+use crate::Candidate;
+
+pub struct Top {
+    size: usize,
+    // Best first.
+    entries: Mutex<Vec<Candidate>>,
+    // Lowest score that still gets in, so workers can skip the lock.
+    floor: AtomicU32,
+    file: Option<PathBuf>,
+    // Print how each score adds up along with the results.
+    explain: bool,
+}
+
+impl Top {
+    // Keep the best `size` results, written to `file` whenever they change.
+    pub fn new(size: usize, file: Option<PathBuf>, explain: bool) -> Self {
+        Top { size, entries: Mutex::new(Vec::with_capacity(size + 1)), floor: AtomicU32::new(0), file, explain }
+    }
+
+    pub fn floor(&self) -> u32 {
+        self.floor.load(Ordering::Relaxed)
+    }
+
+    // The kept results, best first.
+    pub fn entries(&self) -> Vec<Candidate> {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+
+    pub fn insert(&self, candidate: Candidate) {
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        // The same result found again, say by a resumed search.
+        if entries.iter().any(|entry| entry.salt == candidate.salt || entry.address == candidate.address) {
+            return;
+        }
+        // Equal scores keep the one found first.
+        let at = entries.partition_point(|entry| entry.score >= candidate.score);
+        if at == self.size {
+            return;
+        }
+        entries.insert(at, candidate);
+        entries.truncate(self.size);
+        if entries.len() == self.size {
+            self.floor.store(entries[self.size - 1].score + 1, Ordering::Relaxed);
+        }
+        if let Some(file) = &self.file {
+            let stamped: Vec<Stamped> = entries.iter().copied().map(Stamped::from).collect();
+            let json = serde_json::to_string_pretty(&stamped).unwrap();
+            if let Err(e) = persist::write(file, json + "\n") {
+                eprintln!("Cannot write results to {}: {}", file.display(), e);
+            }
+        }
+    }
+
+    // Print the kept results, found in `mode`, before the miner exits.
+    pub fn dump(&self, mode: Mode) {
+        let entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        println!("Best {} results:", entries.len());
+        for (rank, c) in entries.iter().enumerate() {
+            println!("{:>3}. {} with score: {}, {}", rank + 1, color::address(&c.address), c.score, mode.describe(&c.salt));
+            if self.explain {
+                println!("     {}", crate::explain_score(&c.address));
+            }
+        }
+    }
+}
@@ -0,0 +1,60 @@
+// Side objectives tracked alongside the main one, each with its own best, so
+// a single run also yields e.g. the address with the most leading zeros.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Mutex, PoisonError};
+
+use crate::eoa;
+use crate::error::MinerError;
+use crate::mode::Mode;
+use crate::score::{Objective, Scorer};
+
+struct Tracked {
+    objective: Objective,
+    scorer: Box<dyn Scorer>,
+    // Best value so far, checked without locking before taking `lock`.
+    best: AtomicU32,
+    lock: Mutex<()>,
+}
+
+pub struct Tracker {
+    tracked: Vec<Tracked>,
+}
+
+impl Tracker {
+    pub fn new(objectives: &[Objective], hook_flags: Option<u16>) -> Result<Self, MinerError> {
+        let mut tracked = Vec::new();
+        for objective in objectives {
+            tracked.push(Tracked {
+                objective: *objective,
+                scorer: objective.scorer(hook_flags)?,
+                best: AtomicU32::new(0),
+                lock: Mutex::new(()),
+            });
+        }
+        Ok(Tracker { tracked })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tracked.is_empty()
+    }
+
+    pub fn observe(&self, mode: Mode, salt: &[u8; 32], address: &[u8; 20]) {
+        for tracked in &self.tracked {
+            let value = tracked.scorer.score(address);
+            if value <= tracked.best.load(Ordering::Relaxed) {
+                continue;
+            }
+            let _guard = tracked.lock.lock().unwrap_or_else(PoisonError::into_inner);
+            if value > tracked.best.load(Ordering::Relaxed) {
+                tracked.best.store(value, Ordering::Relaxed);
+                // Keys are saved as they are reported.
+                let reproduce = match mode {
+                    Mode::Eoa => eoa::save(salt),
+                    mode => mode.describe(salt),
+                };
+                println!("Tracked best: 0x{} with {}, {}", hex::encode(address), tracked.objective.describe(value), reproduce);
+            }
+        }
+    }
+}
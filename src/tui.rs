@@ -0,0 +1,462 @@
+// The --tui dashboard, compiled in with the `tui` feature: the hashrate over
+// time, each thread's rate, the best candidate, the --top results (or the
+// bests found so far), elapsed time, the expected wait for a better score and
+// the CPU temperature, redrawn a few times a second in place of the stream of
+// status lines.
+//
+// On unix, stdout and stderr go to a pipe while it is up, so what the rest of
+// the miner prints lands in its log pane instead of over the drawing. The
+// lines are printed again once it closes.
+
+use std::collections::VecDeque;
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, Once, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use ratatui::backend::{Backend, CrosstermBackend};
+use ratatui::crossterm::event::{self, Event as TermEvent, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::crossterm::terminal::{self as term, EnterAlternateScreen, LeaveAlternateScreen};
+use ratatui::crossterm::{cursor, execute};
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Bar, BarChart, BarGroup, Block, Paragraph, Row, Sparkline, Table};
+use ratatui::{Frame, Terminal};
+
+use crate::hooks::{Event, EventBus};
+use crate::mode::Mode;
+use crate::search::Control;
+use crate::top::Top;
+use crate::{deadline, odds, Candidate};
+
+// How often the dashboard is redrawn and the keyboard looked at.
+const FRAME: Duration = Duration::from_millis(250);
+// Hashrate samples kept for the sparkline, one a second.
+const HISTORY: usize = 600;
+// Printed lines kept for the log pane and printed again on exit.
+const LOG_LINES: usize = 1000;
+// New bests listed when there is no --top.
+const BESTS: usize = 20;
+
+// Whether the dashboard has the terminal; cleared by whoever restores it first.
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+static LOG: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+static BESTS_FOUND: Mutex<VecDeque<Candidate>> = Mutex::new(VecDeque::new());
+
+// What the dashboard shows.
+pub struct View {
+    pub mode: Mode,
+    pub threads: usize,
+    pub control: Arc<Control>,
+    pub best: Arc<Mutex<Option<Candidate>>>,
+    pub top: Option<Arc<Top>>,
+    pub target: Arc<AtomicU32>,
+    // Scores follow the challenge's rules, so the wait for a better one is known.
+    pub odds: bool,
+}
+
+pub struct Dashboard {
+    done: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Dashboard {
+    // Give the terminal back and print what was logged meanwhile.
+    pub fn finish(mut self) {
+        self.done.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            thread.join().ok();
+        }
+        restore();
+    }
+}
+
+// Take over the terminal until `finish`, a panic or the process exiting.
+// q, Esc or Ctrl-C stop the search and p pauses it.
+pub fn start(view: View, events: &EventBus) -> Result<Dashboard, String> {
+    if !io::stdout().is_terminal() {
+        return Err("--tui needs a terminal, drop it when redirecting the output".to_string());
+    }
+    events.subscribe(|event| {
+        if let Event::NewBest(c) = event {
+            let mut bests = BESTS_FOUND.lock().unwrap_or_else(PoisonError::into_inner);
+            bests.push_front(**c);
+            bests.truncate(BESTS);
+        }
+    });
+    let screen = capture().map_err(|e| format!("cannot capture the output for --tui: {}", e))?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(screen)).map_err(|e| format!("cannot start --tui: {}", e))?;
+    term::enable_raw_mode().map_err(|e| format!("cannot start --tui: {}", e))?;
+    execute!(terminal.backend_mut(), EnterAlternateScreen, cursor::Hide).ok();
+    ACTIVE.store(true, Ordering::SeqCst);
+    restore_on_exit();
+
+    let done = Arc::new(AtomicBool::new(false));
+    let stop = Arc::clone(&done);
+    let thread = thread::Builder::new()
+        .name("tui".to_string())
+        .spawn(move || {
+            let mut dashboard = State::new(view);
+            while !stop.load(Ordering::Relaxed) && ACTIVE.load(Ordering::SeqCst) {
+                dashboard.sample();
+                // Without the pipe, stray lines are painted over on every frame.
+                if !cfg!(unix) {
+                    terminal.current_buffer_mut().reset();
+                }
+                if terminal.draw(|frame| dashboard.draw(frame)).is_err() {
+                    break;
+                }
+                dashboard.handle_keys();
+            }
+        })
+        .map_err(|e| format!("cannot start --tui: {}", e))?;
+    Ok(Dashboard { done, thread: Some(thread) })
+}
+
+// Draw `view` as the dashboard's first frame does, before any rate has been
+// measured, on any ratatui backend.
+pub fn render<B: Backend>(view: View, terminal: &mut Terminal<B>) -> io::Result<()> {
+    let state = State::new(view);
+    terminal.draw(|frame| state.draw(frame)).map(|_| ())
+}
+
+// Where the dashboard stands between frames.
+struct State {
+    view: View,
+    started: Instant,
+    sampled: Instant,
+    hashes: u64,
+    taken: Vec<u64>,
+    // Hashes a second, oldest first.
+    history: VecDeque<u64>,
+    rate: f64,
+    thread_rates: Vec<f64>,
+    temperature: Option<f64>,
+}
+
+impl State {
+    fn new(view: View) -> Self {
+        let now = Instant::now();
+        State {
+            view,
+            started: now,
+            sampled: now,
+            hashes: 0,
+            taken: Vec::new(),
+            history: VecDeque::with_capacity(HISTORY),
+            rate: 0.0,
+            thread_rates: Vec::new(),
+            temperature: cpu_temperature(),
+        }
+    }
+
+    // Measure the rates once a second.
+    fn sample(&mut self) {
+        let elapsed = self.sampled.elapsed().as_secs_f64();
+        if elapsed < 1.0 {
+            return;
+        }
+        self.sampled = Instant::now();
+        let hashes = self.view.control.hashes.load(Ordering::Relaxed);
+        self.rate = (hashes - self.hashes) as f64 / elapsed;
+        self.hashes = hashes;
+        if self.history.len() == HISTORY {
+            self.history.pop_front();
+        }
+        self.history.push_back(self.rate as u64);
+        let taken = self.view.control.taken.lock().unwrap_or_else(PoisonError::into_inner).clone();
+        if taken.len() == self.taken.len() {
+            self.thread_rates = taken.iter().zip(&self.taken).map(|(now, then)| (now - then) as f64 / elapsed).collect();
+        }
+        self.taken = taken;
+        self.temperature = cpu_temperature();
+    }
+
+    fn handle_keys(&self) {
+        let Ok(true) = event::poll(FRAME) else { return };
+        let Ok(TermEvent::Key(key)) = event::read() else { return };
+        if key.kind != KeyEventKind::Press {
+            return;
+        }
+        let control = &self.view.control;
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => control.stopped.store(true, Ordering::Relaxed),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => control.stopped.store(true, Ordering::Relaxed),
+            KeyCode::Char('p') => {
+                control.paused.fetch_xor(true, Ordering::Relaxed);
+            }
+            _ => {}
+        }
+    }
+
+    // How to reproduce a candidate; keys were saved when they were reported.
+    fn reproduce(&self, c: &Candidate) -> String {
+        self.view.mode.describe(&c.salt)
+    }
+
+    // Expected time until something beats the target at the current rate.
+    fn eta(&self) -> String {
+        if !self.view.odds {
+            return "n/a".to_string();
+        }
+        odds::format_eta(self.view.target.load(Ordering::Relaxed), self.rate)
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let stopping = self.view.control.stopped.load(Ordering::Relaxed);
+        let paused = self.view.control.paused.load(Ordering::Relaxed);
+        let [title, top_row, best, middle, log] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(8),
+            Constraint::Length(5),
+            Constraint::Min(6),
+            Constraint::Length(8),
+        ])
+        .areas(frame.area());
+
+        let state = match (stopping, paused) {
+            (true, _) => " stopping".red().bold(),
+            (false, true) => " paused".yellow().bold(),
+            (false, false) => "".into(),
+        };
+        let heading = format!(" uniswapv4-challenge-miner, {} threads, {:?} ", self.view.threads, self.view.mode).to_lowercase();
+        frame.render_widget(Line::from(vec![heading.bold(), state, "   q stop, p pause".dark_gray()]), title);
+
+        let [rate, stats] = Layout::horizontal([Constraint::Min(20), Constraint::Length(36)]).areas(top_row);
+        let history: Vec<u64> = self.history.iter().skip(self.history.len().saturating_sub(rate.width.saturating_sub(2) as usize)).copied().collect();
+        let sparkline = Sparkline::default()
+            .block(Block::bordered().title(format!(" Hashrate {} ", odds::format_rate(self.rate))))
+            .data(&history)
+            .style(Style::default().fg(Color::Cyan));
+        frame.render_widget(sparkline, rate);
+        let temperature = self.temperature.map_or("n/a".to_string(), |celsius| format!("{:.0}°C", celsius));
+        let deadline = deadline::time_left().map_or("n/a".to_string(), deadline::format_countdown);
+        let stats_lines = vec![
+            Line::from(format!("Elapsed   {}", deadline::format_countdown(self.started.elapsed()))),
+            Line::from(format!("Hashes    {}", odds::format_count(self.hashes as f64))),
+            Line::from(format!("Target    {}", self.view.target.load(Ordering::Relaxed))),
+            Line::from(format!("Next best {}", self.eta())),
+            Line::from(format!("Deadline  {}", deadline)),
+            Line::from(format!("CPU       {}", temperature)),
+        ];
+        frame.render_widget(Paragraph::new(stats_lines).block(Block::bordered().title(" Progress ")), stats);
+
+        let best_lines = match *self.view.best.lock().unwrap_or_else(PoisonError::into_inner) {
+            Some(c) => {
+                let score = match self.view.odds {
+                    true => format!("{} ({})", c.score, odds::rarity(c.score)),
+                    false => c.score.to_string(),
+                };
+                vec![
+                    Line::from(vec!["Address ".into(), format!("0x{}", hex::encode(c.address)).green().bold()]),
+                    Line::from(format!("Score   {}", score)),
+                    Line::from(self.reproduce(&c)),
+                ]
+            }
+            None => vec![Line::from("Nothing found yet".dark_gray())],
+        };
+        frame.render_widget(Paragraph::new(best_lines).block(Block::bordered().title(" Best ")), best);
+
+        let [threads, results] = Layout::horizontal([Constraint::Length(28), Constraint::Min(40)]).areas(middle);
+        let bars: Vec<Bar> = self
+            .thread_rates
+            .iter()
+            .enumerate()
+            .map(|(i, rate)| Bar::default().value(*rate as u64).label(Line::from(i.to_string())).text_value(odds::format_rate(*rate)))
+            .collect();
+        let chart = BarChart::default()
+            .block(Block::bordered().title(" Threads "))
+            .direction(Direction::Horizontal)
+            .bar_width(1)
+            .bar_gap(0)
+            .bar_style(Style::default().fg(Color::Blue))
+            .data(BarGroup::default().bars(&bars));
+        frame.render_widget(chart, threads);
+
+        let (title, entries) = match &self.view.top {
+            Some(top) => (" Top results ", top.entries()),
+            None => (" Bests found ", BESTS_FOUND.lock().unwrap_or_else(PoisonError::into_inner).iter().copied().collect()),
+        };
+        let rows = entries.iter().enumerate().map(|(rank, c)| {
+            Row::new(vec![format!("{:>3}.", rank + 1), c.score.to_string(), format!("0x{}", hex::encode(c.address)), self.reproduce(c)])
+        });
+        let table = Table::new(rows, [Constraint::Length(4), Constraint::Length(6), Constraint::Length(42), Constraint::Fill(1)])
+            .header(Row::new(["", "Score", "Address", "Reproduce with"]).bold())
+            .block(Block::bordered().title(title));
+        frame.render_widget(table, results);
+
+        let lines = LOG.lock().unwrap_or_else(PoisonError::into_inner);
+        let shown = lines.iter().skip(lines.len().saturating_sub(log.height.saturating_sub(2) as usize)).map(|line| Line::from(line.as_str()));
+        frame.render_widget(Paragraph::new(shown.collect::<Vec<_>>()).block(Block::bordered().title(" Log ")), log);
+    }
+}
+
+// The hottest CPU sensor in °C, on Linux.
+fn cpu_temperature() -> Option<f64> {
+    let mut hottest: Option<f64> = None;
+    for sensor in fs::read_dir("/sys/class/hwmon").ok()?.flatten() {
+        let name = fs::read_to_string(sensor.path().join("name")).unwrap_or_default();
+        if !matches!(name.trim(), "coretemp" | "k10temp" | "zenpower" | "cpu_thermal" | "soc_thermal") {
+            continue;
+        }
+        for reading in fs::read_dir(sensor.path()).into_iter().flatten().flatten() {
+            let file = reading.file_name();
+            let file = file.to_string_lossy();
+            if !(file.starts_with("temp") && file.ends_with("_input")) {
+                continue;
+            }
+            if let Some(millicelsius) = fs::read_to_string(reading.path()).ok().and_then(|s| s.trim().parse::<f64>().ok()) {
+                hottest = Some(hottest.map_or(millicelsius, |h| h.max(millicelsius)));
+            }
+        }
+    }
+    hottest.map(|millicelsius| millicelsius / 1000.0)
+}
+
+// Put the terminal back the way it was, once, and print the logged lines.
+fn restore() {
+    if !ACTIVE.swap(false, Ordering::SeqCst) {
+        return;
+    }
+    release();
+    term::disable_raw_mode().ok();
+    let mut stdout = io::stdout();
+    execute!(stdout, LeaveAlternateScreen, cursor::Show).ok();
+    let lines = std::mem::take(&mut *LOG.lock().unwrap_or_else(PoisonError::into_inner));
+    for line in lines {
+        writeln!(stdout, "{}", line).ok();
+    }
+}
+
+// Restore the terminal before a panic message or std::process::exit, say when
+// the competition deadline passes.
+fn restore_on_exit() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let report = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            restore();
+            report(info);
+        }));
+        #[cfg(unix)]
+        {
+            extern "C" fn at_exit() {
+                restore();
+            }
+            // SAFETY: registers a plain function, run once by exit().
+            unsafe { libc::atexit(at_exit) };
+        }
+    });
+}
+
+#[cfg(unix)]
+mod pipe {
+    use std::fs::File;
+    use std::io::{self, BufRead, BufReader};
+    use std::os::fd::FromRawFd;
+    use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+    use std::sync::PoisonError;
+    use std::time::{Duration, Instant};
+
+    // The real stdout and stderr while they point at the pipe.
+    static SAVED: [AtomicI32; 2] = [AtomicI32::new(-1), AtomicI32::new(-1)];
+    // The pipe has been read to the end.
+    static DRAINED: AtomicBool = AtomicBool::new(false);
+    const STREAMS: [i32; 2] = [libc::STDOUT_FILENO, libc::STDERR_FILENO];
+
+    fn log(line: String) {
+        let mut lines = super::LOG.lock().unwrap_or_else(PoisonError::into_inner);
+        if lines.len() == super::LOG_LINES {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+
+    // Point stdout and stderr at a pipe read into the log, and return the real
+    // stdout to draw on. On failure, whatever was redirected is put back.
+    pub fn capture() -> io::Result<File> {
+        let mut fds = [0; 2];
+        // SAFETY: plain descriptor calls, each result checked before it is
+        // used; the descriptors handed to File are fresh and owned by nothing
+        // else.
+        unsafe {
+            if libc::pipe(fds.as_mut_ptr()) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            for (saved, stream) in SAVED.iter().zip(STREAMS) {
+                let copy = libc::dup(stream);
+                if copy < 0 || libc::dup2(fds[1], stream) < 0 {
+                    let error = io::Error::last_os_error();
+                    if copy >= 0 {
+                        libc::close(copy);
+                    }
+                    put_back();
+                    libc::close(fds[0]);
+                    libc::close(fds[1]);
+                    return Err(error);
+                }
+                saved.store(copy, Ordering::SeqCst);
+            }
+            libc::close(fds[1]);
+            // Dropped with the closure if the thread cannot start.
+            let reader = BufReader::new(File::from_raw_fd(fds[0]));
+            let drain = std::thread::Builder::new().name("tui-log".to_string()).spawn(move || {
+                for line in reader.lines().map_while(Result::ok) {
+                    log(line);
+                }
+                DRAINED.store(true, Ordering::SeqCst);
+            });
+            if let Err(e) = drain {
+                put_back();
+                return Err(e);
+            }
+            let screen = libc::dup(SAVED[0].load(Ordering::SeqCst));
+            if screen < 0 {
+                let error = io::Error::last_os_error();
+                put_back();
+                return Err(error);
+            }
+            Ok(File::from_raw_fd(screen))
+        }
+    }
+
+    // Point whichever of stdout and stderr were redirected back at the
+    // terminal.
+    fn put_back() {
+        for (saved, stream) in SAVED.iter().zip(STREAMS) {
+            let copy = saved.swap(-1, Ordering::SeqCst);
+            if copy >= 0 {
+                // SAFETY: `copy` is the descriptor saved by capture and used by nothing else.
+                unsafe {
+                    libc::dup2(copy, stream);
+                    libc::close(copy);
+                }
+            }
+        }
+    }
+
+    // Point stdout and stderr back at the terminal and wait, briefly, for the
+    // rest of the pipe to be read.
+    pub fn release() {
+        io::Write::flush(&mut io::stdout()).ok();
+        put_back();
+        let give_up = Instant::now() + Duration::from_secs(1);
+        while !DRAINED.load(Ordering::SeqCst) && Instant::now() < give_up {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
+
+#[cfg(unix)]
+use pipe::{capture, release};
+
+#[cfg(not(unix))]
+fn capture() -> io::Result<io::Stdout> {
+    Ok(io::stdout())
+}
+
+#[cfg(not(unix))]
+fn release() {}
@@ -0,0 +1,177 @@
+#[cfg(any(feature = "ledger", feature = "trezor"))]
+use k256::ecdsa::{RecoveryId, Signature};
+use k256::ecdsa::{SigningKey, VerifyingKey};
+
+use crate::keccak256;
+
+// Ethereum address controlled by a secp256k1 signing key.
+pub fn key_address(key: &SigningKey) -> [u8; 20] {
+    public_key_address(key.verifying_key())
+}
+
+pub fn public_key_address(key: &VerifyingKey) -> [u8; 20] {
+    let point = key.to_encoded_point(false);
+    let hash = keccak256(&point.as_bytes()[1..]);
+    let mut address = [0; 20];
+    address.copy_from_slice(&hash[12..]);
+    address
+}
+
+// Hash signed by `personal_sign` (EIP-191 version 0x45).
+pub fn eip191_hash(message: &[u8]) -> [u8; 32] {
+    let mut data = format!("\x19Ethereum Signed Message:\n{}", message.len()).into_bytes();
+    data.extend_from_slice(message);
+    keccak256(&data).0
+}
+
+// 65-byte r || s || v signature, with v as 27 or 28 like `personal_sign` returns.
+pub fn encode_signature(y_odd: bool, r: &[u8], s: &[u8]) -> [u8; 65] {
+    let mut signature = [0; 65];
+    signature[..32].copy_from_slice(r);
+    signature[32..64].copy_from_slice(s);
+    signature[64] = 27 + y_odd as u8;
+    signature
+}
+
+pub fn sign_message(key: &SigningKey, message: &[u8]) -> Result<[u8; 65], String> {
+    let (signature, recovery_id) = key
+        .sign_prehash_recoverable(&eip191_hash(message))
+        .map_err(|e| format!("signing failed: {}", e))?;
+    let (r, s) = signature.split_bytes();
+    Ok(encode_signature(recovery_id.is_y_odd(), &r, &s))
+}
+
+// Work out the y parity of an (r, s) signature by recovering the signer.
+// Hardware wallets report `v` in different conventions, so this is the
+// reliable way to get it right.
+#[cfg(any(feature = "ledger", feature = "trezor"))]
+pub fn recover_parity(hash: &[u8; 32], r: &[u8; 32], s: &[u8; 32], signer: &[u8; 20]) -> Result<bool, String> {
+    let signature = Signature::from_scalars(*r, *s).map_err(|e| format!("malformed signature: {}", e))?;
+    for y_odd in [false, true] {
+        let recovery_id = RecoveryId::new(y_odd, false);
+        if let Ok(key) = VerifyingKey::recover_from_prehash(hash, &signature, recovery_id) {
+            if public_key_address(&key) == *signer {
+                return Ok(y_odd);
+            }
+        }
+    }
+    Err(format!("signature does not recover to 0x{}", hex::encode(signer)))
+}
+
+// EIP-1559 (type 2) transaction without an access list.
+#[derive(Clone)]
+pub struct Eip1559Tx {
+    pub chain_id: u64,
+    pub nonce: u64,
+    pub max_priority_fee_per_gas: u128,
+    pub max_fee_per_gas: u128,
+    pub gas_limit: u64,
+    pub to: [u8; 20],
+    pub value: u128,
+    pub data: Vec<u8>,
+}
+
+impl Eip1559Tx {
+    fn rlp_fields(&self) -> Vec<Vec<u8>> {
+        vec![
+            rlp_uint(self.chain_id as u128),
+            rlp_uint(self.nonce as u128),
+            rlp_uint(self.max_priority_fee_per_gas),
+            rlp_uint(self.max_fee_per_gas),
+            rlp_uint(self.gas_limit as u128),
+            rlp_bytes(&self.to),
+            rlp_uint(self.value),
+            rlp_bytes(&self.data),
+            rlp_list(&[]),
+        ]
+    }
+
+    // Unsigned payload, 0x02 || rlp(fields), as sent to hardware wallets.
+    pub fn unsigned_payload(&self) -> Vec<u8> {
+        let mut payload = vec![0x02];
+        payload.extend(rlp_list(&self.rlp_fields()));
+        payload
+    }
+
+    // Hash the transaction is signed over.
+    pub fn signing_hash(&self) -> [u8; 32] {
+        keccak256(self.unsigned_payload()).0
+    }
+
+    // Raw bytes for eth_sendRawTransaction given a signature over `signing_hash`.
+    pub fn encode_signed(&self, y_odd: bool, r: &[u8], s: &[u8]) -> Vec<u8> {
+        let mut fields = self.rlp_fields();
+        fields.push(rlp_uint(y_odd as u128));
+        fields.push(rlp_uint_bytes(r));
+        fields.push(rlp_uint_bytes(s));
+
+        let mut raw = vec![0x02];
+        raw.extend(rlp_list(&fields));
+        raw
+    }
+
+    pub fn sign(&self, key: &SigningKey) -> Result<Vec<u8>, String> {
+        let (signature, recovery_id) = key
+            .sign_prehash_recoverable(&self.signing_hash())
+            .map_err(|e| format!("signing failed: {}", e))?;
+        let (r, s) = signature.split_bytes();
+        Ok(self.encode_signed(recovery_id.is_y_odd(), &r, &s))
+    }
+}
+
+fn rlp_length_prefix(len: usize, short_offset: u8) -> Vec<u8> {
+    if len < 56 {
+        vec![short_offset + len as u8]
+    } else {
+        let len_bytes = len.to_be_bytes();
+        let skip = len_bytes.iter().take_while(|b| **b == 0).count();
+        let mut prefix = vec![short_offset + 55 + (len_bytes.len() - skip) as u8];
+        prefix.extend_from_slice(&len_bytes[skip..]);
+        prefix
+    }
+}
+
+pub fn rlp_bytes(bytes: &[u8]) -> Vec<u8> {
+    if bytes.len() == 1 && bytes[0] < 0x80 {
+        return bytes.to_vec();
+    }
+    let mut out = rlp_length_prefix(bytes.len(), 0x80);
+    out.extend_from_slice(bytes);
+    out
+}
+
+// Big-endian integer with leading zeros stripped, as RLP requires.
+pub fn rlp_uint_bytes(bytes: &[u8]) -> Vec<u8> {
+    let skip = bytes.iter().take_while(|b| **b == 0).count();
+    rlp_bytes(&bytes[skip..])
+}
+
+pub fn rlp_uint(value: u128) -> Vec<u8> {
+    rlp_uint_bytes(&value.to_be_bytes())
+}
+
+pub fn rlp_list(items: &[Vec<u8>]) -> Vec<u8> {
+    let payload: Vec<u8> = items.concat();
+    let mut out = rlp_length_prefix(payload.len(), 0xC0);
+    out.extend(payload);
+    out
+}
+
+// EIP-55 mixed-case hex digits of an address, without 0x.
+pub fn checksum_to_slice(address: &[u8; 20], out: &mut [u8; 40]) {
+    hex::encode_to_slice(address, out).unwrap();
+    let hash = keccak256(&out[..]);
+    for (i, c) in out.iter_mut().enumerate() {
+        let nibble = (hash[i / 2] >> (if i % 2 == 0 { 4 } else { 0 })) & 0x0F;
+        if nibble >= 8 {
+            c.make_ascii_uppercase();
+        }
+    }
+}
+
+// EIP-55 mixed-case checksum encoding of an address.
+pub fn to_checksum(address: &[u8; 20]) -> String {
+    let mut out = [0; 40];
+    checksum_to_slice(address, &mut out);
+    format!("0x{}", std::str::from_utf8(&out).unwrap())
+}
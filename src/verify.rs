@@ -0,0 +1,71 @@
+use std::sync::mpsc::{self, Sender};
+
+use crate::challenge::{self, Simulation};
+use crate::compute_score;
+use crate::rpc::RpcClient;
+use crate::runtime;
+use crate::Candidate;
+
+// Where the canonical score comes from.
+pub enum ScoreOracle {
+    // A deployed contract exposing `score(address)`.
+    Library([u8; 20]),
+    // Simulated `updateBestAddress` calls, whose WorseAddress revert carries
+    // the contract's score. Candidates that would take the lead come back
+    // without a score.
+    Challenge { contract: [u8; 20], sender: [u8; 20] },
+}
+
+pub enum Verdict {
+    Match { address: [u8; 20] },
+    Divergence { address: [u8; 20], local: u32, canonical: u32 },
+    // The simulated submission succeeded, so no score was reported.
+    WouldLead,
+}
+
+fn compare(address: [u8; 20], local: u32, canonical: u32) -> Verdict {
+    if local == canonical {
+        Verdict::Match { address }
+    } else {
+        Verdict::Divergence { address, local, canonical }
+    }
+}
+
+pub fn check(rpc: &RpcClient, oracle: &ScoreOracle, candidate: &Candidate) -> Result<Verdict, String> {
+    match oracle {
+        ScoreOracle::Library(scorer) => {
+            let canonical = challenge::library_score(rpc, scorer, &candidate.address)?;
+            Ok(compare(candidate.address, candidate.score, canonical))
+        }
+        ScoreOracle::Challenge { contract, sender } => match challenge::simulate_update(rpc, contract, sender, &candidate.salt)? {
+            Simulation::WorseAddress { address, score } => Ok(compare(address, compute_score(&address), score)),
+            Simulation::Accepted => Ok(Verdict::WouldLead),
+            other => Err(format!("cannot simulate submission: {:?}", other)),
+        },
+    }
+}
+
+// Check every candidate sent to it off the hashing threads, so RPC latency
+// never stalls the workers. Ends once the sender is dropped.
+pub fn spawn(rpc: RpcClient, oracle: ScoreOracle) -> Sender<Candidate> {
+    let (sender, receiver) = mpsc::channel::<Candidate>();
+    runtime::spawn_blocking("score verification", move |_| {
+        for candidate in receiver {
+            match check(&rpc, &oracle, &candidate) {
+                Ok(Verdict::Match { address }) => println!("Score of 0x{} verified on-chain", hex::encode(address)),
+                Ok(Verdict::Divergence { address, local, canonical }) => eprintln!(
+                    "WARNING: score divergence: 0x{} scores {} locally but {} on-chain",
+                    hex::encode(address),
+                    local,
+                    canonical
+                ),
+                Ok(Verdict::WouldLead) => println!(
+                    "Salt 0x{} would take the lead on-chain (score not reported by the contract)",
+                    hex::encode(candidate.salt)
+                ),
+                Err(e) => eprintln!("Warning: score verification: {}", e),
+            }
+        }
+    });
+    sender
+}
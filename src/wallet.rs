@@ -0,0 +1,140 @@
+use std::path::PathBuf;
+
+use clap::{ArgGroup, Args};
+use k256::ecdsa::SigningKey;
+use zeroize::Zeroizing;
+
+#[cfg(feature = "ledger")]
+use crate::hardware::ledger;
+#[cfg(feature = "trezor")]
+use crate::hardware::trezor;
+#[cfg(any(feature = "ledger", feature = "trezor"))]
+use crate::hardware::parse_hd_path;
+use crate::tx::{key_address, sign_message, Eip1559Tx};
+
+/// Where the submitter's signing key comes from
+#[derive(Args, Debug)]
+#[group(skip)]
+#[command(group(
+    ArgGroup::new("key_source")
+        .args(["private_key", "keystore", "ledger", "trezor"])
+))]
+pub struct WalletArgs {
+    /// Private key of the submitter (hex)
+    #[arg(long, env = "PRIVATE_KEY", hide_env_values = true)]
+    pub private_key: Option<String>,
+
+    /// Encrypted JSON keystore (Web3 Secret Storage) holding the submitter key
+    #[arg(long, value_name = "PATH")]
+    pub keystore: Option<PathBuf>,
+
+    /// Environment variable holding the keystore passphrase (prompted for if unset)
+    #[arg(long, value_name = "VAR", default_value = "KEYSTORE_PASSWORD")]
+    pub password_env: String,
+
+    /// Sign with a Ledger running the Ethereum app (needs the `ledger` feature)
+    #[arg(long)]
+    pub ledger: bool,
+
+    /// Sign with a Trezor (needs the `trezor` feature)
+    #[arg(long)]
+    pub trezor: bool,
+
+    /// BIP-32 derivation path of the hardware wallet account
+    #[arg(long, value_name = "PATH", default_value = "m/44'/60'/0'/0/0")]
+    pub hd_path: String,
+}
+
+// Holds the key or the connection to the device that signs submissions.
+pub enum Signer {
+    Local(SigningKey),
+    #[cfg(feature = "ledger")]
+    Ledger(Box<ledger::Ledger>),
+    #[cfg(feature = "trezor")]
+    Trezor(Box<trezor::Trezor>),
+}
+
+impl Signer {
+    pub fn address(&self) -> [u8; 20] {
+        match self {
+            Signer::Local(key) => key_address(key),
+            #[cfg(feature = "ledger")]
+            Signer::Ledger(ledger) => ledger.address(),
+            #[cfg(feature = "trezor")]
+            Signer::Trezor(trezor) => trezor.address(),
+        }
+    }
+
+    pub fn is_hardware(&self) -> bool {
+        !matches!(self, Signer::Local(_))
+    }
+
+    pub fn sign_transaction(&mut self, tx: &Eip1559Tx) -> Result<Vec<u8>, String> {
+        match self {
+            Signer::Local(key) => tx.sign(key),
+            #[cfg(feature = "ledger")]
+            Signer::Ledger(ledger) => ledger.sign_transaction(tx),
+            #[cfg(feature = "trezor")]
+            Signer::Trezor(trezor) => trezor.sign_transaction(tx),
+        }
+    }
+
+    // EIP-191 `personal_sign` signature over `message`.
+    pub fn sign_message(&mut self, message: &[u8]) -> Result<[u8; 65], String> {
+        match self {
+            Signer::Local(key) => sign_message(key, message),
+            #[cfg(feature = "ledger")]
+            Signer::Ledger(ledger) => ledger.sign_message(message),
+            #[cfg(feature = "trezor")]
+            Signer::Trezor(trezor) => trezor.sign_message(message),
+        }
+    }
+}
+
+impl WalletArgs {
+    pub fn signer(&mut self) -> Result<Signer, String> {
+        if self.ledger {
+            #[cfg(feature = "ledger")]
+            return ledger::Ledger::connect(&parse_hd_path(&self.hd_path)?).map(|l| Signer::Ledger(Box::new(l)));
+            #[cfg(not(feature = "ledger"))]
+            return Err("built without Ledger support, rebuild with --features ledger".to_string());
+        }
+        if self.trezor {
+            #[cfg(feature = "trezor")]
+            return trezor::Trezor::connect(&parse_hd_path(&self.hd_path)?).map(|t| Signer::Trezor(Box::new(t)));
+            #[cfg(not(feature = "trezor"))]
+            return Err("built without Trezor support, rebuild with --features trezor".to_string());
+        }
+        self.signing_key().map(Signer::Local)
+    }
+
+    // Load the signing key. Intermediate key material is wiped on drop, as is
+    // the returned key itself.
+    pub fn signing_key(&mut self) -> Result<SigningKey, String> {
+        let private_key = self.private_key.take().map(Zeroizing::new);
+        let secret: Zeroizing<Vec<u8>> = match (private_key, &self.keystore) {
+            (Some(key), _) => Zeroizing::new(
+                hex::decode(key.trim().trim_start_matches("0x"))
+                    .map_err(|e| format!("invalid private key: {}", e))?,
+            ),
+            (None, Some(path)) => {
+                let password = self.password(path)?;
+                Zeroizing::new(
+                    eth_keystore::decrypt_key(path, password.as_bytes())
+                        .map_err(|e| format!("cannot decrypt keystore {}: {}", path.display(), e))?,
+                )
+            }
+            (None, None) => return Err("no signing key given".to_string()),
+        };
+        SigningKey::from_slice(&secret).map_err(|e| format!("invalid private key: {}", e))
+    }
+
+    fn password(&self, path: &std::path::Path) -> Result<Zeroizing<String>, String> {
+        if let Ok(password) = std::env::var(&self.password_env) {
+            return Ok(Zeroizing::new(password));
+        }
+        rpassword::prompt_password(format!("Passphrase for {}: ", path.display()))
+            .map(Zeroizing::new)
+            .map_err(|e| format!("cannot read passphrase: {}", e))
+    }
+}
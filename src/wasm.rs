@@ -0,0 +1,87 @@
+// Scoring by a WebAssembly module, compiled in with the `wasm` feature.
+//
+// The module exports its `memory`, `alloc(len) -> ptr` (called once per
+// instance for the buffer the address is written to) and `score(ptr, len) ->
+// u32`. It gets no imports, so it cannot touch anything outside its own
+// memory, and every call runs on a fixed amount of fuel.
+
+use std::cell::RefCell;
+use std::path::Path;
+
+use thread_local::ThreadLocal;
+use wasmtime::{Config, Engine, Linker, Memory, Module, Store, StoreLimits, StoreLimitsBuilder, TypedFunc};
+
+use crate::error::MinerError;
+use crate::score::Scorer;
+
+// Fuel (roughly wasm instructions) a single score() call may use.
+const FUEL_PER_CALL: u64 = 1_000_000;
+const MAX_MEMORY: usize = 16 << 20;
+
+struct Instance {
+    store: Store<StoreLimits>,
+    memory: Memory,
+    score: TypedFunc<(i32, i32), i32>,
+    ptr: i32,
+}
+
+pub struct WasmScorer {
+    engine: Engine,
+    module: Module,
+    // Stores are single-threaded, so every worker gets its own instance.
+    instances: ThreadLocal<RefCell<Instance>>,
+}
+
+impl WasmScorer {
+    pub fn load(path: &Path) -> Result<Self, MinerError> {
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config).map_err(|e| MinerError::Config(format!("{:#}", e)))?;
+        let module = Module::from_file(&engine, path).map_err(|e| MinerError::Config(format!("cannot load {}: {:#}", path.display(), e)))?;
+        let scorer = WasmScorer { engine, module, instances: ThreadLocal::new() };
+        let mut instance = scorer.instantiate().map_err(|e| MinerError::Config(format!("{}: {}", path.display(), e)))?;
+        call(&mut instance, &[0; 20]).map_err(|e| MinerError::Config(format!("{}: score(): {}", path.display(), e)))?;
+        Ok(scorer)
+    }
+
+    fn instantiate(&self) -> Result<Instance, String> {
+        let limits = StoreLimitsBuilder::new().memory_size(MAX_MEMORY).instances(1).build();
+        let mut store = Store::new(&self.engine, limits);
+        store.limiter(|limits| limits);
+        store.set_fuel(FUEL_PER_CALL).map_err(|e| format!("{:#}", e))?;
+        let instance = Linker::new(&self.engine)
+            .instantiate(&mut store, &self.module)
+            .map_err(|e| format!("{:#}", e))?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or("module does not export its memory")?;
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&mut store, "alloc")
+            .map_err(|e| format!("alloc: {:#}", e))?;
+        let score = instance
+            .get_typed_func::<(i32, i32), i32>(&mut store, "score")
+            .map_err(|e| format!("score: {:#}", e))?;
+        let ptr = alloc.call(&mut store, 20).map_err(|e| format!("alloc: {:#}", e))?;
+        Ok(Instance { store, memory, score, ptr })
+    }
+}
+
+fn call(instance: &mut Instance, address: &[u8; 20]) -> Result<u32, String> {
+    let Instance { store, memory, score, ptr } = instance;
+    memory
+        .write(&mut *store, *ptr as usize, address)
+        .map_err(|_| format!("alloc returned {} outside the module's memory", ptr))?;
+    store.set_fuel(FUEL_PER_CALL).map_err(|e| format!("{:#}", e))?;
+    let result = score.call(&mut *store, (*ptr, 20)).map_err(|e| format!("{:#}", e))?;
+    Ok(result as u32)
+}
+
+impl Scorer for WasmScorer {
+    fn score(&self, address: &[u8; 20]) -> u32 {
+        let instance = self
+            .instances
+            .get_or(|| RefCell::new(self.instantiate().expect("module instantiated when loading")));
+        // A trap (including running out of fuel) scores nothing.
+        call(&mut instance.borrow_mut(), address).unwrap_or(0)
+    }
+}
@@ -0,0 +1,321 @@
+// What the miner reads from the chain and the challenge on it, against a node
+// answering from a script.
+
+mod node;
+
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use node::{address_word, number_word, Node};
+use serde_json::{json, Value};
+use uniswapv4_challenge_miner::challenge::selector;
+use uniswapv4_challenge_miner::{compute_score, Candidate};
+
+const CHALLENGE: [u8; 20] = [0x48; 20];
+
+// The selector an eth_call to the challenge calls.
+fn called(params: &Value) -> String {
+    params[0]["data"].as_str().unwrap()[..10].to_string()
+}
+
+fn is(params: &Value, signature: &str) -> bool {
+    called(params) == format!("0x{}", hex::encode(selector(signature)))
+}
+
+// Wait up to ten seconds for `done`.
+fn eventually(done: impl Fn() -> bool) {
+    let started = Instant::now();
+    while !done() {
+        assert!(started.elapsed() < Duration::from_secs(10), "timed out");
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn the_target_follows_the_on_chain_best_up() {
+    use uniswapv4_challenge_miner::monitor;
+
+    let leaders = [[0x00, 0x00, 0x44, 0x44, 0x12], [0x00, 0x00, 0x00, 0x44, 0x44]].map(|start| {
+        let mut address = [0x12; 20];
+        address[..5].copy_from_slice(&start);
+        address
+    });
+    assert!(compute_score(&leaders[1]) > compute_score(&leaders[0]));
+    let leading = Arc::new(AtomicUsize::new(0));
+    let led = Arc::clone(&leading);
+    let node = Node::serve(move |method, params| match method {
+        "eth_call" if is(params, "bestAddress()") => Ok(address_word(&leaders[led.load(Ordering::Relaxed)])),
+        "eth_call" if is(params, "bestAddressSubmitter()") => Ok(address_word(&[0x5b; 20])),
+        _ => Err(json!({ "code": -32601, "message": method })),
+    });
+    let best = monitor::fetch_best(&node.rpc(), &CHALLENGE).unwrap();
+    assert_eq!((best.address, best.submitter, best.score), (leaders[0], [0x5b; 20], compute_score(&leaders[0])));
+
+    let goal = Arc::new(monitor::Goal::new(true));
+    let target = Arc::clone(&goal.target);
+    assert_eq!(monitor::initialize(&node.rpc(), &CHALLENGE, &goal), Some(leaders[0]));
+    assert_eq!(target.load(Ordering::Relaxed), compute_score(&leaders[0]));
+    monitor::spawn(node.rpc(), CHALLENGE, Duration::from_millis(10), Arc::clone(&goal), Some(leaders[0]));
+    leading.store(1, Ordering::Relaxed);
+    eventually(|| target.load(Ordering::Relaxed) == compute_score(&leaders[1]));
+    // Never lowered, by a worse best or a higher target of its own.
+    goal.raise(1);
+    assert_eq!(target.load(Ordering::Relaxed), compute_score(&leaders[1]));
+}
+
+#[test]
+fn the_search_stops_at_the_competition_deadline() {
+    use uniswapv4_challenge_miner::deadline;
+    use uniswapv4_challenge_miner::search::Control;
+
+    let now = 1_700_000_000;
+    let deadline_at = Arc::new(AtomicU64::new(now - 1));
+    let at = Arc::clone(&deadline_at);
+    let node = Node::serve(move |method, params| match method {
+        "eth_call" if is(params, "competitionDeadline()") => Ok(number_word(at.load(Ordering::Relaxed))),
+        "eth_getBlockByNumber" => Ok(json!({ "timestamp": format!("{:#x}", now), "baseFeePerGas": "0x1" })),
+        _ => Err(json!({ "code": -32601, "message": method })),
+    });
+    let control = Arc::new(Control::default());
+    let ended = deadline::spawn(node.rpc(), CHALLENGE, Arc::default(), None, Arc::clone(&control)).unwrap_err();
+    assert!(ended.contains("competition ended"), "{}", ended);
+    assert_eq!(deadline::time_left(), None);
+
+    deadline_at.store(now + 1, Ordering::Relaxed);
+    deadline::spawn(node.rpc(), CHALLENGE, Arc::default(), None, Arc::clone(&control)).unwrap();
+    assert!(deadline::time_left().is_some_and(|left| left <= Duration::from_secs(1)));
+    eventually(|| control.stopped.load(Ordering::Relaxed));
+    assert!(deadline::passed());
+}
+
+#[test]
+fn submitters_are_checked_against_the_contract_before_mining() {
+    use uniswapv4_challenge_miner::challenge::check_submitter;
+
+    let code_hash = [0x94; 32];
+    // What simulating a submission reverts with, None for no contract.
+    let revert = Arc::new(Mutex::new(Some(Vec::new())));
+    let reverting = Arc::clone(&revert);
+    let node = Node::serve(move |method, params| {
+        let revert = reverting.lock().unwrap().clone();
+        match method {
+            "eth_getCode" => Ok(json!(if revert.is_some() { "0x6080" } else { "0x" })),
+            "eth_call" if is(params, "initCodeHash()") => Ok(json!(format!("0x{}", hex::encode([0x94; 32])))),
+            "eth_call" if is(params, "updateBestAddress(bytes32)") => {
+                assert_eq!(params[0]["data"].as_str().unwrap()[10..50], "5b".repeat(20));
+                match revert.unwrap() {
+                    revert if revert.is_empty() => Ok(json!("0x")),
+                    revert => Err(json!({ "code": 3, "message": "execution reverted", "data": format!("0x{}", hex::encode(revert)) })),
+                }
+            }
+            _ => Err(json!({ "code": -32601, "message": method })),
+        }
+    });
+    let check = |hash: &[u8; 32]| check_submitter(&node.rpc(), &CHALLENGE, &[0x5b; 20], hash);
+    let reverts = |error: &str| Some([&selector(error)[..], &[0; 128]].concat());
+
+    assert_eq!(check(&code_hash), Ok(()));
+    assert!(check(&[0x95; 32]).unwrap_err().contains("contract expects initcode hash 0x9494"));
+    *revert.lock().unwrap() = reverts("WorseAddress(address,address,uint256,uint256)");
+    assert_eq!(check(&code_hash), Ok(()));
+    *revert.lock().unwrap() = reverts("InvalidSender(bytes32,address)");
+    assert!(check(&code_hash).unwrap_err().contains("rejects salts bound to 0x5b5b"));
+    *revert.lock().unwrap() = reverts("CompetitionOver(uint256,uint256)");
+    assert_eq!(check(&code_hash), Err("the competition is over".to_string()));
+    *revert.lock().unwrap() = None;
+    assert!(check(&code_hash).unwrap_err().starts_with("no contract deployed at 0x4848"));
+}
+
+#[test]
+fn new_address_events_raise_the_target_as_they_come() {
+    use tungstenite::Message;
+    use uniswapv4_challenge_miner::{events, monitor};
+    use uniswapv4_challenge_miner::keccak256;
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        let mut socket = tungstenite::accept(listener.accept().unwrap().0).unwrap();
+        let request: Value = serde_json::from_str(socket.read().unwrap().to_text().unwrap()).unwrap();
+        assert_eq!(request["method"], "eth_subscribe");
+        assert_eq!(request["params"][1]["address"], format!("0x{}", hex::encode(CHALLENGE)));
+        assert_eq!(request["params"][1]["topics"][0], format!("0x{}", hex::encode(keccak256(b"NewAddressFound(address,address,uint256)"))));
+        socket.send(Message::Text(json!({ "jsonrpc": "2.0", "id": 1, "result": "0x9c" }).to_string())).unwrap();
+        let log = json!({ "topics": ["0x00", address_word(&[0x77; 20]), address_word(&[0x5b; 20])], "data": number_word(131) });
+        let notification = json!({ "jsonrpc": "2.0", "method": "eth_subscription", "params": { "subscription": "0x9c", "result": log } });
+        socket.send(Message::Text(notification.to_string())).unwrap();
+        // Held open for the miner to keep following.
+        while socket.read().is_ok() {}
+    });
+
+    let goal = Arc::new(monitor::Goal::new(true));
+    goal.target.store(90, Ordering::Relaxed);
+    events::spawn(url, CHALLENGE, Arc::clone(&goal));
+    eventually(|| goal.target.load(Ordering::Relaxed) == 131);
+}
+
+#[test]
+fn scores_are_checked_against_the_contract() {
+    use uniswapv4_challenge_miner::verify::{check, ScoreOracle, Verdict};
+
+    let address = [0x00, 0x00, 0x44, 0x44, 0x12, 0x12, 0x12, 0x12, 0x12, 0x12, 0x12, 0x12, 0x12, 0x12, 0x12, 0x12, 0x12, 0x12, 0x12, 0x12];
+    let score = compute_score(&address);
+    let candidate = Candidate { salt: [0x11; 32], address, score };
+    // What the contract scores, and whether a submission would lead instead.
+    let canonical = Arc::new(Mutex::new((score, false)));
+    let scored = Arc::clone(&canonical);
+    let node = Node::serve(move |method, params| {
+        let (score, leads) = *scored.lock().unwrap();
+        match method {
+            "eth_call" if is(params, "score(address)") => {
+                assert_eq!(params[0]["data"].as_str().unwrap()[34..], hex::encode(address));
+                Ok(number_word(score as u64))
+            }
+            "eth_call" if leads => Ok(json!("0x")),
+            "eth_call" if is(params, "updateBestAddress(bytes32)") => {
+                let revert = [&selector("WorseAddress(address,address,uint256,uint256)")[..], &[0; 12], &address, &[0; 32], &[0; 28], &score.to_be_bytes(), &[0; 32]].concat();
+                Err(json!({ "code": 3, "message": "execution reverted", "data": format!("0x{}", hex::encode(revert)) }))
+            }
+            _ => Err(json!({ "code": -32601, "message": method })),
+        }
+    });
+    let library = ScoreOracle::Library([0x5c; 20]);
+    let challenge = ScoreOracle::Challenge { contract: CHALLENGE, sender: [0x11; 20] };
+
+    for oracle in [&library, &challenge] {
+        assert!(matches!(check(&node.rpc(), oracle, &candidate), Ok(Verdict::Match { address: matched }) if matched == address));
+    }
+    *canonical.lock().unwrap() = (score + 10, false);
+    for oracle in [&library, &challenge] {
+        let checked = check(&node.rpc(), oracle, &candidate);
+        assert!(matches!(checked, Ok(Verdict::Divergence { local, canonical, .. }) if (local, canonical) == (score, score + 10)));
+    }
+    *canonical.lock().unwrap() = (score, true);
+    assert!(matches!(check(&node.rpc(), &challenge, &candidate), Ok(Verdict::WouldLead)));
+}
+
+#[test]
+fn networks_supply_their_contract_chain_and_endpoint() {
+    use clap::Parser;
+    use uniswapv4_challenge_miner::error::MinerError;
+    use uniswapv4_challenge_miner::network::NetworkArgs;
+    use uniswapv4_challenge_miner::DEPLOYER_ADDRESS;
+
+    #[derive(Parser)]
+    #[command(no_binary_name = true)]
+    struct Cli {
+        #[command(flatten)]
+        network: NetworkArgs,
+    }
+    let network = |args: &[&str]| Cli::try_parse_from(args).unwrap().network;
+
+    let mainnet = network(&[]);
+    assert_eq!((mainnet.contract(), mainnet.chain_id()), (Ok(DEPLOYER_ADDRESS), Some(1)));
+    assert!(mainnet.rpc_urls(Vec::new()).is_empty());
+    let anvil = network(&["--network", "anvil"]);
+    assert_eq!((anvil.contract(), anvil.chain_id()), (Ok(DEPLOYER_ADDRESS), None));
+    assert_eq!(anvil.rpc_urls(Vec::new()), ["http://127.0.0.1:8545"]);
+    assert_eq!(anvil.rpc_urls(vec!["http://node:8545".to_string()]), ["http://node:8545"]);
+    assert!(network(&["--network", "sepolia"]).contract().unwrap_err().contains("pass --contract"));
+    let sepolia = network(&["--network", "sepolia", "--contract", "0x4848484848484848484848484848484848484848"]);
+    assert_eq!((sepolia.contract(), sepolia.chain_id()), (Ok(CHALLENGE), Some(11155111)));
+
+    // A node on another chain is a configuration error, and anvil's any chain.
+    let node = Node::serve(|method, _| match method {
+        "eth_chainId" => Ok(json!("0x1")),
+        _ => Err(json!({ "code": -32601, "message": method })),
+    });
+    assert!(matches!(sepolia.check_chain_id(&node.rpc()), Err(MinerError::Config(e)) if e.contains("on chain 1 but chain 11155111")));
+    assert!(matches!(anvil.check_chain_id(&node.rpc()), Ok(1)));
+    assert!(matches!(network(&["--network", "anvil", "--chain-id", "31337"]).check_chain_id(&node.rpc()), Err(MinerError::Config(_))));
+}
+
+#[test]
+fn requests_fail_over_to_the_next_endpoint() {
+    use uniswapv4_challenge_miner::rpc::RpcClient;
+
+    // A node that is rate limited until told otherwise, and one that is fine.
+    let (limited, asked) = (Arc::new(AtomicBool::new(true)), Arc::new([AtomicUsize::new(0), AtomicUsize::new(0)]));
+    let nodes = [0, 1].map(|i| {
+        let (limited, asked) = (Arc::clone(&limited), Arc::clone(&asked));
+        Node::serve(move |method, _| {
+            asked[i].fetch_add(1, Ordering::Relaxed);
+            match method {
+                _ if i == 0 && limited.load(Ordering::Relaxed) => Err(json!({ "code": 429, "message": "Too Many Requests" })),
+                "eth_chainId" => Ok(json!(format!("{:#x}", i + 1))),
+                _ => Err(json!({ "code": 3, "message": "execution reverted" })),
+            }
+        })
+    });
+    let rpc = RpcClient::new(&[nodes[0].url.clone(), nodes[1].url.clone()]);
+    let asked = |i: usize| asked[i].load(Ordering::Relaxed);
+
+    assert_eq!(rpc.chain_id(), Ok(2));
+    assert_eq!((asked(0), asked(1)), (1, 1));
+    // The limited one is left for last while it backs off.
+    limited.store(false, Ordering::Relaxed);
+    assert_eq!(rpc.chain_id(), Ok(2));
+    assert_eq!((asked(0), asked(1)), (1, 2));
+    // An error about the request itself is the answer, not a reason to move on.
+    assert!(rpc.request("eth_call", json!([])).unwrap_err().contains("execution reverted"));
+    assert_eq!((asked(0), asked(1)), (1, 3));
+    // And with every endpoint down, the last error.
+    let down = RpcClient::new(&["http://127.0.0.1:9".to_string()]);
+    assert!(down.chain_id().unwrap_err().starts_with("eth_chainId request failed"));
+}
+
+#[test]
+fn initcode_is_fetched_and_hashed_before_it_is_trusted() {
+    use uniswapv4_challenge_miner::initcode::{verified_hash, InitcodeSource};
+    use uniswapv4_challenge_miner::keccak256;
+
+    // An SSTORE2 data contract, its code the initcode after a STOP, and the
+    // transactions creating it and calling it.
+    let initcode = [0x60, 0x80, 0x60, 0x40, 0x52];
+    let node = Node::serve(move |method, params| match (method, params[0].as_str().unwrap()) {
+        ("eth_getCode", "0x4444444444444444444444444444444444444444") => Ok(json!(format!("0x00{}", hex::encode(initcode)))),
+        ("eth_getCode", _) => Ok(json!("0x")),
+        ("eth_getTransactionByHash", "0xc7ea7e") => Ok(json!({ "to": null, "input": format!("0x{}", hex::encode(initcode)) })),
+        ("eth_getTransactionByHash", "0xca11") => Ok(json!({ "to": "0x4444444444444444444444444444444444444444", "input": "0x" })),
+        ("eth_getTransactionByHash", _) => Ok(Value::Null),
+        _ => Err(json!({ "code": -32601, "message": method })),
+    });
+    let hash = keccak256(initcode).0;
+    let code = |address: u8| InitcodeSource::Code { address: [address; 20], skip: 1 };
+    let created = InitcodeSource::CreationTx("0xc7ea7e".to_string());
+
+    assert_eq!(verified_hash(&node.rpc(), &code(0x44), None), Ok(hash));
+    assert_eq!(verified_hash(&node.rpc(), &created, Some(hash)), Ok(hash));
+    let mismatch = verified_hash(&node.rpc(), &created, Some([0x94; 32])).unwrap_err();
+    assert!(mismatch.starts_with("initcode hash mismatch: expected 0x9494"), "{}", mismatch);
+    assert!(verified_hash(&node.rpc(), &code(0x45), None).unwrap_err().ends_with("holds no initcode"));
+    let called = InitcodeSource::CreationTx("0xca11".to_string());
+    assert!(verified_hash(&node.rpc(), &called, None).unwrap_err().ends_with("is not a contract creation"));
+    let missing = InitcodeSource::CreationTx("0x0f".to_string());
+    assert_eq!(verified_hash(&node.rpc(), &missing, None), Err("transaction 0x0f not found".to_string()));
+}
+
+#[test]
+fn mining_starts_from_the_on_chain_best_when_it_can_be_read() {
+    use uniswapv4_challenge_miner::monitor;
+
+    let leader = [0x00, 0x00, 0x00, 0x44, 0x44, 0x44, 0x44, 0x12, 0x12, 0x12, 0x12, 0x12, 0x12, 0x12, 0x12, 0x12, 0x12, 0x12, 0x12, 0x12];
+    let readable = Arc::new(AtomicBool::new(false));
+    let read = Arc::clone(&readable);
+    let node = Node::serve(move |method, params| match method {
+        _ if !read.load(Ordering::Relaxed) => Err(json!({ "code": -32000, "message": "header not found" })),
+        "eth_call" if is(params, "bestAddress()") => Ok(address_word(&leader)),
+        "eth_call" if is(params, "bestAddressSubmitter()") => Ok(address_word(&[0x5b; 20])),
+        _ => Err(json!({ "code": -32601, "message": method })),
+    });
+    // Unread, mining starts from scratch.
+    let goal = monitor::Goal::new(true);
+    assert_eq!(monitor::initialize(&node.rpc(), &CHALLENGE, &goal), None);
+    assert_eq!(goal.target.load(Ordering::Relaxed), 0);
+    readable.store(true, Ordering::Relaxed);
+    assert_eq!(monitor::initialize(&node.rpc(), &CHALLENGE, &goal), Some(leader));
+    assert_eq!(goal.target.load(Ordering::Relaxed), compute_score(&leader));
+    // Anything below it falls short.
+    assert!(!goal.reached(Some(&Candidate { salt: [0; 32], address: leader, score: compute_score(&leader) - 1 })));
+}
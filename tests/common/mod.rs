@@ -0,0 +1,108 @@
+// Local anvil node for integration tests. Tests using it skip themselves when
+// anvil is not installed or no fork endpoint is configured.
+
+use std::net::TcpListener;
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+
+// First of anvil's default dev accounts.
+pub const DEV_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+pub const DEV_ADDRESS: &str = "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266";
+
+pub const CHALLENGE: &str = "0x48e516b34a1274f49457b9c6182097796d0498cb";
+
+pub struct Anvil {
+    child: Child,
+    pub url: String,
+}
+
+fn free_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port()
+}
+
+impl Anvil {
+    // Fork ANVIL_FORK_URL (at ANVIL_FORK_BLOCK, if set). None when the
+    // environment cannot run the test.
+    pub fn fork() -> Option<Anvil> {
+        let Ok(fork_url) = std::env::var("ANVIL_FORK_URL") else {
+            eprintln!("skipping: ANVIL_FORK_URL is not set");
+            return None;
+        };
+        let port = free_port();
+        let mut command = Command::new("anvil");
+        command
+            .args(["--port", &port.to_string(), "--fork-url", &fork_url])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+        if let Ok(block) = std::env::var("ANVIL_FORK_BLOCK") {
+            command.args(["--fork-block-number", &block]);
+        }
+        let child = match command.spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                eprintln!("skipping: cannot start anvil: {}", e);
+                return None;
+            }
+        };
+        let anvil = Anvil { child, url: format!("http://127.0.0.1:{}", port) };
+
+        let started = Instant::now();
+        while anvil.try_rpc("eth_chainId", json!([])).is_err() {
+            assert!(started.elapsed() < Duration::from_secs(60), "anvil did not come up");
+            thread::sleep(Duration::from_millis(250));
+        }
+        Some(anvil)
+    }
+
+    fn try_rpc(&self, method: &str, params: Value) -> Result<Value, String> {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let response: Value = ureq::post(&self.url)
+            .send_json(body)
+            .map_err(|e| e.to_string())?
+            .into_json()
+            .map_err(|e| e.to_string())?;
+        match response.get("error") {
+            Some(error) => Err(error.to_string()),
+            None => Ok(response["result"].clone()),
+        }
+    }
+
+    pub fn rpc(&self, method: &str, params: Value) -> Value {
+        self.try_rpc(method, params)
+            .unwrap_or_else(|e| panic!("{} failed: {}", method, e))
+    }
+
+    // 32-byte word returned by a parameterless view function.
+    pub fn call_word(&self, to: &str, selector: &str) -> String {
+        let output = self.rpc("eth_call", json!([{ "to": to, "data": selector }, "latest"]));
+        output.as_str().unwrap().to_string()
+    }
+
+    pub fn storage(&self, address: &str, slot: u64) -> String {
+        let value = self.rpc("eth_getStorageAt", json!([address, format!("{:#x}", slot), "latest"]));
+        value.as_str().unwrap().to_string()
+    }
+
+    pub fn set_storage(&self, address: &str, slot: u64, value: &str) {
+        self.rpc("anvil_setStorageAt", json!([address, format!("{:#x}", slot), value]));
+    }
+
+    pub fn timestamp(&self) -> u64 {
+        let block = self.rpc("eth_getBlockByNumber", json!(["latest", false]));
+        u64::from_str_radix(block["timestamp"].as_str().unwrap().trim_start_matches("0x"), 16).unwrap()
+    }
+}
+
+impl Drop for Anvil {
+    fn drop(&mut self) {
+        self.child.kill().ok();
+        self.child.wait().ok();
+    }
+}
+
+pub fn word_to_u64(word: &str) -> u64 {
+    u64::from_str_radix(&word.trim_start_matches("0x")[48..], 16).unwrap()
+}
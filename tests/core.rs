@@ -0,0 +1,37 @@
+// The derivations and scoring at the crate root as a no_std user gets them:
+// CI runs this with `--no-default-features`, so it only uses what builds then.
+
+use uniswapv4_challenge_miner::{compute_score, compute_score_at_least, create2_addr, create3_addr, create_addr, explain_score, CREATE3_PROXY_HASH, DEPLOYER_ADDRESS, INITCODE_HASH};
+
+fn bytes<const N: usize>(value: &str) -> [u8; N] {
+    hex::decode(value.trim_start_matches("0x")).unwrap().try_into().unwrap()
+}
+
+#[test]
+fn derivations_match_known_addresses() {
+    let keccak256_of_00 = bytes("0xbc36789e7a1e281436464229828f817d6612f7b477d66591ff96a9e064bcc98a");
+    assert_eq!(create2_addr(&[0; 20], &[0; 32], &keccak256_of_00), bytes("0x4D1A2e2bB4F88F0250f26Ffff098B0b30B26BF38"));
+    let sender = bytes("0x6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0");
+    assert_eq!(create_addr(&sender, 0), bytes("0xcd234a471b72ba2f1ccf0a70fcaba648a5eecd8d"));
+    assert_eq!(create_addr(&sender, 1), bytes("0x343c43a37d37dff08ae8c4a11544c718abb4fcf8"));
+    // CREATE3 deploys the proxy with CREATE2, and the proxy the contract with
+    // its first nonce.
+    let salt = [0x42; 32];
+    assert_eq!(create3_addr(&DEPLOYER_ADDRESS, &salt), create_addr(&create2_addr(&DEPLOYER_ADDRESS, &salt, &CREATE3_PROXY_HASH), 1));
+    assert_ne!(create2_addr(&DEPLOYER_ADDRESS, &salt, &INITCODE_HASH), create3_addr(&DEPLOYER_ADDRESS, &salt));
+}
+
+#[test]
+fn scores_add_up_rule_by_rule() {
+    let address = bytes("0x0000000044449dbd6b1b8e5dcac0e2ce0ec9ef2e");
+    let breakdown = explain_score(&address);
+    assert_eq!((breakdown.leading_zeros, breakdown.four_fours, breakdown.not_four_after, breakdown.tail_fours, breakdown.fours), (80, 40, 20, 0, 4));
+    assert_eq!(compute_score(&address), 144);
+    assert_eq!(breakdown.total(), compute_score(&address));
+    assert_eq!(compute_score_at_least(&address, 100), 144);
+    // The first nonzero nibble has to be a 4.
+    let address = bytes("0x0000000054449dbd6b1b8e5dcac0e2ce0ec9e444");
+    assert_eq!(compute_score(&address), 0);
+    assert!(!explain_score(&address).starts_with_four);
+    assert_eq!(compute_score(&[0x44; 20]), 40 + 20 + 40);
+}
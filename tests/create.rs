@@ -0,0 +1,62 @@
+// --mode create searches the nonces of each sender, deriving the addresses the
+// CREATE opcode would deploy to.
+
+use std::process::Command;
+
+const MINER: &str = env!("CARGO_BIN_EXE_uniswapv4-challenge-miner");
+const SENDER: &str = "0x6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0";
+
+// The address reported for each nonce, by listing them all with --top.
+fn addresses(args: &[&str], max_nonce: u64) -> Vec<(String, u64, String)> {
+    let max_nonce = max_nonce.to_string();
+    let output = Command::new(MINER).args(["--threads", "2", "--mode", "create", "--max-nonce", &max_nonce, "--top", "1000"]).args(args).output().unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success(), "{}{}", stdout, String::from_utf8_lossy(&output.stderr));
+    assert!(stdout.contains(&format!("Searched nonces 0 to {} of every sender", max_nonce)), "{}", stdout);
+    let listed = stdout.lines().skip_while(|line| !line.starts_with("Best ")).skip(1).take_while(|line| line.contains("nonce: "));
+    listed
+        .map(|line| {
+            let address = line.split_whitespace().nth(1).unwrap().to_string();
+            let sender = line.split("sender: ").nth(1).unwrap().split(',').next().unwrap().to_string();
+            let nonce = line.rsplit("nonce: ").next().unwrap().parse().unwrap();
+            (sender, nonce, address)
+        })
+        .collect()
+}
+
+#[test]
+fn nonces_derive_the_addresses_create_deploys_to() {
+    let found = addresses(&["--sender", SENDER], 299);
+    assert_eq!(found.len(), 300);
+    // Single byte, short string and two byte RLP encodings of the nonce.
+    for (nonce, address) in [
+        (0, "0xcd234a471b72ba2f1ccf0a70fcaba648a5eecd8d"),
+        (1, "0x343c43a37d37dff08ae8c4a11544c718abb4fcf8"),
+        (127, "0x06d9a77f5e4b311bae8d559db9cdb4df94104aa0"),
+        (128, "0x08e190dcb7b73f5fcdabb43e102215c83659a76d"),
+        (255, "0x3ef7c1a519e4b4431e317d7839340e3139b03c65"),
+        (256, "0x3837c1ae70354f670550c746580199ac6a73cb0a"),
+        (299, "0x80b69bd4d52e99a308bfafa84f9510ae74f08897"),
+    ] {
+        assert!(found.contains(&(SENDER.to_string(), nonce, address.to_string())), "nonce {}", nonce);
+    }
+}
+
+#[test]
+fn every_sender_is_searched() {
+    let other = "0x0000000000000000000000000000000000000001";
+    let path = std::env::temp_dir().join(format!("senders-{}.txt", std::process::id()));
+    std::fs::write(&path, format!("# deployers\n{}\n\n", other)).unwrap();
+    let found = addresses(&["--sender", SENDER, "--senders-file", path.to_str().unwrap()], 3);
+    std::fs::remove_file(&path).unwrap();
+    for sender in [SENDER, other] {
+        let mut nonces: Vec<u64> = found.iter().filter(|(s, _, _)| s == sender).map(|(_, nonce, _)| *nonce).collect();
+        nonces.sort();
+        assert_eq!(nonces, [0, 1, 2, 3], "{}", sender);
+    }
+
+    let error = Command::new(MINER).args(["--threads", "1", "--mode", "create"]).output().unwrap();
+    assert!(String::from_utf8_lossy(&error.stderr).contains("searches the nonces of --sender or --senders-file accounts"));
+    let error = Command::new(MINER).args(["--threads", "1", "--sender", SENDER]).output().unwrap();
+    assert!(String::from_utf8_lossy(&error.stderr).contains("only it uses them"));
+}
@@ -0,0 +1,141 @@
+// The address derivations against alloy's on random inputs, against
+// contracts actually deployed on mainnet and through the CREATE3 proxy, as
+// well as the startup self-test.
+
+use proptest::prelude::*;
+use uniswapv4_challenge_miner::error::MinerError;
+use uniswapv4_challenge_miner::mode::Mode;
+use uniswapv4_challenge_miner::{create2_addr, create_addr, decode_hex, keccak256, score, selftest, Address, B256};
+
+proptest! {
+    #[test]
+    fn create2_matches_alloy(deployer: [u8; 20], salt: [u8; 32], code_hash: [u8; 32]) {
+        let expected = Address::from(deployer).create2(salt, code_hash);
+        prop_assert_eq!(Address::from(create2_addr(&deployer, &salt, &code_hash)), expected);
+    }
+
+    #[test]
+    fn create2_matches_alloy_on_initcode(deployer: [u8; 20], salt: [u8; 32], initcode in prop::collection::vec(any::<u8>(), 0..512)) {
+        let expected = Address::from(deployer).create2_from_code(salt, &initcode);
+        prop_assert_eq!(Address::from(create2_addr(&deployer, &salt, &keccak256(&initcode))), expected);
+    }
+
+    // Nonces around each RLP length boundary as well as anywhere.
+    #[test]
+    fn create_matches_alloy(
+        sender: [u8; 20],
+        nonce in prop_oneof![0..=0x100u64, any::<u64>(), (0..64u32).prop_map(|bits| 1u64 << bits), Just(u64::MAX)],
+    ) {
+        prop_assert_eq!(Address::from(create_addr(&sender, nonce)), Address::from(sender).create(nonce));
+    }
+}
+
+fn address(hex: &str) -> [u8; 20] {
+    decode_hex(hex).unwrap()
+}
+
+#[test]
+fn mainnet_create2_deployments() {
+    let usdc = address("0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48");
+    let weth = address("0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2");
+
+    // Uniswap v2's USDC/WETH pair: salt keccak256(abi.encodePacked(token0, token1)).
+    let factory = address("0x5C69bEe701ef814a2B6a3EDD4B1652CB9cc5aA6f");
+    let code_hash: B256 = decode_hex::<32>("0x96e8ac4277198ff8b6f785478aa9a39f403cb768dd02cbee326c3e7da348845f").unwrap().into();
+    let salt = keccak256([usdc, weth].concat());
+    assert_eq!(create2_addr(&factory, &salt, &code_hash), address("0xB4e16d0168e52d35CaCD2c6185b44281Ec28C9Dc"));
+
+    // Uniswap v3's USDC/WETH 0.05% pool: salt keccak256(abi.encode(token0, token1, fee)).
+    let factory = address("0x1F98431c8aD98523631AE4a59f267346ea31F984");
+    let code_hash: B256 = decode_hex::<32>("0xe34f199b19b2b4f47f68442619d555527d244f78a3297ea89325f843f87b8b54").unwrap().into();
+    let mut encoded = [0; 96];
+    encoded[12..32].copy_from_slice(&usdc);
+    encoded[44..64].copy_from_slice(&weth);
+    encoded[92..].copy_from_slice(&500u32.to_be_bytes());
+    let salt = keccak256(encoded);
+    assert_eq!(create2_addr(&factory, &salt, &code_hash), address("0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640"));
+}
+
+// The examples of EIP-1014 itself.
+#[test]
+fn eip1014_examples() {
+    let examples = [
+        ("0x0000000000000000000000000000000000000000", 0u32, "00", "0x4D1A2e2bB4F88F0250f26Ffff098B0b30B26BF38"),
+        ("0xdeadbeef00000000000000000000000000000000", 0, "00", "0xB928f69Bb1D91Cd65274e3c79d8986362984fDA3"),
+        ("0x00000000000000000000000000000000deadbeef", 0xcafebabe, "deadbeef", "0x60f3f640a8508fC6a86d45DF051962668E1e8AC7"),
+        ("0x0000000000000000000000000000000000000000", 0, "", "0xE33C0C7F7df4809055C3ebA6c09CFe4BaF1BD9e0"),
+    ];
+    for (deployer, salt, initcode, expected) in examples {
+        let salt = B256::left_padding_from(&salt.to_be_bytes());
+        let code_hash = keccak256(hex::decode(initcode).unwrap());
+        assert_eq!(create2_addr(&address(deployer), &salt, &code_hash), address(expected), "deployer {}", deployer);
+    }
+}
+
+#[test]
+fn self_test_passes_in_every_mode_and_catches_wrong_scores() {
+    for mode in [Mode::Create2, Mode::Create3, Mode::Create, Mode::Eoa] {
+        selftest::check(mode, Some(&score::UniswapV4)).unwrap();
+    }
+    assert!(matches!(selftest::check(Mode::Create2, Some(&score::Fours)), Err(MinerError::SelfTest(_))));
+}
+
+// Ethereum's keccak256 pads differently from the standardized SHA3-256, so a
+// hasher set up for the wrong one gets every address wrong.
+#[test]
+fn hashing_is_keccak256_not_sha3() {
+    let sha3_256_of_empty = decode_hex::<32>("0xa7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a").unwrap();
+    let keccak256_of_empty = decode_hex::<32>("0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470").unwrap();
+    assert_eq!(keccak256(b"").0, keccak256_of_empty);
+    assert_ne!(keccak256(b"").0, sha3_256_of_empty);
+    assert_eq!(
+        keccak256(b"transfer(address,uint256)")[..4],
+        decode_hex::<4>("0xa9059cbb").unwrap(),
+        "the ERC-20 transfer selector"
+    );
+    // The hashing inside create2_addr, on the empty initcode's hash from EIP-1014.
+    assert_eq!(create2_addr(&[0; 20], &[0; 32], &keccak256_of_empty), address("0xE33C0C7F7df4809055C3ebA6c09CFe4BaF1BD9e0"));
+    // And on contracts deployed on mainnet: Uniswap v2's DAI/WETH pair by
+    // CREATE2, and by CREATE the deterministic deployment proxy and
+    // Multicall3, each its deployer's first transaction.
+    let dai = address("0x6B175474E89094C44Da98b954EedeAC495271d0F");
+    let weth = address("0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2");
+    let pair_code_hash = decode_hex::<32>("0x96e8ac4277198ff8b6f785478aa9a39f403cb768dd02cbee326c3e7da348845f").unwrap();
+    assert_eq!(
+        create2_addr(&address("0x5C69bEe701ef814a2B6a3EDD4B1652CB9cc5aA6f"), &keccak256([dai, weth].concat()), &pair_code_hash),
+        address("0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11")
+    );
+    assert_eq!(create_addr(&address("0x3fab184622dc19b6109349b94811493bf2a45362"), 0), address("0x4e59b44847b379578588920cA78FbF26c0B4956C"));
+    assert_eq!(create_addr(&address("0x05f32b3cc3888453ff71b01135b34ff8e41263f2"), 0), address("0xcA11bde05977b3631167028862bE2a173976CA11"));
+}
+
+#[test]
+fn derivations_agree_with_alloy() {
+    let deployer = Address::from(uniswapv4_challenge_miner::DEPLOYER_ADDRESS);
+    let code_hash = B256::from(uniswapv4_challenge_miner::INITCODE_HASH);
+    let salt = B256::with_last_byte(7);
+    assert_eq!(Address::from(create2_addr(&deployer, &salt, &code_hash)), deployer.create2(salt, code_hash));
+    let candidate = uniswapv4_challenge_miner::Candidate { salt: salt.0, address: create2_addr(&deployer, &salt, &code_hash), score: 0 };
+    assert_eq!((candidate.salt(), candidate.address()), (salt, deployer.create2(salt, code_hash)));
+    assert_eq!(Address::from(candidate), candidate.address());
+}
+
+#[test]
+fn create3_deploys_through_the_proxy_whatever_the_initcode() {
+    use uniswapv4_challenge_miner::mode::Derivation;
+    use uniswapv4_challenge_miner::{create3_addr, CREATE3_PROXY_HASH};
+
+    assert_eq!(CREATE3_PROXY_HASH, keccak256(hex::decode("67363d3d37363d34f03d5260086018f3").unwrap()).0);
+    let (factory, caller, salt) = ([0x3c; 20], [0x5b; 20], [0x42; 32]);
+    // The proxy at the CREATE2 address, and at its first nonce the contract.
+    let proxy = Address::from(factory).create2(salt, CREATE3_PROXY_HASH);
+    assert_eq!(Address::from(create3_addr(&factory, &salt)), proxy.create(1));
+
+    let derive = |code_hash: [u8; 32], create3_caller| Derivation { mode: Mode::Create3, deployer: factory, code_hash, create3_caller }.address(&salt);
+    assert_eq!(derive([0x11; 32], None), create3_addr(&factory, &salt));
+    assert_eq!(derive([0x11; 32], None), derive([0x22; 32], None));
+    // Namespaced by the caller, as factories keeping callers apart do.
+    let namespaced = keccak256([&caller[..], &salt[..]].concat());
+    assert_eq!(derive([0x11; 32], Some(caller)), create3_addr(&factory, &namespaced));
+    assert_ne!(derive([0x11; 32], Some(caller)), derive([0x11; 32], Some([0x5c; 20])));
+}
@@ -0,0 +1,41 @@
+// Measuring the energy a run uses, and the budget it may use.
+
+#[test]
+fn rapl_package_counters_are_added_up_across_wraps() {
+    use uniswapv4_challenge_miner::energy::{parse_power_line, Rapl};
+
+    let powercap = std::env::temp_dir().join(format!("powercap-{}", std::process::id()));
+    let zone = |name: &str, kind: &str, energy: u64| {
+        let dir = powercap.join(name);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("name"), format!("{}\n", kind)).unwrap();
+        std::fs::write(dir.join("energy_uj"), format!("{}\n", energy)).unwrap();
+        std::fs::write(dir.join("max_energy_range_uj"), "1000000\n").unwrap();
+    };
+    zone("intel-rapl:0", "package-0", 900_000);
+    zone("intel-rapl:1", "package-1", 100);
+    // A zone within package 0, already counted in it.
+    zone("intel-rapl:0:0", "core", 5);
+    let mut rapl = Rapl::open(&powercap).unwrap();
+    zone("intel-rapl:0", "package-0", 50_000);
+    zone("intel-rapl:1", "package-1", 400);
+    zone("intel-rapl:0:0", "core", 9_000);
+    let used = rapl.sample().unwrap();
+    std::fs::remove_dir_all(&powercap).ok();
+    assert_eq!(used, 150_000 + 300);
+
+    assert_eq!(parse_power_line("CPU Power: 4210 mW"), Some(4210.0));
+    assert_eq!(parse_power_line("GPU Power: 12 mW"), None);
+}
+
+#[test]
+fn the_energy_budget_is_the_lower_of_kwh_and_cost() {
+    use uniswapv4_challenge_miner::energy::EnergyArgs;
+
+    let args = EnergyArgs { energy: false, max_kwh: Some(3.0), max_cost: Some(0.5), electricity_price: Some(0.25) };
+    assert!(args.metering());
+    assert_eq!(args.budget(), Some(2.0 * 3.6e6));
+    let args = EnergyArgs { max_cost: None, ..args };
+    assert_eq!(args.budget(), Some(3.0 * 3.6e6));
+    assert!(!EnergyArgs { max_kwh: None, ..args }.metering());
+}
@@ -0,0 +1,44 @@
+// Vanity accounts with --mode eoa: found keys are saved encrypted as they are
+// reported, describing a candidate never saves or shows one, and nothing lists
+// keys that were not saved.
+
+use uniswapv4_challenge_miner::eoa;
+use uniswapv4_challenge_miner::mode::Mode;
+
+#[test]
+fn keys_are_saved_when_reported_not_when_described() {
+    let dir = std::env::temp_dir().join(format!("keys-{}", std::process::id()));
+    std::fs::remove_dir_all(&dir).ok();
+    std::env::set_var("EOA_TEST_PASSPHRASE", "correct horse");
+    eoa::init(dir.clone(), "EOA_TEST_PASSPHRASE").unwrap();
+    let key = eoa::random_key();
+    let files = || std::fs::read_dir(&dir).unwrap().map(|entry| entry.unwrap().file_name().into_string().unwrap()).collect::<Vec<_>>();
+
+    let described = Mode::Eoa.describe(&key);
+    assert!(files().is_empty());
+    assert!(!described.contains(&hex::encode(key)), "{}", described);
+
+    let saved = eoa::save(&key);
+    let name = format!("0x{}.json", hex::encode(eoa::address(&key)));
+    assert_eq!(files(), [name.as_str()]);
+    assert_eq!(saved, format!("key: {}", dir.join(&name).display()));
+    let decrypted = eth_keystore::decrypt_key(dir.join(&name), "correct horse").unwrap();
+    std::fs::remove_dir_all(&dir).ok();
+    assert_eq!(decrypted, key);
+
+    let mut salt = [0; 32];
+    salt[..20].copy_from_slice(&[0x11; 20]);
+    salt[24..].copy_from_slice(&7u64.to_be_bytes());
+    assert_eq!(Mode::Create.describe(&salt), format!("sender: 0x{}, nonce: 7", "11".repeat(20)));
+    assert_eq!(Mode::Create2.describe(&salt), format!("salt: 0x{}", hex::encode(salt)));
+}
+
+#[test]
+fn keys_that_are_not_saved_are_not_listed() {
+    let miner = env!("CARGO_BIN_EXE_uniswapv4-challenge-miner");
+    for top in [&["--top", "5"][..], &["--top", "5", "--top-file", "top.txt"]] {
+        let output = std::process::Command::new(miner).args(["--threads", "1", "--mode", "eoa"]).args(top).output().unwrap();
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("--top, --top-file, "), "{}", String::from_utf8_lossy(&output.stderr));
+    }
+}
@@ -0,0 +1,57 @@
+// The estimate subcommand: how long a score takes at a hashrate, and what a
+// number of hours of mining can be expected to reach.
+
+use std::process::Command;
+use std::time::Duration;
+
+use uniswapv4_challenge_miner::deadline::format_countdown;
+use uniswapv4_challenge_miner::odds::{chance_at_least, format_count};
+
+const MINER: &str = env!("CARGO_BIN_EXE_uniswapv4-challenge-miner");
+
+fn estimate(args: &[&str]) -> (bool, String) {
+    let output = Command::new(MINER).arg("estimate").args(args).output().unwrap();
+    (output.status.success(), format!("{}{}", String::from_utf8(output.stdout).unwrap(), String::from_utf8(output.stderr).unwrap()))
+}
+
+#[test]
+fn the_time_to_a_score_follows_its_rarity() {
+    let (ok, output) = estimate(&["--score", "120", "--hashrate", "2.5 MH/s"]);
+    assert!(ok, "{}", output);
+    let p = chance_at_least(120);
+    let time = |hashes: f64| format_countdown(Duration::from_secs_f64(hashes / p / 2.5e6));
+    assert!(output.starts_with("Hashrate: 2.50 MH/s\n"), "{}", output);
+    assert!(output.contains(&format!("Score 120 or more: 1 in {} addresses\n", format_count(1.0 / p))), "{}", output);
+    assert!(output.contains(&format!("Expected time: {}\n", time(1.0))), "{}", output);
+    assert!(output.contains(&format!("Even odds within: {}\n", time(std::f64::consts::LN_2))), "{}", output);
+    assert!(output.contains(&format!("90% chance within: {}\n", time(std::f64::consts::LN_10))), "{}", output);
+
+    assert!(estimate(&["--score", "1", "--hashrate", "1G"]).1.contains("Expected time: under a second"));
+    assert!(estimate(&["--score", "300", "--hashrate", "1"]).1.contains("longer than the universe has existed"));
+    let (ok, output) = estimate(&["--score", "100000", "--hashrate", "1k"]);
+    assert!(!ok && output.contains("no address can score 100000"), "{}", output);
+}
+
+#[test]
+fn the_score_in_some_hours_is_more_likely_the_lower_it_is() {
+    let (ok, output) = estimate(&["--hours", "2", "--hashrate", "1.5GH/s"]);
+    assert!(ok, "{}", output);
+    assert!(output.contains("In 2 hours: 1.08e13 hashes\n"), "{}", output);
+    let at_least = |odds: &str| -> u32 {
+        let line = output.lines().find(|line| line.starts_with(odds)).unwrap();
+        line.rsplit(' ').next().unwrap().parse().unwrap()
+    };
+    let expected: f64 = output.lines().find_map(|line| line.strip_prefix("Expected best score: ")).unwrap().parse().unwrap();
+    let (ninety, even, ten) = (at_least("90% chance"), at_least("Even odds"), at_least("10% chance"));
+    assert!(ninety <= even && even <= ten && (ninety as f64) < expected && expected < ten as f64, "{}", output);
+    // As likely as not to get there, and not to get any further.
+    let hashes = 1.5e9 * 7200.0;
+    let reaching = |score: u32| -f64::exp_m1(hashes * f64::ln_1p(-chance_at_least(score)));
+    assert!(reaching(even) >= 0.5 && reaching(even + 1) < 0.5);
+
+    for hashrate in ["fast", "0", "5 XH/s", "inf"] {
+        let (ok, output) = estimate(&["--hours", "1", "--hashrate", hashrate]);
+        assert!(!ok && output.contains("is not a hashrate like 50MH/s"), "{}: {}", hashrate, output);
+    }
+    assert!(estimate(&["--hours", "0", "--hashrate", "1M"]).1.contains("--hours must be positive"));
+}
@@ -0,0 +1,40 @@
+// The status line printed every --status-interval while a search runs.
+
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+const MINER: &str = env!("CARGO_BIN_EXE_uniswapv4-challenge-miner");
+
+#[test]
+fn status_lines_report_progress_and_the_best_so_far() {
+    let mut child = Command::new(MINER).args(["--threads", "1", "--status-interval", "1s"]).stdout(Stdio::piped()).spawn().unwrap();
+    let stdout = child.stdout.take().unwrap();
+    let (lines, received) = mpsc::channel();
+    thread::spawn(move || {
+        for line in BufReader::new(stdout).lines() {
+            if lines.send(line.unwrap()).is_err() {
+                break;
+            }
+        }
+    });
+    let mut scores = Vec::new();
+    let status = loop {
+        let line = received.recv_timeout(Duration::from_secs(30)).expect("no status line");
+        if line.starts_with("Status: ") {
+            break line;
+        }
+        if let Some(rest) = line.strip_prefix("New best address: ") {
+            scores.push(rest.split("score: ").nth(1).unwrap().split_whitespace().next().unwrap().trim_end_matches(',').to_string());
+        }
+    };
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    assert!(status.contains(" elapsed, ") && status.contains(" hashes at "), "{}", status);
+    // A new best found since the status was taken may be printed first.
+    let best = status.split("best score ").nth(1).expect(&status).split_whitespace().next().unwrap();
+    assert!(scores.iter().any(|score| score == best), "{} not in {:?}", status, scores);
+}
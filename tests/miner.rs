@@ -0,0 +1,333 @@
+// The library's Miner against a brute force over the same candidates.
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use uniswapv4_challenge_miner::error::MinerError;
+use uniswapv4_challenge_miner::miner::Miner;
+use uniswapv4_challenge_miner::mode::Mode;
+use uniswapv4_challenge_miner::salts::{SaltPosition, Sequential};
+use uniswapv4_challenge_miner::schema::{BestResult, Checkpoint, CounterRange, JobSpec, SaltSpec};
+use uniswapv4_challenge_miner::score::{wins_tie, LeadingZeroBytes, Scorer, Tiebreak};
+use uniswapv4_challenge_miner::{compute_score, create2_addr, create_addr, decode_hex, Candidate, DEPLOYER_ADDRESS, INITCODE_HASH};
+
+#[test]
+fn create_search_finds_the_best_nonce() {
+    let sender = decode_hex::<20>("0x6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0").unwrap();
+    let max_nonce = 20_000;
+    let expected = (0..=max_nonce).map(|nonce| compute_score(&create_addr(&sender, nonce))).max().unwrap();
+
+    let reported = Arc::new(Mutex::new(Vec::new()));
+    let miner = {
+        let reported = Arc::clone(&reported);
+        Miner::builder()
+            .mode(Mode::Create)
+            .senders(vec![sender], max_nonce)
+            .threads(3)
+            .on_new_best(move |c| reported.lock().unwrap().push(c.score))
+            .run()
+            .unwrap()
+    };
+    let best = miner.wait().unwrap().unwrap();
+
+    assert_eq!(best.score, expected);
+    assert_eq!(create_addr(&sender, u64::from_be_bytes(best.salt[24..].try_into().unwrap())), best.address);
+    let reported = reported.lock().unwrap();
+    assert_eq!(reported.last(), Some(&expected));
+    assert!(reported.windows(2).all(|w| w[0] < w[1]));
+}
+
+// The first two bytes of the address, so few CREATE addresses tie.
+struct FirstBytes;
+
+impl Scorer for FirstBytes {
+    fn score(&self, address: &[u8; 20]) -> u32 {
+        u16::from_be_bytes([address[0], address[1]]) as u32
+    }
+}
+
+#[test]
+fn each_miner_keeps_its_own_top() {
+    let senders = [[0x11; 20], [0x22; 20]];
+    let max_nonce = 5_000;
+    // Side by side, as two embedders in one process would run them.
+    let miners: Vec<_> = senders
+        .iter()
+        .zip([5, 3])
+        .map(|(sender, size)| {
+            Miner::builder()
+                .mode(Mode::Create)
+                .senders(vec![*sender], max_nonce)
+                .scorer(FirstBytes)
+                .top(size)
+                .threads(2)
+                .run()
+                .unwrap()
+        })
+        .collect();
+    for ((miner, sender), size) in miners.into_iter().zip(senders).zip([5, 3]) {
+        while !miner.stats().finished {
+            thread::sleep(Duration::from_millis(20));
+        }
+        let mut expected: Vec<u32> = (0..=max_nonce).map(|nonce| FirstBytes.score(&create_addr(&sender, nonce))).collect();
+        expected.sort_unstable_by(|a, b| b.cmp(a));
+        let top = miner.top();
+        assert_eq!(top.iter().map(|c| c.score).collect::<Vec<_>>(), expected[..size]);
+        let nonce = |c: &Candidate| u64::from_be_bytes(c.salt[24..].try_into().unwrap());
+        assert!(top.iter().all(|c| c.salt[..20] == sender && create_addr(&sender, nonce(c)) == c.address));
+        let salts = |candidates: &[Candidate]| candidates.iter().map(|c| c.salt).collect::<Vec<_>>();
+        assert_eq!(salts(&miner.snapshot().top), salts(&top));
+        assert_eq!(miner.wait().unwrap().map(|c| c.score), Some(expected[0]));
+    }
+}
+
+#[test]
+fn miners_side_by_side_count_and_derive_their_own() {
+    let create2 = Miner::builder().threads(1).run().unwrap();
+    let sender = [0x33; 20];
+    let max_nonce = 20_000;
+    let create = Miner::builder().mode(Mode::Create).senders(vec![sender], max_nonce).threads(2).run().unwrap();
+    while !create.stats().finished {
+        thread::sleep(Duration::from_millis(20));
+    }
+    // None of the other miner's hashes, though it was hashing all along.
+    assert_eq!(create.stats().hashes, max_nonce + 1);
+    assert!(!create2.stats().finished && create2.stats().hashes > 0);
+    let best = create.wait().unwrap().unwrap();
+    assert_eq!(create_addr(&sender, u64::from_be_bytes(best.salt[24..].try_into().unwrap())), best.address);
+
+    let started = Instant::now();
+    while create2.stats().best.is_none() {
+        assert!(started.elapsed() < Duration::from_secs(60), "no progress");
+        thread::sleep(Duration::from_millis(20));
+    }
+    let best = create2.stop().unwrap().unwrap();
+    assert_eq!(create2_addr(&DEPLOYER_ADDRESS, &best.salt, &INITCODE_HASH), best.address);
+}
+
+#[test]
+fn stopped_miner_reports_progress() {
+    let miner = Miner::builder().threads(2).run().unwrap();
+    let started = Instant::now();
+    while miner.stats().hashes == 0 || miner.stats().best.is_none() {
+        assert!(started.elapsed() < Duration::from_secs(60), "no progress");
+        thread::sleep(Duration::from_millis(20));
+    }
+    let threads = miner.thread_stats();
+    assert_eq!(threads.len(), 2);
+    assert!(threads.iter().any(|t| t.hashes > 0 && t.hashrate > 0.0), "{:?}", threads);
+    miner.pause();
+    let stats = miner.stats();
+    assert!(stats.paused && !stats.finished);
+    miner.resume();
+    assert!(miner.stop().unwrap().is_some());
+}
+
+#[test]
+fn panicking_callback_fails_the_search() {
+    let errors = Arc::new(Mutex::new(Vec::new()));
+    let miner = {
+        let errors = Arc::clone(&errors);
+        Miner::builder()
+            .salts(Sequential { submitter: [0x11; 20], start: 0, end: Some(10_000) })
+            .threads(2)
+            .on_new_best(|_| panic!("callback failed"))
+            .on_error(move |e| errors.lock().unwrap().push(e.to_string()))
+            .run()
+            .unwrap()
+    };
+    assert!(matches!(miner.wait(), Err(MinerError::SearchPanicked)));
+    assert_eq!(*errors.lock().unwrap(), ["the search thread panicked"]);
+}
+
+#[test]
+fn events_reach_every_sink() {
+    let bests = Arc::new(Mutex::new(Vec::new()));
+    let progress = Arc::new(Mutex::new(Vec::new()));
+    let events = Arc::new(Mutex::new(0));
+    let miner = {
+        let (bests, progress, events) = (Arc::clone(&bests), Arc::clone(&progress), Arc::clone(&events));
+        Miner::builder()
+            .salts(Sequential { submitter: [0x11; 20], start: 0, end: Some(50_000) })
+            .threads(2)
+            .on_new_best(move |c| bests.lock().unwrap().push(*c))
+            .on_progress(move |stats| progress.lock().unwrap().push(*stats))
+            .progress_interval(Duration::from_millis(10))
+            .subscribe(move |_| *events.lock().unwrap() += 1)
+            .run()
+            .unwrap()
+    };
+    let best = miner.wait().unwrap().unwrap();
+
+    let bests = bests.lock().unwrap();
+    assert_eq!(bests.last().map(|c| c.address), Some(best.address));
+    let progress = progress.lock().unwrap();
+    let last = progress.last().unwrap();
+    assert!(last.finished && last.hashes > 0);
+    assert!(progress.windows(2).all(|w| w[0].hashes <= w[1].hashes));
+    assert_eq!(last.best.map(|c| c.address), Some(best.address));
+    assert!(progress.iter().rev().skip(1).all(|stats| !stats.finished));
+    assert_eq!(*events.lock().unwrap(), bests.len() + progress.len());
+}
+
+#[test]
+fn the_last_partial_batch_is_counted() {
+    let progress = Arc::new(Mutex::new(Vec::new()));
+    let miner = {
+        let progress = Arc::clone(&progress);
+        Miner::builder()
+            .salts(Sequential { submitter: [0x11; 20], start: 0, end: Some(10_000) })
+            .threads(2)
+            .on_progress(move |stats| progress.lock().unwrap().push(*stats))
+            .progress_interval(Duration::from_millis(10))
+            .run()
+            .unwrap()
+    };
+    miner.wait().unwrap();
+    let last = *progress.lock().unwrap().last().unwrap();
+    assert!(last.finished);
+    assert_eq!(last.hashes, 10_000);
+}
+
+#[test]
+fn sequential_salts_cover_the_range() {
+    let submitter = [0x11; 20];
+    let deployer = decode_hex::<20>("0x48E516B34A1274f49457b9C6182097796D0498Cb").unwrap();
+    let code_hash = [0x22; 32];
+    let salt = |counter: u64| {
+        let mut salt = [0; 32];
+        salt[..20].copy_from_slice(&submitter);
+        salt[24..].copy_from_slice(&counter.to_be_bytes());
+        salt
+    };
+    let expected = (100..20_100).map(|counter| compute_score(&create2_addr(&deployer, &salt(counter), &code_hash))).max().unwrap();
+
+    let best = Miner::builder()
+        .deployer(deployer)
+        .code_hash(code_hash)
+        .salts(Sequential { submitter, start: 100, end: Some(20_100) })
+        .threads(3)
+        .run()
+        .unwrap()
+        .wait()
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(best.score, expected);
+    assert_eq!(best.salt[..24], salt(0)[..24]);
+    assert_eq!(create2_addr(&deployer, &best.salt, &code_hash), best.address);
+}
+
+#[test]
+fn stream_yields_every_candidate_above_the_threshold() {
+    let sender = decode_hex::<20>("0x6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0").unwrap();
+    let expected: Vec<u64> = (0..=20_000).filter(|nonce| compute_score(&create_addr(&sender, *nonce)) >= 20).collect();
+
+    let stream = Miner::builder().mode(Mode::Create).senders(vec![sender], 20_000).threads(3).stream(20).unwrap();
+    let mut found: Vec<u64> = stream
+        .inspect(|c| assert_eq!(compute_score(&c.address), c.score))
+        .map(|c| u64::from_be_bytes(c.salt[24..].try_into().unwrap()))
+        .collect();
+
+    found.sort();
+    assert!(!expected.is_empty());
+    assert_eq!(found, expected);
+}
+
+#[test]
+fn dropping_a_stream_stops_the_search() {
+    let mut stream = Miner::builder().threads(2).stream(0).unwrap();
+    let first: Vec<_> = stream.by_ref().take(500).collect();
+    assert_eq!(first.len(), 500);
+    assert!(!stream.miner().stats().finished);
+    drop(stream);
+}
+
+#[test]
+fn job_specs_and_results_round_trip_through_json() {
+    let job = JobSpec { salts: SaltSpec::Sequential { start: 0, end: Some(5_000) }, ..JobSpec::default() };
+    let json = serde_json::to_string(&job).unwrap();
+    assert!(json.contains(r#""version":1"#) && json.contains(r#""strategy":"sequential""#));
+    let job: JobSpec = serde_json::from_str(&json).unwrap();
+
+    let miner = job.builder().unwrap().threads(2).run().unwrap();
+    while !miner.stats().finished {
+        thread::sleep(Duration::from_millis(20));
+    }
+    let result = BestResult::new(job.clone(), &miner.stats());
+    let best = miner.wait().unwrap().unwrap();
+    let result: BestResult = serde_json::from_str(&serde_json::to_string(&result).unwrap()).unwrap();
+    assert_eq!(result.job, job);
+    assert_eq!(result.best.map(|c| (c.salt, c.address, c.score)), Some((best.salt, best.address, best.score)));
+
+    let newer = json.replace(r#""version":1"#, r#""version":2"#);
+    assert!(serde_json::from_str::<JobSpec>(&newer).is_err());
+}
+
+#[test]
+fn resumed_checkpoint_finishes_the_range() {
+    let submitter = [0x33; 20];
+    let deployer = decode_hex::<20>("0x48E516B34A1274f49457b9C6182097796D0498Cb").unwrap();
+    let code_hash = [0x44; 32];
+    let salts = Sequential { submitter, start: 1_000, end: Some(61_000) };
+    let expected = (1_000..61_000).map(|counter| compute_score(&create2_addr(&deployer, &salts.salt(counter), &code_hash))).max().unwrap();
+    let builder = || Miner::builder().deployer(deployer).code_hash(code_hash);
+
+    let miner = builder().salts(salts).threads(2).run().unwrap();
+    let started = Instant::now();
+    let checkpoint = loop {
+        let checkpoint = miner.snapshot();
+        let Some(SaltPosition::Sequential { start: 1_000, next, end: Some(61_000), .. }) = checkpoint.salts else {
+            panic!("unexpected position {:?}", checkpoint.salts);
+        };
+        if next > 1_000 {
+            break checkpoint;
+        }
+        assert!(started.elapsed() < Duration::from_secs(60), "no progress");
+        thread::sleep(Duration::from_millis(5));
+    };
+    miner.stop().unwrap();
+    assert!(checkpoint.hashes > 0);
+    let checkpoint: Checkpoint = serde_json::from_str(&serde_json::to_string(&checkpoint).unwrap()).unwrap();
+
+    let miner = builder().resume_from(checkpoint).threads(3).run().unwrap();
+    while !miner.stats().finished {
+        thread::sleep(Duration::from_millis(20));
+    }
+    let done = miner.snapshot();
+    assert_eq!(done.covered, [CounterRange { start: 1_000, end: 61_000 }]);
+    assert_eq!(miner.wait().unwrap().unwrap().score, expected);
+}
+
+#[test]
+fn ties_go_the_same_way_however_the_search_is_split() {
+    let candidate = |address: [u8; 20], salt: u8| Candidate { salt: [salt; 32], address, score: 1 };
+    let (zeros, fewer) = (candidate([0; 20], 9), candidate([0x0f; 20], 1));
+    assert!(wins_tie(&[Tiebreak::LeadingZeros], &zeros, &fewer) && !wins_tie(&[Tiebreak::LeadingZeros], &fewer, &zeros));
+    assert!(wins_tie(&[Tiebreak::LowestSalt], &fewer, &zeros) && wins_tie(&[Tiebreak::LowestAddress], &zeros, &fewer));
+    // Rules apply in order, and the earliest keeps what was found first.
+    let (low, high) = (candidate([0x01; 20], 2), candidate([0x02; 20], 1));
+    assert!(wins_tie(&[Tiebreak::LeadingZeros, Tiebreak::LowestSalt], &high, &low));
+    assert!(!wins_tie(&[Tiebreak::LeadingZeros, Tiebreak::Earliest, Tiebreak::LowestSalt], &high, &low));
+    assert!(!wins_tie(&[], &high, &low) && !wins_tie(&[Tiebreak::LowestSalt], &low, &low));
+
+    // Leading zero bytes tie often; the lowest of the addresses with the most wins.
+    let sender = decode_hex::<20>("0x6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0").unwrap();
+    let max_nonce = 20_000;
+    let addresses = (0..=max_nonce).map(|nonce| create_addr(&sender, nonce));
+    let most = addresses.clone().map(|address| LeadingZeroBytes.score(&address)).max().unwrap();
+    let tied: Vec<_> = addresses.filter(|address| LeadingZeroBytes.score(address) == most).collect();
+    assert!(tied.len() > 1);
+    for threads in [1, 4] {
+        let miner = Miner::builder()
+            .mode(Mode::Create)
+            .senders(vec![sender], max_nonce)
+            .scorer(LeadingZeroBytes)
+            .tiebreak(vec![Tiebreak::LowestAddress])
+            .threads(threads)
+            .run()
+            .unwrap();
+        assert_eq!(miner.wait().unwrap().unwrap().address, *tied.iter().min().unwrap(), "{} threads", threads);
+    }
+}
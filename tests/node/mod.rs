@@ -0,0 +1,69 @@
+// A JSON-RPC node answering from a closure, for what reads the chain without
+// needing anvil: each request gets its method and params, and answers with a
+// result or, as `Err`, the node's error object.
+
+#![allow(dead_code)]
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::thread;
+
+use serde_json::{json, Value};
+use uniswapv4_challenge_miner::rpc::RpcClient;
+
+pub struct Node {
+    pub url: String,
+}
+
+impl Node {
+    pub fn serve(answer: impl Fn(&str, &Value) -> Result<Value, Value> + Send + Sync + 'static) -> Node {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let answer = Arc::new(answer);
+        thread::spawn(move || {
+            for stream in listener.incoming().map_while(Result::ok) {
+                let answer = Arc::clone(&answer);
+                thread::spawn(move || {
+                    let mut reader = BufReader::new(&stream);
+                    // One request a connection, told to close after it.
+                    let mut length = 0;
+                    let mut line = String::new();
+                    while reader.read_line(&mut line).is_ok_and(|read| read > 2) {
+                        if let Some((name, value)) = line.split_once(':') {
+                            if name.eq_ignore_ascii_case("content-length") {
+                                length = value.trim().parse().unwrap();
+                            }
+                        }
+                        line.clear();
+                    }
+                    let mut body = vec![0; length];
+                    reader.read_exact(&mut body).unwrap();
+                    let request: Value = serde_json::from_slice(&body).unwrap();
+                    let response = match answer(request["method"].as_str().unwrap(), &request["params"]) {
+                        Ok(result) => json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }),
+                        Err(error) => json!({ "jsonrpc": "2.0", "id": request["id"], "error": error }),
+                    };
+                    let response = response.to_string();
+                    let head = format!("HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", response.len());
+                    (&stream).write_all(format!("{}{}", head, response).as_bytes()).ok();
+                });
+            }
+        });
+        Node { url }
+    }
+
+    pub fn rpc(&self) -> RpcClient {
+        RpcClient::new(std::slice::from_ref(&self.url))
+    }
+}
+
+// The 32-byte word an address is returned as.
+pub fn address_word(address: &[u8; 20]) -> Value {
+    json!(format!("0x{:0>64}", hex::encode(address)))
+}
+
+// The 32-byte word a number is returned as.
+pub fn number_word(number: u64) -> Value {
+    json!(format!("0x{:064x}", number))
+}
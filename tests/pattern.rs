@@ -0,0 +1,118 @@
+// Matching addresses against what was asked for instead of scoring them:
+// hook permission bits, regexes, and prefixes, suffixes and other hex digits
+// compared with or without the checksum.
+
+use clap::Parser;
+use uniswapv4_challenge_miner::pattern::{parse_hook_flags, Pattern, PatternArgs};
+
+#[derive(Parser)]
+#[command(no_binary_name = true)]
+struct Cli {
+    #[command(flatten)]
+    pattern: PatternArgs,
+}
+
+fn pattern(args: &[&str]) -> Pattern {
+    Cli::try_parse_from(args).unwrap().pattern.pattern().unwrap()
+}
+
+// An address ending in `low`, the bits hook permissions are read from.
+fn ending_in(low: u16) -> [u8; 20] {
+    let mut address = [0x5a; 20];
+    address[18..].copy_from_slice(&low.to_be_bytes());
+    address
+}
+
+#[test]
+fn hook_flags_parse_by_name_or_number() {
+    for value in ["BEFORE_SWAP|AFTER_SWAP", "before_swap | After_Swap", "BEFORE_SWAP_FLAG|AFTER_SWAP_FLAG", "0xc0", "192"] {
+        assert_eq!(parse_hook_flags(value), Ok(0xc0), "{}", value);
+    }
+    assert_eq!(parse_hook_flags("BEFORE_INITIALIZE"), Ok(1 << 13));
+    assert_eq!(parse_hook_flags("AFTER_REMOVE_LIQUIDITY_RETURNS_DELTA_FLAG"), Ok(1));
+    assert!(parse_hook_flags("BEFORE_SWAPS").unwrap_err().contains("unknown hook flag"));
+
+    // Only the low 14 bits are permissions.
+    assert_eq!(parse_hook_flags("0x3fff"), Ok(0x3fff));
+    assert_eq!(parse_hook_flags("16383"), Ok(0x3fff));
+    for value in ["0x4000", "16384", "0xffff"] {
+        assert!(parse_hook_flags(value).unwrap_err().contains("above the 14 hook permission bits"), "{}", value);
+    }
+}
+
+#[test]
+fn exact_hook_flags_leave_no_other_permission_set() {
+    let loose = pattern(&["--hook-flags", "BEFORE_SWAP|AFTER_SWAP"]);
+    let exact = pattern(&["--hook-flags", "BEFORE_SWAP|AFTER_SWAP", "--exact-hook-flags"]);
+
+    assert!(loose.matches(&ending_in(0x00c0)) && exact.matches(&ending_in(0x00c0)));
+    // Another permission as well, which PoolManager would hold against it.
+    assert!(loose.matches(&ending_in(0x20c1)));
+    assert!(!exact.matches(&ending_in(0x20c1)));
+    // Half the permissions asked for.
+    assert!(!loose.matches(&ending_in(0x0080)) && !exact.matches(&ending_in(0x0080)));
+    // The two bits above the permissions are not compared either way.
+    assert!(loose.matches(&ending_in(0xc0c0)) && exact.matches(&ending_in(0xc0c0)));
+
+    // No permissions at all, exactly.
+    let none = pattern(&["--hook-flags", "0", "--exact-hook-flags"]);
+    assert!(none.matches(&ending_in(0xc000)));
+    assert!(!none.matches(&ending_in(0x0001)));
+}
+
+#[test]
+fn checksummed_digits_match_in_their_case() {
+    // From EIP-55's examples.
+    let address: [u8; 20] = hex::decode("5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed").unwrap().try_into().unwrap();
+    let matches = |args: &[&str]| pattern(args).matches(&address);
+
+    for args in [&["--prefix", "5aAe"][..], &["--suffix", "BeAed"], &["--contains", "F3E94C9"], &["--pattern-regex", "^5aAe.*BeAed$"]] {
+        assert!(matches(&[args, &["--checksummed"]].concat()), "{:?}", args);
+    }
+    for args in [&["--prefix", "5aae"][..], &["--suffix", "BEAED"], &["--contains", "f3e94c9"], &["--pattern-regex", "^5aae"]] {
+        assert!(!matches(&[args, &["--checksummed"]].concat()), "{:?}", args);
+    }
+    // Without it the digits are any case, and the regex sees lowercase.
+    assert!(matches(&["--prefix", "5AAE", "--suffix", "beaed", "--contains", "F3e94c9"]));
+    assert!(matches(&["--pattern-regex", "^5aae"]));
+    assert!(!matches(&["--pattern-regex", "^5aAe"]));
+    // Every condition has to hold in its case.
+    assert!(!matches(&["--prefix", "5aAe", "--suffix", "beaed", "--checksummed"]));
+}
+
+#[test]
+fn regexes_match_the_lowercase_hex() {
+    let fours = pattern(&["--pattern-regex", "^0{8}4{4}.*4444$"]);
+    let address = |hex: &str| -> [u8; 20] { hex::decode(hex).unwrap().try_into().unwrap() };
+
+    assert!(fours.matches(&address("0000000044445a5a5a5a5a5a5a5a5a5a5a5a4444")));
+    assert!(!fours.matches(&address("0000000044445a5a5a5a5a5a5a5a5a5a5a5a4443")));
+    assert!(!fours.matches(&address("0000000144445a5a5a5a5a5a5a5a5a5a5a5a4444")));
+    // Forty digits and no 0x, looked for anywhere unless anchored.
+    assert!(pattern(&["--pattern-regex", "^[0-9a-f]{40}$"]).matches(&[0xab; 20]));
+    assert!(!pattern(&["--pattern-regex", "0x"]).matches(&[0; 20]));
+    assert!(pattern(&["--pattern-regex", "5a5a"]).matches(&[0x5a; 20]));
+    assert!(Cli::try_parse_from(["--pattern-regex", "(4"]).is_err());
+}
+
+#[test]
+fn prefixes_suffixes_and_contained_digits_all_have_to_match() {
+    let address: [u8; 20] = hex::decode("00000000dead5a5a5a5a5a5a5a5a5a5a5a5abeef").unwrap().try_into().unwrap();
+    let matches = |args: &[&str]| pattern(args).matches(&address);
+
+    // Odd numbers of digits and a leading 0x are fine.
+    assert!(matches(&["--prefix", "0x0000000"]));
+    assert!(matches(&["--suffix", "eef"]));
+    assert!(matches(&["--contains", "dead", "--contains", "a5a5"]));
+    assert!(matches(&["--prefix", "00000000d", "--suffix", "beef", "--contains", "dead"]));
+    assert!(!matches(&["--prefix", "000000000"]));
+    assert!(!matches(&["--suffix", "dead"]));
+    assert!(!matches(&["--contains", "dead", "--contains", "cafe"]));
+    assert!(!matches(&["--prefix", "00000000", "--suffix", "beee"]));
+    // The whole address is a prefix of itself.
+    assert!(matches(&["--prefix", "00000000dead5a5a5a5a5a5a5a5a5a5a5a5abeef"]));
+
+    for value in ["", "0x", "dEaDg", &"0".repeat(41)] {
+        assert!(Cli::try_parse_from(["--prefix", value]).is_err(), "{:?}", value);
+    }
+}
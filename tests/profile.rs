@@ -0,0 +1,49 @@
+// Scoring profiles read from JSON, the presets shipped by name and version,
+// and the points they may add up to.
+
+use uniswapv4_challenge_miner::profile::Profile;
+use uniswapv4_challenge_miner::compute_score;
+use uniswapv4_challenge_miner::score::Scorer;
+
+#[test]
+fn profiles_that_could_overflow_a_score_are_rejected() {
+    let each = |points: u64| format!(r#"{{"runs": [{{"nibble": "0", "each": {}}}]}}"#, points);
+    // 40 zeros at the most.
+    let most = Profile::parse(&each(u32::MAX as u64 / 40)).unwrap();
+    assert_eq!(most.score(&[0; 20]), u32::MAX / 40 * 40);
+    let error = Profile::parse(&each(u32::MAX as u64 / 40 + 1)).err().unwrap().to_string();
+    assert!(error.contains("more than 4294967295"), "{}", error);
+
+    // Every bonus can apply together.
+    let bonuses = |first: u32| {
+        format!(r#"{{"each": {{"a": 1}}, "patterns": [{{"at": 0, "hex": "a", "points": {}}}, {{"at": -1, "hex": "a", "points": 1}}]}}"#, first)
+    };
+    assert_eq!(Profile::parse(&bonuses(u32::MAX - 41)).unwrap().score(&[0xaa; 20]), u32::MAX);
+    assert!(Profile::parse(&bonuses(u32::MAX - 40)).is_err());
+
+    // Only one exact length of a run applies, at least lengths all of them.
+    let exactly = format!(r#"{{"runs": [{{"nibble": "0", "exactly": {{"1": {0}, "2": {0}}}}}]}}"#, u32::MAX);
+    assert!(Profile::parse(&exactly).is_ok());
+    assert!(Profile::parse(&exactly.replace("exactly", "at_least")).is_err());
+}
+
+#[test]
+fn presets_are_named_and_versioned() {
+    for name in ["uniswap-v4-challenge", "uniswap-v4-challenge@1", "uniswap-v4", "uniswap-v4@1"] {
+        let profile = Profile::load(name).unwrap();
+        assert_eq!(profile.id.as_deref(), Some("uniswap-v4-challenge@1"), "{}", name);
+        for address in [[0; 20], [0x44; 20], hex::decode("0000000044445a5a5a5a5a5a5a5a5a5a5a5a4444").unwrap().try_into().unwrap()] {
+            assert_eq!(profile.score(&address), compute_score(&address), "{}", name);
+        }
+    }
+    for name in ["uniswap-v4-challenge@2", "uniswap-v4-challenge@latest"] {
+        let error = Profile::load(name).err().unwrap().to_string();
+        assert!(error.contains("available: uniswap-v4-challenge@1"), "{}", error);
+    }
+
+    // A profile file names and versions itself.
+    let named = |fields: &str| Profile::parse(&format!(r#"{{{}"each": {{"4": 1}}}}"#, fields)).unwrap().id;
+    assert_eq!(named(r#""name": "fours", "version": 3, "#).as_deref(), Some("fours@3"));
+    assert_eq!(named(r#""name": "fours", "#).as_deref(), Some("fours"));
+    assert_eq!(named(""), None);
+}
@@ -0,0 +1,45 @@
+// Proofs of discovery: the signed message recovers to the submitter, and
+// they are the same for the same salt, key and time.
+
+use std::process::Command;
+
+use alloy_consensus::private::alloy_primitives::{Address, Signature};
+
+const MINER: &str = env!("CARGO_BIN_EXE_uniswapv4-challenge-miner");
+
+// First of anvil's default dev accounts.
+const KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+const ADDRESS: &str = "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266";
+
+fn prove(salt: &str) -> serde_json::Value {
+    let output = Command::new(MINER).args(["prove", "--salt", salt, "--timestamp", "1700000000"]).env("PRIVATE_KEY", KEY).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+#[test]
+fn proofs_recover_to_the_signer() {
+    let salt = format!("{}{:0>24}", ADDRESS, 7);
+    let proof = prove(&salt);
+    let message = proof["message"].as_str().unwrap();
+    assert!(message.contains("\nTimestamp: 1700000000 (2023-11-14T22:13:20Z)"), "{}", message);
+    assert_eq!(proof["signer"], ADDRESS);
+
+    let signature: Signature = proof["signature"].as_str().unwrap().parse().unwrap();
+    let recovered = signature.recover_address_from_msg(message).unwrap();
+    assert_eq!(recovered, ADDRESS.parse::<Address>().unwrap());
+    // Signing is deterministic, so the proof can be made again to compare.
+    assert_eq!(prove(&salt)["signature"], proof["signature"]);
+}
+
+#[test]
+fn signed_messages_recover_to_the_key() {
+    use uniswapv4_challenge_miner::tx::{key_address, sign_message};
+
+    let key = k256::ecdsa::SigningKey::from_slice(&hex::decode(&KEY[2..]).unwrap()).unwrap();
+    assert_eq!(key_address(&key), ADDRESS.parse::<Address>().unwrap().0 .0);
+    let signature = sign_message(&key, b"hello").unwrap();
+    assert!([27, 28].contains(&signature[64]));
+    let recovered = Signature::try_from(&signature[..]).unwrap().recover_address_from_msg(b"hello").unwrap();
+    assert_eq!(recovered, ADDRESS.parse::<Address>().unwrap());
+}
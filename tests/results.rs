@@ -0,0 +1,216 @@
+// What a run leaves behind: candidate logs, statistics, checkpoints,
+// reports and the history database, and files written whole.
+
+use std::time::Duration;
+
+use uniswapv4_challenge_miner::salts::Sequential;
+use uniswapv4_challenge_miner::schema::Checkpoint;
+use uniswapv4_challenge_miner::{persist, results, Candidate, B256};
+
+#[test]
+fn candidate_log_appends_json_lines() {
+    let path = std::env::temp_dir().join(format!("candidates-{}.jsonl", std::process::id()));
+    std::fs::remove_file(&path).ok();
+    let candidates: Vec<Candidate> = (0..3u8).map(|n| Candidate { salt: [n; 32], address: [0x44 + n; 20], score: 100 + n as u32 }).collect();
+    results::CandidateLog::open(&path, results::Format::JsonLines).unwrap().record(&candidates[0]);
+    // Reopening adds to what is there.
+    let log = results::CandidateLog::open(&path, results::Format::JsonLines).unwrap();
+    log.record(&candidates[1]);
+    log.record(&candidates[2]);
+
+    let lines: Vec<serde_json::Value> = std::fs::read_to_string(&path).unwrap().lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+    std::fs::remove_file(&path).ok();
+    assert_eq!(lines.len(), 3);
+    for (line, candidate) in lines.iter().zip(&candidates) {
+        let read: Candidate = serde_json::from_value(line.clone()).unwrap();
+        assert_eq!((read.salt, read.address, read.score), (candidate.salt, candidate.address, candidate.score));
+        assert!(line["timestamp"].as_u64().unwrap() > 1_700_000_000);
+        assert_eq!(line["host"], results::hostname());
+    }
+}
+
+#[test]
+fn candidate_logs_skip_results_already_recorded() {
+    for format in [results::Format::JsonLines, results::Format::Csv] {
+        let path = std::env::temp_dir().join(format!("seen-{}-{:?}", std::process::id(), format));
+        std::fs::remove_file(&path).ok();
+        let first = Candidate { salt: [1; 32], address: [0x44; 20], score: 120 };
+        results::CandidateLog::open(&path, format).unwrap().record(&first);
+        // After a restart: the same salt, the same address, then something new.
+        let log = results::CandidateLog::open(&path, format).unwrap();
+        log.record(&first);
+        log.record(&Candidate { salt: [2; 32], ..first });
+        log.record(&Candidate { salt: [3; 32], address: [0x45; 20], score: 110 });
+        log.record(&Candidate { salt: [3; 32], address: [0x45; 20], score: 110 });
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        let header = usize::from(format == results::Format::Csv);
+        assert_eq!(contents.lines().count(), header + 2, "{}", contents);
+    }
+}
+
+#[test]
+fn results_are_stamped_with_where_they_came_from() {
+    use uniswapv4_challenge_miner::salts::{Origin, Random, SaltGenerator};
+
+    let sequential = Sequential { submitter: [0x11; 20], start: 0, end: None };
+    assert_eq!(sequential.origin(&sequential.salt(42)), Some(Origin::Sequential { counter: 42 }));
+    assert_eq!(sequential.origin(&[0x22; 32]), None);
+    let random = Random::new([0x11; 20]);
+    let salt = random.salts(0, 1).nth(5).unwrap();
+    let origin = random.origin(&salt).unwrap();
+    assert!(matches!(origin, Origin::Random { counter: 5, .. }), "{:?}", origin);
+    assert!(origin.to_string().starts_with("random pepper 0x"), "{}", origin);
+
+    let path = std::env::temp_dir().join(format!("stamped-{}.jsonl", std::process::id()));
+    std::fs::remove_file(&path).ok();
+    results::CandidateLog::open(&path, results::Format::JsonLines).unwrap().record(&Candidate { salt, address: [0x44; 20], score: 120 });
+    let entry: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    std::fs::remove_file(&path).ok();
+    assert_eq!(entry["engine"], concat!("uniswapv4-challenge-miner ", env!("CARGO_PKG_VERSION")));
+    assert_eq!(entry["backend"], format!("cpu-{}", std::env::consts::ARCH));
+    assert!(entry["host"].is_string(), "{}", entry);
+}
+
+#[test]
+fn concurrent_writes_to_one_file_each_land_whole() {
+    let dir = std::env::temp_dir().join(format!("concurrent-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("checkpoint.json");
+    let contents = |writer: usize| format!("{}\n", writer.to_string().repeat(10_000 + writer));
+    std::thread::scope(|scope| {
+        for writer in 0..4 {
+            let (path, contents) = (&path, contents(writer));
+            scope.spawn(move || (0..50).for_each(|_| persist::write(path, &contents).unwrap()));
+        }
+    });
+    let last = std::fs::read_to_string(&path).unwrap();
+    let left = std::fs::read_dir(&dir).unwrap().count();
+    std::fs::remove_dir_all(&dir).ok();
+    assert!((0..4).any(|writer| last == contents(writer)));
+    assert_eq!(left, 1);
+}
+
+#[test]
+fn csv_files_get_one_header() {
+    let path = std::env::temp_dir().join(format!("stats-{}.csv", std::process::id()));
+    std::fs::remove_file(&path).ok();
+    results::StatsCsv::open(&path).unwrap().record(4096, 1234.4, None);
+    results::StatsCsv::open(&path).unwrap().record(8192, 2000.0, Some(130));
+    let csv = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).ok();
+    let rows: Vec<Vec<&str>> = csv.lines().map(|line| line.split(',').collect()).collect();
+    assert_eq!(rows[0], ["timestamp", "hashes", "hashrate", "best_score"]);
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[1][1..], ["4096", "1234", ""]);
+    assert_eq!(rows[2][1..], ["8192", "2000", "130"]);
+}
+
+#[test]
+fn persisted_files_are_replaced_whole() {
+    let dir = std::env::temp_dir().join(format!("persist-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("top.json");
+    persist::write(&path, "a much longer first version\n").unwrap();
+    persist::write(&path, "second\n").unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "second\n");
+    let left: Vec<_> = std::fs::read_dir(&dir).unwrap().map(|entry| entry.unwrap().file_name()).collect();
+    // A write that cannot finish leaves the old file as it was.
+    assert!(persist::write(&dir.join("missing").join("top.json"), "x").is_err());
+    std::fs::remove_dir_all(&dir).ok();
+    assert_eq!(left, ["top.json"]);
+}
+
+#[test]
+fn runs_carry_on_from_a_checkpoint_of_the_same_job() {
+    use uniswapv4_challenge_miner::checkpoint::{check_job, CheckpointArgs, Saved};
+
+    let path = std::env::temp_dir().join(format!("checkpoint-{}.json", std::process::id()));
+    std::fs::remove_file(&path).ok();
+    let args = |resume, fresh| CheckpointArgs { checkpoint: Some(path.clone()), checkpoint_interval: Duration::from_secs(60), resume, fresh };
+    assert!(args(false, false).load().unwrap().is_none());
+    assert!(args(true, false).load().is_err());
+
+    let best = Candidate { salt: [1; 32], address: [0x44; 20], score: 120 };
+    let checkpoint = Checkpoint { best: Some(best), top: vec![best], hashes: 4096, ..Checkpoint::default() };
+    let saved = Saved { job: B256::repeat_byte(7), checkpoint };
+    persist::write(&path, serde_json::to_string(&saved).unwrap()).unwrap();
+    let loaded = args(true, false).load().unwrap().unwrap();
+    assert!(args(false, true).load().unwrap().is_none());
+    std::fs::remove_file(&path).ok();
+    assert_eq!(loaded.checkpoint.best.map(|c| c.salt), Some(best.salt));
+    assert_eq!(loaded.checkpoint.hashes, 4096);
+    assert_eq!(loaded.checkpoint.top.iter().map(|c| c.score).collect::<Vec<_>>(), [120]);
+    assert!(check_job(&loaded, B256::repeat_byte(7), &path).is_ok());
+    assert!(check_job(&loaded, B256::repeat_byte(8), &path).is_err());
+}
+
+#[test]
+fn reports_hold_the_run_without_its_secrets() {
+    use uniswapv4_challenge_miner::energy::Energy;
+    use uniswapv4_challenge_miner::provenance::Provenance;
+    use uniswapv4_challenge_miner::report::{redact, Config, Hashrate, Report};
+    use uniswapv4_challenge_miner::timeline::Point;
+
+    let args = ["miner", "--rpc-url", "https://eth.example/v3/KEY", "--private-key=0xabc", "-t", "4"].map(String::from);
+    let command_line = redact(args);
+    assert_eq!(command_line, ["miner", "--rpc-url", "<redacted>", "--private-key=<redacted>", "-t", "4"]);
+
+    let best = Candidate { salt: [7; 32], address: [0x44; 20], score: 90 };
+    let config = Config { command_line, mode: "create2".to_string(), deployer: [1; 20], code_hash: [2; 32], scorer: "uniswap-v4".to_string(), threads: 4 };
+    let report = Report {
+        version: Default::default(),
+        reason: "quit".to_string(),
+        provenance: Provenance { host: "rig".to_string(), backend: "cpu-x86_64".to_string(), engine: "miner 1.0".to_string(), job: None },
+        started: 1_700_000_000,
+        ended: 1_700_000_100,
+        elapsed: Duration::from_secs(100),
+        config,
+        hashes: 1_000_000,
+        hashrate: Hashrate::new(1_000_000, Duration::from_secs(100), &[9000.0, 11000.0]),
+        energy: Some(Energy::new(1_000_000, 7.2e5, Duration::from_secs(100), Some(0.5))),
+        best: Some(best.into()),
+        top: vec![best.into()],
+        best_over_time: vec![Point { elapsed: Duration::from_secs(60), timestamp: 1_700_000_060, hashes: 600_000, score: 90 }],
+    };
+    let dir = std::env::temp_dir().join(format!("reports-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let json = report.write(&dir).unwrap();
+    let text = std::fs::read_to_string(json.with_extension("txt")).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&json).unwrap()).unwrap();
+    std::fs::remove_dir_all(&dir).ok();
+    assert!(json.to_string().contains("\"ended\":1700000100"), "{}", json);
+    assert_eq!(json["hashrate"]["average"], 10000.0);
+    assert_eq!(json["top"][0]["salt"], format!("0x{}", "07".repeat(32)));
+    assert_eq!(json["host"], "rig");
+    assert_eq!(json["best_over_time"][0]["score"], 90);
+    assert!(text.contains("Ended:      2023-11-14T22:15:00Z (quit)\n"), "{}", text);
+    assert!(text.contains("10.00 kH/s average, 11.00 kH/s peak, 9.00 kH/s lowest over 2 status lines"), "{}", text);
+    assert_eq!(json["energy"]["cost"], 0.1);
+    assert!(text.contains("Energy:     720.00 kJ, 7200.0 W average, 1.39 H/J, costing 0.1000\n"), "{}", text);
+    assert!(!text.contains("KEY"), "{}", text);
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn db_records_runs_bests_and_stats() {
+    use uniswapv4_challenge_miner::db::{Db, RunInfo};
+
+    let path = std::env::temp_dir().join(format!("history-{}.db", std::process::id()));
+    std::fs::remove_file(&path).ok();
+    let run = RunInfo { mode: "create2".to_string(), deployer: [1; 20], code_hash: [2; 32], scorer: "uniswap-v4".to_string(), threads: 4 };
+    for hashes in [1000, 5000] {
+        let db = Db::start(&path, &run).unwrap();
+        db.record_best(&Candidate { salt: [3; 32], address: [0x44; 20], score: 60 });
+        db.record_stats(hashes / 2, 100.0, Some(60));
+        db.finish(hashes);
+    }
+
+    let connection = rusqlite::Connection::open(&path).unwrap();
+    let count = |table: &str| connection.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get::<_, i64>(0)).unwrap();
+    assert_eq!((count("runs"), count("bests"), count("stats")), (2, 2, 2));
+    let total: i64 = connection.query_row("SELECT SUM(hashes) FROM runs WHERE ended IS NOT NULL", [], |row| row.get(0)).unwrap();
+    assert_eq!(total, 6000);
+    drop(connection);
+    std::fs::remove_file(&path).ok();
+}
@@ -0,0 +1,28 @@
+// The shared service runtime stops its services on shutdown, async and
+// blocking alike, and lets their last words run.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use uniswapv4_challenge_miner::runtime;
+
+#[test]
+fn shutdown_stops_async_and_blocking_services() {
+    let finished = Arc::new([AtomicBool::new(false), AtomicBool::new(false)]);
+    let done = Arc::clone(&finished);
+    runtime::spawn("async", move |mut shutdown| async move {
+        while shutdown.sleep(Duration::from_secs(3600)).await {}
+        done[0].store(true, Ordering::Relaxed);
+    });
+    let done = Arc::clone(&finished);
+    runtime::spawn_blocking("blocking", move |mut shutdown| {
+        while shutdown.sleep_blocking(Duration::from_millis(10)) {}
+        done[1].store(true, Ordering::Relaxed);
+    });
+
+    let started = Instant::now();
+    runtime::shutdown(Duration::from_secs(30));
+    assert!(started.elapsed() < Duration::from_secs(30));
+    assert!(finished.iter().all(|done| done.load(Ordering::Relaxed)));
+}
@@ -0,0 +1,116 @@
+// Where the salts come from: coverage of the counters, and random peppers
+// retired, confined to a range or shared out by a master seed.
+
+use uniswapv4_challenge_miner::salts::{SaltPosition, Sequential};
+use uniswapv4_challenge_miner::schema::CounterRange;
+
+#[test]
+fn coverage_counts_every_run_of_the_search() {
+    use uniswapv4_challenge_miner::salts::{format_coverage, Random, RandomStream, SaltGenerator};
+    use uniswapv4_challenge_miner::schema::pepper_ranges;
+
+    // Carried on from 250 after an earlier run tried 0..250 of 0..1000.
+    let resumed = Sequential { submitter: [0x11; 20], start: 250, end: Some(1_000) };
+    let before = [CounterRange { start: 0, end: 250 }];
+    assert_eq!(resumed.coverage(&[125, 125], &before), Some(0.5));
+    assert_eq!(resumed.coverage(&[0, 0], &[]), Some(0.0));
+
+    let streams = vec![RandomStream { pepper: 7, next: 1_001, step: 2 }, RandomStream { pepper: 9, next: 1_000, step: 2 }];
+    let random = Random::resume([0x11; 20], streams, Vec::new());
+    let position = random.position(&[]);
+    let ranges: Vec<_> = pepper_ranges(position.as_ref()).iter().map(|r| (r.pepper, r.start, r.end, r.step)).collect();
+    assert_eq!(ranges, [(7, 1, 1_001, 2), (9, 0, 1_000, 2)]);
+    assert_eq!(random.coverage(&[], &[]), Some(1_000.0 / 2f64.powi(96)));
+
+    assert_eq!(format_coverage(0.5), "50.00%");
+    assert_eq!(format_coverage(0.0), "0%");
+    assert_eq!(format_coverage(1e-24), "1.00e-22%");
+}
+
+#[test]
+fn exhausted_peppers_are_retired_for_fresh_ones() {
+    use uniswapv4_challenge_miner::salts::{Random, RandomStream, SaltGenerator};
+    use uniswapv4_challenge_miner::schema::PepperRange;
+
+    let streams = vec![RandomStream { pepper: 7, next: u64::MAX - 3, step: 2 }];
+    let random = Random::resume([0x11; 20], streams, Vec::new());
+    let salts: Vec<_> = random.salts(0, 2).take(4).collect();
+    let pepper = |salt: &[u8; 32]| u32::from_be_bytes(salt[20..24].try_into().unwrap());
+    let counter = |salt: &[u8; 32]| u64::from_be_bytes(salt[24..].try_into().unwrap());
+    assert_eq!(salts.iter().map(counter).collect::<Vec<_>>(), [u64::MAX - 3, u64::MAX - 1, 0, 2]);
+    assert_eq!(pepper(&salts[1]), 7);
+    let fresh = pepper(&salts[2]);
+    assert!(fresh != 7 && pepper(&salts[3]) == fresh);
+
+    let Some(SaltPosition::Random { streams, retired, .. }) = random.position(&[4]) else { panic!("no position") };
+    assert_eq!(streams, [RandomStream { pepper: fresh, next: 4, step: 2 }]);
+    assert_eq!(retired, [PepperRange { pepper: 7, start: 0, end: u64::MAX, step: 2 }]);
+}
+
+#[test]
+fn peppers_are_drawn_from_the_range_given() {
+    use uniswapv4_challenge_miner::salts::{parse_pepper_range, Random, RandomStream, SaltGenerator};
+
+    assert_eq!(parse_pepper_range("5..8"), Ok(5..8));
+    assert_eq!(parse_pepper_range("10.."), Ok(10..1 << 32));
+    assert!(parse_pepper_range("0..4294967297").is_err());
+
+    let random = Random::new([0x11; 20]).within(5..8);
+    random.avoid_peppers(&[6]);
+    let pepper = |salt: [u8; 32]| u32::from_be_bytes(salt[20..24].try_into().unwrap());
+    let peppers: Vec<u32> = (0..2).map(|thread| pepper(random.salts(thread, 2).next().unwrap())).collect();
+    assert!(peppers.iter().all(|p| [5, 7].contains(p)) && peppers[0] != peppers[1]);
+
+    let stream = |pepper| RandomStream { pepper, next: 0, step: 1 };
+    assert_eq!(Random::resume([0x11; 20], vec![stream(3), stream(4), stream(3)], Vec::new()).duplicates(), [3]);
+}
+
+#[test]
+fn workers_of_a_seed_get_peppers_of_their_own() {
+    use uniswapv4_challenge_miner::salts::{Random, SaltGenerator, Seed};
+
+    let seed = |worker| Seed { key: [7; 32], worker };
+    let mut peppers: Vec<u32> = (0..4).flat_map(|worker| (0..256).map(move |index| seed(worker).pepper(index))).collect();
+    assert_eq!(seed(3).owner(peppers[3 * 256 + 5]), (3, 5));
+    peppers.sort_unstable();
+    peppers.dedup();
+    assert_eq!(peppers.len(), 4 * 256);
+
+    // The same seed and worker give the same salts on every machine.
+    let first = |worker| Random::new([0x11; 20]).seeded(seed(worker)).salts(1, 2).next().unwrap();
+    assert_eq!(first(2), first(2));
+    assert_ne!(first(2), first(3));
+    assert_eq!(u32::from_be_bytes(first(2)[20..24].try_into().unwrap()), seed(2).pepper(0));
+}
+
+#[test]
+fn workers_stop_when_the_peppers_run_out() {
+    use uniswapv4_challenge_miner::error::MinerError;
+    use uniswapv4_challenge_miner::salts::{Random, RandomStream, SaltGenerator};
+
+    // Used up before the start: a configuration error, and no salts.
+    let random = Random::new([0x11; 20]).within(5..8);
+    random.avoid_peppers(&[5, 6]);
+    assert!(random.check_peppers(1).is_ok());
+    assert!(matches!(random.check_peppers(2), Err(MinerError::Config(_))));
+    random.avoid_peppers(&[7]);
+    assert_eq!(random.salts(0, 1).count(), 0);
+
+    // Used up as the only stream's counters run out: that stream just ends.
+    let streams = vec![RandomStream { pepper: 5, next: u64::MAX - 5, step: 2 }];
+    let random = Random::resume([0x11; 20], streams, Vec::new()).within(5..6);
+    assert!(random.check_peppers(1).is_ok());
+    assert_eq!(random.salts(0, 1).count(), 3);
+}
+
+#[test]
+fn seed_workers_stop_when_their_peppers_run_out() {
+    use uniswapv4_challenge_miner::error::MinerError;
+    use uniswapv4_challenge_miner::salts::{Random, SaltGenerator, Seed};
+
+    let seed = Seed { key: [7; 32], worker: 3 };
+    let random = Random::new([0x11; 20]).seeded(seed);
+    random.avoid_peppers(&(0..=u16::MAX).map(|index| seed.pepper(index)).collect::<Vec<_>>());
+    assert!(matches!(random.check_peppers(1), Err(MinerError::Config(_))));
+    assert_eq!(random.salts(0, 1).count(), 0);
+}
@@ -0,0 +1,21 @@
+// Mining only within the windows of a weekly schedule.
+
+#[test]
+fn the_schedule_mines_weeknights_and_weekends() {
+    use uniswapv4_challenge_miner::schedule::{Schedule, Window};
+
+    let schedule = Schedule(vec!["mon-fri 22:00-07:00".parse().unwrap(), "weekends".parse().unwrap()]);
+    // 2026-10-12 is a Monday.
+    let at = |day: u32, time: &str| chrono::NaiveDateTime::parse_from_str(&format!("2026-10-{} {}", day, time), "%Y-%m-%d %H:%M").unwrap();
+    assert!(!schedule.contains(at(12, "06:59")));
+    assert!(schedule.contains(at(12, "22:00")) && schedule.contains(at(13, "06:59")));
+    assert!(!schedule.contains(at(13, "07:00")));
+    assert!(schedule.contains(at(17, "12:00")) && schedule.contains(at(18, "23:59")));
+    assert_eq!(schedule.next_change(at(14, "12:30")), Some(at(14, "22:00")));
+    // Friday night runs into the weekend, which ends at midnight on Sunday.
+    assert_eq!(schedule.next_change(at(16, "23:00")), Some(at(19, "00:00")));
+
+    assert_eq!("fri-mon".parse::<Window>().unwrap().to_string(), "Mon,Fri,Sat,Sun");
+    assert!("mon 25:00-07:00".parse::<Window>().is_err());
+    assert!("someday".parse::<Window>().is_err());
+}
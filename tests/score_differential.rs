@@ -0,0 +1,196 @@
+// compute_score against the challenge contract's own scoring, run from its
+// deployed bytecode in revm. The contract reports the score of its stored
+// best whenever a worse address is submitted, so each address checked is
+// written into that slot and a salt scoring 0 is submitted against it.
+// Needs ANVIL_FORK_URL (any mainnet endpoint will do) to fetch the contract.
+
+use proptest::prelude::*;
+use proptest::test_runner::{Config, TestRunner};
+use revm::db::{CacheDB, EmptyDB};
+use revm::primitives::{AccountInfo, Address, Bytecode, ExecutionResult, TxKind, U256};
+use revm::Evm;
+use serde_json::json;
+use uniswapv4_challenge_miner::profile::Profile;
+use uniswapv4_challenge_miner::rpc::{parse_bytes, to_hex, RpcClient};
+use uniswapv4_challenge_miner::score::Scorer;
+use uniswapv4_challenge_miner::{challenge, compute_score, compute_score_at_least, create2_addr, create_addr, decode_hex, explain_score, DEPLOYER_ADDRESS, INITCODE_HASH};
+
+// Storage slots fetched from the contract; its state sits well within these.
+const SLOTS: u64 = 32;
+
+struct Contract {
+    db: CacheDB<EmptyDB>,
+    best_slot: U256,
+    // Bits of the best's slot other than the address, kept as they are.
+    best_slot_rest: U256,
+    deadline: u64,
+    sender: [u8; 20],
+    // A salt for `sender` whose address scores 0, so it never takes the lead.
+    salt: [u8; 32],
+}
+
+impl Contract {
+    fn fetch() -> Option<Contract> {
+        let Ok(url) = std::env::var("ANVIL_FORK_URL") else {
+            eprintln!("skipping: ANVIL_FORK_URL is not set");
+            return None;
+        };
+        let rpc = RpcClient::new(&[url]);
+        let code = rpc.get_code(&DEPLOYER_ADDRESS).unwrap();
+        assert!(!code.is_empty(), "no challenge contract on this chain");
+        let best = challenge::best_address(&rpc, &DEPLOYER_ADDRESS).unwrap();
+        let deadline = challenge::competition_deadline(&rpc, &DEPLOYER_ADDRESS).unwrap();
+
+        let mut db = CacheDB::new(EmptyDB::default());
+        let code = Bytecode::new_raw(code.into());
+        db.insert_account_info(Address::from(DEPLOYER_ADDRESS), AccountInfo::new(U256::ZERO, 1, code.hash_slow(), code));
+        let address_mask = (U256::from(1) << 160usize) - U256::from(1);
+        let mut best_slot = None;
+        for slot in 0..SLOTS {
+            let word = rpc.request("eth_getStorageAt", json!([to_hex(&DEPLOYER_ADDRESS), format!("{:#x}", slot), "latest"])).unwrap();
+            let word = U256::from_be_slice(&parse_bytes(&word).unwrap());
+            db.insert_account_storage(Address::from(DEPLOYER_ADDRESS), U256::from(slot), word).unwrap();
+            if word & address_mask == U256::from_be_slice(&best) && best != [0; 20] {
+                best_slot.get_or_insert((U256::from(slot), word & !address_mask));
+            }
+        }
+        let (best_slot, best_slot_rest) = best_slot.expect("cannot find the best address in the contract's storage");
+
+        let sender = decode_hex::<20>("0x1111111111111111111111111111111111111111").unwrap();
+        let salt = (0u64..)
+            .map(|counter| {
+                let mut salt = [0; 32];
+                salt[..20].copy_from_slice(&sender);
+                salt[24..].copy_from_slice(&counter.to_be_bytes());
+                salt
+            })
+            .find(|salt| compute_score(&create2_addr(&DEPLOYER_ADDRESS, salt, &INITCODE_HASH)) == 0)
+            .unwrap();
+        Some(Contract { db, best_slot, best_slot_rest, deadline, sender, salt })
+    }
+
+    // The contract's score of `address`, as the best it has to beat.
+    fn score(&self, address: &[u8; 20]) -> u32 {
+        let mut db = self.db.clone();
+        let word = self.best_slot_rest | U256::from_be_slice(address);
+        db.insert_account_storage(Address::from(DEPLOYER_ADDRESS), self.best_slot, word).unwrap();
+        let mut evm = Evm::builder()
+            .with_db(db)
+            .modify_block_env(|block| block.timestamp = U256::from(self.deadline - 1))
+            .modify_tx_env(|tx| {
+                tx.caller = Address::from(self.sender);
+                tx.transact_to = TxKind::Call(Address::from(DEPLOYER_ADDRESS));
+                tx.data = challenge::update_best_address_calldata(&self.salt).into();
+                tx.gas_price = U256::ZERO;
+            })
+            .build();
+        let result = evm.transact().unwrap().result;
+        let ExecutionResult::Revert { output, .. } = result else {
+            panic!("submission against 0x{} did not revert: {:?}", hex::encode(address), result);
+        };
+        // WorseAddress(newAddress, bestAddress, newScore, bestScore)
+        assert_eq!(output[..4], challenge::selector("WorseAddress(address,address,uint256,uint256)"), "revert 0x{}", hex::encode(&output));
+        let best_score = U256::from_be_slice(&output[4 + 3 * 32..4 + 4 * 32]);
+        best_score.try_into().unwrap()
+    }
+}
+
+// Addresses shaped like the ones the rules care about: leading zeros, then a
+// run of 4s, then anything, sometimes ending in 4s.
+fn challenge_like() -> impl Strategy<Value = [u8; 20]> {
+    (0..=40usize, 0..=8usize, any::<[u8; 20]>(), any::<bool>()).prop_map(|(zeros, fours, random, tail)| {
+        let mut nibbles: Vec<u8> = random.iter().flat_map(|b| [b >> 4, b & 0x0F]).collect();
+        nibbles[..zeros].fill(0);
+        nibbles[zeros..(zeros + fours).min(40)].fill(4);
+        if tail {
+            nibbles[36..].fill(4);
+        }
+        let mut address = [0; 20];
+        for (byte, pair) in address.iter_mut().zip(nibbles.chunks(2)) {
+            *byte = (pair[0] << 4) | pair[1];
+        }
+        address
+    })
+}
+
+#[test]
+fn compute_score_matches_the_contract() {
+    let Some(contract) = Contract::fetch() else { return };
+
+    let edge_cases = [
+        "0000000000000000000000000000000000000000",
+        "4444444444444444444444444444444444444444",
+        "0000000000000000000000000000000000004444",
+        "0000000000000000000000000000000000000004",
+        "0000000044449dbd6b1b8e5dcac0e2ce0ec9ef2e",
+        "0000000044444dbd6b1b8e5dcac0e2ce0ec9ef2e",
+        "0000000044440000000000000000000000004444",
+        "0000000000000000000000000000000000444444",
+        "1444444444444444444444444444444444444444",
+        "0000000514444444444444444444444444444444",
+    ];
+    for address in edge_cases {
+        let address = decode_hex::<20>(address).unwrap();
+        assert_eq!(compute_score(&address), contract.score(&address), "0x{}", hex::encode(address));
+    }
+
+    let mut runner = TestRunner::new(Config::with_cases(2_000));
+    runner
+        .run(&prop_oneof![any::<[u8; 20]>(), challenge_like()], |address| {
+            prop_assert_eq!(compute_score(&address), contract.score(&address), "0x{}", hex::encode(address));
+            Ok(())
+        })
+        .unwrap();
+}
+
+proptest! {
+    // The early exit is only taken for addresses that cannot reach the
+    // threshold, so the hot path gets the score of every one that can.
+    #[test]
+    fn early_exit_scores_agree_above_the_threshold(address in prop_oneof![any::<[u8; 20]>(), challenge_like()], threshold in 0..=500u32) {
+        let score = compute_score(&address);
+        // Right around the score as well, where an exit taken too soon shows.
+        for threshold in [threshold, score.saturating_sub(1), score, score + 1, score + 21] {
+            let early = compute_score_at_least(&address, threshold);
+            if score >= threshold {
+                prop_assert_eq!(early, score, "0x{} at {}", hex::encode(address), threshold);
+            } else {
+                prop_assert!(early < threshold, "0x{} at {}: {} of {}", hex::encode(address), threshold, early, score);
+            }
+        }
+    }
+
+    // The shipped profile of the challenge's rules, for --score profile:.
+    #[test]
+    fn the_challenge_profile_scores_as_compute_score_does(address in prop_oneof![any::<[u8; 20]>(), challenge_like()]) {
+        let profile = Profile::load("uniswap-v4-challenge@1").unwrap();
+        prop_assert_eq!(profile.score(&address), compute_score(&address), "0x{}", hex::encode(address));
+    }
+}
+
+#[test]
+fn score_explanations_add_up() {
+    let edge_cases = [
+        "0000000000000000000000000000000000000000",
+        "0000444440000000000000000000000000000000",
+        "0000000000000000000000000000000000004444",
+        "0000000000000000000000000000000000000444",
+        "1444444444444444444444444444444444444444",
+        "4444444444444444444444444444444444444444",
+    ];
+    let addresses = edge_cases
+        .iter()
+        .map(|address| decode_hex::<20>(address).unwrap())
+        .chain((0..20_000u64).map(|n| create_addr(&[0x44; 20], n)))
+        .chain((0..20_000u64).map(|n| {
+            // Dense with leading zeros and 4s, where the rules interact.
+            let mut address = create_addr(&[0x11; 20], n);
+            address[..2].fill(0);
+            address[2] = 0x44;
+            address
+        }));
+    for address in addresses {
+        let breakdown = explain_score(&address);
+        assert_eq!(breakdown.total(), compute_score(&address), "0x{}: {}", hex::encode(address), breakdown);
+    }
+}
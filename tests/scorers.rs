@@ -0,0 +1,109 @@
+// Scorers other than the challenge's own, picked with --scorer: the built in
+// ones, and those scripted in Rhai or compiled to WebAssembly.
+
+use uniswapv4_challenge_miner::compute_score;
+use uniswapv4_challenge_miner::miner::Miner;
+use uniswapv4_challenge_miner::mode::Mode;
+use uniswapv4_challenge_miner::score::{LeadingZeroBytes, Objective, Scorer, ScorerSpec};
+use uniswapv4_challenge_miner::{create_addr, decode_hex};
+
+// An address starting with `start`, the rest of it 0x12.
+fn starting(start: &[u8]) -> [u8; 20] {
+    let mut address = [0x12; 20];
+    address[..start.len()].copy_from_slice(start);
+    address
+}
+
+#[test]
+fn scorers_are_named_as_on_the_command_line() {
+    for name in ["uniswap-v4", "profile:challenge", "script:examples/leading-fours.rhai", "wasm:examples/leading-zero-bytes.wat"] {
+        assert_eq!(name.parse::<ScorerSpec>().unwrap().to_string(), name);
+    }
+    assert_eq!(serde_json::to_string(&ScorerSpec::UniswapV4).unwrap(), r#""uniswap-v4""#);
+    assert!("vanity".parse::<ScorerSpec>().unwrap_err().contains("available: uniswap-v4"));
+
+    let challenge = ScorerSpec::UniswapV4.build().unwrap();
+    for address in [starting(&[0x00, 0x00, 0x44, 0x44, 0x40]), starting(&[0x44]), [0; 20]] {
+        assert_eq!(challenge.score(&address), compute_score(&address));
+        assert_eq!(challenge.score_at_least(&address, 0), compute_score(&address));
+    }
+    if !cfg!(feature = "script") {
+        let script = "script:examples/leading-fours.rhai".parse::<ScorerSpec>().unwrap().build().err().unwrap().to_string();
+        assert!(script.contains("--features script"), "{}", script);
+    }
+    if !cfg!(feature = "wasm") {
+        let wasm = "wasm:examples/leading-zero-bytes.wat".parse::<ScorerSpec>().unwrap().build().err().unwrap().to_string();
+        assert!(wasm.contains("--features wasm"), "{}", wasm);
+    }
+}
+
+#[test]
+fn leading_zero_bytes_are_maximized_instead_of_the_score() {
+    let zeros = Objective::LeadingZeros.scorer(None).unwrap();
+    assert_eq!((zeros.score(&starting(&[0, 0, 0x01])), zeros.score(&starting(&[0x00, 0x0f])), zeros.score(&[0; 20])), (2, 1, 20));
+    assert_eq!((Objective::LeadingZeros.describe(1), Objective::LeadingZeros.describe(3)), ("1 leading zero byte".to_string(), "3 leading zero bytes".to_string()));
+
+    let sender = decode_hex::<20>("0x6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0").unwrap();
+    let max_nonce = 3_000;
+    let expected = (0..=max_nonce).map(|nonce| zeros.score(&create_addr(&sender, nonce))).max().unwrap();
+    let miner = Miner::builder().mode(Mode::Create).senders(vec![sender], max_nonce).scorer(LeadingZeroBytes).threads(2).run().unwrap();
+    let best = miner.wait().unwrap().unwrap();
+    assert_eq!((best.score, zeros.score(&best.address)), (expected, expected));
+}
+
+#[test]
+#[cfg(feature = "script")]
+fn scripts_score_what_starts_with_their_prefix() {
+    use uniswapv4_challenge_miner::script::ScriptScorer;
+
+    let script = ScriptScorer::load(std::path::Path::new("examples/leading-fours.rhai")).unwrap();
+    assert_eq!(script.score(&starting(&[0, 0, 0, 0, 0x44, 0x04])), 3);
+    // Without eight leading zeros the script is not even run.
+    assert_eq!(script.score(&starting(&[0, 0, 0, 0x04, 0x44, 0x04])), 0);
+
+    let load = |name: &str, source: &str| {
+        let path = std::env::temp_dir().join(format!("{}-{}.rhai", name, std::process::id()));
+        std::fs::write(&path, source).unwrap();
+        let loaded = ScriptScorer::load(&path);
+        std::fs::remove_file(&path).ok();
+        loaded
+    };
+    // A script running away scores nothing rather than stalling the search.
+    let endless = load("endless", r#"fn score(address) { if address.starts_with("ff") { loop {} } 1 }"#).unwrap();
+    assert_eq!((endless.score(&[0x11; 20]), endless.score(&[0xff; 20])), (1, 0));
+    assert!(load("stuck", "fn score(address) { loop {} }").err().unwrap().to_string().contains("Too many operations"));
+    assert!(load("prefix", r#"fn prefix() { "0x00" } fn score(address) { 1 }"#).err().unwrap().to_string().contains("40 hex digits"));
+    assert!(load("missing", "fn prefix() { \"00\" }").err().unwrap().to_string().contains("score()"));
+}
+
+#[test]
+#[cfg(feature = "wasm")]
+fn modules_score_within_their_fuel() {
+    use uniswapv4_challenge_miner::wasm::WasmScorer;
+
+    let module = WasmScorer::load(std::path::Path::new("examples/leading-zero-bytes.wat")).unwrap();
+    assert_eq!((module.score(&starting(&[0, 0, 0, 1])), module.score(&[0; 20])), (3, 20));
+
+    let load = |name: &str, source: &str| {
+        let path = std::env::temp_dir().join(format!("{}-{}.wat", name, std::process::id()));
+        std::fs::write(&path, source).unwrap();
+        let loaded = WasmScorer::load(&path);
+        std::fs::remove_file(&path).ok();
+        loaded
+    };
+    // Spinning forever on addresses starting with 0xff, which run out of fuel
+    // and score nothing.
+    let endless = load(
+        "endless",
+        r#"(module
+          (memory (export "memory") 1)
+          (func (export "alloc") (param i32) (result i32) i32.const 0)
+          (func (export "score") (param i32 i32) (result i32)
+            (block $done (loop $spin (br_if $done (i32.ne (i32.load8_u (i32.const 0)) (i32.const 255))) (br $spin)))
+            i32.const 1))"#,
+    )
+    .unwrap();
+    assert_eq!((endless.score(&[0x11; 20]), endless.score(&[0xff; 20])), (1, 0));
+    let unexported = r#"(module (memory 1) (func (export "alloc") (param i32) (result i32) i32.const 0) (func (export "score") (param i32 i32) (result i32) i32.const 1))"#;
+    assert!(load("unexported", unexported).err().unwrap().to_string().contains("does not export its memory"));
+}
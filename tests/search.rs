@@ -0,0 +1,173 @@
+// Searches run from the command line over every nonce of a --mode create
+// deployer, or a range of sequential salts, so what they should report is
+// known beforehand.
+
+use std::collections::BTreeMap;
+use std::process::Command;
+
+use uniswapv4_challenge_miner::salts::Sequential;
+use uniswapv4_challenge_miner::{compute_score, create2_addr, create_addr, decode_hex, DEPLOYER_ADDRESS, INITCODE_HASH};
+use uniswapv4_challenge_miner::error::MinerError;
+use uniswapv4_challenge_miner::odds;
+use uniswapv4_challenge_miner::score::{Objective, Scorer};
+
+const MINER: &str = env!("CARGO_BIN_EXE_uniswapv4-challenge-miner");
+const SENDER: &str = "0x6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0";
+const MAX_NONCE: u64 = 3_000;
+
+// The output of a search of nonces 0 to MAX_NONCE.
+fn mine(args: &[&str]) -> String {
+    let max_nonce = MAX_NONCE.to_string();
+    let output = Command::new(MINER).args(["--threads", "2", "--mode", "create", "--sender", SENDER, "--max-nonce", &max_nonce]).args(args).output().unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success(), "{}{}", stdout, String::from_utf8_lossy(&output.stderr));
+    stdout
+}
+
+// Every address searched.
+fn addresses() -> impl Iterator<Item = [u8; 20]> {
+    let sender = decode_hex::<20>(SENDER).unwrap();
+    (0..=MAX_NONCE).map(move |nonce| create_addr(&sender, nonce))
+}
+
+#[test]
+fn tracked_objectives_each_report_their_own_best() {
+    let output = mine(&["--track", "leading-zeros,fours"]);
+    for objective in [Objective::LeadingZeros, Objective::Fours] {
+        let scorer = objective.scorer(None).unwrap();
+        let best = addresses().map(|address| scorer.score(&address)).max().unwrap();
+        // Reported once it is found, whichever thread finds it.
+        let reported = output.lines().filter(|line| line.starts_with("Tracked best:") && line.contains(&format!(" with {},", objective.describe(best))));
+        assert_eq!(reported.count(), 1, "{:?}\n{}", objective, output);
+    }
+    let error = Command::new(MINER).args(["--threads", "1", "--mode", "create", "--sender", SENDER, "--track", "hook-flags"]).output().unwrap();
+    assert!(String::from_utf8_lossy(&error.stderr).contains("needs --objective-hook-flags"));
+}
+
+#[test]
+fn targets_are_matched_all_at_once_each_under_its_name() {
+    let path = std::env::temp_dir().join(format!("targets-{}.txt", std::process::id()));
+    let targets = |lines: &str| {
+        std::fs::write(&path, lines).unwrap();
+        path.to_str().unwrap().to_string()
+    };
+    let output = mine(&["--targets", &targets("# Every one of them.\nzero --prefix 00 --matches 0\n\nfour --prefix 4 --suffix 4 --matches 2\n")]);
+    let hex = |address: &[u8; 20]| hex::encode(address);
+    let zeros = addresses().filter(|address| hex(address).starts_with("00")).count();
+    assert_eq!(output.lines().filter(|line| line.starts_with("Match [zero]: 0x00")).count(), zeros, "{}", output);
+    assert!(addresses().filter(|address| hex(address).starts_with('4') && hex(address).ends_with('4')).count() > 2);
+    assert_eq!(output.lines().filter(|line| line.starts_with("Match [four]: 0x4")).count(), 2, "{}", output);
+
+    // Once every target has its matches there is nothing left to search for.
+    let finite = targets("four --prefix 4 --suffix 4 --matches 2\nfive --prefix 5 --matches 1\n");
+    let output = Command::new(MINER).args(["--threads", "2", "--mode", "create", "--sender", SENDER, "--max-nonce", "100000000", "--targets", &finite]).output().unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success() && !stdout.contains("Searched nonces"), "{}", stdout);
+    assert_eq!((stdout.matches("Match [four]").count(), stdout.matches("Match [five]").count()), (2, 1));
+
+    let bad = targets("x --prefix 0\ny --bogus 1\n");
+    let output = Command::new(MINER).args(["--threads", "1", "--mode", "create", "--sender", SENDER, "--targets", &bad]).output().unwrap();
+    assert!(String::from_utf8_lossy(&output.stderr).contains(&format!("{}:2: unexpected argument '--bogus'", bad)));
+    let output = Command::new(MINER).args(["--threads", "1", "--mode", "create", "--sender", SENDER, "--targets", &bad, "--prefix", "0"]).output().unwrap();
+    assert!(String::from_utf8_lossy(&output.stderr).contains("put every pattern in the --targets file"));
+    std::fs::remove_file(&path).ok();
+}
+
+#[test]
+fn histograms_count_every_score_computed() {
+    let path = std::env::temp_dir().join(format!("histogram-{}.json", std::process::id()));
+    let histogram = |args: &[&str]| -> serde_json::Value {
+        mine(&[&["--histogram", path.to_str().unwrap()], args].concat());
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap()
+    };
+    let counts = |scorer: &dyn Scorer| {
+        let mut counts = BTreeMap::new();
+        addresses().for_each(|address| *counts.entry(scorer.score(&address)).or_insert(0) += 1);
+        counts
+    };
+    let listed = |histogram: &serde_json::Value| -> BTreeMap<u32, u64> {
+        let scores = histogram["scores"].as_array().unwrap().iter();
+        scores.filter(|entry| entry["count"] != 0).map(|entry| (entry["score"].as_u64().unwrap() as u32, entry["count"].as_u64().unwrap())).collect()
+    };
+
+    let challenge = histogram(&[]);
+    assert_eq!(challenge["hashes"], MAX_NONCE + 1);
+    assert_eq!(listed(&challenge), counts(&Objective::ChallengeScore.scorer(None).unwrap()));
+    // Next to what theory expects of so many addresses.
+    let zero = &challenge["scores"][0];
+    assert_eq!(zero["score"], 0);
+    assert!((zero["expected"].as_f64().unwrap() - (MAX_NONCE + 1) as f64 * odds::chance_of(0)).abs() < 1e-6);
+
+    // Other scoring has no theory to go by.
+    let fours = histogram(&["--maximize", "fours"]);
+    assert_eq!(listed(&fours), counts(&Objective::Fours.scorer(None).unwrap()));
+    assert!(fours["scores"].as_array().unwrap().iter().all(|entry| entry.get("expected").is_none()));
+    std::fs::remove_file(&path).ok();
+}
+
+#[test]
+fn addresses_without_the_leading_zeros_asked_for_are_skipped() {
+    let output = mine(&["--maximize", "fours", "--require-leading-zero-nibbles", "2"]);
+    let fours = Objective::Fours.scorer(None).unwrap();
+    let best = addresses().filter(|address| address[0] == 0).map(|address| fours.score(&address)).max().unwrap();
+    // Far from the most fours of any address searched.
+    assert!(addresses().any(|address| fours.score(&address) > best));
+    let reported: Vec<_> = output.lines().filter(|line| line.starts_with("New best address:")).collect();
+    assert!(reported.iter().all(|line| line.starts_with("New best address: 0x00")), "{}", output);
+    assert!(reported.last().unwrap().contains(&format!(" with {},", Objective::Fours.describe(best))), "{}", output);
+}
+
+#[test]
+fn extra_initcode_hashes_each_get_the_best_of_the_same_salts() {
+    let (first, second) = ([0x11; 32], [0x22; 32]);
+    let extra = format!("0x{},0x{}", hex::encode(first), hex::encode(second));
+    let submitter = [0x5b; 20];
+    let args = ["--threads", "2", "--salts", "sequential", "--salt-range", "0..3000", "--submitter", &format!("0x{}", hex::encode(submitter))];
+    let output = Command::new(MINER).args(args).args(["--extra-init-code-hash", &extra]).output().unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success(), "{}", stdout);
+
+    let salts = Sequential { submitter, start: 0, end: Some(3000) };
+    let best = |code_hash: &[u8; 32]| (0..3000).map(|counter| compute_score(&create2_addr(&DEPLOYER_ADDRESS, &salts.salt(counter), code_hash))).max().unwrap();
+    let last = |prefix: &str| stdout.lines().rfind(|line| line.starts_with(prefix)).unwrap().to_string();
+    assert!(last("New best address:").contains(&format!(" with score: {} ", best(&INITCODE_HASH))), "{}", stdout);
+    for code_hash in [first, second] {
+        let reported = last(&format!("Best for initcode 0x{}: 0x", hex::encode(code_hash)));
+        assert!(reported.contains(&format!(" with score: {},", best(&code_hash))), "{}", stdout);
+        // At the address the salt reported makes for this hash.
+        let salt = decode_hex::<32>(reported.rsplit(' ').next().unwrap()).unwrap();
+        assert!(reported.contains(&hex::encode(create2_addr(&DEPLOYER_ADDRESS, &salt, &code_hash))));
+    }
+    assert_ne!(best(&first), best(&second));
+}
+
+#[test]
+fn factory_salts_start_with_the_caller_they_are_bound_to() {
+    use uniswapv4_challenge_miner::factory::Factory;
+
+    let factory = Factory::ImmutableCreate2;
+    let (caller, other) = ([0x5b; 20], [0x5c; 20]);
+    let salt = |start: [u8; 20]| {
+        let mut salt = [0x07; 32];
+        salt[..20].copy_from_slice(&start);
+        salt
+    };
+    assert!(factory.allows(&salt(caller), &caller) && factory.allows(&salt([0; 20]), &caller));
+    assert!(!factory.allows(&salt(other), &caller));
+    assert!(matches!(factory.check_salt(&salt(other), &caller), Err(MinerError::Config(e)) if e.contains("must start with the caller's address or 20 zero bytes")));
+
+    let code_hash = [0x11; 32];
+    let args = ["--threads", "2", "--factory", "immutable-create2", "--salts", "sequential", "--salt-range", "0..500", "--submitter", &format!("0x{}", hex::encode(caller))];
+    let output = Command::new(MINER).args(args).args(["--init-code-hash", &format!("0x{}", hex::encode(code_hash))]).output().unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.starts_with(&format!("Deploy with safeCreate2(salt, initcode) on 0x{} from 0x{}\n", hex::encode(factory.address()), hex::encode(caller))), "{}", stdout);
+    let best = stdout.lines().rfind(|line| line.starts_with("New best address:")).unwrap();
+    let salt = decode_hex::<32>(best.rsplit(' ').next().unwrap()).unwrap();
+    // Usable by the caller, at the address the factory deploys to.
+    assert!(factory.allows(&salt, &caller) && !factory.allows(&salt, &other));
+    assert!(best.starts_with(&format!("New best address: 0x{} ", hex::encode(create2_addr(&factory.address(), &salt, &code_hash)))), "{}", best);
+
+    let output = Command::new(MINER).args(["--threads", "1", "--factory", "immutable-create2"]).output().unwrap();
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--factory needs --mode create2 and the --init-code-hash"));
+}
@@ -0,0 +1,173 @@
+// Running unattended: exit codes, Windows services, systemd, daemons,
+// sleep inhibition, log files and the journal.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use uniswapv4_challenge_miner::error::MinerError;
+use uniswapv4_challenge_miner::mode::Mode;
+use uniswapv4_challenge_miner::salts::Sequential;
+use uniswapv4_challenge_miner::{logfile, Candidate};
+
+#[test]
+fn errors_map_to_distinct_exit_codes() {
+    use uniswapv4_challenge_miner::error::exit_code;
+
+    assert_eq!(MinerError::Config("bad".to_string()).exit_code(), exit_code::CONFIG);
+    assert_eq!(MinerError::InvalidHex("0xz".to_string()).exit_code(), 3);
+    assert_eq!(MinerError::Runtime("unreachable".to_string()).exit_code(), exit_code::FAILED);
+    assert_eq!(MinerError::SelfTest("wrong address".to_string()).exit_code(), exit_code::BACKEND);
+    assert_eq!(MinerError::SearchPanicked.exit_code(), 4);
+}
+
+#[test]
+fn services_run_the_command_line_they_were_installed_with() {
+    use uniswapv4_challenge_miner::service::command_line;
+
+    let args = ["--service", "install", "--log-file", r"C:\mining logs\miner.log", "--target", "130"].map(Into::into);
+    assert_eq!(
+        command_line(r"C:\Program Files\miner.exe".into(), args),
+        r#""C:\Program Files\miner.exe" --log-file "C:\mining logs\miner.log" --target 130 --service run"#
+    );
+    let args = ["--service=install", r#"say "hi"\"#].map(Into::into);
+    assert_eq!(command_line("miner.exe".into(), args), r#"miner.exe "say \"hi\"\\" --service run"#);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn systemd_hears_status_and_watchdog_settings() {
+    use std::os::unix::net::UnixDatagram;
+    use uniswapv4_challenge_miner::systemd::{status, watchdog, Notifier};
+
+    let path = std::env::temp_dir().join(format!("notify-{}.sock", std::process::id()));
+    std::fs::remove_file(&path).ok();
+    let systemd = UnixDatagram::bind(&path).unwrap();
+    Notifier::connect(path.to_str().unwrap()).unwrap().notify("READY=1").unwrap();
+    let mut message = [0; 64];
+    let length = systemd.recv(&mut message).unwrap();
+    std::fs::remove_file(&path).ok();
+    assert_eq!(&message[..length], b"READY=1");
+
+    let best = Candidate { salt: [1; 32], address: [0x44; 20], score: 120 };
+    assert_eq!(status(Some(&best), 2.5e6, 3_000_000, false), format!("Mining: best score 120 at 0x{}, 3.00e6 hashes at 2.50 MH/s", "44".repeat(20)));
+    assert_eq!(watchdog(Some("30000000"), None, 7), Some(Duration::from_secs(30)));
+    assert_eq!(watchdog(Some("30000000"), Some("8"), 7), None);
+    assert_eq!(watchdog(Some("0"), Some("7"), 7), None);
+    assert_eq!(watchdog(None, None, 7), None);
+}
+
+#[cfg(unix)]
+#[test]
+fn daemons_answer_on_the_socket_next_to_their_pid_file() {
+    use uniswapv4_challenge_miner::daemon::{child_args, request, serve, Instance};
+    use uniswapv4_challenge_miner::salts::Coverage;
+    use uniswapv4_challenge_miner::search::Control;
+    use uniswapv4_challenge_miner::snapshot::Sources;
+
+    let dir = std::env::temp_dir().join(format!("daemon-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let instance = Instance { pid_file: dir.join("miner.pid") };
+    assert_eq!(instance.socket(), dir.join("miner.sock"));
+    assert!(request(&instance, "status").is_err());
+    let sources = Sources {
+        mode: Mode::Create2,
+        control: Arc::new(Control::default()),
+        coverage: Arc::new(Coverage { salts: Arc::new(Sequential { submitter: [0x11; 20], start: 0, end: None }), before: Vec::new() }),
+        best: Arc::new(Mutex::new(None)),
+        started: Instant::now(),
+    };
+    let running = serve(&instance, Arc::new(sources)).unwrap();
+    let pid = std::fs::read_to_string(&instance.pid_file).unwrap();
+    assert_eq!(pid.trim(), std::process::id().to_string());
+    assert_eq!(request(&instance, "pid").unwrap(), pid);
+    assert!(request(&instance, "status").unwrap().contains("Best: nothing found yet"));
+    drop(running);
+    let left = std::fs::read_dir(&dir).unwrap().count();
+    std::fs::remove_dir_all(&dir).ok();
+    assert_eq!(left, 0);
+
+    let args = ["-t", "4", "--daemon", "--target", "120"].map(std::ffi::OsString::from);
+    assert_eq!(child_args(args), ["-t", "4", "--target", "120"]);
+}
+
+#[test]
+fn the_system_is_kept_awake_only_while_mining() {
+    use std::sync::atomic::Ordering;
+    use uniswapv4_challenge_miner::inhibit::wanted;
+    use uniswapv4_challenge_miner::search::Control;
+
+    let control = Control::default();
+    assert!(wanted(&control));
+    control.paused.store(true, Ordering::Relaxed);
+    assert!(!wanted(&control));
+    control.paused.store(false, Ordering::Relaxed);
+    control.stopped.store(true, Ordering::Relaxed);
+    assert!(!wanted(&control));
+}
+
+#[test]
+fn log_file_rotates_and_keeps_only_the_newest() {
+    use logfile::{Level, Log, LogFile, Rotation};
+
+    let path = std::env::temp_dir().join(format!("miner-{}.log", std::process::id()));
+    let numbered = |n: usize| std::path::PathBuf::from(format!("{}.{}", path.display(), n));
+    let clean = || (0..=4).for_each(|n| drop(std::fs::remove_file(if n == 0 { path.clone() } else { numbered(n) })));
+    clean();
+    let log = LogFile::open(&path, Rotation { max_size: Some(200), max_age: None, keep: 3 }).unwrap();
+    for i in 0..40 {
+        log.write(Level::Info, &format!("line {:02} of the run", i));
+    }
+    let sizes: Vec<u64> = (1..=3).map(|n| std::fs::metadata(numbered(n)).unwrap().len()).collect();
+    let current = std::fs::read_to_string(&path).unwrap();
+    let dropped = numbered(4).exists();
+    clean();
+    assert!(!dropped);
+    assert!(sizes.iter().all(|&size| size > 0 && size <= 200), "{:?}", sizes);
+    assert!(current.lines().last().unwrap().ends_with(" INFO line 39 of the run"), "{}", current);
+    assert_eq!(logfile::parse_size("10M"), Ok(10 << 20));
+    assert!(logfile::parse_size("ten").is_err());
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn journald_entries_carry_the_best_as_fields() {
+    use logfile::{Level, Log};
+    use std::os::unix::net::UnixDatagram;
+    use uniswapv4_challenge_miner::hooks::Event;
+    use uniswapv4_challenge_miner::syslog::{Protocol, Syslog};
+
+    let path = std::env::temp_dir().join(format!("journal-{}.socket", std::process::id()));
+    std::fs::remove_file(&path).ok();
+    let daemon = UnixDatagram::bind(&path).unwrap();
+    let journal = Syslog::connect(Protocol::Journald, &path).unwrap();
+    let best = Candidate { salt: [0xab; 32], address: [0x44; 20], score: 64 };
+    journal.event(&Event::NewBest(&best), Mode::Create2);
+    // The salt of a --mode eoa search is its key.
+    journal.event(&Event::NewBest(&best), Mode::Eoa);
+    journal.write(Level::Error, "two\nlines");
+    let syslog = Syslog::connect(Protocol::Syslog, &path).unwrap();
+    syslog.write(Level::Warning, "careful");
+    let mut buffer = [0; 1024];
+    let mut receive = || {
+        let n = daemon.recv(&mut buffer).unwrap();
+        buffer[..n].to_vec()
+    };
+    let (best, key, error, warning) = (receive(), receive(), receive(), receive());
+    std::fs::remove_file(&path).ok();
+
+    let best = String::from_utf8(best).unwrap();
+    let fields: Vec<&str> = best.lines().collect();
+    assert!(fields.contains(&"PRIORITY=6"), "{}", best);
+    assert!(fields.contains(&"SCORE=64"), "{}", best);
+    assert!(fields.contains(&format!("ADDRESS=0x{}", "44".repeat(20)).as_str()), "{}", best);
+    assert!(fields.contains(&format!("SALT=0x{}", "ab".repeat(32)).as_str()), "{}", best);
+    assert!(fields[0].starts_with("MESSAGE=new best: score=64 "), "{}", best);
+    let key = String::from_utf8(key).unwrap();
+    assert!(key.contains("SCORE=64") && !key.contains("ab".repeat(32).as_str()), "{}", key);
+    assert!(key.lines().next().unwrap().ends_with(" key=saved-to-key-dir"), "{}", key);
+    let mut multiline = b"MESSAGE\n".to_vec();
+    multiline.extend_from_slice(&9u64.to_le_bytes());
+    multiline.extend_from_slice(b"two\nlines\nPRIORITY=3\n");
+    assert!(error.starts_with(&multiline), "{:?}", error);
+    assert_eq!(String::from_utf8(warning).unwrap(), format!("<12>uniswapv4-challenge-miner[{}]: careful", std::process::id()));
+}
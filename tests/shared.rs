@@ -0,0 +1,69 @@
+// Miners on one machine sharing their best through a file.
+
+use uniswapv4_challenge_miner::Candidate;
+
+#[test]
+fn miners_sharing_a_file_keep_the_best_of_them() {
+    use uniswapv4_challenge_miner::shared::Shared;
+
+    let path = std::env::temp_dir().join(format!("shared-{}.best", std::process::id()));
+    std::fs::remove_file(&path).ok();
+    let (mut first, mut second) = (Shared::open(&path).unwrap(), Shared::open(&path).unwrap());
+    let candidate = |score| Candidate { salt: [score as u8; 32], address: [0x44; 20], score };
+    assert!(first.exchange(None).unwrap().is_none());
+    assert_eq!(first.exchange(Some(candidate(40))).unwrap().unwrap().best.score, 40);
+    // A worse best, or a tie, leaves the shared one alone.
+    assert_eq!(second.exchange(Some(candidate(30))).unwrap().unwrap().best.salt, [40; 32]);
+    assert_eq!(second.exchange(Some(candidate(40))).unwrap().unwrap().best.salt, [40; 32]);
+    second.exchange(Some(candidate(90))).unwrap();
+    let entry = first.exchange(Some(candidate(40))).unwrap().unwrap();
+    assert_eq!((entry.best.score, entry.pid), (90, std::process::id()));
+    // The last miner to leave takes the file with it.
+    second.leave(None).unwrap();
+    assert!(path.exists());
+    first.leave(Some(candidate(95))).unwrap();
+    assert!(!path.exists());
+}
+
+#[test]
+fn a_best_left_by_miners_no_longer_running_is_dropped() {
+    use uniswapv4_challenge_miner::shared::{Board, Entry, Shared};
+
+    let path = std::env::temp_dir().join(format!("shared-stale-{}.best", std::process::id()));
+    let mut exited = std::process::Command::new("true").spawn().unwrap();
+    exited.wait().unwrap();
+    let best = Candidate { salt: [0x11; 32], address: [0x44; 20], score: 200 };
+    let board = Board { best: Some(Entry { best, pid: exited.id() }), miners: vec![exited.id()] };
+    std::fs::write(&path, serde_json::to_string(&board).unwrap()).unwrap();
+    let mut shared = Shared::open(&path).unwrap();
+    let exchanged = shared.exchange(None).unwrap();
+    shared.leave(None).unwrap();
+    assert!(exchanged.is_none());
+    assert!(!path.exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn the_file_is_kept_from_other_users() {
+    use std::os::unix::fs::{MetadataExt, PermissionsExt};
+    use uniswapv4_challenge_miner::shared::Shared;
+
+    let path = std::env::temp_dir().join(format!("shared-own-{}.best", std::process::id()));
+    std::fs::remove_file(&path).ok();
+    let shared = Shared::open(&path).unwrap();
+    assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+    let owner = std::fs::metadata(&path).unwrap().uid();
+    shared.leave(None).unwrap();
+    // A link is not followed, even to a file of ours.
+    let (target, link) = (path.with_extension("target"), path.with_extension("link"));
+    std::fs::write(&target, "").unwrap();
+    std::os::unix::fs::symlink(&target, &link).unwrap();
+    assert!(Shared::open(&link).is_err());
+    std::fs::remove_file(&link).unwrap();
+    // Handing a file to another user takes root, so elsewhere that is skipped.
+    if std::os::unix::fs::chown(&target, Some(owner + 1), None).is_ok() {
+        let error = Shared::open(&target).err().unwrap();
+        assert!(error.to_string().contains("belongs to another user"), "{}", error);
+    }
+    std::fs::remove_file(&target).unwrap();
+}
@@ -0,0 +1,115 @@
+// What a running miner shows: ETAs, the rarity of scores, slow threads,
+// painted addresses, snapshots, stdin commands and the best score chart.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use uniswapv4_challenge_miner::{color, decode_hex, Candidate};
+
+#[test]
+fn eta_to_beat_follows_the_distribution() {
+    use uniswapv4_challenge_miner::odds::{chance_above, format_eta};
+
+    // The rate at which beating 40 takes two hours on average.
+    let rate = 1.0 / (chance_above(40) * 7200.0);
+    assert_eq!(format_eta(40, rate), "~2h");
+    assert_eq!(format_eta(40, 0.0), "-");
+    assert_eq!(format_eta(u32::MAX, 1e9), "never");
+}
+
+#[test]
+fn rarity_is_the_share_of_addresses_scoring_as_much() {
+    use uniswapv4_challenge_miner::odds::{chance_above, chance_at_least, chance_of, format_count, rarity};
+    use uniswapv4_challenge_miner::{compute_score, create_addr};
+
+    assert_eq!((chance_at_least(0), rarity(0)), (1.0, "1 in 1".to_string()));
+    for score in [1, 13, 40, 120] {
+        assert!((chance_at_least(score) - chance_of(score) - chance_above(score)).abs() <= chance_at_least(score) * 1e-9, "{}", score);
+    }
+    assert_eq!((format_count(99_999.4), format_count(123_456.0)), ("99999".to_string(), "1.23e5".to_string()));
+    assert_eq!(rarity(u32::MAX), "1 in inf");
+
+    // Near enough what so many addresses do score.
+    let sender = decode_hex::<20>("0x6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0").unwrap();
+    let scoring = (0..20_000).filter(|nonce| compute_score(&create_addr(&sender, *nonce)) >= 1).count();
+    let expected = 20_000.0 * chance_at_least(1);
+    assert!((scoring as f64 - expected).abs() < expected * 0.1, "{} scoring, {} expected", scoring, expected);
+}
+
+#[test]
+fn slow_threads_are_flagged_against_the_median() {
+    use uniswapv4_challenge_miner::search::slow;
+
+    assert_eq!(slow(&[100.0, 98.0, 60.0, 102.0]), [false, false, true, false]);
+    assert_eq!(slow(&[100.0, 80.0]), [false, false]);
+    assert_eq!(slow(&[5.0]), [false]);
+}
+
+#[test]
+fn painted_addresses_mark_what_scores() {
+    let address = decode_hex::<20>("0x0000444401234567890abcdef0123456789a4440").unwrap();
+    let painted = color::paint(&address);
+    assert_eq!(
+        painted,
+        "0x\x1b[32m0000\x1b[0m\x1b[1;33m4444\x1b[0m0123\x1b[1m4\x1b[0m567890abcdef0123\x1b[1m4\x1b[0m56789a\x1b[1m4\x1b[0m\x1b[1;35m44\x1b[0m0"
+    );
+    // Without the escapes it is the plain address.
+    let plain: String = painted.split('\x1b').enumerate().map(|(i, part)| if i == 0 { part } else { &part[part.find('m').unwrap() + 1..] }).collect();
+    assert_eq!(plain, format!("0x{}", hex::encode(address)));
+    // Addresses scoring 0 have nothing to show.
+    let unscored = decode_hex::<20>("0x0001444401234567890abcdef0123456789a4440").unwrap();
+    assert_eq!(color::paint(&unscored), format!("0x{}", hex::encode(unscored)));
+}
+
+#[test]
+fn snapshots_show_threads_best_and_coverage() {
+    use uniswapv4_challenge_miner::mode::Mode;
+    use uniswapv4_challenge_miner::{salts, search, snapshot};
+
+    let control: Arc<search::Control> = Default::default();
+    *control.taken.lock().unwrap() = vec![300, 100];
+    let best = Arc::new(Mutex::new(Some(Candidate { salt: [1; 32], address: [0x44; 20], score: 80 })));
+    let salts = Arc::new(salts::Sequential { submitter: [0; 20], start: 1000, end: Some(5000) });
+    let coverage = Arc::new(salts::Coverage { salts, before: Vec::new() });
+    let sources = snapshot::Sources { mode: Mode::Create2, control, coverage, best, started: Instant::now() };
+    let snapshot = sources.render();
+    assert!(snapshot.contains("  Thread 0: 300 salts (75.0%)\n  Thread 1: 100 salts (25.0%)\n"), "{}", snapshot);
+    assert!(snapshot.contains(&format!("  Best: score=80 address=0x{} salt=0x{}\n", "44".repeat(20), "01".repeat(32))), "{}", snapshot);
+    // Thread 1 has only got as far as counter 1000 + 1 + 100 * 2.
+    assert!(snapshot.ends_with("  Covered: counters 1000..1201 of 1000..5000 (5.03% of the salts)\n"), "{}", snapshot);
+}
+
+#[test]
+fn stdin_commands_parse() {
+    use uniswapv4_challenge_miner::commands::{parse, Command};
+    use uniswapv4_challenge_miner::monitor::Target;
+
+    assert_eq!(parse("p"), Ok(Command::Pause));
+    assert_eq!(parse(" r "), Ok(Command::Resume));
+    assert_eq!(parse("s"), Ok(Command::Status));
+    assert_eq!(parse("t 120"), Ok(Command::Target(Target::Score(120))));
+    assert_eq!(parse("t beat-chain"), Ok(Command::Target(Target::BeatChain)));
+    assert_eq!(parse("q"), Ok(Command::Quit));
+    assert!(parse("t").is_err());
+    assert!(parse("t high").is_err());
+    assert!(parse("t 0").is_err());
+    assert!(parse("p now").is_err());
+    assert!(parse("x").is_err());
+}
+
+#[test]
+fn best_score_chart_steps_up_over_time() {
+    use uniswapv4_challenge_miner::timeline::{chart, Point};
+
+    let point = |secs, score| Point { elapsed: Duration::from_secs(secs), timestamp: 0, hashes: 0, score };
+    let points = [point(0, 10), point(5, 20), point(8, 30)];
+    let chart = chart(&points, Duration::from_secs(10), 10, 3, false);
+    let expected = [
+        "30 |       ###",
+        "   |    ######",
+        "10 |##########",
+        "   +----------",
+        "    0s     10s",
+    ];
+    assert_eq!(chart.lines().collect::<Vec<_>>(), expected, "\n{}", chart);
+}
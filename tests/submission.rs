@@ -0,0 +1,244 @@
+// Submissions once they are sent, against a node answering from a script:
+// nonces and replacements, confirmations, relayers, and the submissions made
+// unattended when outbid or to secure the best.
+
+mod node;
+
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+
+use alloy_consensus::private::alloy_eips::eip2718::Decodable2718;
+use alloy_consensus::{Transaction, TxEnvelope};
+use clap::Parser;
+use node::{address_word, number_word, Node};
+use serde_json::{json, Value};
+use uniswapv4_challenge_miner::budget::Budget;
+use uniswapv4_challenge_miner::challenge::selector;
+use uniswapv4_challenge_miner::gas::GasStrategy;
+use uniswapv4_challenge_miner::keccak256;
+use uniswapv4_challenge_miner::relay::RelayArgs;
+use uniswapv4_challenge_miner::rpc::parse_bytes;
+use uniswapv4_challenge_miner::submit::{SubmitOptions, Submitter};
+use uniswapv4_challenge_miner::wallet::Signer;
+
+const MINER: &str = env!("CARGO_BIN_EXE_uniswapv4-challenge-miner");
+
+// The first of anvil's dev accounts.
+const KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+const SENDER: [u8; 20] = [0xf3, 0x9f, 0xd6, 0xe5, 0x1a, 0xad, 0x88, 0xf6, 0xf4, 0xce, 0x6a, 0xb8, 0x82, 0x72, 0x79, 0xcf, 0xff, 0xb9, 0x22, 0x66];
+const NONCE: u64 = 7;
+const GWEI: u64 = 1_000_000_000;
+
+// A salt anyone may submit.
+fn salt() -> [u8; 32] {
+    let mut salt = [0; 32];
+    salt[31] = 7;
+    salt
+}
+
+// What the node has been sent, which of it is mined in which block, and the
+// leading address and its submitter.
+struct Chain {
+    sent: Vec<Vec<u8>>,
+    head: u64,
+    mined: Option<(usize, u64)>,
+    polls: u32,
+    leader: ([u8; 20], [u8; 20]),
+}
+
+impl Chain {
+    // At block 100, with the lead already ours.
+    fn new() -> Arc<Mutex<Chain>> {
+        Arc::new(Mutex::new(Chain { sent: Vec::new(), head: 100, mined: None, polls: 0, leader: ([0x12; 20], SENDER) }))
+    }
+}
+
+fn hash(raw: &[u8]) -> String {
+    format!("0x{}", hex::encode(keccak256(raw)))
+}
+
+// A mainnet node holding `chain`, with `poll` run each time the nonce is
+// read, as the submitter does once a poll.
+fn serve(chain: Arc<Mutex<Chain>>, poll: impl Fn(&mut Chain) + Send + Sync + 'static) -> Node {
+    Node::serve(move |method, params| {
+        let mut chain = chain.lock().unwrap();
+        let call = |signature: &str| params[0]["data"].as_str().unwrap()[2..10] == hex::encode(selector(signature));
+        match method {
+            "eth_chainId" => Ok(json!("0x1")),
+            "eth_getBlockByNumber" => Ok(json!({ "timestamp": "0x6553f100", "baseFeePerGas": format!("{:#x}", GWEI) })),
+            "eth_maxPriorityFeePerGas" => Ok(json!(format!("{:#x}", GWEI))),
+            "eth_estimateGas" => Ok(json!("0x10000")),
+            "eth_blockNumber" => Ok(json!(format!("{:#x}", chain.head))),
+            "eth_getTransactionCount" if params[1] == "pending" => Ok(json!(format!("{:#x}", NONCE))),
+            "eth_getTransactionCount" => {
+                chain.polls += 1;
+                poll(&mut chain);
+                Ok(json!(format!("{:#x}", NONCE + chain.mined.is_some() as u64)))
+            }
+            "eth_sendRawTransaction" => {
+                let raw = parse_bytes(&params[0]).unwrap();
+                chain.sent.push(raw.clone());
+                Ok(json!(hash(&raw)))
+            }
+            "eth_getTransactionByHash" => Ok(json!({ "hash": params[0] })),
+            "eth_getCode" => Ok(json!("0x6080")),
+            "eth_getTransactionReceipt" => Ok(match chain.mined {
+                Some((index, block)) if params[0] == hash(&chain.sent[index]) => json!({ "blockNumber": format!("{:#x}", block), "status": "0x1" }),
+                _ => Value::Null,
+            }),
+            "eth_call" if call("updateBestAddress(bytes32)") => Ok(json!("0x")),
+            "eth_call" if call("competitionDeadline()") => Ok(number_word(0x6553f100 + 86_400)),
+            "eth_call" if call("bestAddressSalt()") => Ok(json!(format!("0x{}", hex::encode(salt())))),
+            "eth_call" if call("bestAddress()") => Ok(address_word(&chain.leader.0)),
+            "eth_call" if call("bestAddressSubmitter()") => Ok(address_word(&chain.leader.1)),
+            _ => Err(json!({ "code": -32601, "message": method })),
+        }
+    })
+}
+
+// Submit `salt` through `node` with `args`, returning what it printed.
+fn submit(node: &Node, salt: &[u8; 32], args: &[&str]) -> String {
+    let salt = format!("0x{}", hex::encode(salt));
+    let output = Command::new(MINER).args(["submit", "--rpc-url", &node.url, "--salt", &salt, "--private-key", KEY]).args(args).output().unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success(), "{}{}", stdout, String::from_utf8_lossy(&output.stderr));
+    stdout
+}
+
+// The automatic submitters' signer and settings, returning once sent.
+fn submitter() -> Arc<Mutex<Submitter>> {
+    #[derive(Parser)]
+    #[command(no_binary_name = true)]
+    struct Cli {
+        #[command(flatten)]
+        gas: GasStrategy,
+        #[command(flatten)]
+        budget: Budget,
+        #[command(flatten)]
+        relay: RelayArgs,
+    }
+    let Cli { gas, budget, relay } = Cli::parse_from(Vec::<String>::new());
+    let options = SubmitOptions { gas, gas_limit: None, nonce: None, wait: false, confirmations: 1, budget, relay };
+    let signer = Signer::Local(k256::ecdsa::SigningKey::from_slice(&hex::decode(KEY).unwrap()).unwrap());
+    Arc::new(Mutex::new(Submitter { signer, options, spent: 0 }))
+}
+
+fn decode(raw: &[u8]) -> TxEnvelope {
+    TxEnvelope::decode_2718(&mut &raw[..]).unwrap()
+}
+
+#[test]
+fn a_lingering_submission_is_replaced_with_higher_fees_at_its_nonce() {
+    let chain = Chain::new();
+    // The replacement lands, once there is one.
+    let node = serve(Arc::clone(&chain), |chain| {
+        if chain.sent.len() == 2 {
+            chain.mined = Some((1, chain.head));
+        }
+    });
+    let stdout = submit(&node, &salt(), &["--replace-after", "0s", "--max-replacements", "1"]);
+    assert!(stdout.contains("(nonce 7,"), "{}", stdout);
+    assert!(stdout.contains("Speeding up nonce 7"), "{}", stdout);
+    assert!(stdout.contains("Submission confirmed"), "{}", stdout);
+
+    let chain = chain.lock().unwrap();
+    let [first, replacement] = [&chain.sent[0], &chain.sent[1]].map(|raw| decode(raw));
+    assert_eq!((first.nonce(), replacement.nonce()), (NONCE, NONCE));
+    assert_eq!(first.input(), replacement.input());
+    // Nodes only take a replacement paying at least an eighth more.
+    assert!(replacement.max_fee_per_gas() >= first.max_fee_per_gas() * 9 / 8);
+    assert!(replacement.max_priority_fee_per_gas().unwrap() >= first.max_priority_fee_per_gas().unwrap() * 9 / 8);
+}
+
+#[test]
+fn submissions_wait_for_their_confirmations_through_a_reorg() {
+    let chain = Chain::new();
+    // Mined in 100, reorged out, mined again in 101 and confirmed by 102.
+    let node = serve(Arc::clone(&chain), |chain| {
+        (chain.head, chain.mined) = match chain.polls {
+            1 => (100, Some((0, 100))),
+            2 => (101, None),
+            3 => (101, Some((0, 101))),
+            _ => (102, Some((0, 101))),
+        }
+    });
+    let stdout = submit(&node, &salt(), &["--confirmations", "2"]);
+    let hash = hash(&chain.lock().unwrap().sent[0]);
+    assert!(stdout.contains(&format!("Transaction {} mined in block 100, waiting for 2 confirmations", hash)), "{}", stdout);
+    assert!(stdout.contains("no longer in the chain (reorg)"), "{}", stdout);
+    assert!(stdout.contains(&format!("Transaction {} mined in block 101 (2 confirmations)", hash)), "{}", stdout);
+    assert!(stdout.contains("Submission confirmed"), "{}", stdout);
+    assert_eq!(chain.lock().unwrap().polls, 4);
+}
+
+#[test]
+fn outbid_miners_submit_again_up_to_their_limit() {
+    use uniswapv4_challenge_miner::{compute_score, resubmit, Candidate, DEPLOYER_ADDRESS};
+
+    let chain = Chain::new();
+    let node = serve(Arc::clone(&chain), |_| {});
+    let leads = compute_score(&chain.lock().unwrap().leader.0);
+    let resubmit = resubmit::spawn(node.rpc(), DEPLOYER_ADDRESS, submitter(), 1);
+    let send = |score: u32| {
+        resubmit.send(Candidate { salt: salt(), address: [0x12; 20], score }).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        chain.lock().unwrap().sent.len()
+    };
+    // Nothing to do while what was found does not beat the chain, or the lead is ours.
+    assert_eq!(send(leads), 0);
+    assert_eq!(send(leads + 5), 0);
+    chain.lock().unwrap().leader.1 = [0x5b; 20];
+    assert_eq!(send(leads + 5), 1);
+    assert_eq!(decode(&chain.lock().unwrap().sent[0]).input()[4..], salt());
+    // Outbid again, but out of submissions.
+    assert_eq!(send(leads + 10), 1);
+}
+
+#[test]
+fn relayed_submissions_go_to_the_relayer_wrapped_in_execute() {
+    use uniswapv4_challenge_miner::relay::{decode_execute, Execute};
+    use uniswapv4_challenge_miner::{challenge, DEPLOYER_ADDRESS};
+
+    let relayer = [0x5c; 20];
+    let mut salt = salt();
+    salt[..20].copy_from_slice(&relayer);
+    let chain = Chain::new();
+    let node = serve(Arc::clone(&chain), |_| {});
+    submit(&node, &salt, &["--relayer", &hex::encode(relayer), "--allow-sender-mismatch", "--no-wait"]);
+
+    let sent = decode(&chain.lock().unwrap().sent[0]);
+    assert_eq!(sent.to(), Some(relayer.into()));
+    let calldata = challenge::update_best_address_calldata(&salt);
+    assert_eq!(decode_execute(sent.input()), Some(Execute { target: DEPLOYER_ADDRESS, value: 0, data: &calldata }));
+}
+
+#[test]
+fn the_best_is_secured_once_improving_on_it_is_unlikely() {
+    use std::sync::atomic::AtomicU32;
+    use std::time::{Duration, Instant};
+    use uniswapv4_challenge_miner::odds::{self, Secure};
+    use uniswapv4_challenge_miner::search::Control;
+    use uniswapv4_challenge_miner::{deadline, Candidate, DEPLOYER_ADDRESS};
+
+    let chain = Chain::new();
+    chain.lock().unwrap().leader.1 = [0x5b; 20];
+    let node = serve(Arc::clone(&chain), |_| {});
+    let (control, best) = (Arc::new(Control::default()), Arc::new(Mutex::new(None)));
+    deadline::spawn(node.rpc(), DEPLOYER_ADDRESS, Arc::clone(&best), None, Arc::clone(&control)).unwrap();
+    // Nothing is hashed, so there is no chance of doing better in the day left.
+    let secure = Secure { below: 5.0, rpc: node.rpc(), contract: DEPLOYER_ADDRESS, submitter: submitter() };
+    odds::spawn(Duration::from_millis(20), control, Arc::new(AtomicU32::new(40)), Arc::clone(&best), Some(secure));
+    std::thread::sleep(Duration::from_millis(100));
+    assert!(chain.lock().unwrap().sent.is_empty());
+
+    *best.lock().unwrap() = Some(Candidate { salt: salt(), address: [0x00; 20], score: 200 });
+    let started = Instant::now();
+    while chain.lock().unwrap().sent.is_empty() {
+        assert!(started.elapsed() < Duration::from_secs(10), "the best was not secured");
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(decode(&chain.lock().unwrap().sent[0]).input()[4..], salt());
+    // Each best only once.
+    std::thread::sleep(Duration::from_millis(100));
+    assert_eq!(chain.lock().unwrap().sent.len(), 1);
+}
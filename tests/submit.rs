@@ -0,0 +1,207 @@
+// Submissions as they are built before anything is sent: transactions and
+// their fees, the calldata of relayed calls, the dry run and exports, and the
+// ETH price budgets are checked at.
+
+use std::process::Command;
+use std::time::Duration;
+
+use alloy_consensus::private::alloy_eips::eip2718::Encodable2718;
+use alloy_consensus::private::alloy_primitives::{Address, Bytes, Signature, TxKind, U256};
+use alloy_consensus::{SignableTransaction, TxEip1559, TxEnvelope};
+use k256::ecdsa::SigningKey;
+use uniswapv4_challenge_miner::gas::{Fees, GasStrategy};
+use uniswapv4_challenge_miner::tx::Eip1559Tx;
+use uniswapv4_challenge_miner::challenge::update_best_address_calldata;
+use uniswapv4_challenge_miner::relay::{decode_execute, execute_calldata, Execute};
+use uniswapv4_challenge_miner::rpc::to_hex;
+
+const MINER: &str = env!("CARGO_BIN_EXE_uniswapv4-challenge-miner");
+
+#[test]
+fn eip1559_transactions_sign_as_alloy_does() {
+    let key = SigningKey::from_slice(&[0x42; 32]).unwrap();
+    // Zeros, a long list and values each side of the single byte encoding.
+    let txs = [
+        Eip1559Tx { chain_id: 1, nonce: 0, max_priority_fee_per_gas: 0, max_fee_per_gas: 0x7f, gas_limit: 21_000, to: [0x48; 20], value: 0, data: Vec::new() },
+        Eip1559Tx {
+            chain_id: 11_155_111,
+            nonce: 0x80,
+            max_priority_fee_per_gas: 1_500_000_000,
+            max_fee_per_gas: 123_456_789_012,
+            gas_limit: 90_000,
+            to: [0x11; 20],
+            value: u128::MAX,
+            data: execute_calldata(&[0x48; 20], &update_best_address_calldata(&[0x22; 32])),
+        },
+    ];
+    for tx in txs {
+        let expected = TxEip1559 {
+            chain_id: tx.chain_id,
+            nonce: tx.nonce,
+            gas_limit: tx.gas_limit,
+            max_fee_per_gas: tx.max_fee_per_gas,
+            max_priority_fee_per_gas: tx.max_priority_fee_per_gas,
+            to: TxKind::Call(Address::from(tx.to)),
+            value: U256::from(tx.value),
+            access_list: Default::default(),
+            input: Bytes::from(tx.data.clone()),
+        };
+        assert_eq!(tx.signing_hash(), expected.signature_hash().0);
+        let (signature, recovery_id) = key.sign_prehash_recoverable(&tx.signing_hash()).unwrap();
+        let signature = Signature::from_signature_and_parity(signature, recovery_id.is_y_odd());
+        assert_eq!(tx.sign(&key).unwrap(), TxEnvelope::from(expected.into_signed(signature)).encoded_2718());
+    }
+}
+
+#[test]
+fn fees_escalate_near_the_deadline_and_bump_by_an_eighth() {
+    const GWEI: u128 = 1_000_000_000;
+    let gas = GasStrategy {
+        max_fee: None,
+        priority_fee: None,
+        base_fee_multiplier: 2.0,
+        urgent_within: Some(Duration::from_secs(600)),
+        urgent_multiplier: 3.0,
+        replace_after: Duration::from_secs(60),
+        max_replacements: 5,
+    };
+    let fees = |max, tip| Fees { max_fee_per_gas: max * GWEI, max_priority_fee_per_gas: tip * GWEI };
+    assert_eq!(gas.fees(10 * GWEI, GWEI, None), fees(21, 1));
+    assert_eq!(gas.fees(10 * GWEI, GWEI, Some(Duration::from_secs(600))), fees(21, 1));
+    // Halfway into the last ten minutes, halfway to 3x.
+    assert_eq!(gas.fees(10 * GWEI, GWEI, Some(Duration::from_secs(300))), fees(42, 2));
+    assert!(gas.is_urgent(Some(Duration::from_secs(1))) && !gas.is_urgent(None));
+
+    let bumped = gas.bump(fees(40, 8), fees(21, 1)).unwrap();
+    assert_eq!(bumped, Fees { max_fee_per_gas: 45 * GWEI + 1, max_priority_fee_per_gas: 9 * GWEI + 1 });
+    // The strategy's own fees when they have risen further.
+    assert_eq!(gas.bump(fees(40, 8), fees(60, 10)).unwrap(), fees(60, 10));
+
+    let capped = GasStrategy { max_fee: Some(30.0), ..gas };
+    assert_eq!(capped.fees(10 * GWEI, GWEI, Some(Duration::from_secs(300))), fees(30, 2));
+    assert_eq!(capped.bump(fees(27, 1), fees(21, 1)), None);
+    assert!(capped.check_cap(30 * GWEI).is_ok());
+    assert!(capped.check_cap(30 * GWEI + 1).is_err());
+}
+
+#[test]
+fn dry_runs_print_the_calldata_and_a_cast_command() {
+    let dry_run = |args: &[&str]| {
+        let output = Command::new(MINER).args(["submit", "--dry-run"]).args(args).output().unwrap();
+        assert!(output.status.success(), "{:?}", output);
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let contract = to_hex(&uniswapv4_challenge_miner::DEPLOYER_ADDRESS);
+    let salt = format!("0x{:0>64}", 7);
+    let stdout = dry_run(&["--salt", &salt, "--gas-limit", "90000", "--max-fee", "30", "--priority-fee", "2"]);
+    let calldata = to_hex(&update_best_address_calldata(&uniswapv4_challenge_miner::decode_hex(&salt).unwrap()));
+    assert!(stdout.contains(&format!("To:       {}\nValue:    0\nCalldata: {}\n", contract, calldata)), "{}", stdout);
+    assert!(stdout.contains("Sender:   any"), "{}", stdout);
+    let cast = format!("cast send {} 'updateBestAddress(bytes32)' {} --rpc-url $RPC_URL --gas-limit 90000 --gas-price 30gwei --priority-gas-price 2gwei\n", contract, salt);
+    assert!(stdout.contains(&cast), "{}", stdout);
+
+    // No signer is needed, and a bound salt names who must send it.
+    let bound = format!("0x{}{:0>24}", "11".repeat(20), 7);
+    let stdout = dry_run(&["--salt", &bound, "--rpc-url", "http://localhost:8545"]);
+    assert!(stdout.contains(&format!("Sender:   must be 0x{}\n", "11".repeat(20))), "{}", stdout);
+    assert!(stdout.contains(&format!("{} --rpc-url http://localhost:8545\n", bound)), "{}", stdout);
+}
+
+#[test]
+fn relayed_calls_decode_to_what_was_wrapped() {
+    let challenge = [0x48; 20];
+    for data in [update_best_address_calldata(&[0x11; 32]), Vec::new(), vec![7; 33]] {
+        let calldata = execute_calldata(&challenge, &data);
+        assert_eq!(calldata.len() % 32, 4);
+        assert_eq!(decode_execute(&calldata), Some(Execute { target: challenge, value: 0, data: &data }));
+    }
+    assert_eq!(decode_execute(&update_best_address_calldata(&[0x11; 32])), None);
+    let calldata = execute_calldata(&challenge, &[1; 40]);
+    assert_eq!(decode_execute(&calldata[..calldata.len() - 32]), None);
+    // An offset or length so large that adding them up overflows.
+    for word in [2, 3] {
+        let mut crafted = calldata.clone();
+        crafted[4 + 32 * word + 24..4 + 32 * (word + 1)].fill(0xff);
+        assert_eq!(decode_execute(&crafted), None);
+    }
+}
+
+#[test]
+fn relayed_dry_runs_send_execute_to_the_relayer() {
+    let (relayer, salt) = (format!("0x{}", "11".repeat(20)), format!("0x{}{:0>24}", "11".repeat(20), 7));
+    let dry_run = |salt: &str| Command::new(MINER).args(["submit", "--dry-run", "--salt", salt, "--relayer", &relayer, "--allow-sender-mismatch"]).output().unwrap();
+    let output = dry_run(&salt);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success(), "{}", stdout);
+
+    let contract = uniswapv4_challenge_miner::DEPLOYER_ADDRESS;
+    let inner = update_best_address_calldata(&uniswapv4_challenge_miner::decode_hex(&salt).unwrap());
+    assert!(stdout.contains(&format!("To:       {} (the relayer)\n", relayer)), "{}", stdout);
+    assert!(stdout.contains(&format!("Calldata: {}\n", to_hex(&execute_calldata(&contract, &inner)))), "{}", stdout);
+    assert!(stdout.contains("the challenge sees the relayer as msg.sender"), "{}", stdout);
+    let cast = format!("cast send {} 'execute(address,uint256,bytes)' {} 0 {} ", relayer, to_hex(&contract), to_hex(&inner));
+    assert!(stdout.lines().any(|line| line.starts_with(&cast)), "{}", stdout);
+
+    // A salt bound to someone else could never be accepted from the relayer.
+    let other = salt.replacen(&"11".repeat(20), &"22".repeat(20), 1);
+    assert!(!dry_run(&other).status.success());
+}
+
+#[test]
+fn eth_prices_come_from_a_fresh_positive_round() {
+    use uniswapv4_challenge_miner::budget::{parse_round, MAX_PRICE_AGE};
+
+    // (roundId, answer, startedAt, updatedAt, answeredInRound), as returned.
+    let round = |id: u128, answer: i128, updated_at: u64, answered_in: u128| {
+        // int256, sign extended.
+        let mut answer_word = [if answer < 0 { 0xff } else { 0 }; 32];
+        answer_word[16..].copy_from_slice(&answer.to_be_bytes());
+        let words = [U256::from(id), U256::from_be_bytes(answer_word), U256::from(updated_at), U256::from(updated_at), U256::from(answered_in)];
+        words.iter().flat_map(|word| word.to_be_bytes::<32>()).collect::<Vec<u8>>()
+    };
+    let now = 1_700_000_000;
+    // $2,500 at the feed's 8 decimals.
+    let usd = 2500 * 100_000_000;
+    assert_eq!(parse_round(&round(7, usd, now - 60, 7), now), Ok(2500.0));
+    assert_eq!(parse_round(&round(7, usd, now - MAX_PRICE_AGE, 7), now), Ok(2500.0));
+    // Updated just after the block it was read against.
+    assert_eq!(parse_round(&round(7, usd, now + 12, 7), now), Ok(2500.0));
+
+    let refused = [
+        (round(7, usd, now - MAX_PRICE_AGE - 1, 7), "old"),
+        (round(7, usd, 0, 7), "never updated"),
+        (round(7, usd, now - 60, 6), "earlier round"),
+        (round(7, 0, now - 60, 7), "implausible"),
+        (round(7, -1, now - 60, 7), "implausible"),
+        (round(7, usd, now - 60, 7)[..128].to_vec(), "128 bytes"),
+    ];
+    for (output, error) in refused {
+        let refused = parse_round(&output, now).unwrap_err();
+        assert!(refused.contains(error), "{}", refused);
+    }
+}
+
+#[test]
+fn exports_submit_the_salt_from_its_submitter() {
+    use uniswapv4_challenge_miner::export::{render, ExportFormat};
+
+    let contract = uniswapv4_challenge_miner::DEPLOYER_ADDRESS;
+    // An EIP-55 example, whose checksum case the exports keep.
+    let submitter = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+    let mut salt = [7; 32];
+    salt[..20].copy_from_slice(&hex::decode(&submitter[2..]).unwrap());
+
+    let script = render(ExportFormat::Foundry, &contract, &salt, 1);
+    assert!(script.contains(&format!("// The broadcasting account must be {}\n", submitter)), "{}", script);
+    assert!(script.contains("IUniswapV4DeployerCompetition(0x48E516B34A1274f49457b9C6182097796D0498Cb)"), "{}", script);
+    assert!(script.contains(&format!("bytes32 constant SALT = {};", to_hex(&salt))), "{}", script);
+    assert!(script.contains("COMPETITION.updateBestAddress(SALT);"), "{}", script);
+
+    let batch: serde_json::Value = serde_json::from_str(&render(ExportFormat::Safe, &contract, &salt, 130)).unwrap();
+    assert_eq!(batch["chainId"], "130");
+    assert_eq!(batch["meta"]["createdFromSafeAddress"], submitter);
+    let call = &batch["transactions"][0];
+    assert_eq!((&call["to"], &call["value"]), (&serde_json::json!("0x48E516B34A1274f49457b9C6182097796D0498Cb"), &serde_json::json!("0")));
+    assert_eq!(call["contractMethod"]["name"], "updateBestAddress");
+    assert_eq!(call["contractInputsValues"]["salt"], to_hex(&salt));
+}
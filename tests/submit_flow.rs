@@ -0,0 +1,105 @@
+// End-to-end mine -> verify -> simulate -> submit against an anvil fork of
+// mainnet. Needs anvil on PATH and ANVIL_FORK_URL (an archive endpoint, with
+// ANVIL_FORK_BLOCK inside the competition window once it is over).
+
+mod common;
+
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use common::{word_to_u64, Anvil, CHALLENGE, DEV_ADDRESS, DEV_KEY};
+use serde_json::json;
+
+const MINER: &str = env!("CARGO_BIN_EXE_uniswapv4-challenge-miner");
+
+// Replace the on-chain leader with a zero-score entry, so that whatever the
+// miner finds in a few seconds is good enough to take the lead. The slots are
+// located by value rather than assuming the contract's storage layout.
+fn reset_leader(anvil: &Anvil) {
+    let salt = anvil.call_word(CHALLENGE, "0xedb6e1ca");
+    let address = anvil.call_word(CHALLENGE, "0x756f0683");
+    let mut salt_slot = None;
+    for slot in 0..16 {
+        let value = anvil.storage(CHALLENGE, slot);
+        if value == salt && salt_slot.is_none() {
+            salt_slot = Some(slot);
+        } else if value == address {
+            // An address with no leading zeros and no leading 4 scores zero.
+            anvil.set_storage(CHALLENGE, slot, &format!("0x{:0>64}", "11".repeat(20)));
+        }
+    }
+    let salt_slot = salt_slot.expect("bestAddressSalt storage slot not found");
+    anvil.set_storage(CHALLENGE, salt_slot, &format!("0x{}", "00".repeat(32)));
+}
+
+// Run the miner until it reports a candidate whose score the contract confirms.
+fn mine_verified_salt(anvil: &Anvil) -> String {
+    let mut miner = Command::new(MINER)
+        .args(["--threads", "2", "--network", "anvil", "--rpc-url", &anvil.url])
+        .args(["--submitter", DEV_ADDRESS, "--verify-scores"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let (lines, received) = mpsc::channel();
+    for output in [Box::new(miner.stdout.take().unwrap()) as Box<dyn std::io::Read + Send>, Box::new(miner.stderr.take().unwrap())] {
+        let lines = lines.clone();
+        thread::spawn(move || {
+            for line in BufReader::new(output).lines().map_while(Result::ok) {
+                lines.send(line).ok();
+            }
+        });
+    }
+
+    let mut candidate = None;
+    let verified = loop {
+        let line = received
+            .recv_timeout(Duration::from_secs(120))
+            .expect("miner produced no verified candidate");
+        assert!(!line.contains("score divergence"), "{}", line);
+        if let Some(salt) = line.split("salt: ").nth(1) {
+            candidate = Some(salt[..66].to_string());
+        }
+        if line.contains("verified on-chain") || line.contains("would take the lead") {
+            break candidate.clone().expect("verified before any candidate was reported");
+        }
+    };
+    miner.kill().ok();
+    miner.wait().ok();
+    verified
+}
+
+#[test]
+fn mine_verify_simulate_submit() {
+    let Some(anvil) = Anvil::fork() else { return };
+    let deadline = word_to_u64(&anvil.call_word(CHALLENGE, "0xa94557b8"));
+    if anvil.timestamp() >= deadline {
+        eprintln!("skipping: the forked block is past the competition deadline, set ANVIL_FORK_BLOCK");
+        return;
+    }
+    reset_leader(&anvil);
+
+    let salt = mine_verified_salt(&anvil);
+
+    let simulation = anvil.rpc(
+        "eth_call",
+        json!([{ "from": DEV_ADDRESS, "to": CHALLENGE, "data": format!("0xfd3989f5{}", &salt[2..]) }, "latest"]),
+    );
+    assert_eq!(simulation, json!("0x"), "updateBestAddress would revert");
+
+    let output = Command::new(MINER)
+        .args(["submit", "--network", "anvil", "--rpc-url", &anvil.url])
+        .args(["--salt", &salt, "--private-key", DEV_KEY])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "submit failed: {}{}", stdout, String::from_utf8_lossy(&output.stderr));
+    assert!(stdout.contains("Submission confirmed"), "{}", stdout);
+
+    assert_eq!(anvil.call_word(CHALLENGE, "0xedb6e1ca"), salt);
+    let submitter = anvil.call_word(CHALLENGE, "0xe87a6f84");
+    assert_eq!(&submitter[26..], &DEV_ADDRESS[2..]);
+}
@@ -0,0 +1,65 @@
+// A fixed --target, which the on-chain best leaves alone until the search goes
+// back to beating it.
+
+mod node;
+
+use std::sync::atomic::Ordering;
+
+use node::{address_word, Node};
+use serde_json::json;
+use uniswapv4_challenge_miner::challenge::selector;
+use uniswapv4_challenge_miner::monitor::{self, Goal, Target};
+use uniswapv4_challenge_miner::{compute_score, Candidate};
+
+#[test]
+fn fixed_targets_ignore_the_on_chain_best() {
+    assert_eq!("beat-chain".parse::<Target>(), Ok(Target::BeatChain));
+    assert_eq!("70".parse::<Target>(), Ok(Target::Score(70)));
+    assert!("high".parse::<Target>().unwrap_err().contains("expected beat-chain or a score"));
+    // Nothing scoring 0 is ever reported.
+    assert!("0".parse::<Target>().unwrap_err().contains("at least 1"));
+
+    let mut leader = [0x12; 20];
+    leader[..5].copy_from_slice(&[0x00, 0x00, 0x00, 0x44, 0x44]);
+    let node = Node::serve(move |method, params| {
+        let data = params[0]["data"].as_str().unwrap_or_default();
+        match method {
+            "eth_call" if data.starts_with(&format!("0x{}", hex::encode(selector("bestAddress()")))) => Ok(address_word(&leader)),
+            "eth_call" => Ok(address_word(&[0x5b; 20])),
+            _ => Err(json!({ "code": -32601, "message": method })),
+        }
+    });
+    let chain = compute_score(&leader);
+    assert!(chain > 70);
+
+    let goal = Goal::new(true);
+    goal.set(Target::Score(70)).unwrap();
+    assert_eq!(goal.target.load(Ordering::Relaxed), 69);
+    // Read, but not raised to.
+    assert_eq!(monitor::initialize(&node.rpc(), &[0x48; 20], &goal), Some(leader));
+    goal.raise(chain);
+    assert_eq!(goal.target.load(Ordering::Relaxed), 69);
+
+    let scoring = |score| Candidate { salt: [0; 32], address: [0; 20], score };
+    assert!(goal.reached(Some(&scoring(70))) && !goal.reached(Some(&scoring(69))));
+    assert!(!goal.reached(None));
+    // Another search follows the chain all the same.
+    let other = Goal::new(true);
+    other.raise(chain);
+    assert_eq!(other.target.load(Ordering::Relaxed), chain);
+    assert!(!other.reached(Some(&scoring(70))));
+
+    // Back to the on-chain best seen meanwhile.
+    goal.set(Target::BeatChain).unwrap();
+    assert_eq!(goal.target.load(Ordering::Relaxed), chain);
+    assert!(!goal.reached(Some(&scoring(70))) && goal.reached(Some(&scoring(chain))));
+    goal.raise(chain + 1);
+    assert_eq!(goal.target.load(Ordering::Relaxed), chain + 1);
+    // Without a chain there is nothing to beat, and nothing to reach.
+    assert!(Goal::new(false).set(Target::BeatChain).is_err());
+    assert!(Goal::new(false).reached(None));
+
+    // Following the chain needs one to follow.
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_uniswapv4-challenge-miner")).args(["--threads", "1", "--target", "beat-chain"]).output().unwrap();
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--target beat-chain needs an RPC endpoint"));
+}
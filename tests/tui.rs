@@ -0,0 +1,60 @@
+// The --tui dashboard drawn on ratatui's test backend: the best with its
+// rarity, the --top table, the target and the pause state.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+use ratatui::backend::TestBackend;
+use ratatui::Terminal;
+use uniswapv4_challenge_miner::mode::Mode;
+use uniswapv4_challenge_miner::top::Top;
+use uniswapv4_challenge_miner::tui::{self, View};
+use uniswapv4_challenge_miner::{create2_addr, Candidate, DEPLOYER_ADDRESS, INITCODE_HASH};
+
+fn view(best: Option<Candidate>, top: Option<Arc<Top>>) -> View {
+    View {
+        mode: Mode::Create2,
+        threads: 3,
+        control: Default::default(),
+        best: Arc::new(Mutex::new(best)),
+        top,
+        target: Arc::new(AtomicU32::new(41)),
+        odds: true,
+    }
+}
+
+// The screen, one string a row.
+fn screen(view: View) -> Vec<String> {
+    let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+    tui::render(view, &mut terminal).unwrap();
+    let buffer = terminal.backend().buffer();
+    buffer.content().chunks(buffer.area.width as usize).map(|row| row.iter().map(|cell| cell.symbol()).collect()).collect()
+}
+
+fn shows(screen: &[String], text: &str) -> bool {
+    screen.iter().any(|row| row.contains(text))
+}
+
+#[test]
+fn the_dashboard_shows_the_best_and_the_top_results() {
+    let salt = [0x42; 32];
+    let best = Candidate { salt, address: create2_addr(&DEPLOYER_ADDRESS, &salt, &INITCODE_HASH), score: 57 };
+
+    let empty = screen(view(None, None));
+    assert!(shows(&empty, "3 threads, create2"), "{:#?}", empty);
+    assert!(shows(&empty, "Nothing found yet") && shows(&empty, "Bests found"), "{:#?}", empty);
+    assert!(shows(&empty, "Target    41"), "{:#?}", empty);
+
+    let top = Arc::new(Top::new(5, None, false));
+    top.insert(best);
+    let view = view(Some(best), Some(top));
+    view.control.paused.store(true, Ordering::Relaxed);
+    let found = screen(view);
+    let address = format!("0x{}", hex::encode(best.address));
+    assert!(shows(&found, &format!("Address {}", address)), "{:#?}", found);
+    assert!(shows(&found, "Score   57 (1 in "), "{:#?}", found);
+    assert!(shows(&found, &format!("salt: 0x{}", hex::encode(salt))), "{:#?}", found);
+    assert!(shows(&found, "Top results"), "{:#?}", found);
+    assert!(found.iter().any(|row| row.contains("  1. 57 ") && row.contains(&format!(" {} salt: 0x4242", address))), "{:#?}", found);
+    assert!(shows(&found, "paused"), "{:#?}", found);
+}
@@ -0,0 +1,101 @@
+// Loading the submitter's key from Web3 Secret Storage keystores, with
+// either key derivation, what a wrong passphrase gets, and the derivation
+// paths of hardware wallets.
+
+use uniswapv4_challenge_miner::tx::key_address;
+use uniswapv4_challenge_miner::wallet::WalletArgs;
+
+// The key of the spec's test vectors, under "testpassword" in both keystores.
+const KEY: &str = "7a28b5ba57c53603b0b07b56bba752f7784bf506fa95edc395f5cf6c7514fe9d";
+
+// The spec's PBKDF2-SHA-256 test vector.
+const PBKDF2: &str = r#"{
+    "crypto": {
+        "cipher": "aes-128-ctr",
+        "cipherparams": { "iv": "6087dab2f9fdbbfaddc31a909735c1e6" },
+        "ciphertext": "5318b4d5bcd28de64ee5559e671353e16f075ecae9f99c7a79a38af5f869aa46",
+        "kdf": "pbkdf2",
+        "kdfparams": { "c": 262144, "dklen": 32, "prf": "hmac-sha256", "salt": "ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd" },
+        "mac": "517ead924a9d0dc3124507e3393d175ce3ff7c1e96529c6c555ce9e51205e9b2"
+    },
+    "id": "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+    "version": 3
+}"#;
+
+// The spec's scrypt vector has r = 1 with n = 2^18, which RFC 7914 forbids
+// and the scrypt crate refuses. This one has geth's light scrypt settings
+// and was made with Python's hashlib and cryptography, not this code.
+const SCRYPT: &str = r#"{
+    "crypto": {
+        "cipher": "aes-128-ctr",
+        "cipherparams": { "iv": "a7f1e22d807e7c76a63519ccf76ad6ab" },
+        "ciphertext": "ba84aa76cfaaad13b635e4010dd86c2d2b60ca6239750358541d4231c22aa5ab",
+        "kdf": "scrypt",
+        "kdfparams": { "dklen": 32, "n": 4096, "p": 6, "r": 8, "salt": "2e67897e30ab84156dc5f30bfdef198da80938575c2840fd06badf76268f98be" },
+        "mac": "e67f667e5b8a14e9c40477fe769286d5ef1b23c742966240faa45df1c7bacf45"
+    },
+    "id": "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+    "version": 3
+}"#;
+
+// Decrypt `keystore` with `password`, passed as the environment variable
+// `var` for no prompt to come up.
+fn load(name: &str, keystore: &str, var: &str, password: &str) -> Result<[u8; 20], String> {
+    let path = std::env::temp_dir().join(format!("{}-{}.json", name, std::process::id()));
+    std::fs::write(&path, keystore).unwrap();
+    std::env::set_var(var, password);
+    let mut wallet = WalletArgs {
+        private_key: None,
+        keystore: Some(path.clone()),
+        password_env: var.to_string(),
+        ledger: false,
+        trezor: false,
+        hd_path: String::new(),
+    };
+    let key = wallet.signing_key();
+    std::fs::remove_file(&path).ok();
+    key.map(|key| key_address(&key))
+}
+
+fn expected() -> [u8; 20] {
+    key_address(&k256::ecdsa::SigningKey::from_slice(&hex::decode(KEY).unwrap()).unwrap())
+}
+
+#[test]
+fn pbkdf2_keystores_decrypt() {
+    assert_eq!(load("pbkdf2", PBKDF2, "PBKDF2_KEYSTORE_PASSWORD", "testpassword"), Ok(expected()));
+    let wrong = load("pbkdf2-wrong", PBKDF2, "PBKDF2_WRONG_KEYSTORE_PASSWORD", "testpassw0rd").unwrap_err();
+    assert!(wrong.starts_with("cannot decrypt keystore "), "{}", wrong);
+}
+
+#[test]
+fn scrypt_keystores_decrypt() {
+    assert_eq!(load("scrypt", SCRYPT, "SCRYPT_KEYSTORE_PASSWORD", "testpassword"), Ok(expected()));
+    assert!(load("scrypt-wrong", SCRYPT, "SCRYPT_WRONG_KEYSTORE_PASSWORD", "").is_err());
+}
+
+#[test]
+#[cfg(any(feature = "ledger", feature = "trezor"))]
+fn hardware_wallets_take_bip32_paths() {
+    use uniswapv4_challenge_miner::hardware::parse_hd_path;
+
+    const HARDENED: u32 = 0x8000_0000;
+    assert_eq!(parse_hd_path("m/44'/60'/0'/0/3"), Ok(vec![44 | HARDENED, 60 | HARDENED, HARDENED, 0, 3]));
+    assert_eq!(parse_hd_path("m/44'/60'/2'"), Ok(vec![44 | HARDENED, 60 | HARDENED, 2 | HARDENED]));
+    for path in ["44'/60'/0'/0/0", "m/44'/sixty'/0'", "m/2147483648", "m/44''"] {
+        assert!(parse_hd_path(path).is_err(), "{}", path);
+    }
+}
+
+// Without the features compiled in, asking for one says how to get it.
+#[test]
+fn hardware_wallets_need_their_feature() {
+    let mut wallet = WalletArgs { private_key: None, keystore: None, password_env: String::new(), ledger: true, trezor: false, hd_path: "m/44'/60'/0'/0/0".to_string() };
+    if !cfg!(feature = "ledger") {
+        assert!(wallet.signer().err().unwrap().contains("--features ledger"));
+    }
+    (wallet.ledger, wallet.trezor) = (false, true);
+    if !cfg!(feature = "trezor") {
+        assert!(wallet.signer().err().unwrap().contains("--features trezor"));
+    }
+}